@@ -1,6 +0,0 @@
-pub mod arguments;
-pub mod error;
-pub mod log_file;
-pub mod subcommands;
-pub mod time;
-pub mod project_map;
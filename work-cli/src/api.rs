@@ -0,0 +1,98 @@
+use serde_json::{json, Value};
+
+use crate::arguments::EventKind;
+use crate::subcommands;
+use work_core::error::AppError;
+use work_core::hooks::Hooks;
+use work_core::log_file::{LogEntry, LogFile};
+use work_core::time;
+
+/// Describes the status of the log as a JSON object, for other programs to consume. The object
+/// has the following fields:
+///
+/// * `working` is true if work is in progress.
+/// * `project`, `description` and `client` describe the last (or current) work, or are null.
+/// * `billable` tells whether the last (or current) work is billable, or is null if not recorded.
+/// * `since` is the UNIX timestamp at which the work in progress started, if known.
+/// * `until` is the UNIX timestamp at which the work in progress is scheduled to stop, if ever.
+/// * `remaining` is the number of seconds until then, if the work is scheduled to stop.
+pub fn status(log: &mut LogFile) -> Result<Value, AppError> {
+    let now = log.now();
+    let entry = log.get_latest_entry()?;
+    let working = subcommands::is_working_now(&entry, now);
+    let entry = entry.map(LogEntry::from);
+    let at = |kind| {
+        entry
+            .as_ref()
+            .filter(|entry| entry.kind == kind)
+            .map(|entry| entry.timestamp)
+    };
+    let (since, until) = (
+        at(EventKind::Start),
+        at(EventKind::Stop).filter(|_| working),
+    );
+    let (project, description, client, billable) = match entry {
+        Some(entry) => (
+            entry.project,
+            entry.description,
+            entry.client,
+            entry.billable,
+        ),
+        None => (None, None, None, None),
+    };
+    Ok(json!({
+        "working": working,
+        "project": project,
+        "description": description,
+        "client": client,
+        "billable": billable,
+        "since": since,
+        "until": until,
+        "remaining": until.map(|until| until - now),
+    }))
+}
+
+/// Starts new work, see `subcommands::start`, and returns the resulting status.
+pub fn start(
+    log: &mut LogFile,
+    hooks: &Hooks,
+    project: Option<String>,
+    description: Option<String>,
+    client: Option<String>,
+    billable: Option<bool>,
+) -> Result<Value, AppError> {
+    subcommands::start(
+        log,
+        hooks,
+        project,
+        description,
+        client,
+        billable,
+        None,
+        None,
+        false,
+        false,
+        false,
+        true,
+        None,
+    )?;
+    status(log)
+}
+
+/// Stops the work in progress, see `subcommands::stop`, and returns the resulting status.
+pub fn stop(log: &mut LogFile, hooks: &Hooks) -> Result<Value, AppError> {
+    subcommands::stop(log, hooks, None, None, false, true, None)?;
+    status(log)
+}
+
+/// Returns the number of seconds spent on each project and description within the given
+/// interval, which is given in any form the `of` command understands.
+pub fn report(log: &mut LogFile, interval_input: &str) -> Result<Value, AppError> {
+    let interval = time::Interval::try_parse(interval_input, &time::Search::Backward, log.clock())?;
+    let projects = log.tally_time(&interval)?.unwrap_or_default();
+    Ok(json!({
+        "start": interval.start,
+        "end": interval.end,
+        "projects": projects,
+    }))
+}
@@ -0,0 +1,772 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{ArgAction, ArgGroup, Parser, Subcommand};
+use clap_complete::Shell;
+
+use crate::style::ColorChoice;
+use work_core::filter::{Filter, Pattern};
+use work_core::formatter::Formatter;
+use work_core::sessions::SessionId;
+
+// The types of the values of the arguments are shared with the core, see `work_core::arguments`.
+pub use work_core::arguments::*;
+
+const TIME_FORMATS: &[&str] = &[
+    "m",
+    "minutes",
+    "ma",
+    "minutes-approx",
+    "h",
+    "hours",
+    "hr",
+    "human-readable",
+];
+
+// Accepts only the given values, listing them in the help and the completions, and parses the
+// accepted value into the type of the argument.
+fn one_of<T>(values: &'static [&'static str]) -> impl TypedValueParser<Value = T>
+where
+    T: FromStr + Clone + Send + Sync + 'static,
+    T::Err: Display,
+{
+    PossibleValuesParser::new(values.iter().copied())
+        .try_map(|value| value.parse::<T>().map_err(|e| e.to_string()))
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "Work - Terminal Time Tracker!", disable_help_subcommand = true)]
+pub struct Args {
+    #[command(subcommand)]
+    pub subcommand: SubCommand,
+    /// How to resolve ambiguous times such as `31 20:59`, `ask` confirms guesses interactively
+    #[arg(long, global = true, value_parser = one_of::<Assume>(&["backward", "forward", "ask"]))]
+    pub assume: Option<Assume>,
+    /// When to color the output, `auto` colors it for terminals unless `NO_COLOR` is set
+    #[arg(long, global = true, value_parser = one_of::<ColorChoice>(&["auto", "always", "never"]), default_value = "auto")]
+    pub color: ColorChoice,
+    /// Print errors to stderr as JSON objects holding their code, kind and message
+    #[arg(long, global = true)]
+    pub json_errors: bool,
+    /// Print what would be written to the log without touching it
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    /// Refuse to change the log, as if it couldn't be written to
+    #[arg(long, global = true)]
+    pub read_only: bool,
+    /// Don't confirm what start, stop and since did, nor print the summary of `of --min/--max`
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+    /// Log what Work is doing to stderr, repeat for even more detail
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+    /// Directory to keep the log and config file in, overriding `WORK_HOME` and `XDG_DATA_HOME`
+    #[arg(long, global = true, value_name = "dir")]
+    pub data_dir: Option<PathBuf>,
+    /// Profile to track the work in, overriding `WORK_PROFILE` and the one chosen with `profile
+    /// switch`
+    #[arg(long, global = true, value_name = "name")]
+    pub profile: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SubCommand {
+    /// Appends a new start event to the log
+    #[command(alias = "on")]
+    Start {
+        /// Name of the project
+        project: Option<String>,
+        /// Description of the given project
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Client the work is done for
+        #[arg(long)]
+        client: Option<String>,
+        /// Mark the work as billable, overriding the default set in the config file
+        #[arg(long, conflicts_with = "non_billable")]
+        billable: bool,
+        /// Mark the work as non-billable, overriding the default set in the config file
+        #[arg(long)]
+        non_billable: bool,
+        /// Time the work started, now if not given
+        #[arg(long)]
+        at: Option<String>,
+        /// Stop the work in progress at the given time before starting
+        #[arg(long, conflicts_with = "discard_previous")]
+        close_previous_at: Option<String>,
+        /// Remove the work in progress from the log before starting
+        #[arg(long)]
+        discard_previous: bool,
+        /// Start the work even if the given time is in the future
+        #[arg(long)]
+        allow_future: bool,
+        /// Start the work even if the given time is within a locked period
+        #[arg(short, long)]
+        force: bool,
+        /// Don't warn if the project looks like a misspelling of an existing project
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Appends a new stop event to the log
+    Stop {
+        /// Time the work stopped, now if not given
+        time: Option<String>,
+        /// Description of the work, replacing the one it was started with
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Stop the work at the given time or describe it even if it is within a locked period
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Prints the status of the last event in the log in human readable form
+    Status,
+    /// Exits with an error code of 0 if no work is in progress, and 1 otherwise
+    Free,
+    /// Exits with an error code of 0 if work is in progress, and 1 otherwise
+    Working,
+    /// Outputs a summary of work done within a given interval
+    #[command(allow_hyphen_values = true, group = ArgGroup::new("format").args(["csv", "json"]))]
+    Of {
+        /// The interval to compare start and stop times of work with
+        interval: String,
+        /// Set output format to CSV
+        #[arg(short, long)]
+        csv: bool,
+        /// Separate the CSV fields with the given character instead of a comma, e.g. ';' or tab
+        #[arg(long, requires = "csv")]
+        delimiter: Option<Delimiter>,
+        /// Leave out the line naming the columns of the CSV output
+        #[arg(long, requires = "csv")]
+        no_header: bool,
+        /// Set output format to JSON
+        #[arg(short, long)]
+        json: bool,
+        /// Give the time spent as a plain number of seconds in the JSON output
+        #[arg(long, requires = "json")]
+        raw_seconds: bool,
+        /// Pipe the JSON output to an external formatter, ext:NAME runs work-format-NAME
+        #[arg(long, conflicts_with_all = ["csv", "json", "sessions", "chart"])]
+        output: Option<Formatter>,
+        /// List the sessions within the interval along with their ids
+        #[arg(short, long, conflicts_with_all = ["csv", "json"])]
+        sessions: bool,
+        /// Draw a bar per project, scaled to the width of the terminal
+        #[arg(long, conflicts_with_all = ["csv", "json"])]
+        chart: bool,
+        /// Sum up the time spent for each client instead of on each project
+        #[arg(long)]
+        by_client: bool,
+        /// Sum up the time spent by each user instead of on each project, see record_user
+        #[arg(long, conflicts_with = "by_client")]
+        by_user: bool,
+        /// Compare the time spent on each project with the time spent within another interval
+        #[arg(long, conflicts_with_all = ["sessions", "chart"])]
+        compare: Option<String>,
+        /// Group the time spent by day, week, month, project, client, user or tag instead, or by
+        /// several in turn such as week,project
+        #[arg(long, conflicts_with_all = ["chart", "compare", "by_client", "by_user"])]
+        group_by: Option<GroupBy>,
+        /// Only count the sessions that pass the given filter, e.g. 'project=api and duration>30m'
+        #[arg(long)]
+        filter: Option<Filter>,
+        /// Only count the sessions whose project matches the given regular expression
+        #[arg(long)]
+        project_regex: Option<Pattern>,
+        /// Only count the sessions whose description matches the given regular expression
+        #[arg(long)]
+        description_regex: Option<Pattern>,
+        /// Add up the work done in every profile instead of in the profile in use
+        #[arg(long, conflicts_with = "stdin")]
+        all_profiles: bool,
+        /// Read the log from stdin instead of the log file
+        #[arg(long)]
+        stdin: bool,
+        /// Exit with an error code of 1 unless at least this much work was done in total, e.g. 6h
+        #[arg(long, conflicts_with = "compare")]
+        min: Option<Threshold>,
+        /// Exit with an error code of 1 if more than this much work was done in total, e.g. 9h30m
+        #[arg(long, conflicts_with = "compare")]
+        max: Option<Threshold>,
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+    /// Outputs a summary of work done today, same as `of today`
+    Today {
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+    /// Outputs a summary of work done this week, same as `of this-week`
+    Week {
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+    /// Draws the work done within an interval as a punch card of the days of the week and the
+    /// hours of the day
+    #[command(allow_hyphen_values = true)]
+    Grid {
+        /// The interval to draw
+        #[arg(default_value = "this-week")]
+        interval: String,
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+    /// Draws the work done on each day of an interval as a timeline of the hours of the day
+    #[command(allow_hyphen_values = true)]
+    Timeline {
+        /// The day or interval to draw
+        #[arg(default_value = "today")]
+        interval: String,
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+    /// Outputs a summary of past work meant to be pasted elsewhere, such as into a chat
+    Summary {
+        /// List the projects and descriptions of the last work day as bullets for a daily standup,
+        /// looking back past weekends and days off
+        #[arg(long, required = true)]
+        daily_standup: bool,
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+    /// Outputs the time spent on each project per day, such as for a weekly timesheet
+    #[command(allow_hyphen_values = true)]
+    Report {
+        /// The interval to report on
+        #[arg(default_value = "this-week")]
+        interval: String,
+        /// Format the report as an email with both a plain text and an HTML version
+        #[arg(short, long)]
+        email: bool,
+        /// Send the report by email to the recipients set in the config file, requires the email
+        /// feature
+        #[arg(long)]
+        send: bool,
+        /// Render the report with the Tera template in the given file instead, requires the
+        /// templates feature
+        #[arg(long, conflicts_with_all = ["email", "send"])]
+        template: Option<String>,
+        /// Sum up the time spent by each user instead of on each project, see record_user
+        #[arg(long)]
+        by_user: bool,
+        /// Group the time spent by day, week, month, project, client, user or tag instead, or by
+        /// several in turn such as week,project
+        #[arg(long, conflicts_with_all = ["email", "send", "template", "by_user"])]
+        group_by: Option<GroupBy>,
+        /// Draw a bar per day and per project, scaled to the width of the terminal
+        #[arg(long, conflicts_with_all = ["email", "send", "template", "group_by"])]
+        chart: bool,
+        /// Round the time of each session up to a multiple of this before adding it up, such as
+        /// for clients that bill in increments of 15m
+        #[arg(long, conflicts_with = "group_by")]
+        round_per_session: Option<Threshold>,
+        /// Count each session as at least this much time before adding it up, e.g. 30m
+        #[arg(long, conflicts_with = "group_by")]
+        minimum: Option<Threshold>,
+        /// Only count the sessions that pass the given filter, e.g. 'project=api and duration>30m'
+        #[arg(long)]
+        filter: Option<Filter>,
+        /// Add up the work done in every profile instead of in the profile in use
+        #[arg(long, conflicts_with = "stdin")]
+        all_profiles: bool,
+        /// Read the log from stdin instead of the log file
+        #[arg(long)]
+        stdin: bool,
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+    /// Exports the work done within an interval as a standalone document
+    #[command(allow_hyphen_values = true)]
+    Export {
+        /// The interval to export
+        #[arg(default_value = "this-week")]
+        interval: String,
+        /// The format of the document, work-csv lists the sessions in the format import reads
+        #[arg(short, long, value_parser = one_of::<ExportFormat>(&["html", "work-csv"]), default_value = "html")]
+        format: ExportFormat,
+        /// Only export the sessions whose project matches the given regular expression
+        #[arg(long)]
+        project_regex: Option<Pattern>,
+        /// Only export the sessions whose description matches the given regular expression
+        #[arg(long)]
+        description_regex: Option<Pattern>,
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+    /// Imports sessions into the log, such as corrections made in a spreadsheet to the sessions
+    /// exported with `export --format work-csv`
+    Import {
+        /// The file to import, stdin is read if none is given
+        path: Option<PathBuf>,
+        /// The format of the file
+        #[arg(short, long, value_parser = one_of::<ImportFormat>(&["work-csv"]), default_value = "work-csv")]
+        format: ImportFormat,
+        /// The character that separates the fields of the CSV instead of a comma, e.g. ';' or tab
+        #[arg(long)]
+        delimiter: Option<Delimiter>,
+        /// Import the sessions even if they overlap other work or are within a locked period
+        #[arg(long)]
+        force: bool,
+        /// Import the sessions even if they end in the future
+        #[arg(long)]
+        allow_future: bool,
+    },
+    /// Outputs the time worked in each of the last few weeks along with its moving average
+    Stats {
+        /// Only count the time spent on the given project
+        project: Option<String>,
+        /// The number of complete weeks to cover, e.g. weeks=8
+        #[arg(long, default_value = "weeks=8")]
+        trend: TrendLength,
+        /// Count the minutes worked by hour of the day or by day of the week instead
+        #[arg(long, value_parser = one_of::<HistogramKind>(&["hourly", "weekday"]))]
+        histogram: Option<HistogramKind>,
+        /// The interval the histogram covers
+        #[arg(short, long, default_value = "all")]
+        interval: String,
+        /// Set output format of the histogram to JSON
+        #[arg(short, long)]
+        json: bool,
+        /// Only count the sessions whose project matches the given regular expression
+        #[arg(long)]
+        project_regex: Option<Pattern>,
+        /// Only count the sessions whose description matches the given regular expression
+        #[arg(long)]
+        description_regex: Option<Pattern>,
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+    /// Outputs the time worked in each week over or under the weekly hours set in the config file
+    Overtime {
+        /// The interval to add up the overtime of
+        #[arg(default_value = "all")]
+        interval: String,
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+    /// Appends a new event to the log that started at a given time
+    Since {
+        /// Time since work started
+        time: String,
+        /// Name of the project
+        project: Option<String>,
+        /// Description of the given project
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Client the work is done for
+        #[arg(long)]
+        client: Option<String>,
+        /// Mark the work as billable, overriding the default set in the config file
+        #[arg(long, conflicts_with = "non_billable")]
+        billable: bool,
+        /// Mark the work as non-billable, overriding the default set in the config file
+        #[arg(long)]
+        non_billable: bool,
+        /// Don't append a stop event to the log
+        #[arg(short, long)]
+        r#continue: bool,
+        /// Add the session even if it overlaps work recorded in the log or a locked period
+        #[arg(short, long)]
+        force: bool,
+        /// Start the work even if the given time is in the future, along with --continue
+        #[arg(long)]
+        allow_future: bool,
+        /// Insert the session in chronological order if it starts before the last event in the
+        /// log, which rewrites the log
+        #[arg(long)]
+        reorder: bool,
+    },
+    /// Appends an event to the log that stops at a given time
+    #[command(alias = "for")]
+    Until {
+        /// Time until work stops, or how long to work such as 1.5h or "90 minutes"
+        time: String,
+        /// Name of the project
+        project: Option<String>,
+        /// Description of the given project
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Client the work is done for
+        #[arg(long)]
+        client: Option<String>,
+        /// Keep running until the time is up, interrupting stops the work early
+        #[arg(short, long)]
+        wait: bool,
+    },
+    /// Appends a start event, executes a given command, and then appends stop event once the
+    /// command finishes.
+    While {
+        /// The command to execute
+        cmd: String,
+        /// Name of the project
+        project: Option<String>,
+        /// Description of the given project
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Client the work is done for
+        #[arg(long)]
+        client: Option<String>,
+        /// Don't log the work if the command ends within this long, e.g. 1m
+        #[arg(long)]
+        min_duration: Option<Threshold>,
+        /// Stop the work once nothing was typed into the terminal for this long, e.g. 15m, and
+        /// start it again once something is
+        #[arg(long)]
+        idle_timeout: Option<Threshold>,
+    },
+    Between {
+        /// Time interval in which work was done
+        time: String,
+        /// Name of the project
+        project: Option<String>,
+        /// Description of the given project
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Client the work is done for
+        #[arg(long)]
+        client: Option<String>,
+        /// Mark the work as billable, overriding the default set in the config file
+        #[arg(long, conflicts_with = "non_billable")]
+        billable: bool,
+        /// Mark the work as non-billable, overriding the default set in the config file
+        #[arg(long)]
+        non_billable: bool,
+        /// Add the session even if it overlaps work recorded in the log or a locked period
+        #[arg(short, long)]
+        force: bool,
+        /// Add the session even if it ends in the future
+        #[arg(long)]
+        allow_future: bool,
+        /// Insert the session in chronological order if it starts before the last event in the
+        /// log, which rewrites the log
+        #[arg(long)]
+        reorder: bool,
+    },
+    /// Lists the sessions in the log along with their ids
+    #[command(allow_hyphen_values = true)]
+    Log {
+        /// The interval to list sessions of, the whole log is listed if none is given
+        interval: Option<String>,
+        /// Only list the sessions whose project matches the given regular expression
+        #[arg(long)]
+        project_regex: Option<Pattern>,
+        /// Only list the sessions whose description matches the given regular expression
+        #[arg(long)]
+        description_regex: Option<Pattern>,
+    },
+    /// Removes a session from the log
+    Delete {
+        /// Id of the session to remove, as listed by the `log` command
+        #[arg(short, long, required_unless_present = "last", conflicts_with = "last")]
+        session: Option<SessionId>,
+        /// Remove the last session in the log
+        #[arg(short, long)]
+        last: bool,
+        /// Remove the session even if it is within a locked period
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Combines two adjacent sessions of the same project into one
+    Merge {
+        /// Id of the first session
+        first: SessionId,
+        /// Id of the second session
+        second: SessionId,
+        /// Merge the sessions even if they are within a locked period
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Splits a session into two at a given time
+    Split {
+        /// Id of the session to split
+        session: SessionId,
+        /// Time at which the session should be split
+        time: String,
+        /// Split the session even if it is within a locked period
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Appends a single start or stop event to the log, for scripts and importers
+    Append {
+        /// UNIX timestamp or time of the event
+        time: String,
+        /// Type of the event
+        #[arg(value_parser = one_of::<EventKind>(&["start", "stop"]))]
+        kind: EventKind,
+        /// Name of the project, a stop event takes it from the work it stops if not given
+        project: Option<String>,
+        /// Description of the given project
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Client the work is done for
+        #[arg(long)]
+        client: Option<String>,
+        /// Append the event even if it is within a locked period
+        #[arg(short, long)]
+        force: bool,
+        /// Append the event even if it is in the future
+        #[arg(long)]
+        allow_future: bool,
+    },
+    /// Marks a period as finalized, sessions within it can't be changed without --force
+    #[command(allow_hyphen_values = true)]
+    Lock {
+        /// The period to lock, the locked periods are listed if none is given
+        interval: Option<String>,
+    },
+    /// Moves the sessions of a year or an interval out of the log into compressed archives, which
+    /// of and report still read
+    #[command(allow_hyphen_values = true)]
+    Archive {
+        /// The year to archive, e.g. 2022, or an interval such as "- 1-1-2023"
+        interval: String,
+    },
+    /// Copies the log and the config file into a new backup named after the current time
+    Backup {
+        /// Directory to put the backup in, the backups directory next to the log if not given
+        path: Option<PathBuf>,
+        /// Compress the copies with gzip
+        #[arg(short, long)]
+        gzip: bool,
+    },
+    /// Replaces the log and the config file with the ones in a backup, backing up the current ones
+    /// first
+    Restore {
+        /// The directory of the backup, as printed by `backup`
+        path: PathBuf,
+    },
+    /// Registers days off such as vacation or sick days, which reports don't count as missing work
+    Off {
+        /// The day or days off, e.g. tomorrow or "monday - friday", the days off are listed if none
+        /// are given
+        interval: Option<String>,
+        /// Why the days are off, e.g. Vacation
+        reason: Option<String>,
+    },
+    /// Reminds you to start tracking if no work has been started by a given time, meant to be run
+    /// at that time by cron or a systemd timer, see `remind install`
+    Remind {
+        /// Time of day by which work should have been started, e.g. 9:00
+        #[arg(long)]
+        at: Option<TimeOfDay>,
+        /// Only remind on Monday to Friday
+        #[arg(long)]
+        weekdays: bool,
+        #[command(subcommand)]
+        action: Option<RemindAction>,
+    },
+    /// Installs a systemd service that runs `auto` or `remind` in the background with the data
+    /// directory and profile in use, system-wide unless --user is given
+    InstallService {
+        /// The command to run as a service
+        #[arg(value_parser = one_of::<ServiceKind>(&["auto", "remind"]))]
+        service: ServiceKind,
+        /// Install the service for the current user instead of system-wide
+        #[arg(long)]
+        user: bool,
+        /// Time of day of the reminder, e.g. 9:00
+        #[arg(long)]
+        at: Option<TimeOfDay>,
+        /// Only remind on Monday to Friday
+        #[arg(long)]
+        weekdays: bool,
+        /// Seconds between each look at the focused window of auto
+        #[arg(short, long, default_value = "60")]
+        interval: u64,
+    },
+    /// Removes a service installed by `install-service`
+    UninstallService {
+        /// The command run as a service
+        #[arg(value_parser = one_of::<ServiceKind>(&["auto", "remind"]))]
+        service: ServiceKind,
+        /// Remove the service of the current user instead of the system-wide one
+        #[arg(long)]
+        user: bool,
+    },
+    /// Manages the cache of daily totals that speeds up reports
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Manages the profiles that keep separate logs, such as for work and personal projects
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+    /// Reports on the logs of a whole team, such as a shared directory of synced logs
+    Team {
+        #[command(subcommand)]
+        action: TeamAction,
+    },
+    /// Checks the log for projects that only differ in case or surrounding whitespace, forgotten
+    /// work and sessions that stop before they start
+    Doctor {
+        /// Lowercase and trim every project name in the log, unifying such projects
+        #[arg(long)]
+        fix_case: bool,
+        /// Stop work started on an earlier day at the auto_stop_at time set in the config file
+        #[arg(long, conflicts_with = "fix_case")]
+        auto_stop: bool,
+        /// Remove sessions that stop before or at the moment they start
+        #[arg(long, conflicts_with_all = ["fix_case", "auto_stop"])]
+        remove_empty: bool,
+        /// Make the changes even if they are within a locked period
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Lists every project in the log
+    Projects,
+    /// Lists the exit codes and error codes of Work and what they mean
+    Exitcodes,
+    /// Prints the help of a command, or explains a topic such as the time inputs Work understands
+    Help {
+        /// The command to print the help of, or `times` to explain the time inputs with examples
+        topic: Option<String>,
+    },
+    /// Outputs the status of the log in the format a status bar expects
+    Statusbar {
+        /// The status bar to output for
+        #[arg(short, long, value_parser = one_of::<BarStyle>(&["waybar", "polybar", "i3blocks", "tmux"]), default_value = "waybar")]
+        style: BarStyle,
+        /// Keep running and output the status again every given number of seconds
+        #[arg(short, long)]
+        interval: Option<u64>,
+    },
+    /// Outputs the status of the log as a colored segment for the tmux status line, same as
+    /// `statusbar --style tmux`
+    TmuxStatus,
+    /// Prints a compact indicator of the work in progress for a shell prompt, or nothing when free
+    Prompt,
+    /// Prints the completion script of a shell, e.g. `work completions bash > ~/.bash_completion`
+    Completions {
+        /// The shell to complete in
+        shell: Shell,
+    },
+    /// Answers requests from editors and other programs as a long-lived subprocess
+    Serve {
+        /// Answer newline-delimited JSON-RPC requests on stdin with responses on stdout
+        #[arg(long)]
+        stdio: bool,
+    },
+    /// Tracks work automatically from the focused window or the running processes, queueing the
+    /// sessions for review
+    #[cfg(feature = "auto")]
+    Auto {
+        #[command(subcommand)]
+        action: AutoAction,
+    },
+    /// Updates the Slack status to match the status of the log
+    #[cfg(feature = "presence")]
+    Presence,
+    /// Serves an HTTP API for controlling Work from other programs
+    #[cfg(feature = "server")]
+    Server {
+        /// Address to listen on, only local programs can connect by default
+        #[arg(short, long, default_value = "127.0.0.1:7878")]
+        address: String,
+    },
+    /// Any other command NAME runs the executable work-NAME on the PATH with the remaining
+    /// arguments, such as scripts that extend Work
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// Rebuilds the cache from the log
+    Rebuild,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RemindAction {
+    /// Prints a crontab line that runs the reminder at its time
+    Install {
+        /// Time of day by which work should have been started, e.g. 9:00
+        #[arg(long)]
+        at: Option<TimeOfDay>,
+        /// Only remind on Monday to Friday
+        #[arg(long)]
+        weekdays: bool,
+        /// Print a systemd service and timer instead of a crontab line, see `install-service`
+        #[arg(long)]
+        systemd: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileAction {
+    /// Lists the profiles, marking the one in use
+    List,
+    /// Creates a new profile with a log of its own
+    Create {
+        /// Name of the profile, e.g. personal
+        name: String,
+    },
+    /// Makes a profile the one in use when no --profile is given
+    Switch {
+        /// Name of the profile
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TeamAction {
+    /// Lists the time each member spent on each project within an interval
+    Report {
+        /// The interval to report on
+        #[arg(default_value = "this-week")]
+        interval: String,
+        /// Directory holding a log per member, such as alice.log, or a directory per member
+        /// holding a work.log
+        #[arg(long, value_name = "dir")]
+        logs: PathBuf,
+        /// Set output format to JSON
+        #[arg(long)]
+        json: bool,
+        /// Give the time spent as a plain number of seconds in the JSON output
+        #[arg(long, requires = "json")]
+        raw_seconds: bool,
+        /// Specify the time format of the output
+        #[arg(short, long, value_parser = one_of::<TimeFormat>(TIME_FORMATS), default_value = "human-readable")]
+        time_format: TimeFormat,
+    },
+}
+
+#[cfg(feature = "auto")]
+#[derive(Subcommand, Debug)]
+pub enum AutoAction {
+    /// Watches the focused window and the running processes, queueing a session whenever the rules
+    /// in the [auto] section of the config point to another project, until interrupted
+    Run {
+        /// Number of seconds between looking at what is being worked on
+        #[arg(short, long, default_value = "60")]
+        interval: u64,
+    },
+    /// Lists the queued sessions along with their ids
+    Review,
+    /// Adds queued sessions to the log, every finished one if no ids are given
+    Accept {
+        /// Ids of the sessions to add, as listed by review
+        ids: Vec<SessionId>,
+        /// Add the sessions even if they overlap other work or are within a locked period
+        #[arg(long)]
+        force: bool,
+    },
+    /// Removes queued sessions without adding them to the log, every finished one if no ids are
+    /// given
+    Discard {
+        /// Ids of the sessions to remove, as listed by review
+        ids: Vec<SessionId>,
+    },
+}
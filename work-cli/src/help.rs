@@ -0,0 +1,184 @@
+use work_core::time::{self, Interval, Search, SystemClock};
+
+/// Format of the times the examples resolve to, the same as `time::format_date_time`.
+const DATE_TIME_FORMAT: &str = "%a %d-%m-%Y %H:%M";
+
+/// The topics `work help` knows about besides the commands of Work.
+pub const TOPICS: [&str; 1] = ["times"];
+
+/// A titled section of examples of time input along with what they mean, which are either single
+/// points in time or intervals.
+type Section = (&'static str, bool, &'static [(&'static str, &'static str)]);
+
+/// Examples of each form of time input, grouped into sections.
+const SECTIONS: [Section; 4] = [
+    (
+        "Times of day",
+        false,
+        &[
+            ("9", "9 o'clock"),
+            ("21:30", "half past nine in the evening"),
+            ("9am", "9 o'clock on the 12-hour clock"),
+            ("9:30pm", "half past nine on the 12-hour clock"),
+            ("noon", "12 o'clock"),
+            ("midnight", "0 o'clock"),
+        ],
+    ),
+    (
+        "Days",
+        false,
+        &[
+            ("31 20:59", "the 31st of a month"),
+            ("31-12 9:30", "the 31st of December"),
+            ("31-12-2026 9:30", "the 31st of December 2026"),
+            ("today", "the start of today"),
+            ("yesterday", "the start of yesterday"),
+            ("tomorrow 14:30", "a time of day tomorrow"),
+            ("friday 9am", "a time of day on the closest friday"),
+            ("last friday", "the start of the friday before today"),
+            ("next monday 9", "a time of day on the monday after today"),
+            ("this-week", "the start of monday this week"),
+        ],
+    ),
+    (
+        "Relative to now",
+        false,
+        &[
+            ("now", "this very minute"),
+            ("2h", "2 hours"),
+            ("90m", "90 minutes"),
+            ("1:30h", "1 hour and 30 minutes"),
+            ("3d", "3 days"),
+            ("2w", "2 weeks"),
+            ("2 days ago", "2 days before now, whichever the direction"),
+            ("in 3 hours", "3 hours after now, whichever the direction"),
+        ],
+    ),
+    (
+        "Intervals",
+        true,
+        &[
+            ("9 - 12", "from one time until another"),
+            ("9 -", "from a time until now"),
+            ("- 12", "from the start of the log until a time"),
+            ("all", "the whole log"),
+        ],
+    ),
+];
+
+/// Returns an explanation of every form of time input Work understands, with each example
+/// resolved against the current time, both looking back as `start`, `stop` and `since` do and
+/// looking ahead as `until` and `between` do.
+pub fn times() -> String {
+    let mut text = format!(
+        "Times such as 9 or 31 20:59 leave out the day, month or year. Commands that look back,\n\
+         such as start, stop, since and of, resolve them to the latest such time that has passed,\n\
+         while until and between look ahead to the earliest such time to come. Pass --assume to\n\
+         look the other way, or --dry-run to see how a time is understood.\n\n\
+         Right now it is {}, so:\n",
+        time::format_date_time(time::now())
+    );
+
+    let rows: Vec<Vec<[String; 4]>> = SECTIONS
+        .iter()
+        .map(|(_, interval, examples)| {
+            examples
+                .iter()
+                .map(|(example, meaning)| {
+                    [
+                        example.to_string(),
+                        meaning.to_string(),
+                        resolve(example, *interval, &Search::Backward),
+                        resolve(example, *interval, &Search::Forward),
+                    ]
+                })
+                .collect()
+        })
+        .collect();
+    let header = [
+        "Input".to_string(),
+        "Meaning".to_string(),
+        "Looking back".to_string(),
+        "Looking ahead".to_string(),
+    ];
+    let widths: Vec<usize> = (0..3)
+        .map(|column| {
+            rows.iter()
+                .flatten()
+                .chain(std::iter::once(&header))
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |row: &[String; 4]| {
+        format!(
+            "  {:<w0$}  {:<w1$}  {:<w2$}  {}\n",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2]
+        )
+    };
+
+    for ((title, _, _), rows) in SECTIONS.iter().zip(&rows) {
+        text.push_str(&format!("\n{}\n", title));
+        text.push_str(&line(&header));
+        for row in rows {
+            text.push_str(&line(row));
+        }
+    }
+    text.push_str(
+        "\nRelative times such as 2h go back from now when looking back and ahead when looking\n\
+         ahead. Weekdays may be abbreviated, as in fri, and words are understood in any case.\n\
+         until and its alias for take durations such as 1.5h, 90 minutes or 1 hour 30 min as well.",
+    );
+    text
+}
+
+// Helper function that resolves an example of a time input the way a command searching in the
+// given direction would, as either a single point in time or an interval.
+fn resolve(example: &str, is_interval: bool, search_type: &Search) -> String {
+    if !is_interval {
+        return time::parse_datetime(example, *search_type, &SystemClock)
+            .map_or("-".to_string(), |date_time| {
+                date_time.format(DATE_TIME_FORMAT).to_string()
+            });
+    }
+    match Interval::try_parse(example, search_type, &SystemClock) {
+        Ok(interval) => {
+            let start = match interval.start {
+                time::BEGINNING_OF_TIME => "the start".to_string(),
+                start => time::format_date_time(start),
+            };
+            format!("{} - {}", start, time::format_date_time(interval.end))
+        }
+        Err(_) => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_times() {
+        for (_, interval, examples) in SECTIONS.iter() {
+            for (example, _) in examples.iter() {
+                assert!(Interval::try_parse(example, &Search::Backward, &SystemClock).is_ok());
+                assert!(Interval::try_parse(example, &Search::Forward, &SystemClock).is_ok());
+                assert!(
+                    *interval
+                        || time::parse_datetime(example, Search::Forward, &SystemClock).is_ok()
+                );
+            }
+        }
+        let text = times();
+        assert!(text.contains("\nIntervals\n  Input  "));
+        assert!(text.contains("  all  "));
+        assert!(!text.contains("  -\n"));
+    }
+}
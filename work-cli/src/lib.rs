@@ -0,0 +1,8 @@
+pub mod api;
+pub mod arguments;
+pub mod help;
+pub mod rpc;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod style;
+pub mod subcommands;
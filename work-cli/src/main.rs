@@ -0,0 +1,593 @@
+use std::env;
+use std::rc::Rc;
+
+use clap::Parser;
+use log::{debug, LevelFilter};
+
+use work_cli::arguments::*;
+use work_cli::style;
+use work_cli::subcommands::*;
+use work_core::config::Config;
+use work_core::csv_output::CsvOptions;
+use work_core::error::{AppError, ExitCode};
+use work_core::filter::{Condition, Field, Filter, Pattern};
+use work_core::hooks::Hooks;
+use work_core::log_file::*;
+use work_core::profiles;
+use work_core::rounding::Rounding;
+use work_core::time;
+
+fn main() {
+    let args = Args::parse();
+    style::set_color_choice(args.color);
+    init_logging(args.verbose);
+    let json_errors = args.json_errors;
+    // Hooks and external commands inherit the data directory and the profile through the
+    // environment.
+    if let Some(ref dir) = args.data_dir {
+        env::set_var("WORK_HOME", dir);
+    }
+    if let Some(ref profile) = args.profile {
+        env::set_var("WORK_PROFILE", profile);
+    }
+    let result = match args.subcommand {
+        // External commands exit with whatever code they exit with themselves.
+        SubCommand::External(ref command) => external(command),
+        // Help is available even when the config file or the log can't be read.
+        SubCommand::Help { ref topic } => help(topic.as_deref()).map(ExitCode::code),
+        // Completions are generated from the arguments alone.
+        SubCommand::Completions { shell } => completions(shell).map(ExitCode::code),
+        // Profiles can be managed even when the log of the profile in use can't be opened.
+        SubCommand::Profile { ref action } => profile(action).map(ExitCode::code),
+        // Services only need to know where the log is, so they can be installed before it exists.
+        SubCommand::InstallService {
+            service,
+            user,
+            at,
+            weekdays,
+            interval,
+        } => install_service(service, user, at, weekdays, interval).map(ExitCode::code),
+        SubCommand::UninstallService { service, user } => {
+            uninstall_service(service, user).map(ExitCode::code)
+        }
+        // The logs of a team are read on their own, without opening the log of the profile in use.
+        SubCommand::Team { ref action } => team(action, args.assume).map(ExitCode::code),
+        _ => run_app(args).map(ExitCode::code),
+    };
+    std::process::exit(match result {
+        // If we get back an Ok it can be an exit code of either `Success` or `Negative`.
+        // This is because of the  `of`, `working`, and `free` commands.
+        Ok(code) => code,
+        Err(err) => {
+            if json_errors {
+                eprintln!("{}", err.to_json());
+            } else {
+                eprintln!("{}", style::error(&err.to_string()));
+            }
+            ExitCode::from(err.kind()).code()
+        }
+    });
+}
+
+fn run_app(args: Args) -> Result<ExitCode, AppError> {
+    let config = Config::load()?;
+    let mut log = LogFile::new(&config)?;
+    let dry_run = args.dry_run.then(|| Rc::new(print_change) as DryRun);
+    log.set_dry_run(dry_run.clone());
+    log.set_read_only(args.read_only);
+    // Nothing happens during a dry run, so neither should anything that reacts to it.
+    let hooks = if args.dry_run {
+        Hooks::default()
+    } else {
+        Hooks::from_config(&config)
+    };
+    let assume = args.assume;
+    // A dry run already prints what it would have done.
+    let quiet = args.quiet || args.dry_run;
+    let split_at_midnight = config.get("split_at_midnight") == Some("true");
+
+    match args.subcommand {
+        SubCommand::Start {
+            project,
+            description,
+            client,
+            billable,
+            non_billable,
+            at,
+            close_previous_at,
+            discard_previous,
+            allow_future,
+            force,
+            yes,
+        } => {
+            if !yes {
+                warn_if_misspelled(&mut log, project.as_deref())?;
+            }
+            let project = project.or_else(|| project_of_current_dir(&config));
+            let billable = billable_of(&config, &project, billable, non_billable)?;
+            start(
+                &mut log,
+                &hooks,
+                project,
+                description,
+                client,
+                billable,
+                at.as_deref(),
+                close_previous_at.as_deref(),
+                discard_previous,
+                allow_future,
+                force,
+                quiet,
+                assume,
+            )
+        }
+        SubCommand::Stop {
+            time,
+            description,
+            force,
+        } => stop(
+            &mut log,
+            &hooks,
+            time.as_deref(),
+            description.as_deref(),
+            force,
+            quiet,
+            assume,
+        ),
+        SubCommand::Status => status(
+            &mut log,
+            config.duration("warn_after")?,
+            config.duration("daily_max")?,
+        ),
+        SubCommand::Free => working_or_free(&mut log, false),
+        SubCommand::Working => working_or_free(&mut log, true),
+        SubCommand::Of {
+            interval,
+            csv,
+            delimiter,
+            no_header,
+            json,
+            raw_seconds,
+            output,
+            sessions,
+            chart,
+            by_client,
+            by_user,
+            compare,
+            group_by,
+            filter,
+            project_regex,
+            description_regex,
+            all_profiles,
+            stdin,
+            min,
+            max,
+            time_format,
+        } => {
+            if stdin {
+                log = LogFile::from_stdin(&config)?;
+                log.set_dry_run(dry_run.clone());
+            }
+            let mut logs = logs_of(log, all_profiles)?;
+            filter_logs(
+                &mut logs,
+                combined_filter(filter, project_regex, description_regex),
+            );
+            let checked = match (min, max) {
+                (None, None) => None,
+                _ => Some(check_total(
+                    &mut logs,
+                    &interval,
+                    min.map(|min| min.0),
+                    max.map(|max| max.0),
+                    assume,
+                )?),
+            };
+            if let Some(code) = checked.filter(|_| quiet) {
+                return Ok(code);
+            }
+            let code = of(
+                &mut logs,
+                &interval,
+                csv.then(|| CsvOptions {
+                    delimiter: delimiter.map_or(b',', |delimiter| delimiter.0),
+                    header: !no_header,
+                }),
+                json.then_some(raw_seconds),
+                output.as_ref(),
+                sessions,
+                split_at_midnight,
+                chart,
+                by_client,
+                by_user,
+                compare.as_deref(),
+                group_by.as_ref(),
+                time_format,
+                assume,
+            )?;
+            Ok(checked.unwrap_or(code))
+        }
+        SubCommand::Today { time_format } => summary(&mut log, "today", time_format),
+        SubCommand::Week { time_format } => summary(&mut log, "this-week", time_format),
+        SubCommand::Grid {
+            interval,
+            time_format,
+        } => grid(&mut log, &interval, time_format, assume),
+        SubCommand::Timeline {
+            interval,
+            time_format,
+        } => timeline(&mut log, &interval, time_format, assume),
+        SubCommand::Summary { time_format, .. } => standup(&mut log, time_format),
+        SubCommand::Report {
+            interval,
+            email,
+            send,
+            template,
+            by_user,
+            group_by,
+            chart,
+            round_per_session,
+            minimum,
+            filter,
+            all_profiles,
+            stdin,
+            time_format,
+        } => {
+            if stdin {
+                log = LogFile::from_stdin(&config)?;
+                log.set_dry_run(dry_run.clone());
+            }
+            let mut logs = logs_of(log, all_profiles)?;
+            filter_logs(&mut logs, filter);
+            report(
+                &mut logs,
+                &config,
+                &interval,
+                email,
+                send,
+                template.as_deref(),
+                by_user,
+                group_by.as_ref(),
+                chart,
+                Rounding::new(round_per_session, minimum).as_ref(),
+                time_format,
+                assume,
+            )
+        }
+        SubCommand::Export {
+            interval,
+            format,
+            project_regex,
+            description_regex,
+            time_format,
+        } => {
+            log.set_filter(combined_filter(None, project_regex, description_regex));
+            export(&mut log, &interval, format, time_format, assume)
+        }
+        SubCommand::Import {
+            path,
+            format,
+            delimiter,
+            force,
+            allow_future,
+        } => import(
+            &mut log,
+            path.as_deref(),
+            format,
+            delimiter.map_or(b',', |delimiter| delimiter.0),
+            force,
+            allow_future,
+        ),
+        SubCommand::Stats {
+            project,
+            trend,
+            histogram,
+            interval,
+            json,
+            project_regex,
+            description_regex,
+            time_format,
+        } => {
+            log.set_filter(combined_filter(None, project_regex, description_regex));
+            stats(
+                &mut log,
+                project.as_deref(),
+                trend,
+                histogram,
+                &interval,
+                json,
+                time_format,
+                assume,
+            )
+        }
+        SubCommand::Overtime {
+            interval,
+            time_format,
+        } => overtime(&mut log, &config, &interval, time_format, assume),
+        SubCommand::Since {
+            time,
+            project,
+            description,
+            client,
+            billable,
+            non_billable,
+            r#continue,
+            force,
+            allow_future,
+            reorder,
+        } => {
+            let project = project.or_else(|| project_of_current_dir(&config));
+            let billable = billable_of(&config, &project, billable, non_billable)?;
+            since(
+                &mut log,
+                &hooks,
+                &time,
+                project,
+                description,
+                client,
+                billable,
+                r#continue,
+                force,
+                allow_future,
+                reorder,
+                quiet,
+                assume,
+            )
+        }
+        SubCommand::Until {
+            time,
+            project,
+            description,
+            client,
+            wait,
+        } => {
+            let billable = billable_of(&config, &project, false, false)?;
+            until(
+                &mut log,
+                &hooks,
+                &time,
+                project,
+                description,
+                client,
+                billable,
+                wait,
+                assume,
+            )
+        }
+        SubCommand::Between {
+            time,
+            project,
+            description,
+            client,
+            billable,
+            non_billable,
+            force,
+            allow_future,
+            reorder,
+        } => {
+            let billable = billable_of(&config, &project, billable, non_billable)?;
+            between(
+                &mut log,
+                &time,
+                project,
+                description,
+                client,
+                billable,
+                force,
+                allow_future,
+                reorder,
+                assume,
+            )
+        }
+        SubCommand::While {
+            cmd,
+            project,
+            description,
+            client,
+            min_duration,
+            idle_timeout,
+        } => {
+            let project = project.or_else(|| project_of_current_dir(&config));
+            let billable = billable_of(&config, &project, false, false)?;
+            r#while(
+                &mut log,
+                &hooks,
+                &cmd,
+                project,
+                description,
+                client,
+                billable,
+                min_duration.map(|duration| duration.0),
+                idle_timeout.map(|duration| duration.0),
+            )
+        }
+        SubCommand::Log {
+            interval,
+            project_regex,
+            description_regex,
+        } => {
+            log.set_filter(combined_filter(None, project_regex, description_regex));
+            log_sessions(&mut log, interval.as_deref(), split_at_midnight, assume)
+        }
+        SubCommand::Delete {
+            session,
+            last,
+            force,
+        } => delete(&mut log, session, last, args.dry_run, force),
+        SubCommand::Merge {
+            first,
+            second,
+            force,
+        } => merge(&mut log, first, second, force),
+        SubCommand::Split {
+            session,
+            time,
+            force,
+        } => split(&mut log, session, &time, force, assume),
+        SubCommand::Append {
+            time,
+            kind,
+            project,
+            description,
+            client,
+            force,
+            allow_future,
+        } => {
+            let billable = match kind {
+                EventKind::Start => config.billable(project.as_deref())?,
+                EventKind::Stop => None,
+            };
+            append(
+                &mut log,
+                &time,
+                kind,
+                project,
+                description,
+                client,
+                billable,
+                force,
+                allow_future,
+                assume,
+            )
+        }
+        SubCommand::Lock { interval } => lock(&mut log, interval.as_deref(), assume),
+        SubCommand::Archive { interval } => archive(&mut log, &interval, assume),
+        SubCommand::Backup { path, gzip } => backup(&log, path.as_deref(), gzip),
+        SubCommand::Restore { path } => restore(&mut log, &path),
+        SubCommand::Off { interval, reason } => {
+            off(&mut log, interval.as_deref(), reason.as_deref(), assume)
+        }
+        SubCommand::Remind {
+            at,
+            weekdays,
+            action,
+        } => remind(&mut log, &hooks, at, weekdays, action.as_ref()),
+        SubCommand::Cache { action } => cache(&mut log, action),
+        SubCommand::Doctor {
+            fix_case,
+            auto_stop,
+            remove_empty,
+            force,
+        } => doctor(
+            &mut log,
+            &hooks,
+            config.auto_stop_at()?,
+            fix_case,
+            auto_stop,
+            remove_empty,
+            force,
+        ),
+        SubCommand::Projects => projects(&mut log),
+        SubCommand::Exitcodes => exit_codes(),
+        SubCommand::Statusbar { style, interval } => statusbar(&config, style, interval),
+        SubCommand::TmuxStatus => statusbar(&config, BarStyle::Tmux, None),
+        SubCommand::Prompt => prompt(&mut log, config.duration("warn_after")?),
+        SubCommand::Serve { stdio } => serve(&config, stdio),
+        #[cfg(feature = "auto")]
+        SubCommand::Auto { action } => auto(&mut log, &config, &action),
+        #[cfg(feature = "presence")]
+        SubCommand::Presence => presence(&mut log, &config),
+        #[cfg(feature = "server")]
+        SubCommand::Server { address } => server(&config, &address),
+        SubCommand::Help { .. }
+        | SubCommand::Completions { .. }
+        | SubCommand::Profile { .. }
+        | SubCommand::InstallService { .. }
+        | SubCommand::UninstallService { .. }
+        | SubCommand::Team { .. }
+        | SubCommand::External(_) => {
+            unreachable!("help and external commands are run by main")
+        }
+    }
+}
+
+// Helper function that finds out whether new work is billable, from the `--billable` and
+// `--non-billable` flags or otherwise from the default set in the config file for the project.
+fn billable_of(
+    config: &Config,
+    project: &Option<String>,
+    billable: bool,
+    non_billable: bool,
+) -> Result<Option<bool>, AppError> {
+    match (billable, non_billable) {
+        (true, _) => Ok(Some(true)),
+        (_, true) => Ok(Some(false)),
+        _ => config.billable(project.as_deref()),
+    }
+}
+
+// Helper function that finds the project of the current directory as mapped in the config file, for
+// work started without a project.
+fn project_of_current_dir(config: &Config) -> Option<String> {
+    let dir = env::current_dir().ok()?;
+    let project = config.project_of_dir(&dir);
+    if let Some(ref project) = project {
+        debug!("Found the project {} for {}", project, dir.display());
+    }
+    project
+}
+
+// Helper function that returns the log of every profile, each opened with the config of its own
+// profile, if `all_profiles` is set, or just the given log otherwise.
+fn logs_of(log: LogFile, all_profiles: bool) -> Result<Vec<LogFile>, AppError> {
+    if !all_profiles {
+        return Ok(vec![log]);
+    }
+    profiles::list()?
+        .iter()
+        .map(|profile| LogFile::of_profile(&Config::load_profile(profile)?, profile))
+        .collect()
+}
+
+// Helper function that makes the tallies of the given logs only count the sessions that pass the
+// given filter, if any.
+fn filter_logs(logs: &mut [LogFile], filter: Option<Filter>) {
+    for log in logs.iter_mut() {
+        log.set_filter(filter.clone());
+    }
+}
+
+// Helper function that combines the filter and the regular expressions given as arguments into a
+// single filter, if any were given.
+fn combined_filter(
+    filter: Option<Filter>,
+    project_regex: Option<Pattern>,
+    description_regex: Option<Pattern>,
+) -> Option<Filter> {
+    let regex = |field, pattern: Option<Pattern>| {
+        pattern.map(|pattern| Filter::Condition(Condition::Regex(field, pattern)))
+    };
+    Filter::all(vec![
+        filter,
+        regex(Field::Project, project_regex),
+        regex(Field::Description, description_regex),
+    ])
+}
+
+// Helper function that prints a line of the log that a dry run would have changed, along with its
+// time in readable form.
+fn print_change(change: &Change) {
+    let (timestamp, event) = change.entry();
+    println!(
+        "Would {}: {} ({})",
+        change.action(),
+        event.to_log_line(timestamp),
+        time::format_date_time(timestamp)
+    );
+}
+
+// Helper function that sends the log messages of Work to stderr. `RUST_LOG` decides what gets
+// logged, unless overridden by `-v` for debug messages or `-vv` for trace messages.
+fn init_logging(verbose: u8) {
+    let mut builder = env_logger::Builder::from_default_env();
+    match verbose {
+        0 => {}
+        1 => {
+            builder.filter_module("work", LevelFilter::Debug);
+        }
+        _ => {
+            builder.filter_module("work", LevelFilter::Trace);
+        }
+    }
+    builder.format_timestamp(None).init();
+}
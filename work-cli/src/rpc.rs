@@ -0,0 +1,203 @@
+use std::io::{self, BufRead, Write};
+
+use serde_json::{json, Value};
+
+use crate::api;
+use work_core::config::Config;
+use work_core::error::{AppError, ErrorKind, ExitCode};
+use work_core::hooks::Hooks;
+use work_core::log_file::LogFile;
+
+/// JSON-RPC error code of a request that isn't valid JSON.
+const PARSE_ERROR: i64 = -32700;
+
+/// JSON-RPC error code of a request without a method.
+const INVALID_REQUEST: i64 = -32600;
+
+/// JSON-RPC error code of a request for an unknown method.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// JSON-RPC error code of a request with parameters of the wrong type.
+const INVALID_PARAMS: i64 = -32602;
+
+/// The `Call` enum describes a single request made to Work, see `serve_stdio`.
+#[derive(Debug, PartialEq)]
+enum Call {
+    Status,
+    Start(Option<String>, Option<String>, Option<String>),
+    Stop,
+    Of(String),
+}
+
+/// Answers JSON-RPC 2.0 requests, one per line of the standard input, until the input ends.
+///
+/// Every request is answered with a single line on the standard output. The following methods
+/// are supported:
+///
+/// * `status` returns the status of the log, see `api::status`.
+/// * `start` starts new work, given the optional `project`, `description` and `client`
+///   parameters.
+/// * `stop` stops the work in progress.
+/// * `of` returns the time spent on each project within the `interval` parameter, see
+///   `api::report`. The interval defaults to today.
+///
+/// For example `{"jsonrpc": "2.0", "id": 1, "method": "start", "params": {"project": "work"}}`.
+/// Errors of Work are answered with the exit code the error would have resulted in as their code.
+pub fn serve_stdio(config: &Config) -> Result<(), AppError> {
+    let hooks = Hooks::from_config(config);
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = line.map_err(io_error)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = respond(&line, |call| execute(config, &hooks, call));
+        let mut stdout = stdout.lock();
+        writeln!(stdout, "{}", response)
+            .and_then(|_| stdout.flush())
+            .map_err(io_error)?;
+    }
+    Ok(())
+}
+
+// Answers a single line of input, executing the call it describes with `execute`.
+fn respond<F>(line: &str, execute: F) -> Value
+where
+    F: FnOnce(Call) -> Result<Value, AppError>,
+{
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return error_response(Value::Null, PARSE_ERROR, &e.to_string()),
+    };
+    let id = request["id"].clone();
+    let call = match parse_call(&request) {
+        Ok(call) => call,
+        Err((code, message)) => return error_response(id, code, &message),
+    };
+
+    match execute(call) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => {
+            let mut response =
+                error_response(id, i64::from(ExitCode::from(e.kind()).code()), e.message());
+            response["error"]["data"] = json!({ "code": e.code().name() });
+            response
+        }
+    }
+}
+
+// Finds out which call the given request makes, or returns the JSON-RPC error code and message
+// to answer with if the request is invalid.
+fn parse_call(request: &Value) -> Result<Call, (i64, String)> {
+    let params = &request["params"];
+    let string_param = |name: &str| match &params[name] {
+        Value::Null => Ok(None),
+        Value::String(value) => Ok(Some(value.clone())),
+        _ => Err((INVALID_PARAMS, format!("The {} must be a string.", name))),
+    };
+
+    match request["method"].as_str() {
+        Some("status") => Ok(Call::Status),
+        Some("start") => Ok(Call::Start(
+            string_param("project")?,
+            string_param("description")?,
+            string_param("client")?,
+        )),
+        Some("stop") => Ok(Call::Stop),
+        Some("of") => Ok(Call::Of(
+            string_param("interval")?.unwrap_or_else(|| "today".to_string()),
+        )),
+        Some(method) => Err((METHOD_NOT_FOUND, format!("Unknown method {}.", method))),
+        None => Err((INVALID_REQUEST, "The request has no method.".to_string())),
+    }
+}
+
+// Executes a call against the log. The log is opened anew for every call so changes made from
+// the terminal in the meantime are always taken into account.
+fn execute(config: &Config, hooks: &Hooks, call: Call) -> Result<Value, AppError> {
+    let mut log = LogFile::new(config)?;
+    match call {
+        Call::Status => api::status(&mut log),
+        Call::Start(project, description, client) => {
+            let billable = config.billable(project.as_deref())?;
+            api::start(&mut log, hooks, project, description, client, billable)
+        }
+        Call::Stop => api::stop(&mut log, hooks),
+        Call::Of(interval) => api::report(&mut log, &interval),
+    }
+}
+
+// Helper function for building the answer to a request that failed.
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+// Helper function for the errors of reading requests and writing answers, which unlike most IO
+// errors have nothing to do with the log.
+fn io_error(error: io::Error) -> AppError {
+    AppError::new(ErrorKind::System(format!(
+        "Unable to communicate over stdio: {}",
+        error
+    )))
+    .with_source(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unreachable(_: Call) -> Result<Value, AppError> {
+        panic!("Invalid requests must not be executed")
+    }
+
+    #[test]
+    fn test_parse_call() {
+        let call = |request: &str| parse_call(&serde_json::from_str(request).unwrap());
+        assert_eq!(call(r#"{"method": "status"}"#), Ok(Call::Status));
+        assert_eq!(
+            call(r#"{"method": "start", "params": {"project": "work"}}"#),
+            Ok(Call::Start(Some("work".to_string()), None, None))
+        );
+        assert_eq!(
+            call(r#"{"method": "of"}"#),
+            Ok(Call::Of("today".to_string()))
+        );
+        assert_eq!(
+            call(r#"{"method": "of", "params": {"interval": 3}}"#)
+                .unwrap_err()
+                .0,
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            call(r#"{"method": "dance"}"#).unwrap_err().0,
+            METHOD_NOT_FOUND
+        );
+        assert_eq!(call(r#"{"id": 1}"#).unwrap_err().0, INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_respond() {
+        assert_eq!(respond("{", unreachable)["error"]["code"], PARSE_ERROR);
+
+        let response = respond(r#"{"id": 7, "method": "stop"}"#, |call| {
+            assert_eq!(call, Call::Stop);
+            Err(AppError::new(ErrorKind::User("Unable to stop".to_string())))
+        });
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], 2);
+        assert_eq!(response["error"]["message"], "Unable to stop");
+        assert_eq!(response["error"]["data"]["code"], "invalid-input");
+
+        let response = respond(r#"{"id": "a", "method": "status"}"#, |_| Ok(json!(true)));
+        assert_eq!(
+            response,
+            json!({ "jsonrpc": "2.0", "id": "a", "result": true })
+        );
+    }
+}
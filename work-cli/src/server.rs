@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::api;
+use work_core::config::Config;
+use work_core::error::{AppError, ErrorCode, ErrorKind};
+use work_core::hooks::Hooks;
+use work_core::log_file::LogFile;
+
+/// Paths the server answers to, used to tell unknown paths apart from unsupported methods.
+const PATHS: [&str; 4] = ["/status", "/start", "/stop", "/report"];
+
+/// Serves the HTTP API of Work on the given address until the process is stopped.
+///
+/// The API consists of the following endpoints, all of which answer with JSON:
+///
+/// * `GET /status` returns the status of the log, see `api::status`.
+/// * `POST /start?project=...&description=...&client=...` starts new work.
+/// * `POST /stop` stops the work in progress.
+/// * `GET /report?interval=...` returns the time spent on each project within the interval,
+///   today if no interval is given.
+///
+/// Parameters can also be sent form encoded in the body of a request. Errors are answered with an
+/// object holding an `error` message, with a status of 400 for invalid requests and 500 otherwise.
+///
+/// The log is opened anew for every request so changes made from the terminal in the meantime
+/// are always taken into account.
+pub fn serve(config: &Config, address: &str) -> Result<(), AppError> {
+    let server = Server::http(address).map_err(|e| {
+        AppError::new(ErrorKind::System(format!(
+            "Unable to listen on {}: {}",
+            address, e
+        )))
+        .with_code(ErrorCode::Network)
+    })?;
+    let hooks = Hooks::from_config(config);
+    println!("Listening on http://{}", address);
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = match handle(config, &hooks, &mut request) {
+            Ok(body) => (200, body),
+            Err((status, e)) => (
+                status,
+                json!({ "error": e.message(), "code": e.code().name() }),
+            ),
+        };
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                    .expect("The content type header is valid"),
+            );
+        // A client that hung up doesn't concern the other clients.
+        let _ = request.respond(response);
+    }
+    Ok(())
+}
+
+// Answers a single request. Errors are returned along with the HTTP status code to answer with.
+fn handle(config: &Config, hooks: &Hooks, request: &mut Request) -> Result<Value, (u16, AppError)> {
+    let url = request.url().to_string();
+    let (path, query) = match url.find('?') {
+        Some(i) => (&url[..i], &url[i + 1..]),
+        None => (&url[..], ""),
+    };
+    let mut params = parse_params(query);
+    let mut body = String::new();
+    request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|e| (400, AppError::new(ErrorKind::User(e.to_string()))))?;
+    params.extend(parse_params(&body));
+
+    let mut log = LogFile::new(config).map_err(with_status)?;
+    let result = match (request.method(), path) {
+        (Method::Get, "/status") => api::status(&mut log),
+        (Method::Post, "/start") => {
+            let project = params.remove("project");
+            config.billable(project.as_deref()).and_then(|billable| {
+                api::start(
+                    &mut log,
+                    hooks,
+                    project,
+                    params.remove("description"),
+                    params.remove("client"),
+                    billable,
+                )
+            })
+        }
+        (Method::Post, "/stop") => api::stop(&mut log, hooks),
+        (Method::Get, "/report") => api::report(
+            &mut log,
+            params.get("interval").map_or("today", String::as_str),
+        ),
+        (method, path) if PATHS.contains(&path) => {
+            return Err((
+                405,
+                AppError::new(ErrorKind::User(format!(
+                    "{} is not supported for {}",
+                    method, path
+                ))),
+            ))
+        }
+        (_, path) => {
+            return Err((
+                404,
+                AppError::new(ErrorKind::User(format!("Unknown path {}", path))),
+            ))
+        }
+    };
+    result.map_err(with_status)
+}
+
+// Helper function that pairs an error with the HTTP status code it should be answered with.
+fn with_status(error: AppError) -> (u16, AppError) {
+    match error.kind() {
+        ErrorKind::User(_) | ErrorKind::Parse(_) => (400, error),
+        _ => (500, error),
+    }
+}
+
+// Parses URL encoded parameters such as `project=work&description=Fixing+bugs`.
+fn parse_params(input: &str) -> HashMap<String, String> {
+    input
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let mut parts = param.splitn(2, '=');
+            let key = decode(parts.next().unwrap_or(""));
+            let value = decode(parts.next().unwrap_or(""));
+            (key, value)
+        })
+        .collect()
+}
+
+// Decodes `+` and percent encoded bytes. Malformed escapes are kept as they are.
+fn decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = |byte: u8| (byte as char).to_digit(16);
+                match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                    (Some(high), Some(low)) => {
+                        decoded.push((high * 16 + low) as u8);
+                        i += 2;
+                    }
+                    _ => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_params() {
+        let params = parse_params("project=work&description=Fixing+bugs%21&interval=9%20-%2012");
+        assert_eq!(params["project"], "work");
+        assert_eq!(params["description"], "Fixing bugs!");
+        assert_eq!(params["interval"], "9 - 12");
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%zz"), "%zz");
+        assert_eq!(decode("%C3%A1"), "á");
+        assert!(parse_params("").is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+use std::str::FromStr;
+
+use colored::{control, Color, ColoredString, Colorize};
+
+use work_core::error::{AppError, ErrorKind};
+use work_core::highlight::Highlight;
+
+/// Colors that tell the series of a chart apart, such as the projects of a timeline.
+const SERIES_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::Red,
+];
+
+/// The `ColorChoice` enum decides whether the output of Work is colored.
+///
+/// `Auto` colors the output when it is written to a terminal, unless the `NO_COLOR` environment
+/// variable is set. `Always` and `Never` override both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "Valid values are [auto, always, never]".to_string(),
+            ))),
+        }
+    }
+}
+
+/// Applies the given `ColorChoice` to all output that follows.
+pub fn set_color_choice(choice: ColorChoice) {
+    match choice {
+        ColorChoice::Auto => control::unset_override(),
+        ColorChoice::Always => control::set_override(true),
+        ColorChoice::Never => control::set_override(false),
+    }
+}
+
+/// Styles the name of a project.
+pub fn project(name: &str) -> ColoredString {
+    name.cyan().bold()
+}
+
+/// Styles a duration or a point in time.
+pub fn duration(time: &str) -> ColoredString {
+    time.green()
+}
+
+/// Styles the part of a chart belonging to the series with the given index, cycling through a few
+/// colors.
+pub fn series(text: &str, index: usize) -> ColoredString {
+    text.color(SERIES_COLORS[index % SERIES_COLORS.len()])
+}
+
+/// Styles a warning, such as a guess made while parsing a time.
+pub fn warning(message: &str) -> ColoredString {
+    message.yellow()
+}
+
+/// Styles an error message.
+pub fn error(message: &str) -> ColoredString {
+    message.red().bold()
+}
+
+/// The `Terminal` struct highlights text output with the styles above, colored as long as the
+/// `ColorChoice` allows it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Terminal;
+
+impl Highlight for Terminal {
+    fn project(&self, name: &str) -> String {
+        project(name).to_string()
+    }
+
+    fn duration(&self, time: &str) -> String {
+        duration(time).to_string()
+    }
+
+    fn series(&self, text: &str, index: usize) -> String {
+        series(text, index).to_string()
+    }
+}
@@ -0,0 +1,3043 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate, NaiveTime, TimeZone};
+use clap::error::ErrorKind as ClapErrorKind;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use log::debug;
+
+#[cfg(feature = "auto")]
+use crate::arguments::AutoAction;
+use crate::arguments::{
+    Args, Assume, BarStyle, CacheAction, EventKind, ExportFormat, GroupBy, HistogramKind,
+    ImportFormat, ProfileAction, RemindAction, ServiceKind, TeamAction, TimeFormat, TimeOfDay,
+    TrendLength,
+};
+use crate::help;
+use crate::rpc;
+#[cfg(feature = "server")]
+use crate::server;
+use crate::style::{self, Terminal};
+use work_core::archive;
+#[cfg(feature = "auto")]
+use work_core::auto::{self, Noticed, Rules};
+use work_core::backup::Backup;
+use work_core::comparison::Comparison;
+use work_core::config::Config;
+use work_core::csv_output::CsvOptions;
+use work_core::days_off;
+#[cfg(feature = "email")]
+use work_core::email::Mailer;
+use work_core::error::{AppError, ErrorCode, ErrorKind, ExitCode};
+use work_core::export::Export;
+use work_core::formatter::Formatter;
+use work_core::fuzzy;
+use work_core::grouping::Grouping;
+#[cfg(feature = "email")]
+use work_core::highlight::Plain;
+use work_core::hooks::Hooks;
+use work_core::idle;
+use work_core::locks;
+use work_core::log_file::*;
+use work_core::overtime::Overtime;
+#[cfg(feature = "presence")]
+use work_core::presence::Presence;
+use work_core::profiles;
+use work_core::project_map::{ProjectMap, ProjectMapMethods};
+use work_core::remind::{self, Reminder};
+use work_core::report::{self, Report};
+use work_core::rounding::Rounding;
+use work_core::service::{self, Manager};
+use work_core::sessions::{self, SessionId};
+use work_core::signals::{self, Interrupts};
+use work_core::standup::{self, Standup};
+use work_core::stats::{Grid, Histogram, Trend};
+use work_core::statusbar;
+use work_core::team::{self, TeamReport};
+#[cfg(feature = "templates")]
+use work_core::template;
+use work_core::time::{self, Clock};
+use work_core::timeline::Timeline;
+use work_core::work_csv;
+
+/// Number of columns assumed when the width of the terminal can't be determined.
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+// Helper function to simplify checks of a given Event.
+// Returns false if the last log states that no work is in progress, true otherwise.
+//
+// Mainly used to make the `start` function easier on the eyes.
+fn is_working(event: &Event) -> bool {
+    match event {
+        Event::Stop(..) => false,
+        Event::Start(..) => true,
+    }
+}
+
+// Helper function that checks whether work is in progress according to the final entry in the log.
+// A `stop` event scheduled in the future, by the `until` command for example, means that work is
+// still in progress at the given time `now`.
+pub(crate) fn is_working_now(entry: &Option<(i64, Event)>, now: i64) -> bool {
+    match entry {
+        Some((_, Event::Start(..))) => true,
+        Some((timestamp, Event::Stop(..))) => *timestamp > now,
+        None => false,
+    }
+}
+
+// Helper function for finding the position of a session's `start` event within the events it was
+// reconstructed from.
+fn start_index(events: &[(i64, Event)], session: &sessions::Session) -> usize {
+    // Can unwrap since the session was reconstructed from these very events.
+    events
+        .iter()
+        .position(|(timestamp, event)| *timestamp == session.start && is_working(event))
+        .unwrap()
+}
+
+// Helper function that makes sure a new session from `start` to `end` doesn't overlap work that is
+// already recorded in the log, unless `force` is set. Sessions that merely touch are fine.
+fn check_overlap(log: &mut LogFile, start: i64, end: i64, force: bool) -> Result<(), AppError> {
+    if force {
+        return Ok(());
+    }
+
+    let now = log.now();
+    let overlapping: Vec<String> = sessions::reconstruct(&log.all_events()?)
+        .iter()
+        .filter(|session| session.start < end && session.stop.unwrap_or(now) > start)
+        .map(|session| session.to_line(now))
+        .collect();
+    if overlapping.is_empty() {
+        return Ok(());
+    }
+
+    Err(AppError::new(ErrorKind::User(format!(
+        "The new session overlaps recorded work:\n{}\nUse --force to add it anyway.",
+        overlapping.join("\n")
+    )))
+    .with_code(ErrorCode::Overlap))
+}
+
+// Helper function that refuses to change the log within the given interval if it shares time with
+// a locked period, unless `force` is set.
+fn check_locks(log: &LogFile, interval: &time::Interval, force: bool) -> Result<(), AppError> {
+    if force {
+        return Ok(());
+    }
+
+    match log.locks()?.find(interval) {
+        Some(period) => Err(locked(period)),
+        None => Ok(()),
+    }
+}
+
+// Helper function that refuses to change the event of the log at the given time if it is within a
+// locked period, unless `force` is set.
+fn check_lock_at(log: &LogFile, timestamp: i64, force: bool) -> Result<(), AppError> {
+    if force {
+        return Ok(());
+    }
+
+    match log.locks()?.find_time(timestamp) {
+        Some(period) => Err(locked(period)),
+        None => Ok(()),
+    }
+}
+
+// Helper function for the error of a change that touches the given locked period.
+fn locked(period: &time::Interval) -> AppError {
+    AppError::new(ErrorKind::User(format!(
+        "The change touches the locked period {}.\nUse --force to make it anyway.",
+        locks::format_period(period)
+    )))
+    .with_code(ErrorCode::Locked)
+}
+
+// Helper function that returns the time spent by a session, up until `now` if it is in progress.
+fn span_of(session: &sessions::Session, now: i64) -> time::Interval {
+    time::Interval::new(session.start, Some(session.stop.unwrap_or(now)))
+}
+
+// Helper function that asks the user a yes or no question on the terminal.
+fn confirm(question: &str) -> Result<bool, AppError> {
+    eprint!("{} [y/N] ", question);
+    let mut answer = String::new();
+    if let Err(e) = io::stdin().read_line(&mut answer) {
+        return Err(
+            AppError::new(ErrorKind::System(format!("Unable to read answer: {}", e)))
+                .with_source(e),
+        );
+    }
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+// Helper function for parsing a time input given by the user.
+//
+// Ambiguous times are resolved in the given `search` direction unless the user assumes otherwise.
+// Whenever the date of a time had to be guessed the guess is printed. If the user wants to be
+// asked, every ambiguous time has to be confirmed and declining gives the opposite direction a try.
+// Times relative to now are relative to the given clock.
+fn parse_interval(
+    input: &str,
+    search: time::Search,
+    assume: Option<Assume>,
+    clock: &dyn Clock,
+) -> Result<time::Interval, AppError> {
+    let search = match assume {
+        Some(Assume::Backward) => time::Search::Backward,
+        Some(Assume::Forward) => time::Search::Forward,
+        _ => search,
+    };
+    let opposite = match search {
+        time::Search::Backward => time::Search::Forward,
+        time::Search::Forward => time::Search::Backward,
+    };
+
+    for search in &[search, opposite] {
+        let (interval, resolutions) =
+            time::Interval::try_parse_with_resolutions(input, search, clock)?;
+        let ask = assume == Some(Assume::Ask) && !resolutions.is_empty();
+        resolutions
+            .iter()
+            .filter(|resolution| ask || resolution.guessed)
+            .for_each(|resolution| {
+                eprintln!(
+                    "{}",
+                    style::warning(&format!(
+                        "Interpreting {} as {}",
+                        resolution.input,
+                        resolution.date_time.format("%a %d-%m-%Y %H:%M")
+                    ))
+                )
+            });
+        if !ask || confirm("Is this correct?")? {
+            debug!(
+                "Parsed {:?} as {} - {}",
+                input,
+                time::format_date_time(interval.start),
+                time::format_date_time(interval.end)
+            );
+            return Ok(interval);
+        }
+    }
+    Err(AppError::new(ErrorKind::User(
+        "Unable to resolve the given time, please be more specific.".to_string(),
+    ))
+    .with_code(ErrorCode::InvalidTime))
+}
+
+// Helper function that passes on the warnings of hooks that ran after the log was changed, which
+// don't undo the change, see `Hooks`.
+fn warn(warnings: Vec<String>) {
+    for warning in warnings {
+        eprintln!("{}", style::warning(&warning));
+    }
+}
+
+// Helper function that returns the width of the terminal, falling back to a reasonable default when
+// the output isn't a terminal.
+fn terminal_width() -> usize {
+    term_size::dimensions().map_or(DEFAULT_TERMINAL_WIDTH, |(width, _)| width)
+}
+
+// Helper function that refuses open-ended intervals like `- 12` for commands that add sessions, as
+// those would start at the beginning of time.
+fn require_start(interval: &time::Interval) -> Result<(), AppError> {
+    if interval.start == time::BEGINNING_OF_TIME {
+        return Err(AppError::new(ErrorKind::User(
+            "Please give a start time for the new session.".to_string(),
+        ))
+        .with_code(ErrorCode::InvalidTime));
+    }
+    Ok(())
+}
+
+/// The `start` function corresponds to the `start` command.
+///
+/// The function reads the log for the last event and makes sure that the user isn't starting new
+/// work while other work is in progress. This is done because one should only be working on a
+/// single thing at a time.
+///
+/// If the user isn't trying to append a double `start` event, the function appends a `start` event
+/// to the log, naming the `client` and whether the work is `billable` if given, and runs the
+/// `on_switch` and `on_start` hooks. Unless `quiet` is set, the function confirms when the work
+/// started.
+///
+/// The work starts now unless a time is given `at`, such as `9:00` or `15m`, in which case it
+/// starts at that time instead. The time can't be before the previous work stopped, nor in the
+/// future unless `allow_future` is set, nor within a locked period unless `force` is set.
+///
+/// Work that is still in progress is first stopped at `close_previous_at` if given, see `stop`, or
+/// removed from the log if `discard_previous` is set. Otherwise starting new work is refused,
+/// pointing out these options if the work in progress started on an earlier day and was most
+/// likely forgotten.
+#[allow(clippy::too_many_arguments)]
+pub fn start(
+    log: &mut LogFile,
+    hooks: &Hooks,
+    project: Option<String>,
+    description: Option<String>,
+    client: Option<String>,
+    billable: Option<bool>,
+    at: Option<&str>,
+    close_previous_at: Option<&str>,
+    discard_previous: bool,
+    allow_future: bool,
+    force: bool,
+    quiet: bool,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let mut latest = log.get_latest_entry()?;
+    if is_working_now(&latest, log.now()) {
+        if let Some(close_previous_at) = close_previous_at {
+            stop(
+                log,
+                hooks,
+                Some(close_previous_at),
+                None,
+                force,
+                quiet,
+                assume,
+            )?;
+        } else if discard_previous {
+            discard_running(log, quiet)?;
+        } else {
+            return Err(AppError::new(ErrorKind::User(match &latest {
+                Some((start_time, event @ Event::Start(..)))
+                    if time::date_of(*start_time) < log.clock().today() =>
+                {
+                    format!(
+                        "Still working on {} since {}, it was most likely forgotten.\n\
+                         Pass --close-previous-at <TIME> to stop it at the given time, or \
+                         --discard-previous to remove it.",
+                        event.to_string(),
+                        time::format_date_time(*start_time)
+                    )
+                }
+                _ => "Please stop the current work before starting new work.".to_string(),
+            }))
+            .with_code(ErrorCode::AlreadyWorking));
+        }
+        latest = log.get_latest_entry()?;
+    }
+    let now = log.now();
+    let start_time = match at {
+        Some(at) => {
+            let interval = parse_interval(at, time::Search::Backward, assume, log.clock())?;
+            require_start(&interval)?;
+            let start_time = moment_of(&interval, now);
+            check_future(start_time, "The work starts", allow_future, now)?;
+            check_locks(log, &time::Interval::new(start_time, Some(now)), force)?;
+            start_time
+        }
+        None => now,
+    };
+    if let Some((stop_time, _)) = &latest {
+        if start_time < *stop_time {
+            return Err(AppError::new(ErrorKind::User(format!(
+                "Unable to start, the previous work stopped later ({}).",
+                time::format_date_time(*stop_time)
+            )))
+            .with_code(ErrorCode::OutOfOrder));
+        }
+    }
+
+    let event = Event::Start(project, description, client, billable, Metadata::new());
+    log.append_event(&event, start_time)?;
+    warn(hooks.started(&event, latest.as_ref().map(|(_, event)| event)));
+    if !quiet {
+        println!(
+            "Started {} at {}",
+            label(&event),
+            format_moment(start_time, now)
+        );
+    }
+    Ok(ExitCode::Success)
+}
+
+// Helper function that removes the work in progress from the log, along with its `stop` event if
+// it is scheduled in the future.
+fn discard_running(log: &mut LogFile, quiet: bool) -> Result<(), AppError> {
+    let now = log.now();
+    let mut events = log.all_events()?;
+    if let Some((timestamp, Event::Stop(..))) = events.last() {
+        if *timestamp > now {
+            events.pop();
+        }
+    }
+    if let Some((start_time, event @ Event::Start(..))) = events.pop() {
+        log.rewrite(&events)?;
+        if !quiet {
+            println!(
+                "Discarded {} started at {}",
+                label(&event),
+                format_moment(start_time, now)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Warns the user if the given project isn't in the log yet but looks like a misspelling of a
+/// project that is, such as `bakend` when there is already a `backend` project. The warning is
+/// suppressed with `start --yes`.
+pub fn warn_if_misspelled(log: &mut LogFile, project: Option<&str>) -> Result<(), AppError> {
+    let project = match project {
+        Some(project) => project,
+        None => return Ok(()),
+    };
+    let projects = log.projects()?;
+    if let Some(suggestion) = fuzzy::closest(project, projects.iter().map(String::as_str)) {
+        eprintln!(
+            "{}",
+            style::warning(&format!(
+                "Did you mean \"{}\"? \"{}\" is a new project, pass --yes to skip this check.",
+                suggestion, project
+            ))
+        );
+    }
+    Ok(())
+}
+
+/// The `stop` function corresponds to the `stop` command.
+///
+/// The function reads the log for the last event and makes sure the user isn't trying to stop
+/// already stopped work.
+///
+/// If the last event was a `start` event the function appends a `stop` event to the log with the
+/// same project description as the final `start` event in the log. This is done to make life
+/// easier when adding up time spent on projects in the `log_file.rs`.
+///
+/// The work is stopped now unless a `time` is given, such as `17:30` or `20m`, in which case it is
+/// stopped at that time instead. The time has to be after the work started and not in the future.
+///
+/// If the last event is a `stop` event scheduled in the future, the work is stopped early by moving
+/// that event to the current or given time.
+///
+/// A `description` given when stopping replaces the description of the work, on both the `stop`
+/// event and the `start` event it closes since reports read the description of the `start` event.
+///
+/// Either of these changes the log in the past, so they are refused within a locked period unless
+/// `force` is set.
+///
+/// Either way the `on_stop` hook is run once the work has stopped, and unless `quiet` is set the
+/// function confirms how long the work lasted.
+pub fn stop(
+    log: &mut LogFile,
+    hooks: &Hooks,
+    time: Option<&str>,
+    description: Option<&str>,
+    force: bool,
+    quiet: bool,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let now = log.now();
+    let stop_time = match time {
+        Some(time) => parse_stop_time(time, log.clock(), assume)?,
+        None => now,
+    };
+
+    let entry = log.get_latest_entry()?;
+    if let Some((scheduled_time, Event::Stop(..))) = &entry {
+        if *scheduled_time > now {
+            let stopped =
+                stop_scheduled_early(log, hooks, *scheduled_time, stop_time, description, force)?;
+            if let (false, Some((event, duration))) = (quiet, stopped) {
+                print_stopped(&event, duration);
+            }
+            return Ok(ExitCode::Success);
+        }
+    }
+
+    let (start_time, event) = match entry {
+        Some((start_time, Event::Start(project, description, client, billable, metadata))) => (
+            start_time,
+            Event::Stop(project, description, client, billable, metadata),
+        ),
+        _ => {
+            return Err(AppError::new(ErrorKind::User(
+                "Unable to stop, no work in progress!".to_string(),
+            ))
+            .with_code(ErrorCode::NotWorking))
+        }
+    };
+    require_after_start(start_time, stop_time)?;
+    if time.is_some() || description.is_some() {
+        check_locks(
+            log,
+            &time::Interval::new(start_time, Some(stop_time)),
+            force,
+        )?;
+    }
+    let event = match description {
+        Some(description) => {
+            let event = event.with_description(description);
+            event.validate()?;
+            let mut events = log.all_events()?;
+            describe_last_session(&mut events, description);
+            log.rewrite(&events)?;
+            event
+        }
+        None => event,
+    };
+    let duration = append_stop(log, hooks, &event, start_time, stop_time)?;
+    if !quiet {
+        print_stopped(&event, duration);
+    }
+    Ok(ExitCode::Success)
+}
+
+// Helper function that appends the given `stop` event at `stop_time` to stop the work that started
+// at `start_time`, and runs the `on_stop` hooks. Returns how long the work lasted.
+fn append_stop(
+    log: &mut LogFile,
+    hooks: &Hooks,
+    event: &Event,
+    start_time: i64,
+    stop_time: i64,
+) -> Result<i64, AppError> {
+    log.append_event(event, stop_time)?;
+    let duration = stop_time - start_time;
+    warn(hooks.stopped(event, duration));
+    warn(hooks.worked(log, event, duration));
+    Ok(duration)
+}
+
+// Helper function that parses the time given to `stop`, which has to be in the past according to
+// the given clock.
+fn parse_stop_time(time: &str, clock: &dyn Clock, assume: Option<Assume>) -> Result<i64, AppError> {
+    let now = clock.now();
+    let interval = parse_interval(time, time::Search::Backward, assume, clock)?;
+    if interval.start == time::BEGINNING_OF_TIME {
+        return Err(AppError::new(ErrorKind::User(
+            "Please give the time the work stopped.".to_string(),
+        ))
+        .with_code(ErrorCode::InvalidTime));
+    }
+    let stop_time = moment_of(&interval, now);
+    if stop_time > now {
+        return Err(AppError::new(ErrorKind::User(format!(
+            "Unable to stop work in the future ({}), use `until` instead.",
+            time::format_date_time(stop_time)
+        )))
+        .with_code(ErrorCode::InvalidTime));
+    }
+    Ok(stop_time)
+}
+
+// Helper function that returns the single time the user gave out of the interval it was parsed
+// into. A time in the past spans from that time until `now`, while a time in the future spans from
+// `now` until that time.
+fn moment_of(interval: &time::Interval, now: i64) -> i64 {
+    if interval.end > now {
+        interval.end
+    } else {
+        interval.start
+    }
+}
+
+// Helper function that replaces the description of the final session in the given events, on its
+// `start` event as well as on its `stop` event if it has one.
+fn describe_last_session(events: &mut [(i64, Event)], description: &str) {
+    for (_, event) in events.iter_mut().rev() {
+        *event = event.with_description(description);
+        if let Event::Start(..) = event {
+            break;
+        }
+    }
+}
+
+// Helper function that adds the events of a new session to the log. A session that starts before
+// the last event in the log is only inserted in chronological order if `reorder` is set, since that
+// rewrites the log.
+fn add_session(log: &mut LogFile, entries: &[(i64, Event)], reorder: bool) -> Result<(), AppError> {
+    if reorder {
+        return log.insert_events(entries);
+    }
+    if let (Some((latest, _)), Some((start, _))) = (log.get_latest_entry()?, entries.first()) {
+        if *start < latest {
+            return Err(AppError::new(ErrorKind::User(format!(
+                "The session starts before the last event in the log ({}), pass --reorder to \
+                 insert it in chronological order.",
+                time::format_date_time(latest)
+            )))
+            .with_code(ErrorCode::OutOfOrder));
+        }
+    }
+    entries
+        .iter()
+        .try_for_each(|(timestamp, event)| log.append_event(event, *timestamp))
+}
+
+// Helper function that makes sure work that started at `start_time` doesn't stop before it started.
+fn require_after_start(start_time: i64, stop_time: i64) -> Result<(), AppError> {
+    if stop_time < start_time {
+        return Err(AppError::new(ErrorKind::User(format!(
+            "Unable to stop before the work started ({}).",
+            time::format_date_time(start_time)
+        )))
+        .with_code(ErrorCode::OutOfOrder));
+    }
+    Ok(())
+}
+
+// Helper function that refuses to record work at a time in the future unless `allow_future` is set,
+// as the log holds the work that was done by `now`. `until` is there to schedule when work stops
+// instead.
+fn check_future(timestamp: i64, what: &str, allow_future: bool, now: i64) -> Result<(), AppError> {
+    if allow_future || timestamp <= now {
+        return Ok(());
+    }
+    Err(AppError::new(ErrorKind::User(format!(
+        "{} in the future ({}), use --allow-future to add it anyway.",
+        what,
+        time::format_date_time(timestamp)
+    )))
+    .with_code(ErrorCode::InvalidTime))
+}
+
+// Helper function that confirms that the work of the given `stop` event lasted `duration` seconds.
+fn print_stopped(event: &Event, duration: i64) {
+    println!(
+        "Stopped {} after {}",
+        label(event),
+        format_duration(duration)
+    );
+}
+
+// Helper function that names the project of an event for confirmations, e.g. `'backend'`, or just
+// `work` if the event has no project.
+fn label(event: &Event) -> String {
+    match event {
+        Event::Start(Some(project), ..) | Event::Stop(Some(project), ..) => {
+            format!("'{}'", style::project(project))
+        }
+        _ => "work".to_string(),
+    }
+}
+
+// Helper function that formats a duration for confirmations, e.g. `2 hours and 5 minutes`.
+fn format_duration(duration: i64) -> String {
+    time::format_time(&TimeFormat::HumanReadable, duration).to_lowercase()
+}
+
+// Helper function that formats a point in time for confirmations, as a time of day if it is today
+// and along with the date otherwise, going by the given time `now`.
+fn format_moment(timestamp: i64, now: i64) -> String {
+    if time::date_of(timestamp) == time::date_of(now) {
+        time::format_clock(timestamp)
+    } else {
+        time::format_date_time(timestamp)
+    }
+}
+
+// Helper function that moves a `stop` event scheduled at `stop_time` to the time `at`, given that
+// it is still the final event in the log, and runs the `on_stop` hook. The work is given the
+// `description` if there is one. Returns the moved event along with how long the work lasted, or
+// `None` if nothing was moved.
+fn stop_scheduled_early(
+    log: &mut LogFile,
+    hooks: &Hooks,
+    stop_time: i64,
+    at: i64,
+    description: Option<&str>,
+    force: bool,
+) -> Result<Option<(Event, i64)>, AppError> {
+    let mut events = log.all_events()?;
+    let start_time = match events.iter().rev().nth(1) {
+        Some((start_time, _)) => *start_time,
+        None => stop_time,
+    };
+    if let Some(scheduled_stop @ (_, Event::Stop(..))) = events.last_mut() {
+        if scheduled_stop.0 == stop_time {
+            require_after_start(start_time, at)?;
+            let changed_from = if description.is_some() {
+                start_time
+            } else {
+                at
+            };
+            check_locks(
+                log,
+                &time::Interval::new(changed_from, Some(stop_time)),
+                force,
+            )?;
+            scheduled_stop.0 = at;
+            if let Some(description) = description {
+                scheduled_stop.1.with_description(description).validate()?;
+                describe_last_session(&mut events, description);
+            }
+            let event = events[events.len() - 1].1.clone();
+            log.rewrite(&events)?;
+            warn(hooks.stopped(&event, at - start_time));
+            warn(hooks.worked(log, &event, at - start_time));
+            return Ok(Some((event, at - start_time)));
+        }
+    }
+    Ok(None)
+}
+
+/// The `status` function corresponds to the `status` command.
+///
+/// The function outputs the final event in the log in human readable form. That is, the function
+/// outputs "Free" if the final event is a `stop` event, "Working" if the final event is a `start`
+/// event with no project, and "Working on [PROJECT_NAME]" if the final event is a `start` event
+/// with a project name. Work in progress is followed by when it started and how long ago that
+/// was, e.g. "since 09:12 (2 hours and 5 minutes)".
+///
+/// A `stop` event scheduled in the future means work is still in progress, the function then
+/// outputs "Working until HH:MM" or "Working on [PROJECT_NAME] until HH:MM", followed by a
+/// countdown of the time remaining, e.g. "— 43 minutes remaining".
+///
+/// Work running for longer than `warn_after` is followed by a warning to take a break, and so is
+/// having worked more than `daily_max` today, see the config keys of the same names.
+pub fn status(
+    log: &mut LogFile,
+    warn_after: Option<i64>,
+    daily_max: Option<i64>,
+) -> Result<ExitCode, AppError> {
+    let entry = log.get_latest_entry()?;
+    let now = log.now();
+    let started = match &entry {
+        Some((start_time, Event::Start(..))) => Some(*start_time),
+        _ => None,
+    };
+    match entry {
+        Some((stop_time, Event::Stop(None, ..))) if stop_time > now => println!(
+            "Working until {} — {} remaining",
+            style::duration(&time::format_clock(stop_time)),
+            style::duration(&format_duration(stop_time - now))
+        ),
+        Some((stop_time, Event::Stop(Some(project), ..))) if stop_time > now => println!(
+            "Working on {} until {} — {} remaining",
+            style::project(&project),
+            style::duration(&time::format_clock(stop_time)),
+            style::duration(&format_duration(stop_time - now))
+        ),
+        None | Some((_, Event::Stop(..))) => println!("Free"),
+        Some((start_time, Event::Start(None, ..))) => {
+            println!("Working {}", format_since(start_time, now))
+        }
+        Some((start_time, Event::Start(Some(project), ..))) => println!(
+            "Working on {} {}",
+            style::project(&project),
+            format_since(start_time, now)
+        ),
+    }
+
+    if let (Some(limit), Some(start_time)) = (warn_after, started) {
+        if now - start_time > limit {
+            println!(
+                "{}",
+                style::warning(&format!(
+                    "That's over {} without a break, time to stretch your legs!",
+                    format_duration(limit)
+                ))
+            );
+        }
+    }
+    if let Some(limit) = daily_max {
+        let today = time::Interval::new(time::start_of_day(log.clock().today()), Some(now));
+        let worked = log
+            .tally_time(&today)?
+            .map_or(0, |projects| report::total(&projects));
+        if worked > limit {
+            println!(
+                "{}",
+                style::warning(&format!(
+                    "Worked {} today, over the daily max of {}.",
+                    format_duration(worked),
+                    format_duration(limit)
+                ))
+            );
+        }
+    }
+    Ok(ExitCode::Success)
+}
+
+// Helper function that formats when work in progress started and how long ago that was, e.g.
+// `since 09:12 (2 hours and 5 minutes)`.
+fn format_since(start_time: i64, now: i64) -> String {
+    format!(
+        "since {} ({})",
+        style::duration(&format_moment(start_time, now)),
+        format_duration(now - start_time)
+    )
+}
+
+/// The `working_or_free` function corresponds to both the `free` and the `working` commands.
+///
+/// If the command called is `free` the function exits with an exit code of 0 if the final event in
+/// the log is a `stop` event, and 1 otherwise.
+///
+/// If the command called is `working` the function exits with an exit code of 0 if the final event
+/// in the log is a `start` event, and 1 otherwise.
+///
+/// A final `stop` event that is scheduled in the future counts as work in progress.
+pub fn working_or_free(log: &mut LogFile, check_working: bool) -> Result<ExitCode, AppError> {
+    let working = is_working_now(&log.get_latest_entry()?, log.now());
+    match (working, check_working) {
+        // Not working and user questions whether he is free -> Yes
+        (false, false) => Ok(ExitCode::Success),
+        // Not working and user questions whether he is working -> No
+        (false, true) => Ok(ExitCode::Negative),
+        // Working and user questions whether he is free -> No
+        (true, false) => Ok(ExitCode::Negative),
+        // Working and user questions whether he is working -> Yes
+        (true, true) => Ok(ExitCode::Success),
+    }
+}
+
+/// The `of` function corresponds to the `of` command.
+///
+/// The function receives the user inputted interval, parses it, finds all work that was done
+/// within the given interval, adds the time spent on projects together, and finally outputs the
+/// results.
+///
+/// The user inputted interval can be of the following forms:
+/// * X               meaning at X o'clock
+/// * X:Y             meaning Y minutes past X o'clock
+/// * Xm              meaning X minutes ago
+/// * Xh              meaning X hours ago
+/// * X:Yh            meaning X hours and Y minutes ago
+/// * D X:Y           meaning since day D at Y minutes past X o'clock
+/// * D-M X:Y         meaning since day D and month M at Y minutes past X o'clock
+/// * today           means last possible midnight
+/// * yesterday       means midnight of yesterday
+/// * [START] - [END] means anything between START and END (inclusive) where START and END are any
+/// of the forms above.
+///
+/// Some of these inputs can be ambiguous, if an input given is ambiguous the last possible time
+/// will be chosen.
+///
+/// The maximum of the two values (START and END) in an interval is interpreted as the end date.
+///
+/// If `list_sessions` is set, the sessions within the interval are listed along with their ids
+/// before the summary, split into a line per day if `split_at_midnight` is set. If `chart` is set,
+/// the summary is drawn as a bar per project. If `by_client` is set, the time is summed up for each
+/// client instead, broken down by project, and just the same for each user if `by_user` is set. If
+/// any of the work was non-billable, the billable and non-billable time are listed separately at
+/// the end. If `csv` is given, the summary is written as CSV according to the given options
+/// instead. If `json` is given, it is written as JSON with the time spent given as plain seconds if
+/// it holds `true`. If `output` is given, the JSON is piped to the external formatter instead, see
+/// `Formatter`.
+///
+/// If `compare` is given, the time spent on each project is instead listed next to the time spent
+/// on it within the `compare` interval, along with the difference between the two.
+///
+/// If `group_by` is given, the time spent is instead grouped by each of its keys in turn, such as
+/// by week and then by project, see `Grouping`.
+///
+/// The work done in each of the given `logs` is added up, such as the logs of every profile. Logs
+/// with a filter set only count the sessions that pass it, see `LogFile::set_filter`.
+#[allow(clippy::too_many_arguments)]
+pub fn of(
+    logs: &mut [LogFile],
+    interval_input: &str,
+    csv: Option<CsvOptions>,
+    json: Option<bool>,
+    output: Option<&Formatter>,
+    list_sessions: bool,
+    split_at_midnight: bool,
+    chart: bool,
+    by_client: bool,
+    by_user: bool,
+    compare: Option<&str>,
+    group_by: Option<&GroupBy>,
+    time_format: TimeFormat,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let clock = clock_of(logs);
+    let interval = parse_of_interval(interval_input, assume, &clock)?;
+
+    if let Some(compare) = compare {
+        let compared = parse_of_interval(compare, assume, &clock)?;
+        let comparison = Comparison::new(
+            &tally(logs, &interval, by_client, by_user)?.unwrap_or_default(),
+            &tally(logs, &compared, by_client, by_user)?.unwrap_or_default(),
+        );
+        if comparison.is_empty() {
+            println!("No work done!");
+            return Ok(ExitCode::Negative);
+        }
+        if let Some(options) = csv {
+            println!("{}", comparison.as_csv(&time_format, &options));
+        } else if let Some(raw_seconds) = json {
+            println!("{}", comparison.as_json(&time_format, raw_seconds));
+        } else if let Some(formatter) = output {
+            formatter.run(&comparison.as_json(&time_format, false))?;
+        } else {
+            println!(
+                "{}",
+                comparison.as_table(&time_format, interval_input, compare, &Terminal)
+            );
+        }
+        return Ok(ExitCode::Success);
+    }
+
+    if list_sessions {
+        let mut sessions = Vec::new();
+        for log in logs.iter_mut() {
+            sessions.extend(
+                log.sessions(&interval)?
+                    .into_iter()
+                    .filter(|session| session.overlaps(interval.start, interval.end, clock.now())),
+            );
+        }
+        sessions.sort_by_key(|session| session.start);
+        print_sessions(&sessions, split_at_midnight, clock.now());
+    }
+
+    if let Some(group_by) = group_by {
+        let grouping = group(logs, &interval, group_by, clock.now())?;
+        if grouping.is_empty() {
+            println!("No work done!");
+            return Ok(ExitCode::Negative);
+        }
+        if let Some(options) = csv {
+            print!("{}", grouping.as_csv(&time_format, &options));
+        } else if let Some(raw_seconds) = json {
+            println!("{}", grouping.as_json(&time_format, raw_seconds));
+        } else if let Some(formatter) = output {
+            formatter.run(&grouping.as_json(&time_format, false))?;
+        } else {
+            print!("{}", grouping.as_text(&time_format, &Terminal));
+            print_billable(logs, &interval, &time_format)?;
+        }
+        return Ok(ExitCode::Success);
+    }
+
+    if let Some(map) = tally(logs, &interval, by_client, by_user)? {
+        if let Some(options) = csv {
+            println!("{}", map.as_csv(&time_format, &options));
+        } else if let Some(raw_seconds) = json {
+            println!("{}", map.as_json(&time_format, raw_seconds));
+        } else if let Some(formatter) = output {
+            formatter.run(&map.as_json(&time_format, false))?;
+        } else if chart {
+            println!("{}", map.as_bars(&time_format, terminal_width(), &Terminal));
+        } else {
+            map.iter().for_each(|(key, val)| {
+                println!(
+                    "{} => {}",
+                    style::project(key),
+                    style::duration(&time::format_time(&time_format, val.values().sum()))
+                )
+            });
+        }
+        if csv.is_none() && json.is_none() && output.is_none() {
+            print_billable(logs, &interval, &time_format)?;
+        }
+    } else {
+        println!("No work done!");
+        return Ok(ExitCode::Negative);
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `check_total` function corresponds to the `--min` and `--max` options of the `of` command.
+///
+/// The function adds up the time spent on every project within the given interval and exits with
+/// an error code of 1 if it is less than `min` or more than `max`, so a script can tell whether
+/// enough or too much work was done without parsing the summary. No work at all counts as zero.
+pub fn check_total(
+    logs: &mut [LogFile],
+    interval_input: &str,
+    min: Option<i64>,
+    max: Option<i64>,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let interval = parse_of_interval(interval_input, assume, &clock_of(logs))?;
+    let total =
+        tally(logs, &interval, false, false)?.map_or(0, |projects| report::total(&projects));
+    let met = total >= min.unwrap_or(0) && total <= max.unwrap_or(i64::MAX);
+    debug!(
+        "Worked {} seconds within {}, which is {} the threshold",
+        total,
+        interval_input,
+        if met { "within" } else { "outside" }
+    );
+    Ok(if met {
+        ExitCode::Success
+    } else {
+        ExitCode::Negative
+    })
+}
+
+// Helper function that parses the interval of the `of` command. Yesterday ends at the start of
+// today rather than at the end of yesterday's last minute.
+fn parse_of_interval(
+    input: &str,
+    assume: Option<Assume>,
+    clock: &dyn Clock,
+) -> Result<time::Interval, AppError> {
+    let mut interval = parse_interval(input, time::Search::Backward, assume, clock)?;
+    if input == "yesterday" {
+        interval.end = time::start_of_day(clock.today());
+    }
+    Ok(interval)
+}
+
+// Helper function that returns the clock of the first of the given logs frozen at the current
+// time, so that a command reading several logs at once goes by a single point in time.
+fn clock_of(logs: &[LogFile]) -> time::FixedClock {
+    time::FixedClock(logs.first().map_or_else(time::now, LogFile::now))
+}
+
+// Helper function that adds up the time spent on each project, or for each client if `by_client`
+// is set or by each user if `by_user` is set, within the given interval in each of the given logs.
+// Returns `None` if no work was done.
+fn tally(
+    logs: &mut [LogFile],
+    interval: &time::Interval,
+    by_client: bool,
+    by_user: bool,
+) -> Result<Option<ProjectMap>, AppError> {
+    let mut total: Option<ProjectMap> = None;
+    for log in logs.iter_mut() {
+        let times = if by_client {
+            log.tally_clients(interval)?
+        } else if by_user {
+            log.tally_users(interval)?
+        } else {
+            log.tally_time(interval)?
+        };
+        if let Some(times) = times {
+            total.get_or_insert_with(ProjectMap::new).merge(&times);
+        }
+    }
+    Ok(total)
+}
+
+// Helper function that groups the time spent within the given interval in each of the given logs
+// by the given keys, see `Grouping`. Work in progress lasts until `now`.
+fn group(
+    logs: &mut [LogFile],
+    interval: &time::Interval,
+    group_by: &GroupBy,
+    now: i64,
+) -> Result<Grouping, AppError> {
+    let mut grouping = Grouping::new(&group_by.0);
+    for log in logs.iter_mut() {
+        grouping.add(log.sessions(interval)?, interval, now);
+    }
+    Ok(grouping)
+}
+
+// Helper function that lists the billable and the non-billable time within the given interval in
+// the given logs, if any of the work was non-billable.
+fn print_billable(
+    logs: &mut [LogFile],
+    interval: &time::Interval,
+    time_format: &TimeFormat,
+) -> Result<(), AppError> {
+    let (mut billable, mut non_billable) = (0, 0);
+    for log in logs.iter_mut() {
+        let (log_billable, log_non_billable) = log.tally_billable(interval)?;
+        billable += log_billable;
+        non_billable += log_non_billable;
+    }
+    if non_billable > 0 {
+        println!(
+            "Billable => {}",
+            style::duration(&time::format_time(time_format, billable))
+        );
+        println!(
+            "Non-billable => {}",
+            style::duration(&time::format_time(time_format, non_billable))
+        );
+    }
+    Ok(())
+}
+
+/// The `summary` function corresponds to the `today` and `week` commands.
+///
+/// The function outputs the time spent on each project within the given interval as a bar chart,
+/// followed by the total time spent. Just like the `of` command it exits with an error code of 1 if
+/// no work was done.
+pub fn summary(
+    log: &mut LogFile,
+    interval_input: &str,
+    time_format: TimeFormat,
+) -> Result<ExitCode, AppError> {
+    let interval = time::Interval::try_parse(interval_input, &time::Search::Backward, log.clock())?;
+    match log.tally_time(&interval)? {
+        Some(map) => {
+            println!("{}", map.as_bars(&time_format, terminal_width(), &Terminal));
+            Ok(ExitCode::Success)
+        }
+        None => {
+            println!("No work done!");
+            Ok(ExitCode::Negative)
+        }
+    }
+}
+
+/// The `grid` function corresponds to the `grid` command.
+///
+/// The function draws the work done within the given interval as a grid of the days of the week by
+/// the quarters of an hour of the day, shaded by how much work was done within each, see `Grid`.
+/// Intervals longer than a week add up the work done on the same day of the week. Just like the
+/// `of` command it exits with an error code of 1 if no work was done.
+pub fn grid(
+    log: &mut LogFile,
+    interval_input: &str,
+    time_format: TimeFormat,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let interval = parse_of_interval(interval_input, assume, log.clock())?;
+    let now = log.now();
+    let grid = Grid::of_sessions(log.sessions(&interval)?, &interval, now);
+    if grid.is_empty() {
+        println!("No work done!");
+        return Ok(ExitCode::Negative);
+    }
+    println!(
+        "{}",
+        grid.as_text(&time_format, terminal_width(), &Terminal)
+    );
+    Ok(ExitCode::Success)
+}
+
+/// The `timeline` function corresponds to the `timeline` command.
+///
+/// The function draws the work done on each day within the given interval as a line of blocks per
+/// project running through the hours of the day, with gaps where no work was done, followed by the
+/// time spent on each project, see `Timeline`. Just like the `of` command it exits with an error
+/// code of 1 if no work was done.
+pub fn timeline(
+    log: &mut LogFile,
+    interval_input: &str,
+    time_format: TimeFormat,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let interval = parse_of_interval(interval_input, assume, log.clock())?;
+    let now = log.now();
+    let timeline = Timeline::new(log.sessions(&interval)?, &interval, now);
+    if timeline.is_empty() {
+        println!("No work done!");
+        return Ok(ExitCode::Negative);
+    }
+    println!(
+        "{}",
+        timeline.as_text(&time_format, terminal_width(), &Terminal)
+    );
+    Ok(ExitCode::Success)
+}
+
+/// The `standup` function corresponds to the `summary --daily-standup` command.
+///
+/// The function lists what was worked on during the last work day before today as bullets to paste
+/// into a daily standup, see `Standup`. Weekends and days off are skipped, so on a Monday it looks
+/// back on the Friday before.
+pub fn standup(log: &mut LogFile, time_format: TimeFormat) -> Result<ExitCode, AppError> {
+    let day = standup::last_work_day(log.clock().today(), &log.days_off()?);
+    let interval = time::Interval::new(
+        time::start_of_day(day),
+        Some(time::start_of_day(day.succ())),
+    );
+    let now = log.now();
+    let standup = Standup::new(log.sessions(&interval)?, day, now);
+    if standup.is_empty() {
+        println!("No work done on {}!", day.format("%A %d-%m-%Y"));
+        return Ok(ExitCode::Negative);
+    }
+    println!("{}", standup.as_text(&time_format));
+    Ok(ExitCode::Success)
+}
+
+/// The `stats` function corresponds to the `stats` command.
+///
+/// The function outputs the time worked in each of the last few complete weeks, either on all
+/// projects or only on the given project, along with the moving average of the time worked per
+/// week and whether it is trending up or down, see `Trend`.
+///
+/// If `histogram` is given, the function instead outputs the minutes worked within the given
+/// interval by hour of the day or by day of the week, as a table or as JSON, see `Histogram`. The
+/// archived work within the interval is counted as well.
+#[allow(clippy::too_many_arguments)]
+pub fn stats(
+    log: &mut LogFile,
+    project: Option<&str>,
+    trend: TrendLength,
+    histogram: Option<HistogramKind>,
+    interval_input: &str,
+    json: bool,
+    time_format: TimeFormat,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    if let Some(kind) = histogram {
+        let interval = parse_of_interval(interval_input, assume, log.clock())?;
+        let now = log.now();
+        let histogram =
+            Histogram::of_sessions(kind, log.sessions(&interval)?, &interval, project, now);
+        if json {
+            println!("{}", histogram.as_json());
+        } else {
+            println!("{}", histogram.as_table(&Terminal));
+        }
+        return Ok(ExitCode::Success);
+    }
+
+    let trend = Trend::new(log, trend.0, project)?;
+    println!("{}", trend.as_text(&time_format, &Terminal));
+    Ok(ExitCode::Success)
+}
+
+/// The `overtime` function corresponds to the `overtime` command.
+///
+/// The function compares the time worked in each week of the given interval to the weekly hours
+/// set in the config file, listing the balance of each week along with the balance so far, see
+/// `Overtime`. Days off aren't expected to be worked. Just like the `of` command it exits with an
+/// error code of 1 if no work was done.
+pub fn overtime(
+    log: &mut LogFile,
+    config: &Config,
+    interval_input: &str,
+    time_format: TimeFormat,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let weekly = match config.weekly_hours()? {
+        Some(weekly) => weekly,
+        None => {
+            return Err(AppError::new(ErrorKind::Config(
+                "Please set weekly_hours in the config file, e.g. weekly_hours = 40".to_string(),
+            )))
+        }
+    };
+    let interval = parse_of_interval(interval_input, assume, log.clock())?;
+    let overtime = Overtime::new(log, &interval, weekly)?;
+    if overtime.weeks.is_empty() {
+        println!("No work done!");
+        return Ok(ExitCode::Negative);
+    }
+    println!("{}", overtime.as_text(&time_format, &Terminal));
+    Ok(ExitCode::Success)
+}
+
+/// The `report` function corresponds to the `report` command.
+///
+/// The function outputs the time spent on each project per day within the given interval, followed
+/// by the time spent on each project over the whole interval. With `email` set the report is
+/// formatted as an email, with both a plain text and an HTML version, that can be piped into
+/// `sendmail -t`. With `send` set the email is sent to the recipients in the `[email]` section of
+/// the config file instead, see `Mailer`. With `template` set the report is rendered with the
+/// template in the given file, see `template::render_file`. With `by_user` set the time spent by
+/// each user takes the place of the time spent on each project, see `Report::by_user`. With
+/// `group_by` set the time spent is grouped by its keys instead, just like `of --group-by`. With
+/// `chart` set the time spent per day and on each project is drawn as bars, see
+/// `Report::as_chart`. With `rounding` set the time of each session is rounded up before it is
+/// added up, see `Report::rounded`, for each user too if `by_user` is set. Just like the `of` command it exits with an error code of 1 if no work was
+/// done.
+///
+/// The work done in each of the given `logs` is added up, such as the logs of every profile.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(
+    not(all(feature = "email", feature = "templates")),
+    allow(unused_variables)
+)]
+pub fn report(
+    logs: &mut [LogFile],
+    config: &Config,
+    interval_input: &str,
+    email: bool,
+    send: bool,
+    template: Option<&str>,
+    by_user: bool,
+    group_by: Option<&GroupBy>,
+    chart: bool,
+    rounding: Option<&Rounding>,
+    time_format: TimeFormat,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let clock = clock_of(logs);
+    let interval = parse_interval(interval_input, time::Search::Backward, assume, &clock)?;
+    let tally = |log: &mut LogFile, interval: &time::Interval| match rounding {
+        Some(rounding) if by_user => Report::rounded_by_user(log, interval, rounding),
+        Some(rounding) => Report::rounded(log, interval, rounding),
+        None if by_user => Report::by_user(log, interval),
+        None => Report::new(log, interval),
+    };
+    let mut report: Option<Report> = None;
+    for log in logs.iter_mut() {
+        let other = tally(log, &interval)?;
+        match report {
+            Some(ref mut report) => report.merge(other),
+            None => report = Some(other),
+        }
+    }
+    let report = match report {
+        Some(report) => report,
+        None => tally(&mut LogFile::in_memory(), &interval)?,
+    };
+    if report.is_empty() {
+        println!("No work done!");
+        return Ok(ExitCode::Negative);
+    }
+
+    if let Some(group_by) = group_by {
+        let grouping = group(logs, &interval, group_by, clock.now())?;
+        print!(
+            "{}\n\n{}",
+            report.title(),
+            grouping.as_text(&time_format, &Terminal)
+        );
+        println!(
+            "Total: {}",
+            time::format_time(&time_format, grouping.total())
+        );
+    } else if let Some(path) = template {
+        #[cfg(feature = "templates")]
+        print!(
+            "{}",
+            template::render_file(path, &report.to_value(&time_format))?
+        );
+        #[cfg(not(feature = "templates"))]
+        return Err(AppError::new(ErrorKind::User(
+            "Work was installed without the templates feature, reinstall it with --features \
+             templates to render reports with templates."
+                .to_string(),
+        ))
+        .with_code(ErrorCode::MissingFeature));
+    } else if send {
+        #[cfg(feature = "email")]
+        {
+            Mailer::from_config(config)?.send(
+                &report.title(),
+                report.as_text(&time_format, &Plain),
+                report.as_html(&time_format),
+            )?;
+            println!("Sent {}", report.title());
+        }
+        #[cfg(not(feature = "email"))]
+        return Err(AppError::new(ErrorKind::User(
+            "Work was installed without the email feature, reinstall it with --features email \
+             to send reports."
+                .to_string(),
+        ))
+        .with_code(ErrorCode::MissingFeature));
+    } else if email {
+        println!(
+            "{}",
+            report.as_email(
+                &time_format,
+                config.get("email.from"),
+                config.get("email.to")
+            )
+        );
+    } else if chart {
+        print!(
+            "{}",
+            report.as_chart(&time_format, terminal_width(), &Terminal)
+        );
+    } else {
+        print!("{}", report.as_text(&time_format, &Terminal));
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `export` function corresponds to the `export` command.
+///
+/// The function outputs the work done within the given interval as a standalone document in the
+/// given format. An HTML export holds the time spent on each project, a bar chart of the time
+/// worked per day and the list of sessions, see `Export`. A work CSV export lists the sessions in
+/// the format the `import` command reads, see `work_csv::write`. Just like the `report` command it
+/// exits with an error code of 1 if no work was done.
+pub fn export(
+    log: &mut LogFile,
+    interval_input: &str,
+    format: ExportFormat,
+    time_format: TimeFormat,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let interval = parse_interval(interval_input, time::Search::Backward, assume, log.clock())?;
+    let export = Export::new(log, &interval)?;
+    if export.is_empty() {
+        println!("No work done!");
+        return Ok(ExitCode::Negative);
+    }
+
+    match format {
+        ExportFormat::Html => print!("{}", export.as_html(&time_format)),
+        ExportFormat::WorkCsv => print!("{}", work_csv::write(&export.sessions)),
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `import` function corresponds to the `import` command.
+///
+/// The function reads sessions from the file at the given path, or from stdin if none is given,
+/// and merges them into the log, see `work_csv::reconcile`. Sessions already in the log are
+/// skipped, sessions starting at the same time as one in the log correct it, and the rest are
+/// added. Sessions that overlap other work or are within a locked period are refused unless
+/// `force` is set, and sessions that end in the future unless `allow_future` is set, in which case
+/// nothing is imported at all. If every session is in the log already, the function exits with an
+/// error code of 1.
+pub fn import(
+    log: &mut LogFile,
+    path: Option<&Path>,
+    format: ImportFormat,
+    delimiter: u8,
+    force: bool,
+    allow_future: bool,
+) -> Result<ExitCode, AppError> {
+    let input = match path {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| {
+            AppError::new(ErrorKind::User(format!(
+                "Unable to read {}: {}",
+                path.display(),
+                e
+            )))
+            .with_code(ErrorCode::InvalidInput)
+        })?,
+        None => {
+            let mut input = String::new();
+            io::Read::read_to_string(&mut io::stdin(), &mut input)?;
+            input
+        }
+    };
+    let imported = match format {
+        ImportFormat::WorkCsv => work_csv::parse(&input, delimiter)?,
+    };
+
+    let now = log.now();
+    let reconciled = work_csv::reconcile(&log.all_events()?, &imported, force, now)?;
+    for session in reconciled.added.iter().chain(&reconciled.corrected) {
+        check_future(
+            session.stop.unwrap_or(session.start),
+            "A session ends",
+            allow_future,
+            now,
+        )?;
+        check_locks(log, &span_of(session, now), force)?;
+    }
+    if reconciled.added.is_empty() && reconciled.corrected.is_empty() {
+        println!(
+            "Nothing to import, all {} sessions are in the log already.",
+            reconciled.duplicates
+        );
+        return Ok(ExitCode::Negative);
+    }
+
+    log.rewrite(&reconciled.events)?;
+    println!(
+        "{} {} new sessions and corrected {}, skipped {} already in the log.",
+        if log.is_dry_run() {
+            "Would import"
+        } else {
+            "Imported"
+        },
+        reconciled.added.len(),
+        reconciled.corrected.len(),
+        reconciled.duplicates
+    );
+    Ok(ExitCode::Success)
+}
+
+/// The `since` function corresponds to the `since` command.
+///
+/// The command makes sure that the user is free. If there is no work in progress, the command will
+/// append a `start` event with `project` name and `description` at the specified time and a `stop`
+/// event for the current time.
+///
+/// The command refuses to add a session that overlaps work already recorded in the log, unless
+/// `force` is set, or one that starts before the last event in the log, unless `reorder` is set to
+/// insert it in chronological order. Work that starts in the future is refused unless
+/// `allow_future` is set, which only makes sense along with `continue`. Work that is continued
+/// runs the `on_switch` and `on_start` hooks just like `start` does. Unless `quiet` is set, the
+/// command confirms the session it added.
+#[allow(clippy::too_many_arguments)]
+pub fn since(
+    log: &mut LogFile,
+    hooks: &Hooks,
+    time: &str,
+    project: Option<String>,
+    description: Option<String>,
+    client: Option<String>,
+    billable: Option<bool>,
+    r#continue: bool,
+    force: bool,
+    allow_future: bool,
+    reorder: bool,
+    quiet: bool,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let latest = log.get_latest_entry()?;
+    if is_working_now(&latest, log.now()) {
+        return Err(AppError::new(ErrorKind::User(
+            "Please stop the current work before registering new work.".to_string(),
+        ))
+        .with_code(ErrorCode::AlreadyWorking));
+    }
+
+    let now = log.now();
+    let interval = parse_interval(time, time::Search::Backward, assume, log.clock())?;
+    require_start(&interval)?;
+    let start_time = moment_of(&interval, now);
+    check_future(start_time, "The work starts", allow_future, now)?;
+    let interval = time::Interval::new(start_time, Some(now));
+    check_locks(log, &interval, force)?;
+    check_overlap(log, interval.start, interval.end, force)?;
+    let event = Event::Start(
+        project.clone(),
+        description.clone(),
+        client.clone(),
+        billable,
+        Metadata::new(),
+    );
+    if r#continue {
+        add_session(log, &[(start_time, event.clone())], reorder)?;
+        warn(hooks.started(&event, latest.as_ref().map(|(_, event)| event)));
+        if !quiet {
+            println!(
+                "Started {} at {}",
+                label(&event),
+                format_moment(start_time, now)
+            );
+        }
+        return Ok(ExitCode::Success);
+    }
+
+    let stop_time = now;
+    require_after_start(start_time, stop_time)?;
+    add_session(
+        log,
+        &[
+            (start_time, event.clone()),
+            (
+                stop_time,
+                Event::Stop(project, description, client, billable, Metadata::new()),
+            ),
+        ],
+        reorder,
+    )?;
+    if !quiet {
+        println!(
+            "Added {} from {} to {}, {}",
+            label(&event),
+            format_moment(start_time, now),
+            time::format_clock(stop_time),
+            format_duration(stop_time - start_time)
+        );
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `until` function corresponds to the `until` command.
+///
+/// The command makes sure that user is free. If there is no work in progress the command will
+/// append a `start` event for current time with `project` name and `description` and will finish by
+/// appending a `stop` event at the specified time.
+///
+/// The time is read as a duration from now if it is one, such as `1.5h` or `90 minutes`, see
+/// `time::parse_duration`, and as a time of day otherwise.
+///
+/// If `wait` is set the command keeps running until the specified time and then lets the user know
+/// that the time is up. Interrupting the command in the meantime stops the work early by moving the
+/// scheduled `stop` event to the time of the interruption.
+///
+/// The `on_start` hook is run right away, while the `on_stop` hook is only run when waiting.
+#[allow(clippy::too_many_arguments)]
+pub fn until(
+    log: &mut LogFile,
+    hooks: &Hooks,
+    time: &str,
+    project: Option<String>,
+    description: Option<String>,
+    client: Option<String>,
+    billable: Option<bool>,
+    wait: bool,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let latest = log.get_latest_entry()?;
+    let start_time = log.now();
+    if is_working_now(&latest, start_time) {
+        return Err(AppError::new(ErrorKind::User(
+            "Please stop the current work before starting new work.".to_string(),
+        ))
+        .with_code(ErrorCode::AlreadyWorking));
+    }
+
+    // Durations are told apart from times of day up front, so `work for 1.5h` and
+    // `work for 90 minutes` read as intended.
+    let stop_time = match time::parse_duration(time) {
+        Some(duration) if duration > 0 => start_time + duration,
+        _ => parse_interval(time, time::Search::Forward, assume, log.clock())?.end,
+    };
+    let event = Event::Start(
+        project.clone(),
+        description.clone(),
+        client.clone(),
+        billable,
+        Metadata::new(),
+    );
+    let stop_event = Event::Stop(project, description, client, billable, Metadata::new());
+    log.append_event(&event, start_time)?;
+    log.append_event(&stop_event, stop_time)?;
+    warn(hooks.started(&event, latest.as_ref().map(|(_, event)| event)));
+
+    if wait && !log.is_dry_run() {
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let handler_flag = Arc::clone(&interrupted);
+        if let Err(e) = ctrlc::set_handler(move || handler_flag.store(true, Ordering::SeqCst)) {
+            return Err(AppError::new(ErrorKind::System(format!(
+                "Unable to listen for interruptions: {}",
+                e
+            ))));
+        }
+
+        println!(
+            "Working on {} until {}, press Ctrl-C to stop early.",
+            event.to_string(),
+            time::format_clock(stop_time)
+        );
+        while log.now() < stop_time && !interrupted.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(250));
+        }
+
+        if interrupted.load(Ordering::SeqCst) {
+            // The work was started by this very command, so it isn't checked for locks again.
+            let now = log.now();
+            stop_scheduled_early(log, hooks, stop_time, now, None, true)?;
+            println!("Stopped working on {} early.", event.to_string());
+        } else {
+            warn(hooks.stopped(&stop_event, stop_time - start_time));
+            warn(hooks.time_up(&stop_event));
+            warn(hooks.worked(log, &stop_event, stop_time - start_time));
+            println!("\x07Time is up! Stopped working on {}.", event.to_string());
+        }
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `between` function corresponds to the `between` command.
+///
+/// The command makes sure that user is free. If there is no work in progress the command will
+/// append a `start` event at the specified start time with `project` name and `description` and
+/// will finish by appending a `stop` event at the specified end time.
+///
+/// The command refuses to add a session that overlaps work already recorded in the log, unless
+/// `force` is set, a session that ends in the future, unless `allow_future` is set, or a session
+/// that ends as soon as it starts. A session that starts before the last event in the log is only
+/// inserted in chronological order if `reorder` is set.
+#[allow(clippy::too_many_arguments)]
+pub fn between(
+    log: &mut LogFile,
+    time: &str,
+    project: Option<String>,
+    description: Option<String>,
+    client: Option<String>,
+    billable: Option<bool>,
+    force: bool,
+    allow_future: bool,
+    reorder: bool,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    if is_working_now(&log.get_latest_entry()?, log.now()) {
+        return Err(AppError::new(ErrorKind::User(
+            "Please stop the current work before starting new work.".to_string(),
+        ))
+        .with_code(ErrorCode::AlreadyWorking));
+    }
+
+    let interval = parse_interval(time, time::Search::Backward, assume, log.clock())?;
+    require_start(&interval)?;
+    require_after_start(interval.start, interval.end)?;
+    check_future(interval.end, "The session ends", allow_future, log.now())?;
+    check_locks(log, &interval, force)?;
+    check_overlap(log, interval.start, interval.end, force)?;
+    add_session(
+        log,
+        &[
+            (
+                interval.start,
+                Event::Start(
+                    project.clone(),
+                    description.clone(),
+                    client.clone(),
+                    billable,
+                    Metadata::new(),
+                ),
+            ),
+            (
+                interval.end,
+                Event::Stop(project, description, client, billable, Metadata::new()),
+            ),
+        ],
+        reorder,
+    )?;
+    Ok(ExitCode::Success)
+}
+
+/// The `while` function corresponds to the `while` command.
+///
+/// The command executes a given command tagged with the project name and description.
+/// This is done by searching for the `SHELL` environment variable and then executing that shell
+/// with the `-c` flag with the user inputted command appended to the back.
+///
+/// This will probably not work for windows machines or darwin/linux users who use a niche shell.
+/// If windows support is requested it is possible to add a windows compiler flag to handle that
+/// cause. Possibly by spawning powershell?
+///
+/// Interrupting Work, with Ctrl-C or a SIGTERM or SIGHUP, passes the signal on to the command
+/// rather than ending Work, see `Interrupts`, unless the terminal already sent it to the command
+/// along with Work, see `signals::forward`. The `stop` event is appended once the command ends
+/// however it ends, at the time it did.
+///
+/// A command that ends within `min_duration` isn't logged at all, its `start` event is removed
+/// again. If `idle_timeout` is set, the session is stopped at the last time anything was typed
+/// into the terminal once nothing was for that long, see `idle::last_input`, and a new session is
+/// started as soon as something is typed again.
+///
+/// During a dry run the command isn't executed at all, only the `start` event is printed.
+#[allow(clippy::too_many_arguments)]
+pub fn r#while(
+    log: &mut LogFile,
+    hooks: &Hooks,
+    cmd: &str,
+    project: Option<String>,
+    description: Option<String>,
+    client: Option<String>,
+    billable: Option<bool>,
+    min_duration: Option<i64>,
+    idle_timeout: Option<i64>,
+) -> Result<ExitCode, AppError> {
+    let latest = log.get_latest_entry()?;
+    if is_working_now(&latest, log.now()) {
+        return Err(AppError::new(ErrorKind::User(
+            "Please stop the current work before starting new work.".to_string(),
+        ))
+        .with_code(ErrorCode::AlreadyWorking));
+    }
+    if idle_timeout.is_some() && !idle::is_terminal() {
+        return Err(AppError::new(ErrorKind::User(
+            "--idle-timeout only works when Work is run in a terminal.".to_string(),
+        ))
+        .with_code(ErrorCode::InvalidInput));
+    }
+
+    // The command shouldn't run if the work can't be logged.
+    let event = Event::Start(
+        project.clone(),
+        description.clone(),
+        client.clone(),
+        billable,
+        Metadata::new(),
+    );
+    event.validate()?;
+
+    let shell = match env::var("SHELL") {
+        Ok(name) => name,
+        Err(_) => "sh".to_string(),
+    };
+
+    if log.is_dry_run() {
+        log.append_event_now(&event)?;
+        println!(
+            "Would run {} and append a stop event once it finishes.",
+            cmd
+        );
+        return Ok(ExitCode::Success);
+    }
+
+    // Signals are caught before the command starts, so none slips through before the session does.
+    let interrupts = Interrupts::catch()?;
+    let cmd: Vec<&str> = cmd.split_whitespace().collect();
+    let mut child = match Command::new(&shell).arg("-c").args(&cmd).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return Err(AppError::new(ErrorKind::System(format!(
+                "Failed to start {}: {}",
+                &shell, e
+            )))
+            .with_source(e)
+            .with_code(ErrorCode::CommandFailed));
+        }
+    };
+    let start_time = log.now();
+    log.append_event(&event, start_time)?;
+    warn(hooks.started(&event, latest.as_ref().map(|(_, event)| event)));
+
+    let stop_event = Event::Stop(project, description, client, billable, Metadata::new());
+    // The start of the session in progress, which is closed while the terminal is idle.
+    let mut session = Some(start_time);
+    let mut closed_at = start_time;
+    let mut interrupted = None;
+    let status = loop {
+        if let Some(signal) = interrupts.take() {
+            interrupted = Some(signal);
+            if let Err(e) = signals::forward(&mut child, signal) {
+                debug!("Unable to forward signal {} to the command: {}", signal, e);
+            }
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) => thread::sleep(Duration::from_millis(50)),
+            Err(e) => break Err(e),
+        }
+
+        let (limit, last_input) = match (idle_timeout, idle::last_input()) {
+            (Some(limit), Some(last_input)) => (limit, last_input),
+            _ => continue,
+        };
+        match session {
+            Some(session_start) if log.now() - last_input.max(session_start) > limit => {
+                let stop_time = last_input.max(session_start);
+                // Nothing was typed since the session started, so nothing was worked either.
+                if stop_time == session_start {
+                    discard_running(log, true)?;
+                } else {
+                    log.append_event(&stop_event, stop_time)?;
+                    warn(hooks.worked(log, &stop_event, stop_time - session_start));
+                }
+                warn(hooks.stopped(&stop_event, stop_time - session_start));
+                println!(
+                    "Stopped working on {} at {} as nothing was typed for {}.",
+                    event.to_string(),
+                    time::format_clock(stop_time),
+                    format_duration(limit)
+                );
+                session = None;
+                closed_at = stop_time;
+            }
+            // Other work may have been started in the meantime, which isn't interrupted.
+            None if last_input > closed_at
+                && !is_working_now(&log.get_latest_entry()?, log.now()) =>
+            {
+                log.append_event(&event, last_input)?;
+                warn(hooks.started(&event, Some(&stop_event)));
+                println!("Resumed working on {}.", event.to_string());
+                session = Some(last_input);
+            }
+            _ => {}
+        }
+    };
+
+    // The session is stopped however the command ended, so no work is left in progress.
+    if let Some(session_start) = session {
+        let duration = log.now() - session_start;
+        if session_start == start_time && duration < min_duration.unwrap_or(0) {
+            discard_running(log, true)?;
+            warn(hooks.stopped(&stop_event, duration));
+            println!(
+                "Not logging {} as the command ended within {}.",
+                event.to_string(),
+                format_duration(min_duration.unwrap_or(0))
+            );
+        } else {
+            log.append_event_now(&stop_event)?;
+            warn(hooks.stopped(&stop_event, duration));
+            warn(hooks.worked(log, &stop_event, duration));
+        }
+    }
+    if interrupted.is_some() {
+        println!(
+            "Stopped working on {} as the command was interrupted.",
+            event.to_string()
+        );
+    }
+
+    match status {
+        Ok(status) if status.success() => Ok(ExitCode::Success),
+        Ok(_) => Err(
+            AppError::new(ErrorKind::System("Process failed to execute".to_string()))
+                .with_code(ErrorCode::CommandFailed),
+        ),
+        Err(e) => Err(
+            AppError::new(ErrorKind::System(format!("Process failed to start: {}", e)))
+                .with_source(e)
+                .with_code(ErrorCode::CommandFailed),
+        ),
+    }
+}
+
+/// The `log_sessions` function corresponds to the `log` command.
+///
+/// The function lists every session in the log and its archives that overlaps the given interval,
+/// or all of them if no interval is given. Each session is listed along with its id, which other commands such as
+/// `delete` use to refer to it. Sessions that run past midnight are listed as a line per day if
+/// `split_at_midnight` is set. Sessions that don't pass the filter of the log are left out, see
+/// `LogFile::set_filter`.
+pub fn log_sessions(
+    log: &mut LogFile,
+    interval_input: Option<&str>,
+    split_at_midnight: bool,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let interval = match interval_input {
+        Some(input) => parse_interval(input, time::Search::Backward, assume, log.clock())?,
+        None => time::Interval::new(time::BEGINNING_OF_TIME, Some(i64::MAX)),
+    };
+
+    let now = log.now();
+    let sessions: Vec<_> = log
+        .sessions(&interval)?
+        .into_iter()
+        .filter(|session| session.overlaps(interval.start, interval.end, now))
+        .collect();
+
+    if sessions.is_empty() {
+        println!("No work done!");
+        return Ok(ExitCode::Negative);
+    }
+    print_sessions(&sessions, split_at_midnight, now);
+    Ok(ExitCode::Success)
+}
+
+// Helper function that lists the given sessions, as a line per day of each session if
+// `split_at_midnight` is set. Sessions in progress last until `now`.
+fn print_sessions(sessions: &[sessions::Session], split_at_midnight: bool, now: i64) {
+    for session in sessions {
+        if split_at_midnight {
+            session
+                .to_day_lines(now)
+                .iter()
+                .for_each(|line| println!("{}", line));
+        } else {
+            println!("{}", session.to_line(now));
+        }
+    }
+}
+
+/// The `delete` function corresponds to the `delete` command.
+///
+/// The function removes the session with the given id, or the last session in the log, by removing
+/// its `start` event and the `stop` event following it. If the session is still in progress only
+/// the trailing `start` event is removed.
+///
+/// The log is rewritten as a whole, unless `dry_run` is set in which case the function only prints
+/// the session that would have been removed. Sessions within a locked period are only removed if
+/// `force` is set.
+pub fn delete(
+    log: &mut LogFile,
+    session_id: Option<SessionId>,
+    last: bool,
+    dry_run: bool,
+    force: bool,
+) -> Result<ExitCode, AppError> {
+    let now = log.now();
+    let mut events = log.all_events()?;
+    let sessions = sessions::reconstruct(&events);
+
+    let session = match (session_id, sessions.last()) {
+        (Some(id), _) if !last => sessions::find(&sessions, id)?,
+        (_, Some(session)) => session,
+        (_, None) => {
+            return Err(AppError::new(ErrorKind::User(
+                "Unable to delete, the log contains no sessions!".to_string(),
+            ))
+            .with_code(ErrorCode::SessionNotFound))
+        }
+    };
+
+    check_locks(log, &span_of(session, now), force)?;
+    if dry_run {
+        println!("Would delete: {}", session.to_line(now));
+        return Ok(ExitCode::Success);
+    }
+
+    let index = start_index(&events, session);
+    match events.get(index + 1) {
+        Some((_, Event::Stop(..))) if session.stop.is_some() => {
+            events.drain(index..index + 2);
+        }
+        _ => {
+            events.remove(index);
+        }
+    }
+    log.rewrite(&events)?;
+    println!("Deleted: {}", session.to_line(now));
+    Ok(ExitCode::Success)
+}
+
+/// The `merge` function corresponds to the `merge` command.
+///
+/// The function combines two adjacent sessions of the same project into a single session by
+/// removing the `stop` event of the first session and the `start` event of the second one. The
+/// merged session keeps the description of the first session. Sessions within a locked period are
+/// only merged if `force` is set.
+pub fn merge(
+    log: &mut LogFile,
+    first: SessionId,
+    second: SessionId,
+    force: bool,
+) -> Result<ExitCode, AppError> {
+    let now = log.now();
+    let mut events = log.all_events()?;
+    let sessions = sessions::reconstruct(&events);
+    let (first, second) = (
+        sessions::find(&sessions, first.min(second))?,
+        sessions::find(&sessions, first.max(second))?,
+    );
+
+    if first.project != second.project {
+        return Err(AppError::new(ErrorKind::User(
+            "Unable to merge, the sessions belong to different projects!".to_string(),
+        )));
+    }
+    check_locks(log, &span_of(first, now), force)?;
+    check_locks(log, &span_of(second, now), force)?;
+
+    let index = start_index(&events, first);
+    match (events.get(index + 1), events.get(index + 2)) {
+        (Some((_, Event::Stop(..))), Some((timestamp, Event::Start(..))))
+            if *timestamp == second.start => {}
+        _ => {
+            return Err(AppError::new(ErrorKind::User(
+                "Unable to merge, the sessions are not adjacent!".to_string(),
+            )))
+        }
+    }
+
+    events.drain(index + 1..index + 3);
+    if let Some((_, event @ Event::Stop(..))) = events.get_mut(index + 1) {
+        *event = first.stop_event();
+    }
+    log.rewrite(&events)?;
+
+    let merged = &sessions::reconstruct(&events)[..];
+    println!(
+        "Merged: {}",
+        sessions::find(merged, first.id())?.to_line(now)
+    );
+    Ok(ExitCode::Success)
+}
+
+/// The `split` function corresponds to the `split` command.
+///
+/// The function splits a session into two sessions at the given time by inserting a `stop` event
+/// and a `start` event for the same project at that time. The given time has to be within the
+/// session. Sessions within a locked period are only split if `force` is set.
+pub fn split(
+    log: &mut LogFile,
+    session_id: SessionId,
+    time: &str,
+    force: bool,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let now = log.now();
+    let mut events = log.all_events()?;
+    let sessions = sessions::reconstruct(&events);
+    let session = sessions::find(&sessions, session_id)?;
+    check_locks(log, &span_of(session, now), force)?;
+
+    let split_time = parse_interval(time, time::Search::Backward, assume, log.clock())?.start;
+    if split_time <= session.start || split_time >= session.stop.unwrap_or(now) {
+        return Err(AppError::new(ErrorKind::User(
+            "Unable to split, the given time is not within the session!".to_string(),
+        )));
+    }
+
+    let index = start_index(&events, session);
+    events.insert(index + 1, (split_time, session.stop_event()));
+    events.insert(index + 2, (split_time, session.start_event()));
+    log.rewrite(&events)?;
+
+    let split_sessions = sessions::reconstruct(&events);
+    println!("Split into:");
+    println!(
+        "{}",
+        sessions::find(&split_sessions, session.id())?.to_line(now)
+    );
+    println!(
+        "{}",
+        sessions::find(&split_sessions, SessionId(split_time))?.to_line(now)
+    );
+    Ok(ExitCode::Success)
+}
+
+/// The `append` function corresponds to the `append` command.
+///
+/// The function appends a single event to the log at the given time, which is either a UNIX
+/// timestamp or any time `since` understands. Unlike a line written to the log by hand, the event
+/// is checked against the log first: it can't be earlier than the last event in the log, a `start`
+/// event has to follow a `stop` event and vice versa, it can't be within a locked period unless
+/// `force` is set, and it can't be in the future unless `allow_future` is set.
+///
+/// A `stop` event takes the project, description and client of the work it stops unless given,
+/// just like the events the `stop` command appends.
+#[allow(clippy::too_many_arguments)]
+pub fn append(
+    log: &mut LogFile,
+    time: &str,
+    kind: EventKind,
+    project: Option<String>,
+    description: Option<String>,
+    client: Option<String>,
+    billable: Option<bool>,
+    force: bool,
+    allow_future: bool,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let now = log.now();
+    let timestamp = match time.parse::<i64>() {
+        Ok(timestamp) => timestamp,
+        Err(_) => {
+            let interval = parse_interval(time, time::Search::Backward, assume, log.clock())?;
+            require_start(&interval)?;
+            moment_of(&interval, now)
+        }
+    };
+    check_future(timestamp, "The event is", allow_future, now)?;
+
+    let latest = log.get_latest_entry()?;
+    if let Some((latest_time, _)) = &latest {
+        if timestamp < *latest_time {
+            return Err(AppError::new(ErrorKind::User(format!(
+                "Unable to append, the last event in the log is later ({}).",
+                time::format_date_time(*latest_time)
+            )))
+            .with_code(ErrorCode::OutOfOrder));
+        }
+    }
+    check_lock_at(log, timestamp, force)?;
+
+    let event = match (kind, latest.map(|(_, event)| event)) {
+        (EventKind::Start, Some(Event::Start(..))) => {
+            return Err(AppError::new(ErrorKind::User(
+                "Unable to append, the last event in the log is a start event.".to_string(),
+            ))
+            .with_code(ErrorCode::AlreadyWorking))
+        }
+        (EventKind::Start, _) => {
+            Event::Start(project, description, client, billable, Metadata::new())
+        }
+        (
+            EventKind::Stop,
+            Some(Event::Start(started, started_desc, started_client, billable, metadata)),
+        ) => Event::Stop(
+            project.or(started),
+            description.or(started_desc),
+            client.or(started_client),
+            billable,
+            metadata,
+        ),
+        (EventKind::Stop, _) => {
+            return Err(AppError::new(ErrorKind::User(
+                "Unable to append, there is no work in progress to stop.".to_string(),
+            ))
+            .with_code(ErrorCode::NotWorking))
+        }
+    };
+    log.append_event(&event, timestamp)?;
+    Ok(ExitCode::Success)
+}
+
+/// The `doctor` function corresponds to the `doctor` command.
+///
+/// The function lists the projects in the log whose names only differ in case or surrounding
+/// whitespace, such as `Backend` and `backend`, since those are counted as separate projects unless
+/// `normalize_projects` is set in the config file. It also points out work that was started on an
+/// earlier day and is still in progress, which was most likely forgotten.
+///
+/// With `fix_case` set every project name in the log is normalized instead, see
+/// `normalize_project`, which unifies such projects for good. With `auto_stop` set forgotten work
+/// is stopped at the `auto_stop_at` time of the day it started, or at midnight if it started later,
+/// running the `on_stop` hooks just like `stop` does.
+///
+/// Sessions that stop before or at the moment they start are listed as well, since they count no
+/// time or even negative time. With `remove_empty` set they are removed from the log instead.
+///
+/// None of these changes are made within a locked period unless `force` is set.
+///
+/// Before any of that every line of the log is checked, and the first line that isn't an event,
+/// whose checksum doesn't match or that was cut off is reported, see `checksum::first_bad_line`.
+pub fn doctor(
+    log: &mut LogFile,
+    hooks: &Hooks,
+    auto_stop_at: Option<NaiveTime>,
+    fix_case: bool,
+    auto_stop: bool,
+    remove_empty: bool,
+    force: bool,
+) -> Result<ExitCode, AppError> {
+    if let Some(bad) = log.first_bad_line()? {
+        return Err(AppError::new(ErrorKind::LogFile(format!(
+            "Line {} of the log {}: {}\nFix or remove the line, or run `work restore` with a \
+             backup made before it.",
+            bad.number, bad.reason, bad.line
+        )))
+        .with_code(ErrorCode::LogCorrupt));
+    }
+    let today = log.clock().today();
+    let mut events = log.all_events()?;
+    let forgotten = forgotten_session(&events, today);
+
+    if remove_empty {
+        for session in sessions::Sessions::new(events.iter().cloned()) {
+            if session.is_empty() {
+                check_lock_at(log, session.start, force)?;
+            }
+        }
+        let removed = sessions::remove_empty(&mut events);
+        if removed > 0 {
+            log.rewrite(&events)?;
+        }
+        println!("Removed {} empty sessions.", removed);
+        return Ok(ExitCode::Success);
+    }
+
+    if auto_stop {
+        let (session, auto_stop_at) = match (forgotten, auto_stop_at) {
+            (None, _) => {
+                println!("No forgotten work found.");
+                return Ok(ExitCode::Success);
+            }
+            (_, None) => {
+                return Err(AppError::new(ErrorKind::Config(
+                    "Please set auto_stop_at in the config file, e.g. auto_stop_at = 18:00"
+                        .to_string(),
+                )))
+            }
+            (Some(session), Some(auto_stop_at)) => (session, auto_stop_at),
+        };
+        let stop_time = auto_stop_time(session.start, auto_stop_at);
+        check_locks(
+            log,
+            &time::Interval::new(session.start, Some(stop_time)),
+            force,
+        )?;
+        append_stop(log, hooks, &session.stop_event(), session.start, stop_time)?;
+        println!(
+            "Stopped {} at {}",
+            label(&session.start_event()),
+            time::format_date_time(stop_time)
+        );
+        return Ok(ExitCode::Success);
+    }
+
+    if fix_case {
+        let normalized: Vec<(i64, Event)> = events
+            .iter()
+            .map(|(timestamp, event)| (*timestamp, event.normalized()))
+            .collect();
+        let mut renamed = 0;
+        for (event, normalized) in events.iter().zip(&normalized) {
+            if event != normalized {
+                check_lock_at(log, event.0, force)?;
+                renamed += 1;
+            }
+        }
+        if renamed > 0 {
+            log.rewrite(&normalized)?;
+        }
+        println!("Normalized the project names of {} events.", renamed);
+        return Ok(ExitCode::Success);
+    }
+
+    if let Some(session) = &forgotten {
+        println!(
+            "Working on {} since {}, it was most likely forgotten.",
+            session.start_event().to_string(),
+            time::format_date_time(session.start)
+        );
+        match auto_stop_at {
+            Some(auto_stop_at) => println!(
+                "Run `work doctor --auto-stop` to stop it at {}.",
+                time::format_date_time(auto_stop_time(session.start, auto_stop_at))
+            ),
+            None => println!(
+                "Run `work stop <TIME>` to stop it, or set auto_stop_at in the config file and \
+                 run `work doctor --auto-stop`."
+            ),
+        }
+    }
+
+    // Sessions are paired up in the order of the log here, as they are when they are removed.
+    let empty: Vec<sessions::Session> = sessions::Sessions::new(events.iter().cloned())
+        .filter(|session| session.is_empty())
+        .collect();
+    for session in &empty {
+        println!(
+            "Session {} of {} at {} stops {}.",
+            session.id(),
+            session.start_event().to_string(),
+            time::format_date_time(session.start),
+            if session.stop == Some(session.start) {
+                "as soon as it starts"
+            } else {
+                "before it starts"
+            }
+        );
+    }
+    if !empty.is_empty() {
+        println!("Run `work doctor --remove-empty` to remove them.");
+    }
+
+    let mut spellings: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (_, event) in &events {
+        if let Event::Start(Some(project), ..) = event {
+            spellings
+                .entry(normalize_project(project))
+                .or_default()
+                .insert(project.to_string());
+        }
+    }
+    let duplicates: Vec<&BTreeSet<String>> =
+        spellings.values().filter(|names| names.len() > 1).collect();
+    if duplicates.is_empty() {
+        if forgotten.is_none() && empty.is_empty() {
+            println!("No problems found.");
+        }
+        return Ok(ExitCode::Success);
+    }
+
+    for names in duplicates {
+        let names: Vec<String> = names
+            .iter()
+            .map(|name| format!("\"{}\"", style::project(name)))
+            .collect();
+        println!("{} are counted as separate projects.", names.join(", "));
+    }
+    println!("Run `work doctor --fix-case` to unify them.");
+    Ok(ExitCode::Success)
+}
+
+// Helper function that returns the final session in the log if it is still in progress even though
+// it started on a day before `today`.
+fn forgotten_session(events: &[(i64, Event)], today: NaiveDate) -> Option<sessions::Session> {
+    let session = sessions::reconstruct(events).pop()?;
+    if session.stop.is_none() && time::date_of(session.start) < today {
+        Some(session)
+    } else {
+        None
+    }
+}
+
+// Helper function that returns when forgotten work that started at `start` is stopped, at
+// `auto_stop_at` on the day it started or at midnight if it started after that.
+fn auto_stop_time(start: i64, auto_stop_at: NaiveTime) -> i64 {
+    let day = time::date_of(start);
+    let stop_time = Local
+        .from_local_datetime(&day.and_time(auto_stop_at))
+        .earliest()
+        .map_or(start, |stop_time| stop_time.timestamp());
+    if stop_time > start {
+        stop_time
+    } else {
+        time::start_of_day(day.succ())
+    }
+}
+
+/// The `lock` function corresponds to the `lock` command.
+///
+/// The function locks the given period, for example once its timesheet has been submitted.
+/// Sessions within a locked period can't be added with `since` or `between`, nor changed with
+/// `delete`, `merge` or `split`, unless `--force` is given. Without a period the function lists the
+/// locked periods instead.
+pub fn lock(
+    log: &mut LogFile,
+    interval_input: Option<&str>,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let mut locks = log.locks()?;
+    let interval_input = match interval_input {
+        Some(interval_input) => interval_input,
+        None => {
+            if locks.periods().is_empty() {
+                println!("No periods are locked.");
+            }
+            locks
+                .periods()
+                .iter()
+                .for_each(|period| println!("{}", locks::format_period(period)));
+            return Ok(ExitCode::Success);
+        }
+    };
+
+    let interval = parse_interval(interval_input, time::Search::Backward, assume, log.clock())?;
+    locks.lock(interval);
+    log.save_locks(&locks)?;
+    println!(
+        "{} {}",
+        if log.is_dry_run() {
+            "Would lock"
+        } else {
+            "Locked"
+        },
+        locks::format_period(&interval)
+    );
+    Ok(ExitCode::Success)
+}
+
+/// The `archive` function corresponds to the `archive` command.
+///
+/// The function moves the sessions within the given year, such as `2022`, or interval out of the
+/// log into compressed archives next to the log, one per year, see `LogFile::archive`. The time
+/// within the archives is still counted by `of` and `report`. Exits with an error code of 1 if
+/// there was nothing to archive.
+pub fn archive(
+    log: &mut LogFile,
+    interval_input: &str,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let year = interval_input
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|_| interval_input.trim().len() == 4);
+    let interval = match year.and_then(archive::year_interval) {
+        Some(interval) => interval,
+        None => parse_interval(interval_input, time::Search::Backward, assume, log.clock())?,
+    };
+
+    let archived = log.archive(&interval)?;
+    if archived.is_empty() {
+        println!("Nothing to archive!");
+        return Ok(ExitCode::Negative);
+    }
+    for (year, count) in archived {
+        println!(
+            "{} {} {} into {}",
+            if log.is_dry_run() {
+                "Would archive"
+            } else {
+                "Archived"
+            },
+            count,
+            if count == 1 { "session" } else { "sessions" },
+            archive::file_name(year)
+        );
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `backup` function corresponds to the `backup` command.
+///
+/// The function copies the log and the config file into a new backup within the given directory,
+/// or within the `backups` directory next to the log, compressing the copies if `gzip` is set. See
+/// `Backup`.
+pub fn backup(log: &LogFile, within: Option<&Path>, gzip: bool) -> Result<ExitCode, AppError> {
+    let backup = log.backup(within, gzip)?;
+    println!("Backed up to {}", backup.path().display());
+    Ok(ExitCode::Success)
+}
+
+/// The `restore` function corresponds to the `restore` command.
+///
+/// The function checks that the backup at the given path can be restored and then replaces the log
+/// and the config file with the ones in the backup, see `LogFile::restore`. The current log and
+/// config file are backed up first, so the restore can be undone by restoring that backup.
+pub fn restore(log: &mut LogFile, path: &Path) -> Result<ExitCode, AppError> {
+    let backup = Backup::open(path)?;
+    if log.is_dry_run() {
+        backup.validate()?;
+        println!("Would restore {}", backup.path().display());
+        return Ok(ExitCode::Success);
+    }
+    let current = log.restore(&backup)?;
+    println!("Restored {}", backup.path().display());
+    println!(
+        "The log and config file before the restore were backed up to {}",
+        current.path().display()
+    );
+    Ok(ExitCode::Success)
+}
+
+/// The `off` function corresponds to the `off` command.
+///
+/// The function marks the given day, or every day of the given interval such as `monday - friday`,
+/// as a day off for the given `reason`. Days off are usually planned ahead, so ambiguous days are
+/// looked for in the future unless assumed otherwise. Without any days the function lists the days
+/// off instead.
+pub fn off(
+    log: &mut LogFile,
+    interval_input: Option<&str>,
+    reason: Option<&str>,
+    assume: Option<Assume>,
+) -> Result<ExitCode, AppError> {
+    let mut days_off = log.days_off()?;
+    let interval_input = match interval_input {
+        Some(interval_input) => interval_input,
+        None => {
+            if days_off.days().is_empty() {
+                println!("No days off.");
+            }
+            days_off
+                .days()
+                .iter()
+                .for_each(|(day, reason)| println!("{}", days_off::format_day(*day, reason)));
+            return Ok(ExitCode::Success);
+        }
+    };
+
+    let search = match assume {
+        Some(Assume::Backward) => time::Search::Backward,
+        _ => time::Search::Forward,
+    };
+    let days = interval_input
+        .split(" - ")
+        .map(|day| parse_day(day.trim(), search, log.clock()))
+        .collect::<Result<Vec<_>, AppError>>()?;
+    let (first, last) = match days[..] {
+        [day] => (day, day),
+        [first, last] => (first.min(last), first.max(last)),
+        _ => {
+            return Err(AppError::new(ErrorKind::User(format!(
+                "Invalid days off: {}",
+                interval_input
+            )))
+            .with_code(ErrorCode::InvalidTime))
+        }
+    };
+
+    let reason = reason.unwrap_or("");
+    if reason.contains(&['\n', '\r'][..]) {
+        return Err(AppError::new(ErrorKind::User(
+            "The reason can't contain line breaks.".to_string(),
+        )));
+    }
+    days_off.add(first, last, reason);
+    log.save_days_off(&days_off)?;
+    let marked = if log.is_dry_run() {
+        "Would mark"
+    } else {
+        "Marked"
+    };
+    if first == last {
+        println!(
+            "{} {} as a day off",
+            marked,
+            days_off::format_day(first, reason)
+        );
+    } else {
+        println!(
+            "{} {} - {} as days off",
+            marked,
+            days_off::format_day(first, ""),
+            days_off::format_day(last, reason)
+        );
+    }
+    Ok(ExitCode::Success)
+}
+
+// Helper function that parses a single day off, either a plain date such as `24-12-2026` or any
+// time within the day, relative to the given clock.
+fn parse_day(input: &str, search: time::Search, clock: &dyn Clock) -> Result<NaiveDate, AppError> {
+    match NaiveDate::parse_from_str(input, "%d-%m-%Y") {
+        Ok(day) => Ok(day),
+        Err(_) => Ok(time::parse_datetime(input, search, clock)?.date()),
+    }
+}
+
+/// The `cache` function corresponds to the `cache` command.
+///
+/// The cache of daily totals is kept up to date automatically, `rebuild` simply forces it to be
+/// rebuilt from the log.
+pub fn cache(log: &mut LogFile, action: CacheAction) -> Result<ExitCode, AppError> {
+    match action {
+        CacheAction::Rebuild => {
+            let cache = log.rebuild_cache()?;
+            println!("Cached the totals of {} days.", cache.cached_days());
+        }
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `remind` function corresponds to the `remind` command.
+///
+/// The function reminds the user to start tracking if no work has been started today by the time
+/// of the reminder, see `Reminder::is_due`, and exits with an error code of 1 if it did. The
+/// reminder is printed and, with the `notifications` feature, shown as a desktop notification.
+///
+/// `remind install` prints a crontab line, or a systemd service and timer, that runs the reminder
+/// at its time with the executable of Work that is running.
+pub fn remind(
+    log: &mut LogFile,
+    hooks: &Hooks,
+    at: Option<TimeOfDay>,
+    weekdays: bool,
+    action: Option<&RemindAction>,
+) -> Result<ExitCode, AppError> {
+    // The options can be given either before or after `install`.
+    let (at, weekdays) = match action {
+        Some(RemindAction::Install {
+            at: install_at,
+            weekdays: install_weekdays,
+            ..
+        }) => (install_at.or(at), *install_weekdays || weekdays),
+        None => (at, weekdays),
+    };
+    let reminder = match at {
+        Some(at) => Reminder { at: at.0, weekdays },
+        None => {
+            return Err(AppError::new(ErrorKind::User(
+                "Please give the time of the reminder, e.g. --at 9:00.".to_string(),
+            ))
+            .with_code(ErrorCode::InvalidInput))
+        }
+    };
+
+    if let Some(RemindAction::Install { systemd, .. }) = action {
+        let executable = env::current_exe()?;
+        if *systemd {
+            let service = reminder.service(service::environment()?);
+            for (name, unit) in Manager::Systemd.files(&service, &executable, None) {
+                println!("# ~/.config/systemd/user/{}\n{}", name, unit);
+            }
+        } else {
+            println!("{}", reminder.crontab_line(&executable));
+        }
+        return Ok(ExitCode::Success);
+    }
+
+    let now = log.now();
+    let date_time = Local.timestamp(now, 0).naive_local();
+    let day_off = log.days_off()?.get(date_time.date()).is_some();
+    if !reminder.is_due(date_time, day_off) || is_working_now(&log.get_latest_entry()?, now) {
+        return Ok(ExitCode::Success);
+    }
+    let today = time::Interval::new(time::start_of_day(date_time.date()), Some(now));
+    if log.tally_time(&today)?.is_some() {
+        return Ok(ExitCode::Success);
+    }
+    println!("{}", style::warning(&reminder.message()));
+    warn(hooks.remind(&reminder.message()));
+    Ok(ExitCode::Negative)
+}
+
+/// The `install_service` function corresponds to the `install-service` command.
+///
+/// The function writes the systemd units, or the launchd property list on macOS, that run `auto`
+/// or `remind` in the background with the data directory and the profile in use, either
+/// system-wide as the current user or for the current user alone, and prints how to start them.
+#[cfg_attr(not(feature = "auto"), allow(unused_variables))]
+pub fn install_service(
+    kind: ServiceKind,
+    user: bool,
+    at: Option<TimeOfDay>,
+    weekdays: bool,
+    interval: u64,
+) -> Result<ExitCode, AppError> {
+    let environment = service::environment()?;
+    let service = match kind {
+        #[cfg(feature = "auto")]
+        ServiceKind::Auto => auto::service(interval, environment),
+        #[cfg(not(feature = "auto"))]
+        ServiceKind::Auto => return Err(without_auto()),
+        ServiceKind::Remind => match at {
+            Some(at) => Reminder { at: at.0, weekdays }.service(environment),
+            None => {
+                return Err(AppError::new(ErrorKind::User(
+                    "Please give the time of the reminder, e.g. --at 9:00.".to_string(),
+                ))
+                .with_code(ErrorCode::InvalidInput))
+            }
+        },
+    };
+    let run_as = if user { None } else { service::current_user() };
+    let manager = Manager::current();
+    let files = manager.files(&service, &env::current_exe()?, run_as.as_deref());
+    let dir = manager.dir(user)?;
+    for path in service::install(&files, &dir)? {
+        println!("Installed {}", path.display());
+    }
+    println!(
+        "Start it with `{}`",
+        manager.start_command(&service, &dir, user)
+    );
+    Ok(ExitCode::Success)
+}
+
+/// The `uninstall_service` function corresponds to the `uninstall-service` command.
+///
+/// The function removes the files written by `install-service` and prints how to stop the
+/// service. If the service isn't installed, the function returns an error message.
+pub fn uninstall_service(kind: ServiceKind, user: bool) -> Result<ExitCode, AppError> {
+    let name = match kind {
+        #[cfg(feature = "auto")]
+        ServiceKind::Auto => auto::UNIT,
+        #[cfg(not(feature = "auto"))]
+        ServiceKind::Auto => return Err(without_auto()),
+        ServiceKind::Remind => remind::UNIT,
+    };
+    let manager = Manager::current();
+    let dir = manager.dir(user)?;
+    let paths = service::uninstall(&manager.file_names(name), &dir)?;
+    if paths.is_empty() {
+        return Err(AppError::new(ErrorKind::User(format!(
+            "No {} service is installed in {}.",
+            name,
+            dir.display()
+        )))
+        .with_code(ErrorCode::InvalidInput));
+    }
+    for path in paths {
+        println!("Removed {}", path.display());
+    }
+    println!(
+        "Stop it with `{}`",
+        manager.stop_command(name, kind == ServiceKind::Remind, user)
+    );
+    Ok(ExitCode::Success)
+}
+
+// Helper function that returns the error of a command that needs the auto feature.
+#[cfg(not(feature = "auto"))]
+fn without_auto() -> AppError {
+    AppError::new(ErrorKind::User(
+        "Work was installed without the auto feature, reinstall it with --features auto to track \
+         work automatically."
+            .to_string(),
+    ))
+    .with_code(ErrorCode::MissingFeature)
+}
+
+/// The `profile` function corresponds to the `profile` command.
+///
+/// The function lists the profiles, marking the one in use with a `*`, creates a new profile, or
+/// makes a profile the one in use when no other is given, see `profiles`.
+pub fn profile(action: &ProfileAction) -> Result<ExitCode, AppError> {
+    match action {
+        ProfileAction::List => {
+            let current = profiles::current()?;
+            for profile in profiles::list()? {
+                let marker = if profile == current { "*" } else { " " };
+                println!("{} {}", marker, profile);
+            }
+        }
+        ProfileAction::Create { name } => {
+            let dir = profiles::create(name)?;
+            println!("Created the profile {} in {}", name, dir.display());
+        }
+        ProfileAction::Switch { name } => {
+            profiles::switch(name)?;
+            println!("Switched to the profile {}", name);
+        }
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `team` function corresponds to the `team` command.
+///
+/// The function reports the time each member of a team spent on each project within the given
+/// interval, out of the logs of the members within the given directory, see `TeamReport`. Project
+/// names are normalized if `normalize_projects` is set in the config file. Just like the `report`
+/// command it exits with an error code of 1 if no work was done.
+pub fn team(action: &TeamAction, assume: Option<Assume>) -> Result<ExitCode, AppError> {
+    match action {
+        TeamAction::Report {
+            interval,
+            logs,
+            json,
+            raw_seconds,
+            time_format,
+        } => {
+            let config = Config::load()?;
+            let mut members = team::member_logs(logs)?;
+            // The members are read at a single point in time, the current time of the first one.
+            let now = members.first().map_or_else(time::now, |(_, log)| log.now());
+            let interval = parse_interval(
+                interval,
+                time::Search::Backward,
+                assume,
+                &time::FixedClock(now),
+            )?;
+            let normalize = config.get("normalize_projects") == Some("true");
+            let report = TeamReport::new(&mut members, &interval, normalize)?;
+            if report.is_empty() {
+                println!("No work done!");
+                return Ok(ExitCode::Negative);
+            }
+            if *json {
+                println!("{}", report.to_value(time_format, *raw_seconds));
+            } else {
+                print!("{}", report.as_text(time_format));
+            }
+        }
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `projects` function corresponds to the `projects` command.
+///
+/// The function lists every project work has been started on in the log, one per line.
+pub fn projects(log: &mut LogFile) -> Result<ExitCode, AppError> {
+    for project in log.projects()? {
+        println!("{}", project);
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `exit_codes` function corresponds to the `exitcodes` command.
+///
+/// The function lists every exit code of Work along with what it means, followed by every error
+/// code.
+pub fn exit_codes() -> Result<ExitCode, AppError> {
+    for exit_code in ExitCode::ALL.iter() {
+        println!("{}  {}", exit_code.code(), exit_code.description());
+    }
+    println!();
+    let width = ErrorCode::ALL
+        .iter()
+        .map(|code| code.name().len())
+        .max()
+        .unwrap_or(0);
+    for code in ErrorCode::ALL.iter() {
+        println!(
+            "{:<width$}  {}",
+            code.name(),
+            code.description(),
+            width = width
+        );
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `help` function corresponds to the `help` command.
+///
+/// The function prints the help of Work as a whole, or of the given command, just like `--help`
+/// does. The topic `times` explains every time input Work understands instead, resolving an example
+/// of each against the current time.
+pub fn help(topic: Option<&str>) -> Result<ExitCode, AppError> {
+    let mut app = Args::command();
+    match topic {
+        None => {
+            app.print_long_help().map_err(|e| {
+                AppError::new(ErrorKind::System(format!(
+                    "Unable to print the help: {}",
+                    e
+                )))
+            })?;
+            println!();
+        }
+        Some("times") => println!("{}", help::times()),
+        Some(command) => match app.try_get_matches_from_mut(["work", command, "--help"]) {
+            Err(e) if e.kind() == ClapErrorKind::DisplayHelp => print!("{}", e.render()),
+            _ => {
+                return Err(AppError::new(ErrorKind::User(format!(
+                    "There is no command or topic called {}, run `work help` to list the \
+                     commands, the other topics are: {}",
+                    command,
+                    help::TOPICS.join(", ")
+                )))
+                .with_code(ErrorCode::UnknownCommand));
+            }
+        },
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `completions` function corresponds to the `completions` command.
+///
+/// The function prints the script that completes the commands and options of Work in the given
+/// shell, generated from the same definitions the arguments are parsed with.
+pub fn completions(shell: Shell) -> Result<ExitCode, AppError> {
+    clap_complete::generate(shell, &mut Args::command(), "work", &mut io::stdout());
+    Ok(ExitCode::Success)
+}
+
+/// The `external` function corresponds to any command Work doesn't know itself.
+///
+/// Just like git, the function runs the executable `work-NAME` on the PATH for a command `NAME`,
+/// passing it the remaining arguments. Rather than an `ExitCode` the function returns the code the
+/// executable exited with, so scripts calling it can rely on it.
+pub fn external(command: &[String]) -> Result<i32, AppError> {
+    let (name, arguments) = match command.split_first() {
+        Some(split) => split,
+        None => {
+            return Err(AppError::new(ErrorKind::User(
+                "No command was given, see `work help`".to_string(),
+            )))
+        }
+    };
+    // A name holding a slash would run an executable by its path rather than one on the PATH.
+    if name.contains('/') {
+        return Err(AppError::new(ErrorKind::User(format!(
+            "Unknown command '{}', the names of commands can't contain '/'. See `work help` for \
+             the commands Work knows.",
+            name
+        )))
+        .with_code(ErrorCode::UnknownCommand));
+    }
+    let executable = format!("work-{}", name);
+    debug!("Running external command {} {:?}", executable, arguments);
+    match Command::new(&executable).args(arguments).status() {
+        Ok(status) => Ok(status
+            .code()
+            .unwrap_or_else(|| ExitCode::SystemError.code())),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+            Err(AppError::new(ErrorKind::User(format!(
+                "Unknown command '{}', nor is there an executable named {} on the PATH. See \
+                 `work help` for the commands Work knows.",
+                name, executable
+            )))
+            .with_code(ErrorCode::UnknownCommand))
+        }
+        Err(e) => Err(AppError::new(ErrorKind::System(format!(
+            "Unable to run {}: {}",
+            executable, e
+        )))
+        .with_source(e)
+        .with_code(ErrorCode::CommandFailed)),
+    }
+}
+
+/// The `statusbar` function corresponds to the `statusbar` command.
+///
+/// The function outputs the status of the log in the format the given status bar expects, see
+/// `statusbar::render`. If an `interval` is given, the function keeps running and outputs the
+/// status again every `interval` seconds, for bars that read the output of a long running command.
+/// The log is then opened anew every time, so changes to it are always picked up.
+pub fn statusbar(
+    config: &Config,
+    style: BarStyle,
+    interval: Option<u64>,
+) -> Result<ExitCode, AppError> {
+    loop {
+        let mut log = LogFile::new(config)?;
+        let entry = log.get_latest_entry()?;
+        println!("{}", statusbar::render(&entry, log.now(), style));
+        match interval {
+            Some(seconds) => thread::sleep(Duration::from_secs(seconds.max(1))),
+            None => return Ok(ExitCode::Success),
+        }
+    }
+}
+
+/// The `prompt` function corresponds to the `prompt` command.
+///
+/// The function prints a compact indicator of the work in progress, or nothing if there is none,
+/// see `statusbar::prompt`. Prompts run it before every command, so only the final line of the
+/// log is read, see `LogFile::get_latest_entry`, keeping it fast however long the log gets.
+pub fn prompt(log: &mut LogFile, warn_after: Option<i64>) -> Result<ExitCode, AppError> {
+    let indicator = statusbar::prompt(&log.get_latest_entry()?, log.now(), warn_after);
+    if !indicator.is_empty() {
+        println!("{}", indicator);
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `serve` function corresponds to the `serve` command.
+///
+/// The function answers requests on stdin until the input ends, see `rpc::serve_stdio`. Standard
+/// IO is the only way to talk to `serve` for now, so `stdio` has to be set.
+pub fn serve(config: &Config, stdio: bool) -> Result<ExitCode, AppError> {
+    if !stdio {
+        return Err(AppError::new(ErrorKind::User(
+            "Please pass --stdio, the only way `serve` can be talked to for now.".to_string(),
+        )));
+    }
+    rpc::serve_stdio(config)?;
+    Ok(ExitCode::Success)
+}
+
+/// The `auto` function corresponds to the `auto` command.
+///
+/// The function either watches what is being worked on and queues sessions for it, see
+/// `auto::watch`, or reviews the queue. Accepted sessions are merged into the log just like
+/// imported ones, see `work_csv::reconcile`, so sessions that overlap other work or are within a
+/// locked period are refused unless `force` is set. The queue itself is left untouched by a dry
+/// run.
+#[cfg(feature = "auto")]
+pub fn auto(log: &mut LogFile, config: &Config, action: &AutoAction) -> Result<ExitCode, AppError> {
+    let mut queue = log.auto_queue()?;
+    match action {
+        AutoAction::Run { interval } => {
+            let rules = Rules::from_config(config)?;
+            if rules.is_empty() {
+                return Err(AppError::new(ErrorKind::Config(
+                    "Please add rules to the [auto] section of the config file first, such as \
+                     backend = window:work-api, process:cargo."
+                        .to_string(),
+                )));
+            }
+            auto::watch(&mut queue, &rules, *interval, |noticed| match noticed {
+                Noticed::Discarded(start, event) => println!(
+                    "Discarded {} queued at {} by a previous run.",
+                    event.to_string(),
+                    time::format_date_time(start)
+                ),
+                Noticed::Working(project, start) => {
+                    println!("Working on {} since {}", project, time::format_clock(start))
+                }
+            })?;
+        }
+        AutoAction::Review => {
+            let queued = auto::queued(&mut queue)?;
+            if queued.is_empty() {
+                println!("No sessions are queued.");
+                return Ok(ExitCode::Negative);
+            }
+            print_sessions(&queued, false, log.now());
+        }
+        AutoAction::Accept { ids, force } => {
+            let queued = auto::queued(&mut queue)?;
+            let picked = auto::pick(&queued, ids)?;
+            if picked.is_empty() {
+                println!("No finished sessions are queued.");
+                return Ok(ExitCode::Negative);
+            }
+            let now = log.now();
+            let reconciled = work_csv::reconcile(&log.all_events()?, &picked, *force, now)?;
+            for session in reconciled.added.iter().chain(&reconciled.corrected) {
+                check_locks(log, &span_of(session, now), *force)?;
+            }
+            log.rewrite(&reconciled.events)?;
+            queue.rewrite(&auto::remaining(&queued, &picked))?;
+            println!(
+                "{} {} sessions to the log.",
+                if log.is_dry_run() {
+                    "Would add"
+                } else {
+                    "Added"
+                },
+                reconciled.added.len() + reconciled.corrected.len()
+            );
+        }
+        AutoAction::Discard { ids } => {
+            let queued = auto::queued(&mut queue)?;
+            let picked = auto::pick(&queued, ids)?;
+            queue.rewrite(&auto::remaining(&queued, &picked))?;
+            println!(
+                "{} {} queued sessions.",
+                if log.is_dry_run() {
+                    "Would discard"
+                } else {
+                    "Discarded"
+                },
+                picked.len()
+            );
+        }
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `presence` function corresponds to the `presence` command.
+///
+/// The function sets the Slack status to the work in progress, or clears it if there is none. The
+/// status is kept up to date by `start` and `stop` as well, this is for when it got out of sync.
+#[cfg(feature = "presence")]
+pub fn presence(log: &mut LogFile, config: &Config) -> Result<ExitCode, AppError> {
+    let presence = match Presence::from_config(config) {
+        Some(presence) => presence,
+        None => {
+            return Err(AppError::new(ErrorKind::Config(
+                "Please configure a Slack token (presence.slack_token) first.".to_string(),
+            )))
+        }
+    };
+    let entry = log.get_latest_entry()?;
+    match &entry {
+        Some((_, event)) if is_working_now(&entry, log.now()) => presence.working(event)?,
+        _ => presence.free()?,
+    }
+    Ok(ExitCode::Success)
+}
+
+/// The `server` function corresponds to the `server` command.
+///
+/// The function serves the HTTP API described in `server::serve` until the process is stopped.
+#[cfg(feature = "server")]
+pub fn server(config: &Config, address: &str) -> Result<ExitCode, AppError> {
+    server::serve(config, address)?;
+    Ok(ExitCode::Success)
+}
@@ -0,0 +1,234 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, NaiveDate};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::error::{AppError, ErrorCode, ErrorKind};
+use crate::log_file::Event;
+use crate::sessions::{self, Session};
+use crate::time::{self, Interval};
+
+/// Start of the name of an archive, which is followed by its year.
+const PREFIX: &str = "work-";
+
+/// End of the name of an archive.
+const SUFFIX: &str = ".log.gz";
+
+/// The `Archive` struct is a compressed file next to the log, such as `work-2022.log.gz`, holding
+/// the sessions started in a single year that were moved out of the log with `work archive`.
+///
+/// An archive holds lines of the log, so `zcat work-2022.log.gz` shows them just as they were in
+/// the log. Only whole sessions are archived, each `start` event followed by its `stop` event and
+/// sorted by their start, so the time within an archive can be tallied on its own. The events of
+/// an archive are only read once they are needed.
+#[derive(Debug)]
+pub struct Archive {
+    pub year: i32,
+    path: PathBuf,
+    events: Option<Vec<(i64, Event)>>,
+}
+
+impl Archive {
+    /// Returns the archive of the given year in the given directory, whether or not it exists.
+    pub fn of_year(dir: &Path, year: i32) -> Self {
+        Archive {
+            year,
+            path: dir.join(file_name(year)),
+            events: None,
+        }
+    }
+
+    /// Returns every archive in the given directory, earliest first.
+    pub fn all_in(dir: &Path) -> Result<Vec<Self>, AppError> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::from(e)),
+        };
+        let mut archives = Vec::new();
+        for entry in entries {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if let Some(year) = year_of(&name) {
+                archives.push(Archive::of_year(dir, year));
+            }
+        }
+        archives.sort_by_key(|archive| archive.year);
+        Ok(archives)
+    }
+
+    /// Returns the path of the archive.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if any session of the archive may share time with the given interval. Sessions
+    /// started late in the year may run into the next one.
+    pub fn may_overlap(&self, interval: &Interval) -> bool {
+        let year_start = |year| {
+            NaiveDate::from_ymd_opt(year, 1, 1).map_or(time::BEGINNING_OF_TIME, time::start_of_day)
+        };
+        year_start(self.year) < interval.end && interval.start < year_start(self.year + 2)
+    }
+
+    /// Returns the events of the archive, reading them first if they haven't been read yet. An
+    /// archive that doesn't exist has no events.
+    pub fn events(&mut self) -> Result<&[(i64, Event)], AppError> {
+        if self.events.is_none() {
+            self.events = Some(self.read()?);
+        }
+        Ok(self.events.as_deref().unwrap_or_default())
+    }
+
+    /// Adds the given sessions to the archive and writes it, creating it if it doesn't exist.
+    /// Sessions already in the archive are kept as they are.
+    ///
+    /// The archive is first written to a temporary file which is then moved over the archive, so
+    /// a failure half way through never leaves a truncated archive behind.
+    pub fn add(&mut self, new: &[Session]) -> Result<(), AppError> {
+        let mut sessions = sessions::reconstruct(self.events()?);
+        for session in new {
+            if !sessions
+                .iter()
+                .any(|archived| archived.start == session.start)
+            {
+                sessions.push(session.clone());
+            }
+        }
+        sessions.sort_by_key(|session| session.start);
+        let events: Vec<(i64, Event)> = sessions
+            .iter()
+            .flat_map(|session| {
+                let stop = session.stop.unwrap_or(session.start);
+                vec![
+                    (session.start, session.start_event()),
+                    (stop, session.stop_event()),
+                ]
+            })
+            .collect();
+
+        let tmp_path = self.path.with_extension("gz.tmp");
+        let mut encoder = GzEncoder::new(
+            BufWriter::new(File::create(&tmp_path)?),
+            Compression::default(),
+        );
+        for (timestamp, event) in &events {
+            writeln!(encoder, "{}", event.to_log_line(*timestamp))?;
+        }
+        let file = encoder.finish()?.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.events = Some(events);
+        Ok(())
+    }
+
+    // Reads and parses the events of the archive.
+    fn read(&self) -> Result<Vec<(i64, Event)>, AppError> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::from(e)),
+        };
+        let mut events = Vec::new();
+        for line in BufReader::new(GzDecoder::new(file)).lines() {
+            let line = line.map_err(|e| self.corrupt(&e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let timestamp = line.split(',').next().unwrap_or("").trim();
+            match timestamp.parse::<i64>() {
+                Ok(timestamp) => events.push((timestamp, Event::from(line.as_str()))),
+                Err(_) => return Err(self.corrupt(&line)),
+            }
+        }
+        Ok(events)
+    }
+
+    // Helper function for the error of an archive that can't be read.
+    fn corrupt(&self, reason: &str) -> AppError {
+        AppError::new(ErrorKind::LogFile(format!(
+            "Unable to read the archive {}: {}",
+            self.path.display(),
+            reason
+        )))
+        .with_code(ErrorCode::LogCorrupt)
+    }
+}
+
+/// Returns the name of the archive of the given year.
+///
+/// # Examples
+/// ```
+/// # use work_core::archive;
+/// assert_eq!(archive::file_name(2022), "work-2022.log.gz");
+/// ```
+pub fn file_name(year: i32) -> String {
+    format!("{}{}{}", PREFIX, year, SUFFIX)
+}
+
+/// Returns the interval of the given year, from its first midnight until the first midnight of the
+/// next year.
+pub fn year_interval(year: i32) -> Option<Interval> {
+    let start = NaiveDate::from_ymd_opt(year, 1, 1)?;
+    let end = NaiveDate::from_ymd_opt(year + 1, 1, 1)?;
+    Some(Interval::new(
+        time::start_of_day(start),
+        Some(time::start_of_day(end)),
+    ))
+}
+
+/// Returns the year the given timestamp falls within, the year of the archive of a session that
+/// started at that time.
+pub fn year_of_timestamp(timestamp: i64) -> i32 {
+    time::date_of(timestamp).year()
+}
+
+// Helper function that returns the year of an archive with the given file name, or `None` if it
+// isn't the name of an archive.
+fn year_of(name: &str) -> Option<i32> {
+    let year = name.strip_prefix(PREFIX)?.strip_suffix(SUFFIX)?;
+    if year.len() == 4 && year.chars().all(|c| c.is_ascii_digit()) {
+        year.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_year_of() {
+        assert_eq!(year_of("work-2022.log.gz"), Some(2022));
+        assert_eq!(year_of("work-22.log.gz"), None);
+        assert_eq!(year_of("work-2022.log.gz.tmp"), None);
+        assert_eq!(year_of("work.log"), None);
+    }
+
+    #[test]
+    fn test_add_and_read() {
+        let dir = std::env::temp_dir().join(format!("work-archive-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let session = |start: i64, project| Session::new(start, Some(start + 100), project, None);
+
+        let mut archive = Archive::of_year(&dir, 2022);
+        assert!(archive.events().unwrap().is_empty());
+        archive.add(&[session(500, "b")]).unwrap();
+        archive
+            .add(&[session(100, "a"), session(500, "b")])
+            .unwrap();
+
+        let mut archives = Archive::all_in(&dir).unwrap();
+        assert_eq!(archives.len(), 1);
+        let events = archives[0].events().unwrap().to_vec();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            sessions::reconstruct(&events),
+            vec![session(100, "a"), session(500, "b")]
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use std::str::FromStr;
+
+use chrono::NaiveTime;
+
+use crate::error::{AppError, ErrorKind};
+use crate::time;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeFormat {
+    Minutes,
+    MinutesApprox,
+    HoursApprox,
+    HumanReadable,
+}
+
+impl FromStr for TimeFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "m" => Ok(TimeFormat::Minutes),
+            "minutes" => Ok(TimeFormat::Minutes),
+            "h" => Ok(TimeFormat::HoursApprox),
+            "hours" => Ok(TimeFormat::HoursApprox),
+            "ma" => Ok(TimeFormat::MinutesApprox),
+            "minutes-approx" => Ok(TimeFormat::MinutesApprox),
+            "hr" => Ok(TimeFormat::HumanReadable),
+            "human-readable" => Ok(TimeFormat::HumanReadable),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "Valid values are [m, minutes, ma, minutes-approx, h, hours, hr, human-readable]"
+                    .to_string(),
+            ))),
+        }
+    }
+}
+
+/// The `TrendLength` struct holds the number of weeks `work stats --trend` covers, given either as
+/// `weeks=N` or simply as `N`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrendLength(pub usize);
+
+impl FromStr for TrendLength {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim_start_matches("weeks=").parse() {
+            Ok(weeks) if weeks > 0 => Ok(TrendLength(weeks)),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "The trend should be given as a number of weeks, e.g. weeks=8".to_string(),
+            ))),
+        }
+    }
+}
+
+/// The `Delimiter` struct holds the character that separates the fields of CSV output, given
+/// either as a single character or as `tab`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delimiter(pub u8);
+
+impl FromStr for Delimiter {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tab" | "\\t" => Ok(Delimiter(b'\t')),
+            _ if s.len() == 1 && s != "\"" && !s.contains(&['\n', '\r'][..]) => {
+                Ok(Delimiter(s.as_bytes()[0]))
+            }
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "The delimiter should be a single character other than a quote, or tab".to_string(),
+            ))),
+        }
+    }
+}
+
+/// The `Threshold` struct holds the number of seconds of a duration such as `6h`, `1h30m` or
+/// `90 minutes`, which the total time of `of` is checked against, the limits of `while`, or the
+/// rounding of each session in `report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Threshold(pub i64);
+
+impl FromStr for Threshold {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        time::parse_duration(s).map(Threshold).ok_or_else(|| {
+            AppError::new(ErrorKind::Parse(
+                "Expected a duration such as 6h, 45m, 1h30m, 1.5h or 90 minutes".to_string(),
+            ))
+        })
+    }
+}
+
+/// The `TimeOfDay` struct holds a time of day given as `HH:MM`, such as `9:00` or `17:30`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeOfDay(pub NaiveTime);
+
+impl FromStr for TimeOfDay {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NaiveTime::parse_from_str(s, "%H:%M")
+            .map(TimeOfDay)
+            .map_err(|_| {
+                AppError::new(ErrorKind::Parse(
+                    "Expected a time of day such as 9:00 or 17:30".to_string(),
+                ))
+            })
+    }
+}
+
+/// The `ExportFormat` enum lists the formats `work export` can export to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Html,
+    WorkCsv,
+}
+
+impl FromStr for ExportFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "html" => Ok(ExportFormat::Html),
+            "work-csv" => Ok(ExportFormat::WorkCsv),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "Valid values are [html, work-csv]".to_string(),
+            ))),
+        }
+    }
+}
+
+/// The `ImportFormat` enum lists the formats `work import` can import from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    WorkCsv,
+}
+
+impl FromStr for ImportFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "work-csv" => Ok(ImportFormat::WorkCsv),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "Valid values are [work-csv]".to_string(),
+            ))),
+        }
+    }
+}
+
+/// The `ServiceKind` enum lists the commands `work install-service` can run in the background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceKind {
+    Auto,
+    Remind,
+}
+
+impl FromStr for ServiceKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ServiceKind::Auto),
+            "remind" => Ok(ServiceKind::Remind),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "Valid values are [auto, remind]".to_string(),
+            ))),
+        }
+    }
+}
+
+/// The `EventKind` enum lists the types of events `work append` can append.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Start,
+    Stop,
+}
+
+impl FromStr for EventKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(EventKind::Start),
+            "stop" => Ok(EventKind::Stop),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "Valid values are [start, stop]".to_string(),
+            ))),
+        }
+    }
+}
+
+/// The `HistogramKind` enum lists the ways `work stats --histogram` can count the time worked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistogramKind {
+    Hourly,
+    Weekday,
+}
+
+impl FromStr for HistogramKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hourly" => Ok(HistogramKind::Hourly),
+            "weekday" => Ok(HistogramKind::Weekday),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "Valid values are [hourly, weekday]".to_string(),
+            ))),
+        }
+    }
+}
+
+/// The `GroupKey` enum lists what `work of --group-by` can group the time worked by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKey {
+    Day,
+    Week,
+    Month,
+    Project,
+    Client,
+    User,
+    Tag,
+}
+
+impl FromStr for GroupKey {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "day" => Ok(GroupKey::Day),
+            "week" => Ok(GroupKey::Week),
+            "month" => Ok(GroupKey::Month),
+            "project" => Ok(GroupKey::Project),
+            "client" => Ok(GroupKey::Client),
+            "user" => Ok(GroupKey::User),
+            "tag" => Ok(GroupKey::Tag),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "Valid values are [day, week, month, project, client, user, tag], separated by \
+                 commas such as week,project"
+                    .to_string(),
+            ))),
+        }
+    }
+}
+
+/// The `GroupBy` struct holds the keys `work of --group-by` groups by in turn, given separated by
+/// commas such as `week,project`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupBy(pub Vec<GroupKey>);
+
+impl FromStr for GroupBy {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<GroupKey>, AppError>>()?;
+        if (1..keys.len()).any(|i| keys[..i].contains(&keys[i])) {
+            return Err(AppError::new(ErrorKind::Parse(format!(
+                "Each key can only be grouped by once: {}",
+                s
+            ))));
+        }
+        Ok(GroupBy(keys))
+    }
+}
+
+/// The `BarStyle` enum lists the status bars `work statusbar` can output for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarStyle {
+    Waybar,
+    Polybar,
+    I3blocks,
+    Tmux,
+}
+
+impl FromStr for BarStyle {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "waybar" => Ok(BarStyle::Waybar),
+            "polybar" => Ok(BarStyle::Polybar),
+            "i3blocks" => Ok(BarStyle::I3blocks),
+            "tmux" => Ok(BarStyle::Tmux),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "Valid values are [waybar, polybar, i3blocks, tmux]".to_string(),
+            ))),
+        }
+    }
+}
+
+/// The `Assume` enum overrides the direction in which ambiguous time inputs are resolved, or asks
+/// the user to confirm the guesses the parser makes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assume {
+    Backward,
+    Forward,
+    Ask,
+}
+
+impl FromStr for Assume {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "backward" => Ok(Assume::Backward),
+            "forward" => Ok(Assume::Forward),
+            "ask" => Ok(Assume::Ask),
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "Valid values are [backward, forward, ask]".to_string(),
+            ))),
+        }
+    }
+}
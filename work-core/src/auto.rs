@@ -0,0 +1,340 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::debug;
+
+use crate::config::Config;
+use crate::error::{AppError, ErrorKind};
+use crate::log_file::{Event, LogFile, Metadata};
+use crate::service::Service;
+use crate::sessions::{self, Session, SessionId};
+use crate::signals::Interrupts;
+
+/// Section of the config file holding the rules, see `Rules`.
+const SECTION: &str = "auto";
+
+/// Name of the systemd unit that runs `work auto run`, see `service`.
+pub const UNIT: &str = "work-auto";
+
+/// The `Source` enum lists what a rule looks at to tell what is being worked on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Source {
+    /// The title of the focused window.
+    Window,
+    /// The names of the running processes.
+    Process,
+}
+
+/// A single rule, mapping a window title or process name to a project.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Rule {
+    project: String,
+    source: Source,
+    /// Part of the window title, or the whole process name, in lowercase.
+    pattern: String,
+}
+
+/// The `Rules` struct holds the rules `work auto` tells what is being worked on by, as found in
+/// the `[auto]` section of the config file. Each key is a project and its value a comma separated
+/// list of patterns, either `window:` followed by part of the title of a window or `process:`
+/// followed by the name of a process, such as:
+///
+/// ```text
+/// [auto]
+/// backend = window:work-api, process:cargo
+/// design = process:figma
+/// ```
+///
+/// Patterns are matched without regard to case. The focused window decides over the running
+/// processes, and projects are tried in alphabetical order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+
+impl Rules {
+    /// Reads the rules from the given config. If a pattern is invalid, the function returns an
+    /// error message.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::auto::Rules;
+    /// # use work_core::config::Config;
+    /// let config = Config::parse("[auto]\nbackend = window:work-api, process:cargo").unwrap();
+    /// let rules = Rules::from_config(&config).unwrap();
+    /// let processes = vec!["bash".to_string(), "cargo".to_string()];
+    /// assert_eq!(rules.project_for(Some("Work-API - VS Code"), &[]), Some("backend"));
+    /// assert_eq!(rules.project_for(Some("Inbox"), &processes), Some("backend"));
+    /// assert_eq!(rules.project_for(Some("Inbox"), &[]), None);
+    /// ```
+    pub fn from_config(config: &Config) -> Result<Self, AppError> {
+        let mut rules = Vec::new();
+        for (project, patterns) in config.section(SECTION) {
+            for pattern in patterns.split(',').map(str::trim) {
+                let (source, pattern) = match pattern.split_once(':') {
+                    Some(("window", pattern)) => (Source::Window, pattern),
+                    Some(("process", pattern)) => (Source::Process, pattern),
+                    _ => {
+                        return Err(AppError::new(ErrorKind::Config(format!(
+                            "Invalid rule for {} in the config file: {}, use window:TITLE or \
+                             process:NAME.",
+                            project, pattern
+                        ))))
+                    }
+                };
+                rules.push(Rule {
+                    project: project.to_string(),
+                    source,
+                    pattern: pattern.trim().to_lowercase(),
+                });
+            }
+        }
+        // The focused window is a better hint than a process running somewhere in the background.
+        rules.sort_by_key(|rule| rule.source == Source::Process);
+        Ok(Rules { rules })
+    }
+
+    /// Returns true if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the project of the first rule matching the title of the focused window or one of
+    /// the running processes, if any.
+    pub fn project_for(&self, window: Option<&str>, processes: &[String]) -> Option<&str> {
+        let window = window.map(str::to_lowercase);
+        self.rules
+            .iter()
+            .find(|rule| match (rule.source, &window) {
+                (Source::Window, Some(window)) => window.contains(&rule.pattern),
+                (Source::Window, None) => false,
+                (Source::Process, _) => processes
+                    .iter()
+                    .any(|process| process.to_lowercase() == rule.pattern),
+            })
+            .map(|rule| rule.project.as_str())
+    }
+}
+
+/// Returns the title of the focused window, asking `xdotool` on Linux and AppleScript on macOS,
+/// which only knows the name of the focused application. Returns `None` if it can't be told, such
+/// as without a graphical session.
+pub fn focused_window() -> Option<String> {
+    if cfg!(target_os = "macos") {
+        output_of(
+            "osascript",
+            &[
+                "-e",
+                "tell application \"System Events\" to get name of first application process \
+                 whose frontmost is true",
+            ],
+        )
+    } else {
+        output_of("xdotool", &["getactivewindow", "getwindowname"])
+    }
+}
+
+/// Returns the names of the running processes, as listed by `ps`.
+pub fn running_processes() -> Vec<String> {
+    output_of("ps", &["-A", "-o", "comm="])
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| Path::new(line.trim()).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .collect()
+}
+
+/// The `Noticed` enum holds what `watch` noticed while watching, for the caller to show.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Noticed {
+    /// The session in progress left by a previous run was discarded, it started at the given time.
+    Discarded(i64, Event),
+    /// Work on the given project started at the given time.
+    Working(String, i64),
+}
+
+/// Watches the focused window and the running processes every `interval` seconds until Work is
+/// interrupted, queueing a session in the given queue for every stretch of time the rules point
+/// to the same project, see `LogFile::auto_queue`. The sessions only end up in the log once they
+/// are accepted with `work auto accept`.
+///
+/// A session left in progress by a previous run that didn't get to stop it is discarded, as there
+/// is no telling when it really ended. Both that and each change of project are passed to
+/// `notice`.
+pub fn watch<F>(
+    queue: &mut LogFile,
+    rules: &Rules,
+    interval: u64,
+    mut notice: F,
+) -> Result<(), AppError>
+where
+    F: FnMut(Noticed),
+{
+    let interrupts = Interrupts::catch()?;
+    let mut events = queue.all_events()?;
+    if let Some((start, event @ Event::Start(..))) = events.pop() {
+        queue.rewrite(&events)?;
+        notice(Noticed::Discarded(start, event));
+    }
+
+    let mut current: Option<String> = None;
+    loop {
+        let window = focused_window();
+        let project = rules
+            .project_for(window.as_deref(), &running_processes())
+            .map(str::to_string);
+        debug!("Focused window {:?} points to {:?}", window, project);
+        if project != current {
+            let now = queue.now();
+            if let Some(previous) = current.take() {
+                queue.append_event(&event_of(false, &previous), now)?;
+            }
+            if let Some(project) = &project {
+                queue.append_event(&event_of(true, project), now)?;
+                notice(Noticed::Working(project.to_string(), now));
+            }
+            current = project;
+        }
+
+        // Sleeping in short steps notices an interruption right away.
+        let next = Instant::now() + Duration::from_secs(interval.max(1));
+        while Instant::now() < next {
+            if interrupts.take().is_some() {
+                if let Some(project) = current {
+                    queue.append_event(&event_of(false, &project), queue.now())?;
+                }
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+/// Returns the service that runs `watch` every `interval` seconds with the given environment, see
+/// `service::environment`.
+pub fn service(interval: u64, environment: Vec<(String, String)>) -> Service {
+    Service {
+        name: UNIT.to_string(),
+        description: "Track work automatically".to_string(),
+        arguments: vec![
+            "auto".to_string(),
+            "run".to_string(),
+            "--interval".to_string(),
+            interval.to_string(),
+        ],
+        schedule: None,
+        environment,
+    }
+}
+
+/// Returns the queued sessions with the given ids, or every finished one if no ids are given.
+/// Sessions still in progress can't be picked. If an id doesn't belong to a finished session in
+/// the queue, the function returns an error message.
+pub fn pick(queued: &[Session], ids: &[SessionId]) -> Result<Vec<Session>, AppError> {
+    if ids.is_empty() {
+        return Ok(queued
+            .iter()
+            .filter(|session| session.stop.is_some())
+            .cloned()
+            .collect());
+    }
+    ids.iter()
+        .map(|id| {
+            queued
+                .iter()
+                .find(|session| session.id() == *id && session.stop.is_some())
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::new(ErrorKind::User(format!(
+                        "No finished session with the id {} is queued, `work auto review` lists \
+                         them.",
+                        id
+                    )))
+                })
+        })
+        .collect()
+}
+
+/// Returns the events of the queued sessions other than the given ones, which is what is left of
+/// the queue once they are accepted or discarded.
+pub fn remaining(queued: &[Session], picked: &[Session]) -> Vec<(i64, Event)> {
+    let picked: BTreeSet<SessionId> = picked.iter().map(Session::id).collect();
+    let mut events = Vec::new();
+    for session in queued
+        .iter()
+        .filter(|session| !picked.contains(&session.id()))
+    {
+        events.push((session.start, session.start_event()));
+        if let Some(stop) = session.stop {
+            events.push((stop, session.stop_event()));
+        }
+    }
+    events
+}
+
+/// Returns the sessions in the given queue.
+pub fn queued(queue: &mut LogFile) -> Result<Vec<Session>, AppError> {
+    Ok(sessions::reconstruct(&queue.all_events()?))
+}
+
+// Helper function that returns the `start` or `stop` event of work on the given project.
+fn event_of(start: bool, project: &str) -> Event {
+    let project = Some(project.to_string());
+    if start {
+        Event::Start(project, None, None, None, Metadata::new())
+    } else {
+        Event::Stop(project, None, None, None, Metadata::new())
+    }
+}
+
+// Helper function that runs the given command and returns its trimmed output, or `None` if it
+// fails or outputs nothing.
+fn output_of(program: &str, arguments: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(arguments).output().ok()?;
+    let output = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.is_empty() {
+        None
+    } else {
+        Some(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let config = Config::parse("[auto]\na = process:cargo\nb = window:Figma").unwrap();
+        let rules = Rules::from_config(&config).unwrap();
+        let processes = vec!["Cargo".to_string()];
+        assert_eq!(
+            rules.project_for(Some("figma - home"), &processes),
+            Some("b")
+        );
+        assert_eq!(rules.project_for(None, &processes), Some("a"));
+
+        let config = Config::parse("[auto]\na = cargo").unwrap();
+        assert!(Rules::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_pick_and_remaining() {
+        let queued = vec![
+            Session::new(0, Some(10), "backend", None),
+            Session::new(20, Some(30), "backend", None),
+            Session::new(40, None, "backend", None),
+        ];
+        assert_eq!(pick(&queued, &[]).unwrap().len(), 2);
+        let picked = pick(&queued, &[SessionId(20)]).unwrap();
+        assert_eq!(picked, vec![Session::new(20, Some(30), "backend", None)]);
+        assert!(pick(&queued, &[SessionId(40)]).is_err());
+
+        let events = remaining(&queued, &picked);
+        let timestamps: Vec<i64> = events.iter().map(|(timestamp, _)| *timestamp).collect();
+        assert_eq!(timestamps, vec![0, 10, 40]);
+    }
+}
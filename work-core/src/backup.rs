@@ -0,0 +1,297 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate, TimeZone};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::checksum;
+use crate::config::Config;
+use crate::encryption::Cipher;
+use crate::error::{AppError, ErrorCode, ErrorKind};
+
+/// Name of the directory next to the log that holds backups unless another one is given.
+pub const BACKUPS_DIR: &str = "backups";
+
+/// Start of the name of a backup, which is followed by the time it was made.
+const PREFIX: &str = "work-";
+
+/// Format of the time in the name of a backup, which sorts backups by the time they were made.
+const TIME_FORMAT: &str = "%Y-%m-%d-%H%M%S";
+
+/// Name of the copy of the log within a backup.
+const LOG: &str = "work.log";
+
+/// Name of the copy of the config file within a backup.
+const CONFIG: &str = "work.config";
+
+/// Extension of the copies within a backup that was compressed.
+const GZIP: &str = "gz";
+
+/// The `Backup` struct is a directory holding a copy of the log and of the config file as they
+/// were at one point in time, named after that time such as `work-2026-10-16-093000`. The copies
+/// are compressed with gzip if asked for, as `work.log.gz` and `work.config.gz`.
+///
+/// The log is copied as it is, so a backup of an encrypted log is just as encrypted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Backup {
+    dir: PathBuf,
+}
+
+impl Backup {
+    /// Copies the log and the config file, if there is one, at the given paths into a new backup
+    /// within the given directory, which is created if it doesn't exist. The backup is named after
+    /// the given time `now`.
+    pub fn create(
+        within: &Path,
+        log: &Path,
+        config: &Path,
+        gzip: bool,
+        now: i64,
+    ) -> Result<Self, AppError> {
+        fs::create_dir_all(within)?;
+        let name = format!("{}{}", PREFIX, Local.timestamp(now, 0).format(TIME_FORMAT));
+        // Two backups made within the same second get a number to tell them apart.
+        let mut dir = within.join(&name);
+        let mut number = 1;
+        while dir.exists() {
+            number += 1;
+            dir = within.join(format!("{}-{}", name, number));
+        }
+        fs::create_dir(&dir)?;
+
+        let backup = Backup { dir };
+        backup.copy(log, LOG, gzip)?;
+        if config.exists() {
+            backup.copy(config, CONFIG, gzip)?;
+        }
+        Ok(backup)
+    }
+
+    /// Opens the backup at the given path, which has to hold at least a copy of the log.
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        let backup = Backup {
+            dir: path.to_path_buf(),
+        };
+        if backup.find(LOG).is_none() {
+            return Err(AppError::new(ErrorKind::User(format!(
+                "No backup was found at {}, it should be a directory holding {} or {}.{}",
+                path.display(),
+                LOG,
+                LOG,
+                GZIP
+            )))
+            .with_code(ErrorCode::InvalidInput));
+        }
+        Ok(backup)
+    }
+
+    /// Returns the path of the directory of the backup.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the contents of the copy of the log.
+    pub fn log(&self) -> Result<Vec<u8>, AppError> {
+        match self.read(LOG)? {
+            Some(contents) => Ok(contents),
+            None => Err(self.invalid("the copy of the log is missing")),
+        }
+    }
+
+    /// Returns the contents of the copy of the config file, or `None` if there was no config file.
+    pub fn config(&self) -> Result<Option<Vec<u8>>, AppError> {
+        self.read(CONFIG)
+    }
+
+    /// Checks that the backup can be restored: every line of a plain text log has to be a whole
+    /// event, see `checksum::first_bad_line`, and the config file has to be readable. An encrypted
+    /// log can't be read without its key, so it is restored as it is.
+    pub fn validate(&self) -> Result<(), AppError> {
+        let log = self.log()?;
+        if !Cipher::is_encrypted(&log) {
+            let log = String::from_utf8(log).map_err(|_| self.invalid("the log isn't text"))?;
+            if let Some(bad) = checksum::first_bad_line(&log) {
+                return Err(self.invalid(&format!(
+                    "line {} of the log {}: {}",
+                    bad.number, bad.reason, bad.line
+                )));
+            }
+        }
+
+        if let Some(config) = self.config()? {
+            let config = String::from_utf8(config)
+                .map_err(|_| self.invalid("the config file isn't text"))?;
+            Config::parse(&config)
+                .map_err(|e| self.invalid(&format!("the config file is invalid, {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the log and the config file at the given paths with the copies in the backup,
+    /// after checking that it can be restored, see `validate`. A config file is left as it is if
+    /// the backup has none.
+    ///
+    /// Each file is first written next to the one it replaces and then moved over it, so a
+    /// failure half way through never leaves a truncated file behind.
+    pub fn restore(&self, log: &Path, config: &Path) -> Result<(), AppError> {
+        self.validate()?;
+        replace(log, &self.log()?)?;
+        if let Some(contents) = self.config()? {
+            replace(config, &contents)?;
+        }
+        Ok(())
+    }
+
+    // Copies the file at the given path into the backup under the given name, compressing it if
+    // `gzip` is set.
+    fn copy(&self, from: &Path, name: &str, gzip: bool) -> Result<(), AppError> {
+        let contents = match fs::read(from) {
+            Ok(contents) => contents,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(AppError::from(e)),
+        };
+        if gzip {
+            let file = File::create(self.dir.join(name).with_extension(gzipped(name)))?;
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            encoder.write_all(&contents)?;
+            encoder.finish()?.sync_all()?;
+        } else {
+            let mut file = File::create(self.dir.join(name))?;
+            file.write_all(&contents)?;
+            file.sync_all()?;
+        }
+        Ok(())
+    }
+
+    // Returns the path of the copy with the given name and whether it is compressed, or `None` if
+    // the backup holds no such copy.
+    fn find(&self, name: &str) -> Option<(PathBuf, bool)> {
+        let plain = self.dir.join(name);
+        let compressed = plain.with_extension(gzipped(name));
+        if plain.is_file() {
+            Some((plain, false))
+        } else if compressed.is_file() {
+            Some((compressed, true))
+        } else {
+            None
+        }
+    }
+
+    // Reads the copy with the given name, decompressing it if needed, or returns `None` if the
+    // backup holds no such copy.
+    fn read(&self, name: &str) -> Result<Option<Vec<u8>>, AppError> {
+        let (path, compressed) = match self.find(name) {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        let mut contents = Vec::new();
+        if compressed {
+            GzDecoder::new(File::open(&path)?)
+                .read_to_end(&mut contents)
+                .map_err(|e| self.invalid(&format!("{} can't be decompressed, {}", name, e)))?;
+        } else {
+            contents = fs::read(&path)?;
+        }
+        Ok(Some(contents))
+    }
+
+    // Helper function for the error of a backup that can't be restored.
+    fn invalid(&self, reason: &str) -> AppError {
+        AppError::new(ErrorKind::User(format!(
+            "Unable to restore the backup at {}, {}.",
+            self.dir.display(),
+            reason
+        )))
+        .with_code(ErrorCode::InvalidInput)
+    }
+}
+
+/// Returns true if a backup within the given directory was made on the given date.
+pub fn made_on(within: &Path, date: NaiveDate) -> Result<bool, AppError> {
+    let entries = match fs::read_dir(within) {
+        Ok(entries) => entries,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(AppError::from(e)),
+    };
+    let prefix = format!("{}{}-", PREFIX, date.format("%Y-%m-%d"));
+    for entry in entries {
+        if entry?.file_name().to_string_lossy().starts_with(&prefix) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// Helper function that returns the extension of the compressed copy of the file with the given
+// name, e.g. `log.gz` for `work.log`.
+fn gzipped(name: &str) -> String {
+    match Path::new(name).extension() {
+        Some(extension) => format!("{}.{}", extension.to_string_lossy(), GZIP),
+        None => GZIP.to_string(),
+    }
+}
+
+// Helper function that safely replaces the file at the given path with the given contents.
+fn replace(path: &Path, contents: &[u8]) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("restore.tmp");
+    let mut tmp_file = File::create(&tmp_path)?;
+    tmp_file.write_all(contents)?;
+    tmp_file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::{self, Clock};
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = std::env::temp_dir().join(format!("work-backup-test-{}", std::process::id()));
+        let (log, config) = (dir.join("work.log"), dir.join("work.config"));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&log, "100,Start,work,\n200,Stop,work,\n").unwrap();
+        fs::write(&config, "auto_backup = daily\n").unwrap();
+
+        let within = dir.join(BACKUPS_DIR);
+        let clock = time::frozen(2026, 10, 16, 9, 30);
+        let plain = Backup::create(&within, &log, &config, false, clock.now()).unwrap();
+        let compressed = Backup::create(&within, &log, &config, true, clock.now()).unwrap();
+        assert_ne!(plain, compressed);
+        assert!(compressed.path().join("work.log.gz").is_file());
+        assert!(made_on(&within, clock.today()).unwrap());
+        assert!(!made_on(&within, clock.today().succ()).unwrap());
+
+        fs::write(&log, "300,Start,other,\n").unwrap();
+        fs::remove_file(&config).unwrap();
+        Backup::open(compressed.path())
+            .unwrap()
+            .restore(&log, &config)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&log).unwrap(),
+            "100,Start,work,\n200,Stop,work,\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&config).unwrap(),
+            "auto_backup = daily\n"
+        );
+
+        // A backup that doesn't hold a log can't be restored, and neither can a corrupt log.
+        assert!(Backup::open(&dir.join("missing")).is_err());
+        fs::write(
+            plain.path().join("work.log"),
+            "100,Start,work,\nnot an event\n",
+        )
+        .unwrap();
+        assert!(plain.restore(&log, &config).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
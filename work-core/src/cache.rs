@@ -0,0 +1,415 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, Metadata};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use chrono::NaiveDate;
+
+use crate::error::AppError;
+use crate::log_file::Event;
+use crate::project_map::{ProjectMap, ProjectMapMethods};
+use crate::sessions::{self, Sessions};
+use crate::time::{self, Clock, Interval};
+
+/// First line of the cache file, bumped whenever the format of the cache changes.
+const HEADER: &str = "work-cache 1";
+
+/// The `LogStamp` struct identifies a version of the log by its length and modification time. A
+/// cache is only used while the log still has the stamp the cache was built from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogStamp {
+    len: u64,
+    modified: u128,
+}
+
+impl LogStamp {
+    /// Creates the stamp of the log from its metadata.
+    pub fn of(metadata: &Metadata) -> Self {
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |modified| modified.as_nanos());
+        LogStamp {
+            len: metadata.len(),
+            modified,
+        }
+    }
+}
+
+// The time spent on each project during a single day, along with the position in the log of the
+// first session that day.
+#[derive(Debug, PartialEq)]
+struct Day {
+    offset: u64,
+    projects: ProjectMap,
+}
+
+/// The `Cache` struct holds the time spent on each project and description per day, so reports
+/// over long stretches of time don't have to go through every session in the log.
+///
+/// Only days before `until` are cached, that is days before today and before any session that is
+/// still in progress. For everything else, as well as for days that are only partially within a
+/// report, the log itself has to be consulted. The cache knows where in the log the sessions of
+/// each day start, so that never means reading the log from the beginning.
+#[derive(Debug, PartialEq)]
+pub struct Cache {
+    stamp: LogStamp,
+    built: NaiveDate,
+    until: NaiveDate,
+    tail_offset: u64,
+    days: BTreeMap<NaiveDate, Day>,
+}
+
+impl Cache {
+    /// Builds the cache out of the events of the log, each given along with its byte offset within
+    /// the log. The sessions of a log that isn't in chronological order could be anywhere within
+    /// it, so the days of such a log are all read from the start of the log. The given clock tells
+    /// which day it is and how long a session in progress has lasted.
+    pub fn build(entries: &[(u64, (i64, Event))], stamp: LogStamp, clock: &dyn Clock) -> Self {
+        let mut entries = entries.to_vec();
+        if entries.windows(2).any(|pair| (pair[1].1).0 < (pair[0].1).0) {
+            entries.sort_by_key(|(_, (timestamp, _))| *timestamp);
+            entries.iter_mut().for_each(|(offset, _)| *offset = 0);
+        }
+        let today = clock.today();
+        // The byte offset of each session, found through the offset of its `start` event.
+        let offsets: HashMap<i64, u64> = entries
+            .iter()
+            .rev()
+            .filter(|(_, (_, event))| matches!(event, Event::Start(..)))
+            .map(|(offset, (timestamp, _))| (*timestamp, *offset))
+            .collect();
+        let spans: Vec<_> = sessions::spans(
+            Sessions::new(entries.iter().map(|(_, entry)| entry.clone())),
+            clock.now(),
+        )
+        .collect();
+
+        // A session in progress keeps adding time to the days it spans, so those can't be cached.
+        let until = match spans.last() {
+            Some((session, _)) if session.stop.is_none() => time::date_of(session.start).min(today),
+            _ => today,
+        };
+        let uncached = Interval::new(time::start_of_day(until), Some(i64::MAX));
+
+        let mut days: BTreeMap<NaiveDate, Day> = BTreeMap::new();
+        let mut tail_offset = None;
+        for (session, span) in &spans {
+            let offset = offsets[&session.start];
+            if tail_offset.is_none() && sessions::overlap(span, &uncached).is_some() {
+                tail_offset = Some(offset);
+            }
+
+            let mut day = time::date_of(span.start);
+            while day < until {
+                let whole_day = Interval::new(
+                    time::start_of_day(day),
+                    Some(time::start_of_day(day.succ())),
+                );
+                if let Some(overlap) = sessions::overlap(span, &whole_day) {
+                    days.entry(day)
+                        .or_insert_with(|| Day {
+                            offset,
+                            projects: HashMap::new(),
+                        })
+                        .projects
+                        .add_event(&overlap.duration().num_seconds(), &session.start_event());
+                }
+                if whole_day.end >= span.end {
+                    break;
+                }
+                day = day.succ();
+            }
+        }
+
+        Cache {
+            stamp,
+            built: today,
+            until,
+            tail_offset: tail_offset.unwrap_or(stamp.len),
+            days,
+        }
+    }
+
+    /// Returns true if the cache was built today, according to the given clock, from the log with
+    /// the given stamp.
+    pub fn is_valid(&self, stamp: LogStamp, clock: &dyn Clock) -> bool {
+        self.stamp == stamp && self.built == clock.today()
+    }
+
+    /// Returns the number of days that have work cached.
+    pub fn cached_days(&self) -> usize {
+        self.days.len()
+    }
+
+    /// Sums up the time spent on each project during the days that are completely within the
+    /// given interval and cached.
+    ///
+    /// The rest of the interval is returned as a list of intervals that have to be tallied from
+    /// the log, each along with the byte offset in the log from which to start reading.
+    pub fn split(&self, interval: &Interval) -> (ProjectMap, Vec<(u64, Interval)>) {
+        let mut projects = HashMap::new();
+        let first_day = time::date_of(interval.start);
+        let first_full_day = if time::start_of_day(first_day) == interval.start {
+            first_day
+        } else {
+            first_day.succ()
+        };
+        let end_of_full_days = time::date_of(interval.end).min(self.until);
+
+        if first_full_day >= end_of_full_days {
+            return (projects, vec![(self.offset(first_day), *interval)]);
+        }
+        for day in self
+            .days
+            .range(first_full_day..end_of_full_days)
+            .map(|(_, day)| day)
+        {
+            projects.merge(&day.projects);
+        }
+
+        let mut rest = Vec::new();
+        let head_end = time::start_of_day(first_full_day);
+        if interval.start < head_end {
+            rest.push((
+                self.offset(first_day),
+                Interval::new(interval.start, Some(head_end)),
+            ));
+        }
+        let tail_start = time::start_of_day(end_of_full_days);
+        if tail_start < interval.end {
+            rest.push((
+                self.offset(end_of_full_days),
+                Interval::new(tail_start, Some(interval.end)),
+            ));
+        }
+        (projects, rest)
+    }
+
+    // Returns the byte offset in the log of the first session that overlaps the given day, or any
+    // day after it.
+    fn offset(&self, day: NaiveDate) -> u64 {
+        if day >= self.until {
+            return self.tail_offset;
+        }
+        self.days
+            .range(day..self.until)
+            .next()
+            .map_or(self.tail_offset, |(_, day)| day.offset)
+    }
+
+    /// Reads the cache from the given path. Returns `None` if there is no cache or if it can't be
+    /// understood, in which case it simply has to be rebuilt.
+    pub fn load(path: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        let mut lines = contents.lines();
+        if lines.next()? != HEADER {
+            return None;
+        }
+
+        let header: Vec<&str> = lines.next()?.split_whitespace().collect();
+        let (stamp, built, until, tail_offset) = match &header[..] {
+            [len, modified, built, until, tail_offset] => (
+                LogStamp {
+                    len: len.parse().ok()?,
+                    modified: modified.parse().ok()?,
+                },
+                built.parse().ok()?,
+                until.parse().ok()?,
+                tail_offset.parse().ok()?,
+            ),
+            _ => return None,
+        };
+
+        let mut days = BTreeMap::new();
+        let mut current: Option<(NaiveDate, Day)> = None;
+        for line in lines {
+            let values: Vec<&str> = line.splitn(3, ',').collect();
+            match &values[..] {
+                ["day", date, offset] => {
+                    let day = Day {
+                        offset: offset.parse().ok()?,
+                        projects: HashMap::new(),
+                    };
+                    if let Some((date, day)) = current.replace((date.parse().ok()?, day)) {
+                        days.insert(date, day);
+                    }
+                }
+                [time, project, description] => {
+                    let (_, day) = current.as_mut()?;
+                    day.projects
+                        .add_time(project, description, time.parse().ok()?);
+                }
+                _ => return None,
+            }
+        }
+        if let Some((date, day)) = current {
+            days.insert(date, day);
+        }
+
+        Some(Cache {
+            stamp,
+            built,
+            until,
+            tail_offset,
+            days,
+        })
+    }
+
+    /// Writes the cache to the given path.
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        let mut contents = format!(
+            "{}\n{} {} {} {} {}\n",
+            HEADER, self.stamp.len, self.stamp.modified, self.built, self.until, self.tail_offset
+        );
+        for (date, day) in &self.days {
+            contents.push_str(&format!("day,{},{}\n", date, day.offset));
+            for (project, descs) in &day.projects {
+                for (description, time) in descs {
+                    contents.push_str(&format!("{},{},{}\n", time, project, description));
+                }
+            }
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn stamp() -> LogStamp {
+        LogStamp {
+            len: 1000,
+            modified: 42,
+        }
+    }
+
+    // Helper function for building a log of sessions given as (start, stop, project) in seconds
+    // since the start of the given day, every line of the log taking up 10 bytes.
+    fn build_log(day: NaiveDate, sessions: &[(i64, i64, &str)]) -> Vec<(u64, (i64, Event))> {
+        let midnight = time::start_of_day(day);
+        let mut entries = Vec::new();
+        for (start, stop, project) in sessions {
+            let project = Some(project.to_string());
+            let offset = entries.len() as u64 * 10;
+            entries.push((
+                offset,
+                (
+                    midnight + start,
+                    Event::Start(project.clone(), None, None, None, Default::default()),
+                ),
+            ));
+            entries.push((
+                offset + 10,
+                (
+                    midnight + stop,
+                    Event::Stop(project, None, None, None, Default::default()),
+                ),
+            ));
+        }
+        entries
+    }
+
+    #[test]
+    fn test_split_uses_cached_days() {
+        let clock = time::frozen(2026, 10, 16, 12, 0);
+        let day = clock.today() - Duration::days(3);
+        let entries = build_log(
+            day,
+            &[(9 * 3600, 17 * 3600, "a"), (47 * 3600, 50 * 3600, "b")],
+        );
+        let cache = Cache::build(&entries, stamp(), &clock);
+        assert_eq!(cache.cached_days(), 3);
+
+        let interval = Interval::new(
+            time::start_of_day(day) + 12 * 3600,
+            Some(time::start_of_day(day.succ().succ())),
+        );
+        let (projects, rest) = cache.split(&interval);
+        // The whole second day is cached, only the second half of the first day isn't.
+        assert_eq!(projects["b"]["No description"], 3600);
+        assert!(!projects.contains_key("a"));
+        assert_eq!(
+            rest,
+            vec![(
+                0,
+                Interval::new(interval.start, Some(time::start_of_day(day.succ())))
+            )]
+        );
+    }
+
+    #[test]
+    fn test_sessions_in_progress_are_not_cached() {
+        let clock = time::frozen(2026, 10, 16, 12, 0);
+        let day = clock.today() - Duration::days(2);
+        let mut entries = build_log(day, &[(9 * 3600, 17 * 3600, "a")]);
+        entries.push((
+            20,
+            (
+                time::start_of_day(day) + 18 * 3600,
+                Event::Start(None, None, None, None, Default::default()),
+            ),
+        ));
+        let cache = Cache::build(&entries, stamp(), &clock);
+
+        assert_eq!(cache.until, day);
+        assert_eq!(cache.cached_days(), 0);
+        assert_eq!(cache.tail_offset, 0);
+    }
+
+    #[test]
+    fn test_unsorted_log() {
+        let clock = time::frozen(2026, 10, 16, 12, 0);
+        let day = clock.today() - Duration::days(3);
+        let entries = build_log(
+            day,
+            &[(9 * 3600, 12 * 3600, "a"), (13 * 3600, 17 * 3600, "b")],
+        );
+        let mut shuffled = entries[2..].to_vec();
+        shuffled.extend_from_slice(&entries[..2]);
+        let cache = Cache::build(&shuffled, stamp(), &clock);
+
+        // Every day is tallied the same, but has to be read from the start of the log.
+        let sorted = Cache::build(&entries, stamp(), &clock);
+        let (projects, rest) = cache.split(&Interval::new(
+            time::start_of_day(day),
+            Some(time::start_of_day(day) + 14 * 3600),
+        ));
+        assert_eq!(cache.cached_days(), sorted.cached_days());
+        assert!(projects.is_empty());
+        assert!(rest.iter().all(|(offset, _)| *offset == 0));
+        assert_eq!(cache.days[&day].projects, sorted.days[&day].projects);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let clock = time::frozen(2026, 10, 16, 12, 0);
+        let day = clock.today() - Duration::days(1);
+        let entries = build_log(day, &[(3600, 7200, "a"), (7200, 9000, "b")]);
+        let cache = Cache::build(&entries, stamp(), &clock);
+
+        let path = std::env::temp_dir().join(format!("work-cache-test-{}", std::process::id()));
+        cache.save(&path).unwrap();
+        let loaded = Cache::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, Some(cache));
+    }
+
+    #[test]
+    fn test_is_valid() {
+        let clock = time::frozen(2026, 10, 16, 23, 59);
+        let entries = build_log(clock.today(), &[(3600, 7200, "a")]);
+        let cache = Cache::build(&entries, stamp(), &clock);
+        assert!(cache.is_valid(stamp(), &clock));
+        assert!(!cache.is_valid(stamp(), &time::frozen(2026, 10, 17, 0, 1)));
+        let other = LogStamp {
+            len: 1010,
+            ..stamp()
+        };
+        assert!(!cache.is_valid(other, &clock));
+    }
+}
@@ -0,0 +1,117 @@
+use flate2::Crc;
+
+/// Key of the metadata field holding the checksum of a line of the log.
+pub const KEY: &str = "crc";
+
+/// Number of fields a line of the log has before its metadata, see `Event::to_log_line`.
+const FIELDS: usize = 6;
+
+/// The `BadLine` struct is a line of the log that can't be trusted, see `first_bad_line`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BadLine {
+    /// Number of the line within the log, counting from 1.
+    pub number: usize,
+    pub line: String,
+    pub reason: &'static str,
+}
+
+/// Returns the given line of the log with the CRC-32 checksum of the line appended to it as a
+/// `crc=` metadata field. Lines without metadata get their empty fields filled in first, since
+/// metadata only comes after the sixth field.
+///
+/// # Examples
+/// ```
+/// # use work_core::checksum;
+/// let line = checksum::seal("1,Start,work,");
+/// assert!(line.starts_with("1,Start,work,,,,crc="));
+/// assert_eq!(checksum::verify(&line), Some(true));
+/// assert_eq!(checksum::verify(&line.replace("work", "play")), Some(false));
+/// assert_eq!(checksum::verify("1,Start,work,"), None);
+/// ```
+pub fn seal(line: &str) -> String {
+    let mut line = line.to_string();
+    for _ in line.split(',').count()..FIELDS {
+        line.push(',');
+    }
+    let crc = crc_of(&line);
+    format!("{},{}={:08x}", line, KEY, crc)
+}
+
+/// Checks the checksum of the given line of the log, see `seal`. Returns `None` if the line has no
+/// checksum, such as lines written before checksums were turned on.
+pub fn verify(line: &str) -> Option<bool> {
+    let fields: Vec<&str> = line.split(',').collect();
+    let prefix = format!("{}=", KEY);
+    let index = fields
+        .iter()
+        .skip(FIELDS)
+        .position(|field| field.trim().starts_with(&prefix))?
+        + FIELDS;
+    let expected = fields[index].trim()[prefix.len()..].to_string();
+    let rest: Vec<&str> = fields
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .map(|(_, field)| *field)
+        .collect();
+    Some(format!("{:08x}", crc_of(&rest.join(","))) == expected)
+}
+
+/// Checks every line of the given contents of a log and returns the first one that isn't an
+/// event, whose checksum doesn't match, or that was cut off before its line break. Returns `None`
+/// if every line is fine.
+pub fn first_bad_line(contents: &str) -> Option<BadLine> {
+    let count = contents.lines().count();
+    contents.lines().enumerate().find_map(|(i, line)| {
+        let mut values = line.split(',').map(str::trim);
+        let timestamp = values.next().unwrap_or("").parse::<i64>();
+        let kind = values.next();
+        let reason = if timestamp.is_err() || !matches!(kind, Some("Start") | Some("Stop")) {
+            "isn't an event"
+        } else if verify(line) == Some(false) {
+            "doesn't match its checksum"
+        } else if i + 1 == count && !contents.ends_with('\n') {
+            "was cut off before its line break"
+        } else {
+            return None;
+        };
+        Some(BadLine {
+            number: i + 1,
+            line: line.to_string(),
+            reason,
+        })
+    })
+}
+
+// Helper function that returns the CRC-32 checksum of the given line.
+fn crc_of(line: &str) -> u32 {
+    let mut crc = Crc::new();
+    crc.update(line.as_bytes());
+    crc.sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_keeps_metadata() {
+        let line = seal("1,Stop,work,fix,acme,billable,machine=laptop");
+        assert!(line.starts_with("1,Stop,work,fix,acme,billable,machine=laptop,crc="));
+        assert_eq!(verify(&line), Some(true));
+    }
+
+    #[test]
+    fn test_first_bad_line() {
+        let good = format!("{}\n{}\n", seal("1,Start,work,"), seal("2,Stop,work,"));
+        assert_eq!(first_bad_line(&good), None);
+        assert_eq!(first_bad_line("1,Start,work,\n2,Stop,work,\n"), None);
+
+        let tampered = good.replacen("Start,work", "Start,play", 1);
+        assert_eq!(first_bad_line(&tampered).unwrap().number, 1);
+        let garbled = format!("{}oops\n", good);
+        assert_eq!(first_bad_line(&garbled).unwrap().reason, "isn't an event");
+        let cut_off = good.trim_end_matches('\n');
+        assert_eq!(first_bad_line(cut_off).unwrap().number, 2);
+    }
+}
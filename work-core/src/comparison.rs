@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::arguments::TimeFormat;
+use crate::csv_output::{self, CsvOptions};
+use crate::highlight::Highlight;
+use crate::project_map::{duration_json, ProjectMap};
+use crate::time::{format_delta, format_time};
+
+/// Label of the total time spent on all projects.
+const TOTAL_STR: &str = "Total";
+
+/// The `Comparison` struct holds the time spent on each project within two intervals, such as this
+/// week and last week, see `work of --compare`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    /// The time spent on each project within the first and the second interval, sorted by project.
+    /// Projects that were only worked on within one of the intervals have no time in the other.
+    pub projects: BTreeMap<String, (i64, i64)>,
+}
+
+impl Comparison {
+    /// Pairs up the time spent on each project within the first interval with the time spent on it
+    /// within the second interval.
+    pub fn new(first: &ProjectMap, second: &ProjectMap) -> Self {
+        let mut projects: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+        for (project, descs) in first {
+            projects.entry(project.to_string()).or_default().0 += descs.values().sum::<i64>();
+        }
+        for (project, descs) in second {
+            projects.entry(project.to_string()).or_default().1 += descs.values().sum::<i64>();
+        }
+        Comparison { projects }
+    }
+
+    /// Returns true if no work was done within either interval.
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty()
+    }
+
+    /// Returns the time spent on all projects within the first and the second interval.
+    pub fn totals(&self) -> (i64, i64) {
+        self.projects
+            .values()
+            .fold((0, 0), |(first, second), times| {
+                (first + times.0, second + times.1)
+            })
+    }
+
+    /// Returns the comparison as a table with a row per project and a final row with the totals.
+    /// The columns are headed by the names of the intervals.
+    pub fn as_table(
+        &self,
+        time_format: &TimeFormat,
+        first: &str,
+        second: &str,
+        highlight: &dyn Highlight,
+    ) -> String {
+        let mut rows = vec![(
+            "Project".to_string(),
+            first.to_string(),
+            second.to_string(),
+            "Delta".to_string(),
+        )];
+        let totals = self.totals();
+        let times = self
+            .projects
+            .iter()
+            .map(|(project, times)| (project.as_str(), *times))
+            .chain(std::iter::once((TOTAL_STR, totals)));
+        for (project, (first, second)) in times {
+            rows.push((
+                project.to_string(),
+                format_time(time_format, first),
+                format_time(time_format, second),
+                format_delta(time_format, first - second),
+            ));
+        }
+
+        let width = |column: fn(&(String, String, String, String)) -> &String| {
+            rows.iter()
+                .map(|row| column(row).chars().count())
+                .max()
+                .unwrap_or(0)
+        };
+        let widths = (
+            width(|row| &row.0),
+            width(|row| &row.1),
+            width(|row| &row.2),
+        );
+
+        let last = rows.len() - 1;
+        let mut table = String::new();
+        for (i, (project, first, second, delta)) in rows.iter().enumerate() {
+            let project = format!("{:<width$}", project, width = widths.0);
+            let project = if i == 0 || i == last {
+                project
+            } else {
+                highlight.project(&project)
+            };
+            table.push_str(&format!(
+                "{}  {:>w1$}  {:>w2$}  {}",
+                project,
+                first,
+                second,
+                delta,
+                w1 = widths.1,
+                w2 = widths.2
+            ));
+            if i < last {
+                table.push('\n');
+            }
+        }
+        table
+    }
+
+    /// Returns a CSV format of the comparison as a string.
+    pub fn as_csv(&self, time_format: &TimeFormat, options: &CsvOptions) -> String {
+        let rows = self.projects.iter().map(|(project, (first, second))| {
+            vec![
+                project.to_string(),
+                format_time(time_format, *first),
+                format_time(time_format, *second),
+                format_delta(time_format, first - second),
+            ]
+        });
+        csv_output::write(
+            &["Project", "Time Spent", "Compared Time Spent", "Delta"],
+            rows,
+            options,
+        )
+    }
+
+    /// Returns a JSON format of the comparison as a string, an object per project holding the time
+    /// spent within each interval and the delta. Each of them is given as described by
+    /// `duration_json`, except that the formatted delta carries its sign.
+    pub fn as_json(&self, time_format: &TimeFormat, raw_seconds: bool) -> String {
+        let mut map = Map::new();
+        for (project, (first, second)) in &self.projects {
+            let delta = first - second;
+            map.insert(
+                project.to_string(),
+                json!({
+                    "time": duration_json(time_format, *first, raw_seconds),
+                    "compared": duration_json(time_format, *second, raw_seconds),
+                    "delta": if raw_seconds {
+                        json!(delta)
+                    } else {
+                        json!({ "seconds": delta, "formatted": format_delta(time_format, delta) })
+                    },
+                }),
+            );
+        }
+        serde_json::to_string_pretty(&Value::Object(map)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::highlight::Plain;
+    use crate::project_map::ProjectMapMethods;
+
+    fn comparison() -> Comparison {
+        let mut this_week = ProjectMap::new();
+        this_week.add_time("work", "No description", 3 * 3600);
+        this_week.add_time("work", "Bugs", 3600);
+        this_week.add_time("docs", "No description", 1800);
+        let mut last_week = ProjectMap::new();
+        last_week.add_time("work", "No description", 5 * 3600);
+        last_week.add_time("play", "No description", 600);
+        Comparison::new(&this_week, &last_week)
+    }
+
+    #[test]
+    fn test_new() {
+        let comparison = comparison();
+        assert_eq!(comparison.projects["work"], (4 * 3600, 5 * 3600));
+        assert_eq!(comparison.projects["docs"], (1800, 0));
+        assert_eq!(comparison.projects["play"], (0, 600));
+        assert_eq!(comparison.totals(), (4 * 3600 + 1800, 5 * 3600 + 600));
+    }
+
+    #[test]
+    fn test_output() {
+        let comparison = comparison();
+        assert_eq!(
+            comparison.as_table(&TimeFormat::Minutes, "this-week", "last-week", &Plain),
+            "Project  this-week  last-week  Delta\n\
+             docs            30          0  +30\n\
+             play             0         10  -10\n\
+             work           240        300  -60\n\
+             Total          270        310  -40"
+        );
+        assert_eq!(
+            comparison.as_csv(&TimeFormat::Minutes, &CsvOptions::default()),
+            "Project,Time Spent,Compared Time Spent,Delta\n\
+             docs,30,0,+30\nplay,0,10,-10\nwork,240,300,-60\n"
+        );
+        let options = CsvOptions {
+            delimiter: b'\t',
+            header: false,
+        };
+        assert_eq!(
+            comparison.as_csv(&TimeFormat::Minutes, &options),
+            "docs\t30\t0\t+30\nplay\t0\t10\t-10\nwork\t240\t300\t-60\n"
+        );
+        let json: Value =
+            serde_json::from_str(&comparison.as_json(&TimeFormat::Minutes, false)).unwrap();
+        assert_eq!(
+            json["work"],
+            json!({
+                "time": { "seconds": 14400, "formatted": "240" },
+                "compared": { "seconds": 18000, "formatted": "300" },
+                "delta": { "seconds": -3600, "formatted": "-60" },
+            })
+        );
+        let json: Value =
+            serde_json::from_str(&comparison.as_json(&TimeFormat::Minutes, true)).unwrap();
+        assert_eq!(
+            json["work"],
+            json!({ "time": 14400, "compared": 18000, "delta": -3600 })
+        );
+    }
+}
@@ -0,0 +1,529 @@
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveTime;
+use dirs;
+
+use crate::error::{AppError, ErrorCode, ErrorKind};
+use crate::profiles;
+use crate::time;
+
+/// The `Config` struct holds the settings found in the `work.config` file.
+///
+/// The config file is a simple list of `key = value` lines. Lines starting with `#` are comments
+/// and a line of the form `[section]` prefixes the keys that follow it with `section.`, so the
+/// following config:
+///
+/// ```text
+/// [encryption]
+/// passphrase = hunter2
+/// ```
+///
+/// can be queried with `config.get("encryption.passphrase")`.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    values: HashMap<String, String>,
+}
+
+impl Config {
+    /// Reads the config file from its default path for the profile in use, see `for_profile`. A
+    /// missing config file is not an error, it simply results in an empty config.
+    pub fn load() -> Result<Self, AppError> {
+        Self::load_profile(&profiles::current()?)
+    }
+
+    /// Reads the config file from its default path for the given profile, see `for_profile`.
+    pub fn load_profile(profile: &str) -> Result<Self, AppError> {
+        let path = Self::config_file_path()?;
+        match fs::read_to_string(&path) {
+            Ok(contents) => Ok(Self::parse(&contents)?.for_profile(profile)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(AppError::new(ErrorKind::Config(format!(
+                "Unable to read config file {}: {}",
+                path.display(),
+                e
+            )))
+            .with_source(e)),
+        }
+    }
+
+    /// Parses the contents of a config file.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::config::Config;
+    /// let config = Config::parse("# A comment\nfoo = bar\n[section]\nkey = \"value\"").unwrap();
+    /// assert_eq!(config.get("foo"), Some("bar"));
+    /// assert_eq!(config.get("section.key"), Some("value"));
+    /// assert_eq!(config.get("key"), None);
+    /// ```
+    pub fn parse(contents: &str) -> Result<Self, AppError> {
+        let mut values = HashMap::new();
+        let mut section = String::new();
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') && line.ends_with(']') {
+                section = line[1..line.len() - 1].trim().to_string();
+                continue;
+            }
+
+            let values_in_line: Vec<&str> = line.splitn(2, '=').map(|s| s.trim()).collect();
+            match &values_in_line[..] {
+                [key, value] if !key.is_empty() => {
+                    let key = if section.is_empty() {
+                        key.to_string()
+                    } else {
+                        format!("{}.{}", section, key)
+                    };
+                    values.insert(key, unquote(value).to_string());
+                }
+                _ => {
+                    return Err(AppError::new(ErrorKind::Config(format!(
+                        "Invalid line in config file (line {}): {}",
+                        number + 1,
+                        line
+                    ))))
+                }
+            }
+        }
+        Ok(Config { values })
+    }
+
+    /// Returns the config of the given profile, in which the keys set in the `[profile.NAME]`
+    /// section of the profile override the keys set outside of it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::config::Config;
+    /// let config = Config::parse(
+    ///     "weekly_hours = 40\n[hooks]\non_start = a\n[profile.personal]\nweekly_hours = 0\n\
+    ///      [profile.personal.hooks]\non_start = b",
+    /// )
+    /// .unwrap();
+    /// let personal = config.for_profile("personal");
+    /// assert_eq!(personal.get("weekly_hours"), Some("0"));
+    /// assert_eq!(personal.get("hooks.on_start"), Some("b"));
+    /// assert_eq!(config.for_profile("default").get("hooks.on_start"), Some("a"));
+    /// ```
+    pub fn for_profile(&self, profile: &str) -> Config {
+        let prefix = format!("profile.{}.", profile);
+        let mut values = self.values.clone();
+        for (key, value) in &self.values {
+            if let Some(key) = key.strip_prefix(&prefix) {
+                values.insert(key.to_string(), value.clone());
+            }
+        }
+        Config { values }
+    }
+
+    /// Returns the value of a given `key` if it is set.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|s| s.as_str())
+    }
+
+    /// Returns the keys and values set in a given `section`, without the section prefix, sorted
+    /// by key.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::config::Config;
+    /// let config = Config::parse("[budgets]\nb = 2h\na = 1h\n[other]\nc = 3h").unwrap();
+    /// assert_eq!(config.section("budgets"), vec![("a", "1h"), ("b", "2h")]);
+    /// ```
+    pub fn section(&self, section: &str) -> Vec<(&str, &str)> {
+        let prefix = format!("{}.", section);
+        let mut values: Vec<(&str, &str)> = self
+            .values
+            .iter()
+            .filter_map(|(key, value)| Some((key.strip_prefix(&prefix)?, value.as_str())))
+            .collect();
+        values.sort();
+        values
+    }
+
+    /// Returns whether work on the given project is billable by default, as set in the
+    /// `[billable]` section. A project without a setting of its own falls back on `default`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::config::Config;
+    /// let config = Config::parse("[billable]\ndefault = true\ninternal = false").unwrap();
+    /// assert_eq!(config.billable(Some("internal")).unwrap(), Some(false));
+    /// assert_eq!(config.billable(Some("website")).unwrap(), Some(true));
+    /// assert_eq!(Config::default().billable(None).unwrap(), None);
+    /// ```
+    pub fn billable(&self, project: Option<&str>) -> Result<Option<bool>, AppError> {
+        let value = project
+            .and_then(|project| self.get(&format!("billable.{}", project)))
+            .or_else(|| self.get("billable.default"));
+        match value {
+            None => Ok(None),
+            Some("true") => Ok(Some(true)),
+            Some("false") => Ok(Some(false)),
+            Some(value) => Err(AppError::new(ErrorKind::Config(format!(
+                "Invalid value in the [billable] section of the config file: {}, use true or false.",
+                value
+            )))),
+        }
+    }
+
+    /// Returns the time of day set as `auto_stop_at`, which `doctor --auto-stop` stops forgotten
+    /// work at.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chrono::NaiveTime;
+    /// # use work_core::config::Config;
+    /// let config = Config::parse("auto_stop_at = 18:00").unwrap();
+    /// assert_eq!(config.auto_stop_at().unwrap(), Some(NaiveTime::from_hms(18, 0, 0)));
+    /// assert!(Config::parse("auto_stop_at = 6pm").unwrap().auto_stop_at().is_err());
+    /// ```
+    pub fn auto_stop_at(&self) -> Result<Option<NaiveTime>, AppError> {
+        match self.get("auto_stop_at") {
+            None => Ok(None),
+            Some(value) => match NaiveTime::parse_from_str(value, "%H:%M") {
+                Ok(time) => Ok(Some(time)),
+                Err(_) => Err(AppError::new(ErrorKind::Config(format!(
+                    "Invalid auto_stop_at in the config file: {}, use a time such as 18:00.",
+                    value
+                )))),
+            },
+        }
+    }
+
+    /// Returns the duration set as the given `key`, in seconds, such as `warn_after = 5h` which
+    /// `status` and `prompt` flag sessions running longer than.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::config::Config;
+    /// let config = Config::parse("warn_after = 5h\ndaily_max = lots").unwrap();
+    /// assert_eq!(config.duration("warn_after").unwrap(), Some(5 * 3600));
+    /// assert_eq!(config.duration("weekly_hours").unwrap(), None);
+    /// assert!(config.duration("daily_max").is_err());
+    /// ```
+    pub fn duration(&self, key: &str) -> Result<Option<i64>, AppError> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(value) => match time::parse_duration(value) {
+                Some(seconds) => Ok(Some(seconds)),
+                None => Err(AppError::new(ErrorKind::Config(format!(
+                    "Invalid {} in the config file: {}, use a duration such as 5h or 1h30m.",
+                    key, value
+                )))),
+            },
+        }
+    }
+
+    /// Returns true if `auto_backup` is set to `daily`, which backs up the log and the config file
+    /// before the first change to the log each day, see `Backup`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::config::Config;
+    /// assert!(Config::parse("auto_backup = daily").unwrap().auto_backup().unwrap());
+    /// assert!(!Config::default().auto_backup().unwrap());
+    /// assert!(Config::parse("auto_backup = hourly").unwrap().auto_backup().is_err());
+    /// ```
+    pub fn auto_backup(&self) -> Result<bool, AppError> {
+        match self.get("auto_backup") {
+            None | Some("never") => Ok(false),
+            Some("daily") => Ok(true),
+            Some(value) => Err(AppError::new(ErrorKind::Config(format!(
+                "Invalid auto_backup in the config file: {}, use daily or never.",
+                value
+            )))),
+        }
+    }
+
+    /// Returns true if `checksums` is set to `true`, which appends a checksum to each line written
+    /// to the log so that `work doctor` can find lines that were damaged, see `checksum::seal`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::config::Config;
+    /// assert!(Config::parse("checksums = true").unwrap().checksums().unwrap());
+    /// assert!(!Config::default().checksums().unwrap());
+    /// assert!(Config::parse("checksums = yes").unwrap().checksums().is_err());
+    /// ```
+    pub fn checksums(&self) -> Result<bool, AppError> {
+        match self.get("checksums") {
+            None | Some("false") => Ok(false),
+            Some("true") => Ok(true),
+            Some(value) => Err(AppError::new(ErrorKind::Config(format!(
+                "Invalid checksums in the config file: {}, use true or false.",
+                value
+            )))),
+        }
+    }
+
+    /// Returns the name of the user to record with each event if `record_user` is set to `true`,
+    /// which is the `user` set in the config file or else the name the system knows the user by.
+    /// Returns `None` if `record_user` isn't set.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::config::Config;
+    /// let config = Config::parse("record_user = true\nuser = alice").unwrap();
+    /// assert_eq!(config.user().unwrap().as_deref(), Some("alice"));
+    /// assert_eq!(Config::parse("user = alice").unwrap().user().unwrap(), None);
+    /// ```
+    pub fn user(&self) -> Result<Option<String>, AppError> {
+        match self.get("record_user") {
+            None | Some("false") => Ok(None),
+            Some("true") => match self.get("user") {
+                Some(user) => Ok(Some(user.to_string())),
+                None => match env::var("USER").or_else(|_| env::var("USERNAME")) {
+                    Ok(user) if !user.is_empty() => Ok(Some(user)),
+                    _ => Err(AppError::new(ErrorKind::Config(
+                        "Unable to tell who the user is for record_user, set user in the config \
+                         file, e.g. user = alice"
+                            .to_string(),
+                    ))),
+                },
+            },
+            Some(value) => Err(AppError::new(ErrorKind::Config(format!(
+                "Invalid record_user in the config file: {}, use true or false.",
+                value
+            )))),
+        }
+    }
+
+    /// Returns the contracted time per week set as `weekly_hours`, in seconds. The time is either a
+    /// number of hours or a duration such as `37h30m`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::config::Config;
+    /// assert_eq!(Config::parse("weekly_hours = 40").unwrap().weekly_hours().unwrap(), Some(40 * 3600));
+    /// assert_eq!(Config::parse("weekly_hours = 37h30m").unwrap().weekly_hours().unwrap(), Some(135000));
+    /// assert!(Config::parse("weekly_hours = lots").unwrap().weekly_hours().is_err());
+    /// ```
+    pub fn weekly_hours(&self) -> Result<Option<i64>, AppError> {
+        let value = match self.get("weekly_hours") {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let seconds = match value.parse::<i64>() {
+            Ok(hours) => hours.checked_mul(3600),
+            Err(_) => time::parse_duration(value),
+        };
+        match seconds {
+            Some(seconds) => Ok(Some(seconds)),
+            None => Err(AppError::new(ErrorKind::Config(format!(
+                "Invalid weekly_hours in the config file: {}, use a number of hours such as 40.",
+                value
+            )))),
+        }
+    }
+
+    /// Returns the value of a given `key` as a path, expanding a leading `~` to the home
+    /// directory of the user.
+    pub fn get_path(&self, key: &str) -> Option<PathBuf> {
+        self.get(key).map(expand_home)
+    }
+
+    /// Returns the project that work done in the given directory belongs to, as mapped in the
+    /// `[directories]` section. Each key is a pattern of a directory in which `*` stands for any
+    /// part of a single directory name and `**` for any number of nested directories. If several
+    /// patterns match, the longest one wins.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::path::Path;
+    /// # use work_core::config::Config;
+    /// let config = Config::parse(
+    ///     "[directories]\n/code/acme/** = acme-backend\n/code/acme/web/** = acme-web\n\
+    ///      /code/*-tools = tooling",
+    /// )
+    /// .unwrap();
+    /// let project = |dir| config.project_of_dir(Path::new(dir));
+    /// assert_eq!(project("/code/acme/api/src"), Some("acme-backend".to_string()));
+    /// assert_eq!(project("/code/acme/web"), Some("acme-web".to_string()));
+    /// assert_eq!(project("/code/dev-tools"), Some("tooling".to_string()));
+    /// assert_eq!(project("/code/dev-tools/bin"), None);
+    /// ```
+    pub fn project_of_dir(&self, dir: &Path) -> Option<String> {
+        let dir = components(dir);
+        self.section("directories")
+            .into_iter()
+            .filter(|(pattern, _)| {
+                let pattern = components(&expand_home(pattern));
+                matches_dir(&pattern, &dir)
+            })
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, project)| project.to_string())
+    }
+
+    /// Fetches the path of the `work.config` file. If it fails to find the data directory, the
+    /// function returns an error message.
+    pub fn config_file_path() -> Result<PathBuf, AppError> {
+        Ok(data_dir()?.join("work.config"))
+    }
+}
+
+/// Returns the directory Work keeps its files in, such as the log and the config file.
+///
+/// That is `WORK_HOME` if it is set, which is what `--data-dir` sets as well, or otherwise the
+/// `work` directory within `XDG_DATA_HOME` if it is set, or within the data directory of the
+/// platform if it isn't. If neither can be found, the function returns an error message.
+pub fn data_dir() -> Result<PathBuf, AppError> {
+    resolve_data_dir(
+        env::var_os("WORK_HOME"),
+        env::var_os("XDG_DATA_HOME"),
+        dirs::data_dir(),
+    )
+    .ok_or_else(|| {
+        AppError::new(ErrorKind::LogFile(
+            "Unable to find the data directory, set WORK_HOME or pass --data-dir!".to_string(),
+        ))
+        .with_code(ErrorCode::LogNotFound)
+    })
+}
+
+// Helper function that picks the data directory out of the values of `WORK_HOME`, `XDG_DATA_HOME`
+// and the data directory of the platform, see `data_dir`. Empty variables count as unset, and so
+// does an `XDG_DATA_HOME` that isn't absolute, as the XDG specification asks.
+fn resolve_data_dir(
+    work_home: Option<OsString>,
+    xdg_data_home: Option<OsString>,
+    platform: Option<PathBuf>,
+) -> Option<PathBuf> {
+    let work_home = work_home.filter(|dir| !dir.is_empty()).map(PathBuf::from);
+    let xdg_data_home = xdg_data_home
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute());
+    work_home.or_else(|| xdg_data_home.or(platform).map(|dir| dir.join("work")))
+}
+
+// Helper function that strips a single pair of surrounding quotes from a config value.
+fn unquote(value: &str) -> &str {
+    if value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')))
+    {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+// Helper function that splits a path into the names of its components.
+fn components(path: &Path) -> Vec<String> {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect()
+}
+
+// Helper function that checks whether the components of a directory match those of a pattern, in
+// which `**` matches any number of components.
+fn matches_dir(pattern: &[String], dir: &[String]) -> bool {
+    match pattern.split_first() {
+        None => dir.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=dir.len()).any(|skipped| matches_dir(rest, &dir[skipped..]))
+        }
+        Some((first, rest)) => match dir.split_first() {
+            Some((name, dir)) => matches_name(first, name) && matches_dir(rest, dir),
+            None => false,
+        },
+    }
+}
+
+// Helper function that checks whether a name matches a pattern in which `*` matches any part of
+// the name.
+fn matches_name(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    if name.len() < first.len() + last.len() || !name.starts_with(first) || !name.ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+// Helper function that expands a leading `~` in a path to the home directory of the user.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(mut home)) => {
+            home.push(rest);
+            home
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_name() {
+        assert!(matches_name("acme", "acme"));
+        assert!(!matches_name("acme", "acme2"));
+        assert!(matches_name("*", ""));
+        assert!(matches_name("acme-*", "acme-web"));
+        assert!(matches_name("*-web", "acme-web"));
+        assert!(matches_name("a*m*e", "acme"));
+        assert!(!matches_name("a*m*e", "amen"));
+        assert!(!matches_name("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn test_project_of_dir() {
+        let config = Config::parse("[directories]\n/code/** = code\n/code/*/docs = docs").unwrap();
+        let project = |dir| config.project_of_dir(Path::new(dir));
+        assert_eq!(project("/code"), Some("code".to_string()));
+        assert_eq!(project("/code/acme/docs"), Some("docs".to_string()));
+        assert_eq!(project("/code/acme/docs/api"), Some("code".to_string()));
+        assert_eq!(project("/elsewhere"), None);
+        assert_eq!(Config::default().project_of_dir(Path::new("/code")), None);
+    }
+
+    #[test]
+    fn test_resolve_data_dir() {
+        let platform = Some(PathBuf::from("/home/me/.local/share"));
+        let resolve = |work_home: &str, xdg_data_home: &str, platform: &Option<PathBuf>| {
+            resolve_data_dir(
+                Some(OsString::from(work_home)),
+                Some(OsString::from(xdg_data_home)),
+                platform.clone(),
+            )
+        };
+        assert_eq!(
+            resolve("/tmp/work", "/tmp/data", &platform),
+            Some(PathBuf::from("/tmp/work"))
+        );
+        assert_eq!(
+            resolve("", "/tmp/data", &platform),
+            Some(PathBuf::from("/tmp/data/work"))
+        );
+        assert_eq!(
+            resolve("", "data", &platform),
+            Some(PathBuf::from("/home/me/.local/share/work"))
+        );
+        assert_eq!(
+            resolve_data_dir(None, None, platform),
+            Some(PathBuf::from("/home/me/.local/share/work"))
+        );
+        assert_eq!(resolve("", "", &None), None);
+    }
+}
@@ -0,0 +1,81 @@
+use csv::WriterBuilder;
+
+/// The `CsvOptions` struct holds how CSV output is written, see `work of --csv`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// The character that separates the fields of a row.
+    pub delimiter: u8,
+    /// Whether the first row names the columns.
+    pub header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            header: true,
+        }
+    }
+}
+
+/// Writes the header followed by the rows as CSV according to RFC 4180, quoting the fields that
+/// contain the delimiter, quotes or line breaks.
+///
+/// # Examples
+/// ```
+/// # use work_core::csv_output::{self, CsvOptions};
+/// let rows = vec![vec!["work".to_string(), "Bugs, mostly".to_string()]];
+/// assert_eq!(
+///     csv_output::write(&["Project", "Description"], rows, &CsvOptions::default()),
+///     "Project,Description\nwork,\"Bugs, mostly\"\n"
+/// );
+/// ```
+pub fn write<I>(header: &[&str], rows: I, options: &CsvOptions) -> String
+where
+    I: IntoIterator<Item = Vec<String>>,
+{
+    let mut writer = WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .from_writer(Vec::new());
+    // Writing to memory can't fail, so neither can any of the writes below.
+    if options.header {
+        writer.write_record(header).unwrap();
+    }
+    for row in rows {
+        writer.write_record(&row).unwrap();
+    }
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write() {
+        let rows = || {
+            vec![
+                vec!["work".to_string(), "Said \"hi\"".to_string()],
+                vec!["play".to_string(), "Line\nbreak".to_string()],
+                vec!["docs".to_string(), "Tab\tand;semicolon".to_string()],
+            ]
+        };
+        assert_eq!(
+            write(&["Project", "Description"], rows(), &CsvOptions::default()),
+            "Project,Description\n\
+             work,\"Said \"\"hi\"\"\"\n\
+             play,\"Line\nbreak\"\n\
+             docs,Tab\tand;semicolon\n"
+        );
+        let options = CsvOptions {
+            delimiter: b';',
+            header: false,
+        };
+        assert_eq!(
+            write(&["Project", "Description"], rows(), &options),
+            "work;\"Said \"\"hi\"\"\"\n\
+             play;\"Line\nbreak\"\n\
+             docs;\"Tab\tand;semicolon\"\n"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use crate::error::{AppError, ErrorCode, ErrorKind};
+
+/// First line of the days off file, bumped whenever the format of the file changes.
+const HEADER: &str = "work-days-off 1";
+
+/// Format of the dates in the days off file.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The `DaysOff` struct holds the days no work was expected, such as vacation or sick days, along
+/// with the reason for each day. Reports point these days out so they don't look like missing work.
+///
+/// The days off are kept in `work.off` next to the log, one day per line as the date followed by
+/// the reason.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DaysOff {
+    days: BTreeMap<NaiveDate, String>,
+}
+
+impl DaysOff {
+    /// Reads the days off from the given path. A missing file simply means there are no days off.
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(DaysOff::default()),
+            Err(e) => return Err(AppError::from(e)),
+        };
+        Self::parse(&contents).ok_or_else(|| {
+            AppError::new(ErrorKind::LogFile(format!(
+                "Unable to read the days off in {}!",
+                path.display()
+            )))
+            .with_code(ErrorCode::LogCorrupt)
+        })
+    }
+
+    /// Writes the days off to the given path.
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        let mut contents = format!("{}\n", HEADER);
+        for (day, reason) in &self.days {
+            contents.push_str(&format!("{},{}\n", day.format(DATE_FORMAT), reason));
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Marks every day from `first` to `last` (inclusive) as a day off for the given reason,
+    /// replacing the reason of days that were already off.
+    pub fn add(&mut self, first: NaiveDate, last: NaiveDate, reason: &str) {
+        let mut day = first;
+        while day <= last {
+            self.days.insert(day, reason.to_string());
+            day = day.succ();
+        }
+    }
+
+    /// Returns the reason the given day is off, or `None` if it isn't a day off.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chrono::{Datelike, NaiveDate, Weekday};
+    /// # use work_core::days_off::DaysOff;
+    /// let mut days_off = DaysOff::default();
+    /// days_off.add(NaiveDate::from_ymd(2026, 12, 24), NaiveDate::from_ymd(2026, 12, 26), "Holidays");
+    /// assert_eq!(days_off.get(NaiveDate::from_ymd(2026, 12, 25)), Some("Holidays"));
+    /// assert_eq!(days_off.get(NaiveDate::from_ymd(2026, 12, 27)), None);
+    /// ```
+    pub fn get(&self, day: NaiveDate) -> Option<&str> {
+        self.days.get(&day).map(String::as_str)
+    }
+
+    /// Returns true if work was expected on the given day, that is on a weekday that isn't a day
+    /// off.
+    pub fn is_work_day(&self, day: NaiveDate) -> bool {
+        !matches!(day.weekday(), Weekday::Sat | Weekday::Sun) && self.get(day).is_none()
+    }
+
+    /// Returns the days off from `first` to `last` (inclusive) along with their reasons, earliest
+    /// first.
+    pub fn within(&self, first: NaiveDate, last: NaiveDate) -> BTreeMap<NaiveDate, String> {
+        self.days
+            .range(first..=last)
+            .map(|(day, reason)| (*day, reason.to_string()))
+            .collect()
+    }
+
+    /// Returns every day off along with its reason, earliest first.
+    pub fn days(&self) -> &BTreeMap<NaiveDate, String> {
+        &self.days
+    }
+
+    // Parses the contents of a days off file, returns `None` if they can't be understood.
+    fn parse(contents: &str) -> Option<Self> {
+        let mut lines = contents.lines();
+        if lines.next()? != HEADER {
+            return None;
+        }
+
+        let mut days_off = DaysOff::default();
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let mut values = line.splitn(2, ',');
+            let day = NaiveDate::parse_from_str(values.next()?, DATE_FORMAT).ok()?;
+            days_off.add(day, day, values.next().unwrap_or(""));
+        }
+        Some(days_off)
+    }
+}
+
+/// Formats a day off for the user along with its reason, if it has one, e.g.
+/// `Mon 12-10-2026 (Vacation)`.
+pub fn format_day(day: NaiveDate, reason: &str) -> String {
+    if reason.is_empty() {
+        day.format("%a %d-%m-%Y").to_string()
+    } else {
+        format!("{} ({})", day.format("%a %d-%m-%Y"), reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("work-days-off-test-{}", std::process::id()));
+        let mut days_off = DaysOff::default();
+        let monday = NaiveDate::from_ymd(2026, 10, 12);
+        days_off.add(monday, monday.succ().succ(), "Vacation, finally");
+        days_off.add(monday.succ(), monday.succ(), "");
+        days_off.save(&path).unwrap();
+
+        let loaded = DaysOff::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, days_off);
+        assert_eq!(loaded.days().len(), 3);
+        assert_eq!(loaded.get(monday), Some("Vacation, finally"));
+        assert_eq!(loaded.get(monday.succ()), Some(""));
+        assert_eq!(loaded.within(monday.succ(), monday.succ()).len(), 1);
+        assert_eq!(DaysOff::load(&path).unwrap(), DaysOff::default());
+        assert_eq!(DaysOff::parse("garbage\n2026-10-12,a"), None);
+    }
+
+    #[test]
+    fn test_is_work_day() {
+        let monday = NaiveDate::from_ymd(2026, 9, 7);
+        let mut days_off = DaysOff::default();
+        days_off.add(monday, monday, "Sick");
+        assert!(!days_off.is_work_day(monday));
+        assert!(days_off.is_work_day(monday.succ()));
+        assert!(!days_off.is_work_day(monday + Duration::days(5)));
+    }
+}
@@ -0,0 +1,110 @@
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+use crate::config::Config;
+use crate::error::{AppError, ErrorCode, ErrorKind};
+
+/// The `Mailer` struct sends email over SMTP, such as weekly reports with `work report --send`.
+///
+/// It is configured in the `[email]` section of the config file. The port defaults to the SMTP
+/// submission port over TLS, and the username and password can be left out for servers that don't
+/// require them. Several recipients are separated with commas:
+///
+/// ```text
+/// [email]
+/// smtp_server = smtp.example.com
+/// smtp_port = 465
+/// username = me@example.com
+/// password = hunter2
+/// from = Me <me@example.com>
+/// to = timesheets@example.com, boss@example.com
+/// ```
+#[derive(Clone, Debug)]
+pub struct Mailer {
+    server: String,
+    port: Option<u16>,
+    credentials: Option<(String, String)>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl Mailer {
+    /// Creates a `Mailer` from the `email.smtp_server`, `email.smtp_port`, `email.username`,
+    /// `email.password`, `email.from` and `email.to` config values.
+    pub fn from_config(config: &Config) -> Result<Self, AppError> {
+        let required = |key: &str| {
+            config.get(key).ok_or_else(|| {
+                AppError::new(ErrorKind::Config(format!(
+                    "Please set {} in the config file to send email.",
+                    key
+                )))
+            })
+        };
+        let mailbox = |address: &str| {
+            address.trim().parse::<Mailbox>().map_err(|e| {
+                AppError::new(ErrorKind::Config(format!(
+                    "Invalid email address {}: {}",
+                    address.trim(),
+                    e
+                )))
+            })
+        };
+
+        let port = match config.get("email.smtp_port") {
+            Some(port) => Some(port.parse().map_err(|_| {
+                AppError::new(ErrorKind::Config(format!("Invalid SMTP port {}.", port)))
+            })?),
+            None => None,
+        };
+        let credentials = match (config.get("email.username"), config.get("email.password")) {
+            (Some(username), Some(password)) => Some((username.to_string(), password.to_string())),
+            _ => None,
+        };
+        Ok(Mailer {
+            server: required("email.smtp_server")?.to_string(),
+            port,
+            credentials,
+            from: mailbox(required("email.from")?)?,
+            to: required("email.to")?
+                .split(',')
+                .map(mailbox)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Sends an email with the given subject to the configured recipients, with both a plain text
+    /// and an HTML version of its contents.
+    pub fn send(&self, subject: &str, text: String, html: String) -> Result<(), AppError> {
+        let mut message = Message::builder().from(self.from.clone()).subject(subject);
+        for to in &self.to {
+            message = message.to(to.clone());
+        }
+        let message = message
+            .multipart(MultiPart::alternative_plain_html(text, html))
+            .map_err(|e| email_error(&e.to_string()))?;
+
+        let mut transport =
+            SmtpTransport::relay(&self.server).map_err(|e| email_error(&e.to_string()))?;
+        if let Some(port) = self.port {
+            transport = transport.port(port);
+        }
+        if let Some((username, password)) = &self.credentials {
+            transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        transport
+            .build()
+            .send(&message)
+            .map_err(|e| email_error(&e.to_string()))?;
+        Ok(())
+    }
+}
+
+// Helper function for building the error returned when an email can't be sent.
+fn email_error(reason: &str) -> AppError {
+    AppError::new(ErrorKind::System(format!(
+        "Unable to send email: {}",
+        reason
+    )))
+    .with_code(ErrorCode::Network)
+}
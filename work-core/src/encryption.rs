@@ -0,0 +1,208 @@
+use std::fs;
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+use crate::config::Config;
+use crate::error::{AppError, ErrorCode, ErrorKind};
+
+/// Marks the start of an encrypted log, used to tell encrypted logs apart from plain text ones.
+const MAGIC: &[u8] = b"WORKENC1";
+
+/// Length of the salt used when deriving the key from the passphrase or keyfile.
+const SALT_LEN: usize = 16;
+
+/// Length of the XChaCha20-Poly1305 nonce.
+const NONCE_LEN: usize = 24;
+
+/// The `Cipher` struct encrypts and decrypts the whole contents of the log file with
+/// XChaCha20-Poly1305.
+///
+/// An encrypted log is laid out as `[MAGIC][SALT][NONCE][CIPHERTEXT]`. The key is derived from
+/// the configured passphrase or keyfile with Argon2 and the salt stored in the log, which means
+/// the (slow) key derivation only has to happen once per run.
+pub struct Cipher {
+    secret: Vec<u8>,
+    derived: Option<([u8; SALT_LEN], Key)>,
+}
+
+impl Cipher {
+    /// Creates a `Cipher` from the `encryption.passphrase` or `encryption.keyfile` config values.
+    /// Returns `None` if neither is set, in which case the log is kept as plain text.
+    pub fn from_config(config: &Config) -> Result<Option<Self>, AppError> {
+        let keyfile = config.get_path("encryption.keyfile");
+        let secret = match (config.get("encryption.passphrase"), keyfile) {
+            (Some(_), Some(_)) => {
+                return Err(AppError::new(ErrorKind::User(
+                    "Please configure either a passphrase or a keyfile for encryption, not both."
+                        .to_string(),
+                ))
+                .with_code(ErrorCode::Encryption));
+            }
+            (Some(passphrase), None) => passphrase.as_bytes().to_vec(),
+            (None, Some(path)) => match fs::read(&path) {
+                Ok(secret) => secret,
+                Err(e) => {
+                    return Err(AppError::new(ErrorKind::User(format!(
+                        "Unable to read keyfile {}: {}",
+                        path.display(),
+                        e
+                    )))
+                    .with_source(e)
+                    .with_code(ErrorCode::Encryption));
+                }
+            },
+            (None, None) => return Ok(None),
+        };
+
+        if secret.is_empty() {
+            return Err(AppError::new(ErrorKind::User(
+                "The encryption passphrase or keyfile must not be empty.".to_string(),
+            ))
+            .with_code(ErrorCode::Encryption));
+        }
+        Ok(Some(Cipher {
+            secret,
+            derived: None,
+        }))
+    }
+
+    /// Returns true if the given log contents were written by a `Cipher`.
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    /// Encrypts the given plain text log contents.
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+        let salt = match &self.derived {
+            Some((salt, _)) => *salt,
+            None => {
+                let mut salt = [0; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                salt
+            }
+        };
+        let cipher = XChaCha20Poly1305::new(&self.key(&salt)?);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher.encrypt(&nonce, plaintext).map_err(|_| {
+            AppError::new(ErrorKind::System("Unable to encrypt work log!".into()))
+                .with_code(ErrorCode::Encryption)
+        })?;
+
+        let mut data = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&salt);
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+        Ok(data)
+    }
+
+    /// Decrypts the given encrypted log contents. Fails if the log has been tampered with or if
+    /// the configured passphrase or keyfile is not the one the log was encrypted with.
+    pub fn decrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, AppError> {
+        let header_len = MAGIC.len() + SALT_LEN + NONCE_LEN;
+        if !Self::is_encrypted(data) || data.len() < header_len {
+            return Err(AppError::new(ErrorKind::LogFile(
+                "Work log is not a valid encrypted log!".to_string(),
+            ))
+            .with_code(ErrorCode::Encryption));
+        }
+
+        let mut salt = [0; SALT_LEN];
+        salt.copy_from_slice(&data[MAGIC.len()..MAGIC.len() + SALT_LEN]);
+        let nonce = XNonce::from_slice(&data[MAGIC.len() + SALT_LEN..header_len]);
+        let cipher = XChaCha20Poly1305::new(&self.key(&salt)?);
+        cipher.decrypt(nonce, &data[header_len..]).map_err(|_| {
+            AppError::new(ErrorKind::LogFile(
+                "Unable to decrypt work log, is the passphrase or keyfile correct?".to_string(),
+            ))
+            .with_code(ErrorCode::Encryption)
+        })
+    }
+
+    // Derives the key for the given salt, reusing the previously derived key if the salt matches.
+    fn key(&mut self, salt: &[u8; SALT_LEN]) -> Result<Key, AppError> {
+        if let Some((derived_salt, key)) = &self.derived {
+            if derived_salt == salt {
+                return Ok(*key);
+            }
+        }
+
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(&self.secret, salt, &mut key)
+            .map_err(|e| {
+                AppError::new(ErrorKind::System(format!(
+                    "Unable to derive encryption key: {}",
+                    e
+                )))
+                .with_code(ErrorCode::Encryption)
+            })?;
+        self.derived = Some((*salt, key));
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Helper function for tests, returning the cipher of a config with the given passphrase.
+    fn cipher(passphrase: &str) -> Cipher {
+        let config = Config::parse(&format!("[encryption]\npassphrase = {}", passphrase)).unwrap();
+        Cipher::from_config(&config).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let log = b"100,Start,work,\n200,Stop,work,\n";
+        let data = cipher("secret").encrypt(log).unwrap();
+        assert!(Cipher::is_encrypted(&data));
+        assert!(!data.windows(4).any(|window| window == b"work"));
+        assert_eq!(cipher("secret").decrypt(&data).unwrap(), log);
+    }
+
+    #[test]
+    fn test_wrong_secret() {
+        let data = cipher("secret").encrypt(b"100,Start,work,\n").unwrap();
+        let error = cipher("guess").decrypt(&data).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::Encryption);
+
+        let dir = std::env::temp_dir().join(format!("work-keyfile-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let keyfile = |name: &str, key: &[u8]| {
+            let path = dir.join(name);
+            fs::write(&path, key).unwrap();
+            let config =
+                Config::parse(&format!("[encryption]\nkeyfile = {}", path.display())).unwrap();
+            Cipher::from_config(&config).unwrap().unwrap()
+        };
+        let data = keyfile("right.key", &[1; 32])
+            .encrypt(b"100,Start,work,\n")
+            .unwrap();
+        let error = keyfile("wrong.key", &[2; 32]).decrypt(&data).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::Encryption);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_tampered() {
+        let mut data = cipher("secret").encrypt(b"100,Start,work,\n").unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        let error = cipher("secret").decrypt(&data).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::Encryption);
+    }
+
+    #[test]
+    fn test_truncated_header() {
+        let data = cipher("secret").encrypt(b"100,Start,work,\n").unwrap();
+        let header_len = MAGIC.len() + SALT_LEN + NONCE_LEN;
+        for len in &[MAGIC.len(), header_len - 1] {
+            let error = cipher("secret").decrypt(&data[..*len]).unwrap_err();
+            assert_eq!(error.code(), ErrorCode::Encryption);
+        }
+    }
+}
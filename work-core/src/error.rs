@@ -0,0 +1,387 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use serde_json::json;
+
+/// An error that can occur in this crate.
+///
+/// There are two main reasons for a error in this crate.
+/// Either the user did something silly like a wrong input or something went wrong regardin the
+/// log file.
+///
+/// These errors are meant to "flow upwards" and eventually printed to the terminal. If a function
+/// returns an AppError, it most likely returns all the way back to `main()`.
+///
+/// Every error carries an `ErrorCode` as well, so scripts can tell errors apart without having to
+/// match on their messages. Errors caused by another error, such as an `io::Error`, keep it as
+/// their `source`.
+#[derive(Clone, Debug)]
+pub struct AppError {
+    kind: ErrorKind,
+    code: ErrorCode,
+    source: Option<Arc<dyn error::Error + Send + Sync>>,
+}
+
+impl AppError {
+    /// Creates an error of the given `kind`, with the code that kind implies.
+    pub fn new(kind: ErrorKind) -> AppError {
+        let code = match kind {
+            ErrorKind::User(_) | ErrorKind::Parse(_) => ErrorCode::InvalidInput,
+            ErrorKind::Config(_) => ErrorCode::InvalidConfig,
+            ErrorKind::System(_) => ErrorCode::System,
+            ErrorKind::LogFile(_) => ErrorCode::LogIo,
+        };
+        AppError {
+            kind,
+            code,
+            source: None,
+        }
+    }
+
+    /// Keeps the error that caused this error as its `source`.
+    pub fn with_source<E>(mut self, source: E) -> AppError
+    where
+        E: error::Error + Send + Sync + 'static,
+    {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    /// Sets a more specific `code` than the one the `kind` of this error implies.
+    pub fn with_code(mut self, code: ErrorCode) -> AppError {
+        self.code = code;
+        self
+    }
+
+    /// Return the `kind` of this error
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Returns the `code` of this error.
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// Returns the message of this error, without its code.
+    pub fn message(&self) -> &str {
+        match self.kind {
+            ErrorKind::User(ref s)
+            | ErrorKind::Parse(ref s)
+            | ErrorKind::Config(ref s)
+            | ErrorKind::System(ref s)
+            | ErrorKind::LogFile(ref s) => s,
+        }
+    }
+
+    /// Returns the error as a JSON object holding its `code`, `kind` and `message`, see
+    /// `--json-errors`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::error::AppError;
+    /// let error = AppError::from(std::io::Error::from(std::io::ErrorKind::NotFound));
+    /// assert_eq!(
+    ///     error.to_json(),
+    ///     r#"{"code":"log-not-found","kind":"log-file","message":"Work log does not exist!"}"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        json!({
+            "code": self.code.name(),
+            "kind": self.kind.name(),
+            "message": self.message(),
+        })
+        .to_string()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ErrorKind {
+    User(String),
+    /// An input such as a time or an argument couldn't be parsed.
+    Parse(String),
+    /// The config file is invalid or misses a required value.
+    Config(String),
+    System(String),
+    LogFile(String),
+}
+
+impl ErrorKind {
+    /// Returns the name of the kind of error, as given by `--json-errors`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::User(_) => "user",
+            ErrorKind::Parse(_) => "parse",
+            ErrorKind::Config(_) => "config",
+            ErrorKind::System(_) => "system",
+            ErrorKind::LogFile(_) => "log-file",
+        }
+    }
+}
+
+/// The `ErrorCode` enum lists the errors Work can run into, each with a stable name that is part
+/// of every error message, e.g. `Unable to stop, no work in progress! [not-working]`.
+///
+/// Codes are never renamed nor reused, so scripts can rely on them. `work exitcodes` lists them
+/// along with the exit codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    InvalidInput,
+    InvalidTime,
+    NotWorking,
+    AlreadyWorking,
+    OutOfOrder,
+    Overlap,
+    Locked,
+    SessionNotFound,
+    InvalidConfig,
+    MissingFeature,
+    UnknownCommand,
+    CommandFailed,
+    Encryption,
+    LogNotFound,
+    LogPermission,
+    LogCorrupt,
+    LogIo,
+    Network,
+    System,
+}
+
+impl ErrorCode {
+    /// Every error code, in the order they are listed in.
+    pub const ALL: [ErrorCode; 19] = [
+        ErrorCode::InvalidInput,
+        ErrorCode::InvalidTime,
+        ErrorCode::NotWorking,
+        ErrorCode::AlreadyWorking,
+        ErrorCode::OutOfOrder,
+        ErrorCode::Overlap,
+        ErrorCode::Locked,
+        ErrorCode::SessionNotFound,
+        ErrorCode::InvalidConfig,
+        ErrorCode::MissingFeature,
+        ErrorCode::UnknownCommand,
+        ErrorCode::CommandFailed,
+        ErrorCode::Encryption,
+        ErrorCode::LogNotFound,
+        ErrorCode::LogPermission,
+        ErrorCode::LogCorrupt,
+        ErrorCode::LogIo,
+        ErrorCode::Network,
+        ErrorCode::System,
+    ];
+
+    /// Returns the stable name of the error code.
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidInput => "invalid-input",
+            ErrorCode::InvalidTime => "invalid-time",
+            ErrorCode::NotWorking => "not-working",
+            ErrorCode::AlreadyWorking => "already-working",
+            ErrorCode::OutOfOrder => "out-of-order",
+            ErrorCode::Overlap => "overlap",
+            ErrorCode::Locked => "locked",
+            ErrorCode::SessionNotFound => "session-not-found",
+            ErrorCode::InvalidConfig => "invalid-config",
+            ErrorCode::MissingFeature => "missing-feature",
+            ErrorCode::UnknownCommand => "unknown-command",
+            ErrorCode::CommandFailed => "command-failed",
+            ErrorCode::Encryption => "encryption",
+            ErrorCode::LogNotFound => "log-not-found",
+            ErrorCode::LogPermission => "log-permission",
+            ErrorCode::LogCorrupt => "log-corrupt",
+            ErrorCode::LogIo => "log-io",
+            ErrorCode::Network => "network",
+            ErrorCode::System => "system",
+        }
+    }
+
+    /// Returns a short description of when the error occurs.
+    pub fn description(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidInput => "An argument or input couldn't be understood",
+            ErrorCode::InvalidTime => {
+                "A time or interval couldn't be understood or is out of range"
+            }
+            ErrorCode::NotWorking => "The command needs work in progress, but there is none",
+            ErrorCode::AlreadyWorking => "The command needs no work in progress, but there is",
+            ErrorCode::OutOfOrder => "The event would come before the latest event in the log",
+            ErrorCode::Overlap => "The new session overlaps work already in the log",
+            ErrorCode::Locked => "The change touches a locked period",
+            ErrorCode::SessionNotFound => "No session matches the given id",
+            ErrorCode::InvalidConfig => "The config file is invalid or misses a required value",
+            ErrorCode::MissingFeature => "Work was installed without the feature the command needs",
+            ErrorCode::UnknownCommand => "No such command or external formatter exists",
+            ErrorCode::CommandFailed => "A command run by Work failed or couldn't be started",
+            ErrorCode::Encryption => "The log couldn't be encrypted or decrypted",
+            ErrorCode::LogNotFound => "The log does not exist",
+            ErrorCode::LogPermission => "The log can't be accessed with the current permissions",
+            ErrorCode::LogCorrupt => "The log or one of the files next to it can't be understood",
+            ErrorCode::LogIo => "The log or its folder couldn't be read or written",
+            ErrorCode::Network => "A server couldn't be reached or listened on",
+            ErrorCode::System => "Something else went wrong with the system",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl error::Error for AppError {
+    fn description(&self) -> &str {
+        match self.kind {
+            ErrorKind::User(_) => "user error",
+            ErrorKind::Parse(_) => "parse error",
+            ErrorKind::Config(_) => "config error",
+            ErrorKind::System(_) => "system error",
+            ErrorKind::LogFile(_) => "log file error",
+        }
+    }
+
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source.as_ref() as &(dyn error::Error + 'static))
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} [{}]", self.message(), self.code)
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(error: io::Error) -> Self {
+        let app_error = match error.kind() {
+            io::ErrorKind::NotFound => {
+                AppError::new(ErrorKind::LogFile("Work log does not exist!".to_string()))
+                    .with_code(ErrorCode::LogNotFound)
+            }
+            io::ErrorKind::PermissionDenied => AppError::new(ErrorKind::LogFile(
+                "Invalid permissions for work log!".to_string(),
+            ))
+            .with_code(ErrorCode::LogPermission),
+            _ => AppError::new(ErrorKind::LogFile(
+                "Unable to write/read to/from work log!".to_string(),
+            )),
+        };
+        app_error.with_source(error)
+    }
+}
+
+/// The `ExitCode` enum lists the codes Work exits with, so scripts can rely on them.
+///
+/// The `working`, `free` and `of` commands answer a question with their exit code, a negative
+/// answer is signaled with `ExitCode::Negative`. Errors exit with the code of their `ErrorKind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitCode {
+    Success,
+    Negative,
+    UserError,
+    LogFileError,
+    SystemError,
+}
+
+impl ExitCode {
+    /// Every exit code in the order of their numbers.
+    pub const ALL: [ExitCode; 5] = [
+        ExitCode::Success,
+        ExitCode::Negative,
+        ExitCode::UserError,
+        ExitCode::LogFileError,
+        ExitCode::SystemError,
+    ];
+
+    /// Returns the number the process exits with.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::error::ExitCode;
+    /// assert_eq!(ExitCode::Success.code(), 0);
+    /// assert_eq!(ExitCode::SystemError.code(), 4);
+    /// ```
+    pub fn code(self) -> i32 {
+        match self {
+            ExitCode::Success => 0,
+            ExitCode::Negative => 1,
+            ExitCode::UserError => 2,
+            ExitCode::LogFileError => 3,
+            ExitCode::SystemError => 4,
+        }
+    }
+
+    /// Returns a short description of when Work exits with this code.
+    pub fn description(self) -> &'static str {
+        match self {
+            ExitCode::Success => "The command succeeded",
+            ExitCode::Negative => {
+                "`working` found no work in progress, `free` found work in progress, or `of` found \
+                 no work within the interval"
+            }
+            ExitCode::UserError => {
+                "Invalid input, such as a time or a config file that can't be understood"
+            }
+            ExitCode::LogFileError => "The log could not be found, read or written",
+            ExitCode::SystemError => {
+                "Something went wrong with the system, such as a failed command"
+            }
+        }
+    }
+}
+
+impl From<&ErrorKind> for ExitCode {
+    fn from(kind: &ErrorKind) -> Self {
+        match kind {
+            ErrorKind::User(_) | ErrorKind::Parse(_) | ErrorKind::Config(_) => ExitCode::UserError,
+            ErrorKind::LogFile(_) => ExitCode::LogFileError,
+            ErrorKind::System(_) => ExitCode::SystemError,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_error_codes() {
+        let names: HashSet<&str> = ErrorCode::ALL.iter().map(|code| code.name()).collect();
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+
+        let error = AppError::new(ErrorKind::User("Unable to stop".to_string()))
+            .with_code(ErrorCode::NotWorking);
+        assert_eq!(error.code(), ErrorCode::NotWorking);
+        assert_eq!(error.message(), "Unable to stop");
+        assert_eq!(error.to_string(), "Unable to stop [not-working]");
+        assert_eq!(
+            AppError::new(ErrorKind::System(String::new())).code(),
+            ErrorCode::System
+        );
+        assert_eq!(
+            AppError::new(ErrorKind::Config(String::new())).code(),
+            ErrorCode::InvalidConfig
+        );
+    }
+
+    #[test]
+    fn test_source() {
+        use std::error::Error;
+
+        let error = AppError::from(io::Error::other("disk on fire"));
+        assert_eq!(error.message(), "Unable to write/read to/from work log!");
+        assert_eq!(error.source().unwrap().to_string(), "disk on fire");
+        let io_error = error.source().unwrap().downcast_ref::<io::Error>();
+        assert_eq!(io_error.unwrap().kind(), io::ErrorKind::Other);
+        assert!(AppError::new(ErrorKind::User(String::new()))
+            .source()
+            .is_none());
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use chrono::{Local, NaiveDate, TimeZone};
+
+use crate::arguments::TimeFormat;
+use crate::error::AppError;
+use crate::log_file::LogFile;
+use crate::project_map::ProjectMap;
+use crate::report::{self, Report};
+use crate::sessions::{self, Session};
+use crate::time::{self, format_time, Interval};
+
+/// Format of the days in the bar chart.
+const DAY_FORMAT: &str = "%a %d-%m";
+
+/// Width in pixels of the bar of the day with the most work done.
+const BAR_WIDTH: i64 = 400;
+
+/// Height in pixels of each row of the bar chart.
+const ROW_HEIGHT: i64 = 24;
+
+/// Width in pixels of the labels on either side of the bars.
+const LABEL_WIDTH: i64 = 150;
+
+/// Styling of the exported HTML, kept inline so the file stands on its own.
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; }\n\
+                     table { border-collapse: collapse; margin-bottom: 1em; }\n\
+                     th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }\n\
+                     svg text { font-size: 12px; }";
+
+/// The `Export` struct holds everything needed to export the work done within an interval as a
+/// single document, see `work export`.
+#[derive(Clone, Debug)]
+pub struct Export {
+    /// The time spent on each project per day.
+    pub report: Report,
+    /// The sessions within the interval, earliest first.
+    pub sessions: Vec<Session>,
+    /// The time the export was made, which a session in progress lasts until.
+    pub now: i64,
+}
+
+impl Export {
+    /// Gathers the time spent on each project per day and the sessions within the given interval.
+    pub fn new(log: &mut LogFile, interval: &Interval) -> Result<Self, AppError> {
+        let report = Report::new(log, interval)?;
+        let now = log.now();
+        let sessions = sessions::reconstruct(&log.all_events()?)
+            .into_iter()
+            .filter(|session| session.overlaps(interval.start, interval.end, now))
+            .filter(|session| log.passes_filter(session))
+            .collect();
+        Ok(Export {
+            report,
+            sessions,
+            now,
+        })
+    }
+
+    /// Returns true if no work was done within the interval, nor were any days off.
+    pub fn is_empty(&self) -> bool {
+        self.report.is_empty()
+    }
+
+    /// Returns the export as a standalone HTML document, holding the time spent on each project,
+    /// a bar chart of the time worked per day and a list of the sessions.
+    pub fn as_html(&self, time_format: &TimeFormat) -> String {
+        let projects = self.report.projects();
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\n{style}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n",
+            title = report::escape(&self.report.title()),
+            style = STYLE
+        );
+
+        html.push_str("<h2>Projects</h2>\n<table>\n<tr><th>Project</th><th>Time Spent</th></tr>\n");
+        for (project, time) in report::totals(&projects) {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>\n",
+                report::escape(project),
+                format_time(time_format, time)
+            ));
+        }
+        html.push_str(&format!(
+            "<tr><th>Total</th><th>{}</th></tr>\n</table>\n",
+            format_time(time_format, report::total(&projects))
+        ));
+
+        html.push_str("<h2>Days</h2>\n");
+        html.push_str(&chart(&self.report.days, time_format));
+
+        html.push_str(
+            "<h2>Sessions</h2>\n<table>\n<tr><th>Day</th><th>Start</th><th>Stop</th>\
+             <th>Project</th><th>Description</th><th>Time Spent</th></tr>\n",
+        );
+        for session in &self.sessions {
+            let event = session.start_event();
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                Local.timestamp(session.start, 0).format(DAY_FORMAT),
+                time::format_clock(session.start),
+                session.stop.map_or("now".to_string(), time::format_clock),
+                report::escape(&event.to_project()),
+                report::escape(&event.to_description()),
+                format_time(time_format, session.duration(self.now))
+            ));
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+// Helper function that draws the time worked per day as an inline SVG bar chart, a bar per day
+// scaled to the day with the most work done.
+fn chart(days: &BTreeMap<NaiveDate, ProjectMap>, time_format: &TimeFormat) -> String {
+    let totals: Vec<(&NaiveDate, i64)> = days
+        .iter()
+        .map(|(day, projects)| (day, report::total(projects)))
+        .collect();
+    let longest = totals
+        .iter()
+        .map(|(_, time)| *time)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+        2 * LABEL_WIDTH + BAR_WIDTH,
+        ROW_HEIGHT * totals.len() as i64
+    );
+    for (row, (day, time)) in totals.iter().enumerate() {
+        let y = ROW_HEIGHT * row as i64;
+        let formatted = format_time(time_format, *time);
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{text_y}\">{day}</text>\
+             <rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" fill=\"#4a90d9\">\
+             <title>{time}</title></rect>\
+             <text x=\"{label_x}\" y=\"{text_y}\">{time}</text>\n",
+            text_y = y + ROW_HEIGHT * 2 / 3,
+            day = day.format(DAY_FORMAT),
+            x = LABEL_WIDTH,
+            y = y + 2,
+            width = BAR_WIDTH * time / longest,
+            height = ROW_HEIGHT - 4,
+            time = report::escape(&formatted),
+            label_x = LABEL_WIDTH + BAR_WIDTH * time / longest + 6
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_map::ProjectMapMethods;
+
+    #[test]
+    fn test_chart() {
+        let monday = NaiveDate::from_ymd(2026, 10, 12);
+        let mut first = ProjectMap::new();
+        first.add_time("work", "No description", 2 * 3600);
+        let mut second = ProjectMap::new();
+        second.add_time("<play>", "No description", 3600);
+        let mut days = BTreeMap::new();
+        days.insert(monday, first);
+        days.insert(monday.succ(), second);
+
+        let svg = chart(&days, &TimeFormat::Minutes);
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"700\" height=\"48\">"));
+        assert!(svg.contains(">Mon 12-10</text><rect x=\"150\" y=\"2\" width=\"400\""));
+        assert!(svg.contains(">Tue 13-10</text><rect x=\"150\" y=\"26\" width=\"200\""));
+        assert!(svg.contains("<text x=\"356\" y=\"40\">60</text>"));
+        assert!(chart(&BTreeMap::new(), &TimeFormat::Minutes).contains("height=\"0\""));
+    }
+}
@@ -0,0 +1,567 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+use regex::Regex;
+
+use crate::error::{AppError, ErrorKind};
+use crate::grouping;
+use crate::sessions::Session;
+use crate::time;
+
+/// The fields a filter can look at, as listed in error messages.
+const FIELDS: &str = "project, description, client, user, tag, billable or duration";
+
+/// The `Filter` enum holds a filter expression that picks the sessions a command looks at, such as
+/// `project=backend and tag=review and duration>30m`, see `work of --filter`.
+///
+/// Conditions compare a field of a session with a value using `=`, `!=`, `~` (contains), or, for
+/// the duration, `<`, `<=`, `>` and `>=`. They are combined with `and`, `or`, `not` and
+/// parentheses, where `and` binds tighter than `or`. Names and descriptions are compared without
+/// regard to case, and values holding spaces or operators can be quoted.
+///
+/// # Examples
+/// ```
+/// # use work_core::filter::Filter;
+/// # use work_core::log_file::Metadata;
+/// # use work_core::sessions::Session;
+/// let session = Session {
+///     start: 0,
+///     stop: Some(3600),
+///     project: Some("backend".to_string()),
+///     description: Some("Code review #review".to_string()),
+///     client: None,
+///     billable: None,
+///     metadata: Metadata::new(),
+/// };
+/// let filter: Filter = "project=Backend and (tag=review or duration>2h)".parse().unwrap();
+/// assert!(filter.matches(&session, 3600));
+/// let filter: Filter = "not description~review or duration<=30m".parse().unwrap();
+/// assert!(!filter.matches(&session, 3600));
+/// assert!("project>backend".parse::<Filter>().is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Filter {
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Condition(Condition),
+}
+
+/// A single comparison of a field of a session with a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Condition {
+    /// Compares the project, description, client or user of a session, the value is lowercase.
+    Text(Field, Operator, String),
+    /// Whether a session has the given tag, or doesn't if the operator is `!=`, see
+    /// `grouping::tags`.
+    Tag(Operator, String),
+    /// Whether a session is billable.
+    Billable(Operator, bool),
+    /// Compares the duration of a session with the given number of seconds.
+    Duration(Operator, i64),
+    /// Whether the project, description, client or user of a session matches the given regular
+    /// expression, see `--project-regex` and `--description-regex`.
+    Regex(Field, Pattern),
+}
+
+/// A regular expression compiled once when it is parsed. Patterns are equal if they are written
+/// the same.
+///
+/// # Examples
+/// ```
+/// # use work_core::filter::Pattern;
+/// let pattern: Pattern = "^(api|web)-".parse().unwrap();
+/// assert!(pattern.0.is_match("api-gateway"));
+/// assert!("(api".parse::<Pattern>().is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Pattern(pub Regex);
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+impl FromStr for Pattern {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Regex::new(s).map(Pattern).map_err(|e| {
+            AppError::new(ErrorKind::Parse(format!(
+                "Invalid regular expression, {}",
+                e
+            )))
+        })
+    }
+}
+
+/// The text fields of a session a filter can compare.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Project,
+    Description,
+    Client,
+    User,
+}
+
+/// The ways a filter can compare a field with a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Equal,
+    NotEqual,
+    Contains,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Filter {
+    /// Combines the given filters into one that only passes the sessions passing all of them, or
+    /// returns `None` if there are none.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::filter::{Condition, Field, Filter};
+    /// let project = Filter::Condition(Condition::Regex(Field::Project, "^api".parse().unwrap()));
+    /// let filter = Filter::all(vec![Some(project), None, "tag=bug".parse().ok()]).unwrap();
+    /// assert!(matches!(filter, Filter::And(_, _)));
+    /// assert_eq!(Filter::all(vec![None]), None);
+    /// ```
+    pub fn all<I>(filters: I) -> Option<Filter>
+    where
+        I: IntoIterator<Item = Option<Filter>>,
+    {
+        filters
+            .into_iter()
+            .flatten()
+            .reduce(|left, right| Filter::And(Box::new(left), Box::new(right)))
+    }
+
+    /// Returns true if the given session passes the filter. A session in progress lasts until
+    /// `now`.
+    pub fn matches(&self, session: &Session, now: i64) -> bool {
+        match self {
+            Filter::And(left, right) => left.matches(session, now) && right.matches(session, now),
+            Filter::Or(left, right) => left.matches(session, now) || right.matches(session, now),
+            Filter::Not(filter) => !filter.matches(session, now),
+            Filter::Condition(condition) => condition.matches(session, now),
+        }
+    }
+}
+
+impl Condition {
+    /// Returns true if the given session meets the condition. A session in progress lasts until
+    /// `now`.
+    pub fn matches(&self, session: &Session, now: i64) -> bool {
+        match self {
+            Condition::Text(field, operator, value) => {
+                let text = text(*field, session).to_lowercase();
+                match operator {
+                    Operator::NotEqual => text != *value,
+                    Operator::Contains => text.contains(value.as_str()),
+                    _ => text == *value,
+                }
+            }
+            Condition::Tag(operator, tag) => {
+                let description = session.description.as_deref().unwrap_or("");
+                let tagged = grouping::tags(description)
+                    .iter()
+                    .any(|other| other.to_lowercase() == *tag);
+                tagged == (*operator != Operator::NotEqual)
+            }
+            Condition::Billable(operator, billable) => {
+                let is_billable = session.start_event().is_billable();
+                (is_billable == *billable) == (*operator == Operator::Equal)
+            }
+            Condition::Duration(operator, seconds) => {
+                let duration = session.duration(now);
+                match operator {
+                    Operator::Equal => duration == *seconds,
+                    Operator::NotEqual => duration != *seconds,
+                    Operator::Less => duration < *seconds,
+                    Operator::LessOrEqual => duration <= *seconds,
+                    Operator::Greater => duration > *seconds,
+                    _ => duration >= *seconds,
+                }
+            }
+            Condition::Regex(field, pattern) => pattern.0.is_match(&text(*field, session)),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let filter = parser.or()?;
+        match parser.next() {
+            None => Ok(filter),
+            Some(token) => Err(invalid(&format!("unexpected {}", token))),
+        }
+    }
+}
+
+/// A single token of a filter expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Operator(Operator),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "'{}'", word),
+            Token::Operator(operator) => write!(f, "'{}'", symbol(*operator)),
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+        }
+    }
+}
+
+// Parses a list of tokens by recursive descent, with `or` binding loosest and `not` tightest.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        match self.tokens.get(self.position) {
+            Some(Token::Word(word)) => word.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<Filter, AppError> {
+        let mut filter = self.and()?;
+        while self.peek_keyword("or") {
+            self.position += 1;
+            filter = Filter::Or(Box::new(filter), Box::new(self.and()?));
+        }
+        Ok(filter)
+    }
+
+    fn and(&mut self) -> Result<Filter, AppError> {
+        let mut filter = self.not()?;
+        while self.peek_keyword("and") {
+            self.position += 1;
+            filter = Filter::And(Box::new(filter), Box::new(self.not()?));
+        }
+        Ok(filter)
+    }
+
+    fn not(&mut self) -> Result<Filter, AppError> {
+        if self.peek_keyword("not") {
+            self.position += 1;
+            return Ok(Filter::Not(Box::new(self.not()?)));
+        }
+        match self.next() {
+            Some(Token::Open) => {
+                let filter = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(filter),
+                    _ => Err(invalid("missing ')'")),
+                }
+            }
+            Some(Token::Word(field)) => self.condition(&field),
+            Some(token) => Err(invalid(&format!(
+                "expected a field such as project, got {}",
+                token
+            ))),
+            None => Err(invalid(&format!("expected one of {}", FIELDS))),
+        }
+    }
+
+    fn condition(&mut self, field: &str) -> Result<Filter, AppError> {
+        let operator = match self.next() {
+            Some(Token::Operator(operator)) => operator,
+            _ => {
+                return Err(invalid(&format!(
+                    "expected an operator such as = after {}",
+                    field
+                )))
+            }
+        };
+        let value = match self.next() {
+            Some(Token::Word(value)) => value,
+            _ => {
+                return Err(invalid(&format!(
+                    "expected a value after {}{}",
+                    field,
+                    symbol(operator)
+                )))
+            }
+        };
+        let ordered = !matches!(
+            operator,
+            Operator::Equal | Operator::NotEqual | Operator::Contains
+        );
+        let condition = match field.to_lowercase().as_str() {
+            "duration" => match time::parse_duration(&value) {
+                Some(seconds) if operator != Operator::Contains => {
+                    Condition::Duration(operator, seconds)
+                }
+                Some(_) => {
+                    return Err(invalid(
+                        "the duration can only be compared with =, !=, <, <=, > or >=",
+                    ))
+                }
+                None => {
+                    return Err(invalid(&format!(
+                        "expected a duration such as 30m or 1h30m, got '{}'",
+                        value
+                    )))
+                }
+            },
+            _ if ordered => {
+                return Err(invalid(&format!(
+                    "only the duration can be compared with {}",
+                    symbol(operator)
+                )))
+            }
+            "tag" if operator == Operator::Contains => {
+                return Err(invalid("tags can only be compared with = or !="))
+            }
+            "tag" => Condition::Tag(operator, value.trim_start_matches('#').to_lowercase()),
+            "billable" => match value.to_lowercase().as_str() {
+                _ if operator == Operator::Contains => {
+                    return Err(invalid("billable can only be compared with = or !="))
+                }
+                "true" | "yes" => Condition::Billable(operator, true),
+                "false" | "no" => Condition::Billable(operator, false),
+                _ => return Err(invalid("billable is either true or false")),
+            },
+            name => {
+                let field = match name {
+                    "project" => Field::Project,
+                    "description" => Field::Description,
+                    "client" => Field::Client,
+                    "user" => Field::User,
+                    _ => {
+                        return Err(invalid(&format!(
+                            "unknown field '{}', expected one of {}",
+                            field, FIELDS
+                        )))
+                    }
+                };
+                Condition::Text(field, operator, value.to_lowercase())
+            }
+        };
+        Ok(Filter::Condition(condition))
+    }
+}
+
+// Helper function that returns the given text field of a session as it was written.
+fn text(field: Field, session: &Session) -> String {
+    let event = session.start_event();
+    match field {
+        Field::Project => event.to_project(),
+        Field::Description => session.description.clone().unwrap_or_default(),
+        Field::Client => event.to_client(),
+        Field::User => event.to_user(),
+    }
+}
+
+// Helper function that splits a filter expression into its tokens.
+fn tokenize(input: &str) -> Result<Vec<Token>, AppError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '=' | '!' | '<' | '>' | '~' => tokens.push(Token::Operator(operator(&mut chars)?)),
+            '\'' | '"' => {
+                chars.next();
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(other) => word.push(other),
+                        None => return Err(invalid(&format!("missing closing {}", c))),
+                    }
+                }
+                tokens.push(Token::Word(word));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "()=!<>~'\"".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// Helper function that reads an operator, which is one or two characters long.
+fn operator(chars: &mut Peekable<Chars>) -> Result<Operator, AppError> {
+    let first = chars.next().unwrap_or_default();
+    let equals = chars.peek() == Some(&'=');
+    if equals {
+        chars.next();
+    }
+    match (first, equals) {
+        ('=', false) => Ok(Operator::Equal),
+        ('!', true) => Ok(Operator::NotEqual),
+        ('~', false) => Ok(Operator::Contains),
+        ('<', false) => Ok(Operator::Less),
+        ('<', true) => Ok(Operator::LessOrEqual),
+        ('>', false) => Ok(Operator::Greater),
+        ('>', true) => Ok(Operator::GreaterOrEqual),
+        _ => Err(invalid(&format!(
+            "unknown operator '{}{}'",
+            first,
+            if equals { "=" } else { "" }
+        ))),
+    }
+}
+
+// Helper function that returns how the given operator is written.
+fn symbol(operator: Operator) -> &'static str {
+    match operator {
+        Operator::Equal => "=",
+        Operator::NotEqual => "!=",
+        Operator::Contains => "~",
+        Operator::Less => "<",
+        Operator::LessOrEqual => "<=",
+        Operator::Greater => ">",
+        Operator::GreaterOrEqual => ">=",
+    }
+}
+
+// Helper function that returns the error of an invalid filter expression.
+fn invalid(reason: &str) -> AppError {
+    AppError::new(ErrorKind::Parse(format!("Invalid filter, {}", reason)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passes(filter: &str, session: &Session) -> bool {
+        filter.parse::<Filter>().unwrap().matches(session, 0)
+    }
+
+    #[test]
+    fn test_precedence() {
+        let session = Session {
+            client: Some("Acme".to_string()),
+            ..Session::new(0, Some(600), "web", None)
+        };
+        // `and` binds tighter than `or`.
+        assert!(passes(
+            "project=web or project=api and duration>1h",
+            &session
+        ));
+        assert!(!passes(
+            "(project=web or project=api) and duration>1h",
+            &session
+        ));
+        assert!(passes("not not project=web", &session));
+        assert!(!passes("not project=web and client=acme", &session));
+    }
+
+    #[test]
+    fn test_conditions() {
+        let session = Session {
+            client: Some("Acme".to_string()),
+            billable: Some(false),
+            ..Session::new(
+                0,
+                Some(45 * 60),
+                "Mobile App",
+                Some("Fix login #bug #urgent"),
+            )
+        };
+        assert!(passes("project='mobile app'", &session));
+        assert!(passes("project!=web AND description~LOGIN", &session));
+        assert!(passes("tag=#bug and tag!=feature", &session));
+        assert!(passes("billable=false and client=acme", &session));
+        assert!(passes(
+            "duration>=45m and duration<1h and duration!=30m",
+            &session
+        ));
+        assert!(!passes("duration>45m", &session));
+    }
+
+    #[test]
+    fn test_duration_in_progress() {
+        let session = Session::new(0, None, "web", None);
+        let filter: Filter = "duration>=1h".parse().unwrap();
+        assert!(!filter.matches(&session, 3599));
+        assert!(filter.matches(&session, 3600));
+    }
+
+    #[test]
+    fn test_regex() {
+        let session = Session::new(0, Some(45 * 60), "api-gateway", Some("Fix login"));
+        let regex = |field, pattern: &str| {
+            Filter::Condition(Condition::Regex(field, pattern.parse().unwrap()))
+                .matches(&session, 0)
+        };
+        assert!(regex(Field::Project, "^(api|web)-"));
+        assert!(!regex(Field::Project, "^web"));
+        // Unlike other conditions, regular expressions are case-sensitive unless told otherwise.
+        assert!(!regex(Field::Description, "^fix"));
+        assert!(regex(Field::Description, "(?i)^fix"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        for input in &[
+            "",
+            "project",
+            "project=",
+            "project=web and",
+            "(project=web",
+            "project=web)",
+            "colour=red",
+            "project<web",
+            "duration>soon",
+            "tag~bug",
+            "billable=maybe",
+            "project=\"web",
+            "project==web",
+        ] {
+            assert!(
+                input.parse::<Filter>().is_err(),
+                "{} should be invalid",
+                input
+            );
+        }
+    }
+}
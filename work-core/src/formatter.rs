@@ -0,0 +1,121 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+use crate::error::{AppError, ErrorCode, ErrorKind};
+
+/// Prefix of the executables that format output, e.g. `work-format-xlsx`.
+const PREFIX: &str = "work-format-";
+
+/// The `Formatter` struct names an external formatter, given as `ext:NAME`.
+///
+/// A formatter is any executable called `work-format-NAME` on the `PATH`. It gets the JSON output
+/// of a command on its stdin and whatever it writes to its stdout is the output of the command, so
+/// new output formats don't have to be built into Work.
+///
+/// # Examples
+/// ```
+/// # use work_core::formatter::Formatter;
+/// let formatter: Formatter = "ext:xlsx".parse().unwrap();
+/// assert_eq!(formatter.executable(), "work-format-xlsx");
+/// assert!("xlsx".parse::<Formatter>().is_err());
+/// assert!("ext:".parse::<Formatter>().is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Formatter(pub String);
+
+impl Formatter {
+    /// Returns the name of the executable of the formatter.
+    pub fn executable(&self) -> String {
+        format!("{}{}", PREFIX, self.0)
+    }
+
+    /// Runs the formatter with the given input on its stdin, its output goes straight to stdout.
+    pub fn run(&self, input: &str) -> Result<(), AppError> {
+        let executable = self.executable();
+        let mut child = Command::new(&executable)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                if e.kind() == io::ErrorKind::NotFound {
+                    AppError::new(ErrorKind::User(format!(
+                        "No formatter called {} was found, it should be an executable named {} \
+                         on the PATH",
+                        self.0, executable
+                    )))
+                    .with_source(e)
+                    .with_code(ErrorCode::UnknownCommand)
+                } else {
+                    AppError::new(ErrorKind::System(format!(
+                        "Unable to run {}: {}",
+                        executable, e
+                    )))
+                    .with_source(e)
+                    .with_code(ErrorCode::CommandFailed)
+                }
+            })?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // A formatter is free to stop reading its input early.
+            match stdin.write_all(input.as_bytes()) {
+                Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+                    return Err(AppError::new(ErrorKind::System(format!(
+                        "Unable to pass the output to {}: {}",
+                        executable, e
+                    )))
+                    .with_source(e)
+                    .with_code(ErrorCode::CommandFailed));
+                }
+                _ => (),
+            }
+        }
+
+        let status = child.wait()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(AppError::new(ErrorKind::System(format!(
+                "The formatter {} failed with {}",
+                executable, status
+            )))
+            .with_code(ErrorCode::CommandFailed))
+        }
+    }
+}
+
+impl fmt::Display for Formatter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ext:{}", self.0)
+    }
+}
+
+impl FromStr for Formatter {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("ext:") {
+            Some(name) if !name.is_empty() && !name.contains('/') => {
+                Ok(Formatter(name.to_string()))
+            }
+            _ => Err(AppError::new(ErrorKind::Parse(
+                "The output should be given as ext:NAME, which runs the formatter work-format-NAME"
+                    .to_string(),
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_missing_formatter() {
+        let formatter = Formatter("surely-not-installed".to_string());
+        let error = formatter.run("{}").unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("work-format-surely-not-installed"));
+    }
+}
@@ -0,0 +1,73 @@
+/// Returns the edit distance between two strings, that is the least number of characters that
+/// have to be inserted, removed or replaced to turn one into the other.
+///
+/// # Examples
+/// ```
+/// # use work_core::fuzzy::distance;
+/// assert_eq!(distance("bakend", "backend"), 1);
+/// assert_eq!(distance("frontend", "backend"), 5);
+/// assert_eq!(distance("", "work"), 4);
+/// ```
+pub fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // Distances between the part of `a` read so far and every prefix of `b`.
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let replaced = diagonal + if a_char == *b_char { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Finds the candidate that the given name is most likely a misspelling of. Only candidates that
+/// are a character or two away from the name count, fewer for short names, and a name that is
+/// one of the candidates isn't a misspelling at all.
+///
+/// # Examples
+/// ```
+/// # use work_core::fuzzy::closest;
+/// let projects = ["backend", "frontend", "docs"];
+/// assert_eq!(closest("bakend", projects.iter().copied()), Some("backend"));
+/// assert_eq!(closest("backend", projects.iter().copied()), None);
+/// assert_eq!(closest("ops", projects.iter().copied()), None);
+/// ```
+pub fn closest<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let allowed = if name.chars().count() <= 4 { 1 } else { 2 };
+    let mut best: Option<(usize, &str)> = None;
+    for candidate in candidates {
+        let distance = distance(name, candidate);
+        if distance == 0 {
+            return None;
+        }
+        if distance <= allowed && !matches!(best, Some((best, _)) if best <= distance) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closest() {
+        let projects = ["work", "word", "backend", "backends"];
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("café", "cafe"), 1);
+        assert_eq!(closest("wrk", projects.iter().copied()), Some("work"));
+        assert_eq!(closest("bacend", projects.iter().copied()), Some("backend"));
+        assert_eq!(closest("wo", projects.iter().copied()), None);
+        assert_eq!(closest("word", projects.iter().copied()), None);
+        assert_eq!(closest("anything", Vec::new()), None);
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::BTreeSet;
+
+use serde_json::{json, Value};
+
+use crate::arguments::{GroupKey, TimeFormat};
+use crate::csv_output::{self, CsvOptions};
+use crate::highlight::Highlight;
+use crate::project_map::duration_json;
+use crate::sessions::{self, Session};
+use crate::time::{self, format_time, Interval};
+
+/// Format of the days of a grouping, as in reports.
+const DAY_FORMAT: &str = "%a %d-%m-%Y";
+
+/// Name given to time spent on work without any tags when grouping by tag.
+pub const NO_TAG_STR: &str = "No tag";
+
+/// Marks a word of the description as a tag, such as `#meeting`.
+const TAG_MARKER: char = '#';
+
+/// The `Grouping` struct holds the time worked within an interval grouped by one or more keys in
+/// turn, such as by week and then by project within each week, see `work of --group-by`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Grouping {
+    /// The keys the time is grouped by, outermost first.
+    pub keys: Vec<GroupKey>,
+    /// The groups of the outermost key.
+    pub groups: Vec<Group>,
+}
+
+/// A single group of a `Grouping`, such as a week, along with the groups of the next key within it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+    pub name: String,
+    /// The name the group is sorted by, which puts days, weeks and months in chronological order
+    /// and everything else in alphabetical order.
+    order: String,
+    /// The seconds worked within the group.
+    pub seconds: i64,
+    /// The groups of the next key, empty for the innermost key.
+    pub groups: Vec<Group>,
+}
+
+impl Grouping {
+    /// Creates an empty grouping by the given keys, outermost first.
+    pub fn new(keys: &[GroupKey]) -> Self {
+        Grouping {
+            keys: keys.to_vec(),
+            groups: Vec::new(),
+        }
+    }
+
+    /// Adds the time of the given sessions that falls within the interval to the groups, which can
+    /// be done for the sessions of several logs in turn. Time keys split sessions at midnight, so
+    /// each day, week or month gets the part of a session that falls within it. A session with
+    /// several tags counts towards each of them.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chrono::NaiveDate;
+    /// # use work_core::arguments::GroupKey;
+    /// # use work_core::grouping::Grouping;
+    /// # use work_core::log_file::Metadata;
+    /// # use work_core::sessions::Session;
+    /// # use work_core::time::{self, Interval};
+    /// let session = |day: u32, project: &str| {
+    ///     let start = time::start_of_day(NaiveDate::from_ymd(2026, 10, day)) + 9 * 3600;
+    ///     Session {
+    ///         start,
+    ///         stop: Some(start + 3600),
+    ///         project: Some(project.to_string()),
+    ///         description: None,
+    ///         client: None,
+    ///         billable: None,
+    ///         metadata: Metadata::new(),
+    ///     }
+    /// };
+    /// let mut grouping = Grouping::new(&[GroupKey::Week, GroupKey::Project]);
+    /// let interval = Interval::new(0, Some(i64::MAX / 2));
+    /// let sessions = vec![session(12, "web"), session(13, "api"), session(20, "api")];
+    /// grouping.add(sessions, &interval, interval.end);
+    /// assert_eq!(grouping.groups.len(), 2);
+    /// assert_eq!(grouping.groups[0].name, "Week 42 2026");
+    /// assert_eq!(grouping.groups[0].seconds, 7200);
+    /// assert_eq!(grouping.groups[0].groups[0].name, "api");
+    /// assert_eq!(grouping.total(), 10800);
+    /// ```
+    pub fn add<I>(&mut self, sessions: I, interval: &Interval, now: i64)
+    where
+        I: IntoIterator<Item = Session>,
+    {
+        for (session, span) in sessions::spans(sessions, now) {
+            // Sessions are in chronological order, none after this one can be within the interval.
+            if session.start > interval.end {
+                break;
+            }
+            let overlap = match sessions::overlap(&span, interval) {
+                Some(overlap) => overlap,
+                None => continue,
+            };
+            for part in overlap.split_at_midnight() {
+                let seconds = part.end - part.start;
+                if seconds > 0 {
+                    let names: Vec<Vec<(String, String)>> = self
+                        .keys
+                        .iter()
+                        .map(|key| names(*key, &session, &part))
+                        .collect();
+                    add(&mut self.groups, &names, seconds);
+                }
+            }
+        }
+    }
+
+    /// Returns true if no work was done within the groups.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Returns the time worked within all groups.
+    pub fn total(&self) -> i64 {
+        self.groups.iter().map(|group| group.seconds).sum()
+    }
+
+    /// Returns the groups as a line each with the time worked within them, the groups of each key
+    /// indented below those of the key before it.
+    pub fn as_text(&self, time_format: &TimeFormat, highlight: &dyn Highlight) -> String {
+        let mut text = String::new();
+        text_lines(&mut text, &self.groups, 0, time_format, highlight);
+        text
+    }
+
+    /// Returns the groups as CSV, a row per innermost group with a column per key followed by the
+    /// time worked within it.
+    pub fn as_csv(&self, time_format: &TimeFormat, options: &CsvOptions) -> String {
+        let mut header: Vec<&str> = self.keys.iter().map(|key| title(*key)).collect();
+        header.push("Time Spent");
+        let mut rows = Vec::new();
+        csv_rows(&mut rows, &mut Vec::new(), &self.groups, time_format);
+        csv_output::write(&header, rows, options)
+    }
+
+    /// Returns the groups as JSON, nested just like the groups themselves, see `duration_json` for
+    /// how the time worked within each group is given.
+    pub fn as_json(&self, time_format: &TimeFormat, raw_seconds: bool) -> String {
+        let value = json!({
+            "group_by": self.keys.iter().map(|key| title(*key).to_lowercase()).collect::<Vec<String>>(),
+            "groups": groups_json(&self.groups, time_format, raw_seconds),
+        });
+        serde_json::to_string_pretty(&value).unwrap()
+    }
+}
+
+/// Returns the tags of the given description, which are the words starting with `#`, without the
+/// `#` and any punctuation following them.
+///
+/// # Examples
+/// ```
+/// # use work_core::grouping::tags;
+/// assert_eq!(tags("Planning #meeting with #team-a, #meeting"), vec!["meeting", "team-a"]);
+/// assert!(tags("Issue # 42").is_empty());
+/// ```
+pub fn tags(description: &str) -> Vec<String> {
+    let tags: BTreeSet<String> = description
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix(TAG_MARKER))
+        .map(|tag| tag.trim_end_matches(|c: char| c.is_ascii_punctuation()))
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect();
+    tags.into_iter().collect()
+}
+
+// Helper function that returns the groups a part of a session that falls within a single day
+// belongs to for the given key, each as the name to sort it by and the name to show. Only tags
+// can put a session in more than one group.
+fn names(key: GroupKey, session: &Session, part: &Interval) -> Vec<(String, String)> {
+    let day = time::date_of(part.start);
+    let same = |name: String| vec![(name.clone(), name)];
+    match key {
+        GroupKey::Day => vec![(
+            day.format("%Y-%m-%d").to_string(),
+            day.format(DAY_FORMAT).to_string(),
+        )],
+        GroupKey::Week => vec![(
+            day.format("%G-%V").to_string(),
+            day.format("Week %V %G").to_string(),
+        )],
+        GroupKey::Month => vec![(
+            day.format("%Y-%m").to_string(),
+            day.format("%B %Y").to_string(),
+        )],
+        GroupKey::Project => same(session.start_event().to_project()),
+        GroupKey::Client => same(session.start_event().to_client()),
+        GroupKey::User => same(session.start_event().to_user()),
+        GroupKey::Tag => {
+            let tags = tags(session.description.as_deref().unwrap_or(""));
+            if tags.is_empty() {
+                same(NO_TAG_STR.to_string())
+            } else {
+                tags.into_iter().flat_map(same).collect()
+            }
+        }
+    }
+}
+
+// Helper function that returns the name of the given key as the title of a column.
+fn title(key: GroupKey) -> &'static str {
+    match key {
+        GroupKey::Day => "Day",
+        GroupKey::Week => "Week",
+        GroupKey::Month => "Month",
+        GroupKey::Project => "Project",
+        GroupKey::Client => "Client",
+        GroupKey::User => "User",
+        GroupKey::Tag => "Tag",
+    }
+}
+
+// Helper function that adds the given seconds to the groups named by each key in turn, keeping
+// the groups of each key in order.
+fn add(groups: &mut Vec<Group>, names: &[Vec<(String, String)>], seconds: i64) {
+    let (first, rest) = match names.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    for (order, name) in first {
+        let index = match groups.binary_search_by(|group| group.order.cmp(order)) {
+            Ok(index) => index,
+            Err(index) => {
+                groups.insert(
+                    index,
+                    Group {
+                        name: name.clone(),
+                        order: order.clone(),
+                        seconds: 0,
+                        groups: Vec::new(),
+                    },
+                );
+                index
+            }
+        };
+        groups[index].seconds += seconds;
+        add(&mut groups[index].groups, rest, seconds);
+    }
+}
+
+// Helper function that adds a line per group to the text, indented by its depth.
+fn text_lines(
+    text: &mut String,
+    groups: &[Group],
+    depth: usize,
+    time_format: &TimeFormat,
+    highlight: &dyn Highlight,
+) {
+    for group in groups {
+        text.push_str(&format!(
+            "{}{} => {}\n",
+            "  ".repeat(depth),
+            highlight.project(&group.name),
+            highlight.duration(&format_time(time_format, group.seconds))
+        ));
+        text_lines(text, &group.groups, depth + 1, time_format, highlight);
+    }
+}
+
+// Helper function that adds a row per innermost group to the rows, starting with the names of
+// the groups it lies within.
+fn csv_rows(
+    rows: &mut Vec<Vec<String>>,
+    path: &mut Vec<String>,
+    groups: &[Group],
+    time_format: &TimeFormat,
+) {
+    for group in groups {
+        path.push(group.name.clone());
+        if group.groups.is_empty() {
+            let mut row = path.clone();
+            row.push(format_time(time_format, group.seconds));
+            rows.push(row);
+        } else {
+            csv_rows(rows, path, &group.groups, time_format);
+        }
+        path.pop();
+    }
+}
+
+// Helper function that returns the JSON of the given groups and the groups within them.
+fn groups_json(groups: &[Group], time_format: &TimeFormat, raw_seconds: bool) -> Value {
+    Value::Array(
+        groups
+            .iter()
+            .map(|group| {
+                let mut value = json!({
+                    "name": group.name,
+                    "time": duration_json(time_format, group.seconds, raw_seconds),
+                });
+                if !group.groups.is_empty() {
+                    value["groups"] = groups_json(&group.groups, time_format, raw_seconds);
+                }
+                value
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_split_at_midnight() {
+        let evening = time::start_of_day(NaiveDate::from_ymd(2026, 10, 12)) + 22 * 3600;
+        let mut grouping = Grouping::new(&[GroupKey::Day]);
+        grouping.add(
+            vec![Session::new(evening, Some(evening + 3 * 3600), "api", None)],
+            &Interval::new(evening, Some(evening + 24 * 3600)),
+            evening + 24 * 3600,
+        );
+        let days: Vec<(&str, i64)> = grouping
+            .groups
+            .iter()
+            .map(|group| (group.name.as_str(), group.seconds))
+            .collect();
+        assert_eq!(
+            days,
+            vec![("Mon 12-10-2026", 7200), ("Tue 13-10-2026", 3600)]
+        );
+    }
+
+    #[test]
+    fn test_tags_and_csv() {
+        let start = time::start_of_day(NaiveDate::from_ymd(2026, 10, 12)) + 9 * 3600;
+        let mut grouping = Grouping::new(&[GroupKey::Tag, GroupKey::Project]);
+        grouping.add(
+            vec![
+                Session::new(
+                    start,
+                    Some(start + 3600),
+                    "api",
+                    Some("Planning #meeting #team"),
+                ),
+                Session::new(start + 3600, Some(start + 3 * 3600), "web", Some("Styling")),
+            ],
+            &Interval::new(start, Some(start + 24 * 3600)),
+            start + 24 * 3600,
+        );
+        let names: Vec<&str> = grouping
+            .groups
+            .iter()
+            .map(|group| group.name.as_str())
+            .collect();
+        assert_eq!(names, vec![NO_TAG_STR, "meeting", "team"]);
+        assert_eq!(
+            grouping.as_csv(&TimeFormat::Minutes, &CsvOptions::default()),
+            "Tag,Project,Time Spent\nNo tag,web,120\nmeeting,api,60\nteam,api,60\n"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+/// The `Highlight` trait marks the parts of text output that stand out, such as the names of
+/// projects and durations, leaving it up to the front end what that looks like. A terminal colors
+/// them for example, while a report sent by email is left plain, see `Plain`.
+///
+/// The text handed to each method is already padded to its width, so that highlighting it doesn't
+/// throw off the alignment of tables and charts.
+pub trait Highlight {
+    /// Highlights the name of a project.
+    fn project(&self, name: &str) -> String {
+        name.to_string()
+    }
+
+    /// Highlights a duration or a point in time.
+    fn duration(&self, time: &str) -> String {
+        time.to_string()
+    }
+
+    /// Highlights the part of a chart belonging to the series with the given index, such as the
+    /// blocks of a single project in a timeline.
+    fn series(&self, text: &str, _index: usize) -> String {
+        text.to_string()
+    }
+}
+
+/// The `Plain` struct leaves text output as it is.
+///
+/// # Examples
+/// ```
+/// # use work_core::highlight::{Highlight, Plain};
+/// assert_eq!(Plain.project("api"), "api");
+/// assert_eq!(Plain.series("█", 3), "█");
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Plain;
+
+impl Highlight for Plain {}
@@ -0,0 +1,228 @@
+use std::process::Command;
+
+use crate::config::Config;
+#[cfg(feature = "presence")]
+use crate::error::AppError;
+use crate::log_file::{Event, LogFile};
+#[cfg(feature = "notifications")]
+use crate::notify::Notifier;
+#[cfg(feature = "presence")]
+use crate::presence::Presence;
+
+/// The `Hooks` struct holds the commands configured to run whenever tracking changes.
+///
+/// Hooks are set in the `[hooks]` section of the config file:
+///
+/// ```text
+/// [hooks]
+/// on_start = notify-send "Working on $WORK_PROJECT"
+/// on_stop = notify-send "Done with $WORK_PROJECT after $WORK_DURATION seconds"
+/// on_switch = slack-status "$WORK_PROJECT"
+/// ```
+///
+/// `on_start` runs whenever work starts and `on_stop` whenever it stops. `on_switch` runs right
+/// before `on_start` when the new work is on a different project than the last work was. Each hook
+/// is run with `sh -c` and gets the details of the work through the following environment
+/// variables:
+///
+/// * `WORK_HOOK` is the name of the hook, `start`, `stop` or `switch`.
+/// * `WORK_PROJECT` and `WORK_DESCRIPTION` describe the work.
+/// * `WORK_DURATION` is the number of seconds the work lasted, only set for `on_stop`.
+/// * `WORK_PREVIOUS_PROJECT` is the project of the last work, only set for `on_switch`.
+///
+/// With the `presence` feature enabled, the Slack status is updated along with the hooks, see
+/// `Presence`. With the `notifications` feature enabled, desktop notifications are shown when the
+/// time is up or goals are met, see `Notifier`.
+///
+/// A failing hook doesn't undo the change to the log, so instead of an error each method returns
+/// the warnings for the caller to pass on to the user.
+#[derive(Clone, Debug, Default)]
+pub struct Hooks {
+    on_start: Option<String>,
+    on_stop: Option<String>,
+    on_switch: Option<String>,
+    #[cfg(feature = "presence")]
+    presence: Option<Presence>,
+    #[cfg(feature = "notifications")]
+    notifier: Notifier,
+}
+
+impl Hooks {
+    /// Reads the hooks from the `hooks.on_start`, `hooks.on_stop` and `hooks.on_switch` config
+    /// values.
+    pub fn from_config(config: &Config) -> Self {
+        let hook = |key| config.get(key).map(|command| command.to_string());
+        Hooks {
+            on_start: hook("hooks.on_start"),
+            on_stop: hook("hooks.on_stop"),
+            on_switch: hook("hooks.on_switch"),
+            #[cfg(feature = "presence")]
+            presence: Presence::from_config(config),
+            #[cfg(feature = "notifications")]
+            notifier: Notifier::from_config(config),
+        }
+    }
+
+    /// Runs the hooks for work that just started with the given `start` event. `previous` is the
+    /// last event in the log before the work started, if any.
+    pub fn started(&self, event: &Event, previous: Option<&Event>) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if let Some(previous) = previous {
+            if previous.to_project() != event.to_project() {
+                warnings.extend(run(
+                    "switch",
+                    &self.on_switch,
+                    event,
+                    &[("WORK_PREVIOUS_PROJECT", previous.to_project())],
+                ));
+            }
+        }
+        warnings.extend(run("start", &self.on_start, event, &[]));
+        #[cfg(feature = "presence")]
+        warnings.extend(self.update_presence(|presence| presence.working(event)));
+        warnings
+    }
+
+    /// Runs the hooks for work that just stopped with the given `stop` event after lasting
+    /// `duration` seconds.
+    pub fn stopped(&self, event: &Event, duration: i64) -> Vec<String> {
+        let mut warnings = Vec::new();
+        warnings.extend(run(
+            "stop",
+            &self.on_stop,
+            event,
+            &[("WORK_DURATION", duration.to_string())],
+        ));
+        #[cfg(feature = "presence")]
+        warnings.extend(self.update_presence(|presence| presence.free()));
+        warnings
+    }
+
+    /// Reacts to the work started with `until --wait` being over.
+    #[cfg_attr(not(feature = "notifications"), allow(unused_variables, unused_mut))]
+    pub fn time_up(&self, event: &Event) -> Vec<String> {
+        let mut warnings = Vec::new();
+        #[cfg(feature = "notifications")]
+        warnings.extend(self.notifier.time_up(event).err().map(|e| e.to_string()));
+        warnings
+    }
+
+    /// Reminds the user to start tracking with the given message, see `Reminder`.
+    #[cfg_attr(not(feature = "notifications"), allow(unused_variables, unused_mut))]
+    pub fn remind(&self, message: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+        #[cfg(feature = "notifications")]
+        warnings.extend(self.notifier.remind(message).err().map(|e| e.to_string()));
+        warnings
+    }
+
+    /// Reacts to the given `stop` event having been added to the log after `duration` seconds of
+    /// work, once the `on_stop` hook has run. Just like with hooks, failing to do so only warrants
+    /// a warning, such as a budget in the config file that isn't a duration.
+    #[cfg_attr(not(feature = "notifications"), allow(unused_variables, unused_mut))]
+    pub fn worked(&self, log: &mut LogFile, event: &Event, duration: i64) -> Vec<String> {
+        let mut warnings = Vec::new();
+        #[cfg(feature = "notifications")]
+        warnings.extend(
+            self.notifier
+                .worked(log, event, duration)
+                .err()
+                .map(|e| e.to_string()),
+        );
+        warnings
+    }
+
+    // Updates the Slack status if presence is configured. Just like with hooks, failing to update
+    // the status only warrants a warning, which is returned.
+    #[cfg(feature = "presence")]
+    fn update_presence<F>(&self, update: F) -> Option<String>
+    where
+        F: Fn(&Presence) -> Result<(), AppError>,
+    {
+        match self.presence.as_ref().map(update) {
+            Some(Err(e)) => Some(e.to_string()),
+            _ => None,
+        }
+    }
+}
+
+// Helper function that runs a single hook, if it is configured. A failing hook doesn't undo the
+// change to the log, so the user is only warned about it with the returned message.
+fn run(
+    name: &str,
+    command: &Option<String>,
+    event: &Event,
+    extra_vars: &[(&str, String)],
+) -> Option<String> {
+    let command = match command {
+        Some(command) => command,
+        None => return None,
+    };
+
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("WORK_HOOK", name)
+        .env("WORK_PROJECT", event.to_project())
+        .env("WORK_DESCRIPTION", event.to_description())
+        .envs(extra_vars.iter().map(|(key, value)| (key, value)))
+        .status();
+    match status {
+        Ok(status) if status.success() => None,
+        Ok(status) => Some(format!("The on_{} hook failed with {}", name, status)),
+        Err(e) => Some(format!("Unable to run the on_{} hook: {}", name, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_file::Metadata;
+    use std::fs;
+
+    #[test]
+    fn test_hooks_describe_the_work() {
+        let path = std::env::temp_dir().join(format!("work-hooks-test-{}", std::process::id()));
+        let config = Config::parse(&format!(
+            "[hooks]\n\
+             on_start = echo \"$WORK_HOOK $WORK_PROJECT $WORK_DESCRIPTION\" >> {path}\n\
+             on_stop = echo \"$WORK_HOOK $WORK_PROJECT $WORK_DURATION\" >> {path}\n\
+             on_switch = echo \"$WORK_HOOK $WORK_PREVIOUS_PROJECT\" >> {path}",
+            path = path.display()
+        ))
+        .unwrap();
+        let hooks = Hooks::from_config(&config);
+
+        let previous = Event::Stop(Some("a".to_string()), None, None, None, Metadata::new());
+        let warnings = hooks.started(
+            &Event::Start(Some("a".to_string()), None, None, None, Metadata::new()),
+            Some(&previous),
+        );
+        assert!(warnings.is_empty());
+        hooks.started(
+            &Event::Start(Some("b".to_string()), None, None, None, Metadata::new()),
+            Some(&previous),
+        );
+        hooks.stopped(
+            &Event::Stop(Some("b".to_string()), None, None, None, Metadata::new()),
+            60,
+        );
+
+        let output = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            output,
+            "start a No description\nswitch a\nstart b No description\nstop b 60\n"
+        );
+    }
+
+    #[test]
+    fn test_failing_hooks_warn() {
+        let config = Config::parse("[hooks]\non_stop = exit 3").unwrap();
+        let hooks = Hooks::from_config(&config);
+        let event = Event::Stop(Some("a".to_string()), None, None, None, Metadata::new());
+        let warnings = hooks.stopped(&event, 60);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("The on_stop hook failed with"));
+    }
+}
@@ -0,0 +1,27 @@
+use std::fs;
+use std::time::UNIX_EPOCH;
+
+/// Path of the terminal Work reads its input from, if it does.
+const STDIN: &str = "/dev/stdin";
+
+/// Returns true if Work reads its input from a terminal, which `last_input` needs.
+pub fn is_terminal() -> bool {
+    // Only asks about a file descriptor every process has.
+    unsafe { libc::isatty(0) == 1 }
+}
+
+/// Returns the UNIX timestamp of the last time anything was typed into the terminal Work reads its
+/// input from, going by the access time of the terminal device the same way `w` tells how long a
+/// user has been idle. The kernel only updates the time every few seconds, so it is only good for
+/// telling idleness over minutes. Returns `None` if the input isn't a terminal or its time is
+/// unknown.
+pub fn last_input() -> Option<i64> {
+    if !is_terminal() {
+        return None;
+    }
+    let accessed = fs::metadata(STDIN).ok()?.accessed().ok()?;
+    accessed
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs() as i64)
+}
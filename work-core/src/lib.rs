@@ -0,0 +1,48 @@
+pub mod archive;
+pub mod arguments;
+#[cfg(feature = "auto")]
+pub mod auto;
+pub mod backup;
+pub mod cache;
+pub mod checksum;
+pub mod comparison;
+pub mod config;
+pub mod csv_output;
+pub mod days_off;
+#[cfg(feature = "email")]
+pub mod email;
+pub mod encryption;
+pub mod error;
+pub mod export;
+pub mod filter;
+pub mod formatter;
+pub mod fuzzy;
+pub mod grouping;
+pub mod highlight;
+pub mod hooks;
+pub mod idle;
+pub mod locks;
+pub mod log_file;
+#[cfg(feature = "notifications")]
+pub mod notify;
+pub mod overtime;
+#[cfg(feature = "presence")]
+pub mod presence;
+pub mod profiles;
+pub mod project_map;
+pub mod remind;
+pub mod report;
+pub mod rounding;
+pub mod service;
+pub mod sessions;
+pub mod signals;
+pub mod standup;
+pub mod stats;
+pub mod statusbar;
+pub mod suggest;
+pub mod team;
+#[cfg(feature = "templates")]
+pub mod template;
+pub mod time;
+pub mod timeline;
+pub mod work_csv;
@@ -0,0 +1,142 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::{AppError, ErrorCode, ErrorKind};
+use crate::time::{self, Interval};
+
+/// First line of the locks file, bumped whenever the format of the file changes.
+const HEADER: &str = "work-locks 1";
+
+/// The `Locks` struct holds the periods of the log that have been finalized, for example because
+/// the timesheet of the period has been submitted. Sessions within a locked period can't be added,
+/// changed or removed without `--force`.
+///
+/// The locks are kept in `work.locks` next to the log, one period per line as the UNIX timestamps
+/// of its start and end.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Locks {
+    periods: Vec<Interval>,
+}
+
+impl Locks {
+    /// Reads the locks from the given path. A missing file simply means nothing is locked.
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Locks::default()),
+            Err(e) => return Err(AppError::from(e)),
+        };
+        Self::parse(&contents).ok_or_else(|| {
+            AppError::new(ErrorKind::LogFile(format!(
+                "Unable to read the locked periods in {}!",
+                path.display()
+            )))
+            .with_code(ErrorCode::LogCorrupt)
+        })
+    }
+
+    /// Writes the locks to the given path.
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        let mut contents = format!("{}\n", HEADER);
+        for period in &self.periods {
+            contents.push_str(&format!("{},{}\n", period.start, period.end));
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Locks the given period.
+    pub fn lock(&mut self, period: Interval) {
+        self.periods.push(period);
+        self.periods
+            .sort_by_key(|period| (period.start, period.end));
+    }
+
+    /// Returns the locked periods, earliest first.
+    pub fn periods(&self) -> &[Interval] {
+        &self.periods
+    }
+
+    /// Returns the first locked period that shares time with the given interval, if any.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::locks::Locks;
+    /// # use work_core::time::Interval;
+    /// let mut locks = Locks::default();
+    /// locks.lock(Interval::new(100, Some(200)));
+    /// assert_eq!(locks.find(&Interval::new(150, Some(300))), Some(&Interval::new(100, Some(200))));
+    /// assert_eq!(locks.find(&Interval::new(200, Some(300))), None);
+    /// ```
+    pub fn find(&self, interval: &Interval) -> Option<&Interval> {
+        self.periods
+            .iter()
+            .find(|period| period.start < interval.end && interval.start < period.end)
+    }
+
+    /// Returns the first locked period the given time falls within, if any. A period includes its
+    /// start but not its end, so work can start again the moment a locked period ends.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::locks::Locks;
+    /// # use work_core::time::Interval;
+    /// let mut locks = Locks::default();
+    /// locks.lock(Interval::new(100, Some(200)));
+    /// assert_eq!(locks.find_time(100), Some(&Interval::new(100, Some(200))));
+    /// assert_eq!(locks.find_time(200), None);
+    /// ```
+    pub fn find_time(&self, timestamp: i64) -> Option<&Interval> {
+        self.periods
+            .iter()
+            .find(|period| period.start <= timestamp && timestamp < period.end)
+    }
+
+    // Parses the contents of a locks file, returns `None` if they can't be understood.
+    fn parse(contents: &str) -> Option<Self> {
+        let mut lines = contents.lines();
+        if lines.next()? != HEADER {
+            return None;
+        }
+
+        let mut locks = Locks::default();
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let mut values = line.splitn(2, ',');
+            let start = values.next()?.parse().ok()?;
+            let end = values.next()?.parse().ok()?;
+            locks.lock(Interval::new(start, Some(end)));
+        }
+        Some(locks)
+    }
+}
+
+/// Formats a locked period for the user, e.g. `Mon 12-10-2026 00:00 - Mon 19-10-2026 00:00`.
+pub fn format_period(period: &Interval) -> String {
+    format!(
+        "{} - {}",
+        time::format_date_time(period.start),
+        time::format_date_time(period.end)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("work-locks-test-{}", std::process::id()));
+        let mut locks = Locks::default();
+        locks.lock(Interval::new(500, Some(600)));
+        locks.lock(Interval::new(100, Some(200)));
+        locks.save(&path).unwrap();
+
+        let loaded = Locks::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, locks);
+        assert_eq!(loaded.periods()[0], Interval::new(100, Some(200)));
+        assert_eq!(Locks::load(&path).unwrap(), Locks::default());
+        assert_eq!(Locks::parse("garbage\n1,2"), None);
+    }
+}
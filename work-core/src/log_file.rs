@@ -0,0 +1,1967 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, create_dir_all, File, OpenOptions};
+use std::io::prelude::*;
+use std::io::{self, BufReader, Cursor, SeekFrom};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use log::debug;
+
+use crate::archive::{self, Archive};
+use crate::arguments::EventKind;
+use crate::backup::{self, Backup, BACKUPS_DIR};
+use crate::cache::{Cache, LogStamp};
+use crate::checksum::{self, BadLine};
+use crate::config::Config;
+use crate::days_off::DaysOff;
+use crate::encryption::Cipher;
+use crate::error::{AppError, ErrorCode, ErrorKind};
+use crate::filter::Filter;
+use crate::locks::Locks;
+use crate::profiles;
+use crate::project_map::{ProjectMap, ProjectMapMethods};
+use crate::rounding::Rounding;
+use crate::sessions::{self, Session};
+use crate::time::{self, Clock, SystemClock};
+
+/// The `Event` enum describes a single event in the log. Each event in the log can either be a
+/// `start` event with or without a project description or a `stop` event with or without a project
+/// description. Either kind of event may also name the client the work is done for, record
+/// whether the work is billable, and carry any further `Metadata`.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum Event {
+    Start(
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<bool>,
+        Metadata,
+    ),
+    Stop(
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<bool>,
+        Metadata,
+    ),
+}
+
+/// The `Metadata` of an event holds any further data about it as `key=value` fields at the end of
+/// its line in the log, such as which machine it was recorded on. Keys Work doesn't know are kept
+/// as they are whenever the log is rewritten, so logs written by newer versions of Work survive
+/// older ones.
+pub type Metadata = BTreeMap<String, String>;
+
+/// Name given to time spent on work without a client when grouping by client.
+pub const NO_CLIENT_STR: &str = "No client";
+
+/// Name given to time spent by no known user when grouping by user.
+pub const NO_USER_STR: &str = "Unknown user";
+
+/// Key of the metadata naming the user who recorded an event, see `Config::user`.
+pub const USER_KEY: &str = "user";
+
+/// Marks billable work in the log.
+const BILLABLE_STR: &str = "billable";
+
+/// Marks non-billable work in the log.
+const NON_BILLABLE_STR: &str = "non-billable";
+
+impl Event {
+    // Extract project string from Event
+    pub fn to_project(&self) -> String {
+        match self {
+            Event::Stop(None, ..) => "Unnamed project".to_string(),
+            Event::Start(None, ..) => "Unnamed project".to_string(),
+            Event::Stop(Some(project), ..) => project.to_string(),
+            Event::Start(Some(project), ..) => project.to_string(),
+        }
+    }
+
+    // Extract description string from Event
+    pub fn to_description(&self) -> String {
+        match self {
+            Event::Stop(_, None, ..) => "No description".to_string(),
+            Event::Start(_, None, ..) => "No description".to_string(),
+            Event::Stop(_, Some(description), ..) => description.to_string(),
+            Event::Start(_, Some(description), ..) => description.to_string(),
+        }
+    }
+
+    /// Returns the name of the client the work is done for, or `NO_CLIENT_STR` if there is none.
+    pub fn to_client(&self) -> String {
+        match self {
+            Event::Start(_, _, Some(client), ..) | Event::Stop(_, _, Some(client), ..) => {
+                client.to_string()
+            }
+            _ => NO_CLIENT_STR.to_string(),
+        }
+    }
+
+    /// Returns the name of the user who recorded the event, or `NO_USER_STR` if it is unknown.
+    pub fn to_user(&self) -> String {
+        self.metadata()
+            .get(USER_KEY)
+            .filter(|user| !user.is_empty())
+            .map_or_else(|| NO_USER_STR.to_string(), |user| user.to_string())
+    }
+
+    /// Returns false only if the work was marked as non-billable, work is billable unless stated
+    /// otherwise.
+    pub fn is_billable(&self) -> bool {
+        match self {
+            Event::Start(_, _, _, billable, _) | Event::Stop(_, _, _, billable, _) => {
+                *billable != Some(false)
+            }
+        }
+    }
+
+    /// Returns the event with its project name normalized, see `normalize_project`.
+    pub fn normalized(&self) -> Event {
+        let normalize = |project: &Option<String>| {
+            project
+                .as_deref()
+                .map(normalize_project)
+                .filter(|project| !project.is_empty())
+        };
+        match self {
+            Event::Start(project, description, client, billable, metadata) => Event::Start(
+                normalize(project),
+                description.clone(),
+                client.clone(),
+                *billable,
+                metadata.clone(),
+            ),
+            Event::Stop(project, description, client, billable, metadata) => Event::Stop(
+                normalize(project),
+                description.clone(),
+                client.clone(),
+                *billable,
+                metadata.clone(),
+            ),
+        }
+    }
+
+    /// Returns the event with its description replaced by the given one.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::log_file::{Event, Metadata};
+    /// let event = Event::Stop(Some("work".to_string()), None, None, None, Metadata::new());
+    /// assert_eq!(event.with_description("Bugs").to_description(), "Bugs");
+    /// ```
+    pub fn with_description(&self, description: &str) -> Event {
+        let description = Some(description.to_string());
+        match self.clone() {
+            Event::Start(project, _, client, billable, metadata) => {
+                Event::Start(project, description, client, billable, metadata)
+            }
+            Event::Stop(project, _, client, billable, metadata) => {
+                Event::Stop(project, description, client, billable, metadata)
+            }
+        }
+    }
+
+    /// Returns the metadata of the event.
+    pub fn metadata(&self) -> &Metadata {
+        match self {
+            Event::Start(.., metadata) | Event::Stop(.., metadata) => metadata,
+        }
+    }
+
+    // Returns the metadata of the event for changing it.
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        match self {
+            Event::Start(.., metadata) | Event::Stop(.., metadata) => metadata,
+        }
+    }
+
+    /// Makes sure the event can be written to the log. The fields of a line in the log are
+    /// separated by commas and the lines by line breaks, so neither can be part of the project,
+    /// description, client or metadata. Nor can the keys of the metadata be empty or contain `=`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::log_file::{Event, Metadata};
+    /// let start = |project: &str| Event::Start(Some(project.to_string()), None, None, None, Metadata::new());
+    /// assert!(start("work").validate().is_ok());
+    /// assert!(start("foo,bar").validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), AppError> {
+        let (project, description, client, metadata) = match self {
+            Event::Start(project, description, client, _, metadata)
+            | Event::Stop(project, description, client, _, metadata) => {
+                (project, description, client, metadata)
+            }
+        };
+        for (key, value) in metadata {
+            if key.is_empty() || key.contains(&['=', ',', '\n', '\r'][..]) {
+                return Err(AppError::new(ErrorKind::User(format!(
+                    "Invalid metadata key: {}, keys can't be empty or contain =, commas or line \
+                     breaks",
+                    key
+                ))));
+            }
+            if key == checksum::KEY {
+                return Err(AppError::new(ErrorKind::User(format!(
+                    "The metadata key {} is reserved for the checksum of the line",
+                    key
+                ))));
+            }
+            if value.contains(&[',', '\n', '\r'][..]) {
+                return Err(AppError::new(ErrorKind::User(format!(
+                    "The metadata can't contain commas or line breaks: {}={}",
+                    key, value
+                ))));
+            }
+        }
+        let fields = [
+            ("project name", project),
+            ("description", description),
+            ("client", client),
+        ];
+        for (name, value) in fields.iter() {
+            if let Some(value) = value {
+                if value.contains(&[',', '\n', '\r'][..]) {
+                    return Err(AppError::new(ErrorKind::User(format!(
+                        "The {} can't contain commas or line breaks: {}",
+                        name, value
+                    ))));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Formats the event as a line of the log with the given `timestamp`. The client and whether
+    /// the work is billable are only written if they are known, so logs without them keep their
+    /// four fields per line. The metadata follows as `key=value` fields, sorted by key.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::log_file::{Event, Metadata};
+    /// let mut metadata = Metadata::new();
+    /// metadata.insert("machine".to_string(), "laptop".to_string());
+    /// let event = Event::Start(Some("work".to_string()), None, None, None, metadata);
+    /// assert_eq!(event.to_log_line(1), "1,Start,work,,,,machine=laptop");
+    /// assert_eq!(Event::from("1,Start,work,,,,machine=laptop"), event);
+    /// ```
+    pub fn to_log_line(&self, timestamp: i64) -> String {
+        let (kind, project, description, client, billable, metadata) = match self {
+            Event::Start(project, description, client, billable, metadata) => {
+                ("Start", project, description, client, billable, metadata)
+            }
+            Event::Stop(project, description, client, billable, metadata) => {
+                ("Stop", project, description, client, billable, metadata)
+            }
+        };
+        let line = format!(
+            "{},{},{},{}",
+            timestamp,
+            kind,
+            project.as_deref().unwrap_or(""),
+            description.as_deref().unwrap_or("")
+        );
+        let client = client.as_deref().unwrap_or("");
+        let line = match billable {
+            Some(true) => format!("{},{},{}", line, client, BILLABLE_STR),
+            Some(false) => format!("{},{},{}", line, client, NON_BILLABLE_STR),
+            // The metadata comes after the sixth field, so the fields before it can't be left out.
+            None if !metadata.is_empty() => format!("{},{},", line, client),
+            None if !client.is_empty() => format!("{},{}", line, client),
+            None => line,
+        };
+        metadata.iter().fold(line, |line, (key, value)| {
+            format!("{},{}={}", line, key, value)
+        })
+    }
+}
+
+/// The `LogEntry` struct is a single line of the log with its fields named, for code that would
+/// rather not pick apart the `(timestamp, Event)` pairs the log is read as. The two convert into
+/// each other freely.
+///
+/// # Examples
+/// ```
+/// # use work_core::arguments::EventKind;
+/// # use work_core::log_file::{Event, LogEntry, Metadata};
+/// let event = Event::Start(Some("work".to_string()), None, None, None, Metadata::new());
+/// let entry = LogEntry::from((1000, event));
+/// assert_eq!(entry.kind, EventKind::Start);
+/// assert_eq!(entry.project.as_deref(), Some("work"));
+/// assert_eq!(<(i64, Event)>::from(entry.clone()).0, entry.timestamp);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogEntry {
+    pub timestamp: i64,
+    pub kind: EventKind,
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub client: Option<String>,
+    /// `None` if it wasn't recorded whether the work is billable.
+    pub billable: Option<bool>,
+    pub metadata: Metadata,
+}
+
+impl LogEntry {
+    /// Returns the event of the entry.
+    pub fn event(&self) -> Event {
+        let (project, description, client, metadata) = (
+            self.project.clone(),
+            self.description.clone(),
+            self.client.clone(),
+            self.metadata.clone(),
+        );
+        match self.kind {
+            EventKind::Start => Event::Start(project, description, client, self.billable, metadata),
+            EventKind::Stop => Event::Stop(project, description, client, self.billable, metadata),
+        }
+    }
+}
+
+impl From<(i64, Event)> for LogEntry {
+    fn from((timestamp, event): (i64, Event)) -> Self {
+        let (kind, project, description, client, billable, metadata) = match event {
+            Event::Start(project, description, client, billable, metadata) => (
+                EventKind::Start,
+                project,
+                description,
+                client,
+                billable,
+                metadata,
+            ),
+            Event::Stop(project, description, client, billable, metadata) => (
+                EventKind::Stop,
+                project,
+                description,
+                client,
+                billable,
+                metadata,
+            ),
+        };
+        LogEntry {
+            timestamp,
+            kind,
+            project,
+            description,
+            client,
+            billable,
+            metadata,
+        }
+    }
+}
+
+impl From<LogEntry> for (i64, Event) {
+    fn from(entry: LogEntry) -> Self {
+        (entry.timestamp, entry.event())
+    }
+}
+
+/// Normalizes a project name by trimming and lowercasing it, so `Backend` and ` backend` end up as
+/// the same project when `normalize_projects` is set in the config file.
+///
+/// # Examples
+/// ```
+/// # use work_core::log_file::normalize_project;
+/// assert_eq!(normalize_project(" Backend "), "backend");
+/// ```
+pub fn normalize_project(project: &str) -> String {
+    project.trim().to_lowercase()
+}
+
+// For nice outputting of an Event type.
+impl ToString for Event {
+    fn to_string(&self) -> String {
+        match self {
+            Event::Stop(None, None, ..) => "Unnamed project".to_string(),
+            Event::Start(None, None, ..) => "Unnamed project".to_string(),
+            Event::Stop(None, Some(description), ..) => {
+                format!("Unnamed project - {}", description)
+            }
+            Event::Start(None, Some(description), ..) => {
+                format!("Unnamed project - {}", description)
+            }
+            Event::Stop(Some(project), None, ..) => project.to_string(),
+            Event::Start(Some(project), None, ..) => project.to_string(),
+            Event::Stop(Some(project), Some(description), ..) => {
+                format!("{} - {}", project, description)
+            }
+            Event::Start(Some(project), Some(description), ..) => {
+                format!("{} - {}", project, description)
+            }
+        }
+    }
+}
+
+// Used for parsing Events out of the log. The client and whether the work is billable are
+// optional fifth and sixth fields, older logs don't have them. Any fields after those are
+// `key=value` pairs of metadata, a field without a `=` is kept as a key with an empty value. The
+// checksum of the line is not metadata of the event, see `checksum::seal`.
+impl From<&str> for Event {
+    fn from(event: &str) -> Self {
+        let values: Vec<&str> = event.split(',').map(|s| s.trim()).collect();
+        let metadata: Metadata = values
+            .iter()
+            .skip(6)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                let mut parts = value.splitn(2, '=');
+                let key = parts.next().unwrap_or("").to_string();
+                (key, parts.next().unwrap_or("").to_string())
+            })
+            .filter(|(key, _)| key != checksum::KEY)
+            .collect();
+        let field = |i: usize| {
+            values
+                .get(i)
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_string())
+        };
+        let billable = match values.get(5) {
+            Some(&BILLABLE_STR) => Some(true),
+            Some(&NON_BILLABLE_STR) => Some(false),
+            _ => None,
+        };
+        match values.get(1) {
+            Some(&"Start") if values.len() >= 4 => {
+                Event::Start(field(2), field(3), field(4), billable, metadata)
+            }
+            Some(&"Stop") if values.len() >= 4 => {
+                Event::Stop(field(2), field(3), field(4), billable, metadata)
+            }
+            _ => Event::Stop(None, None, None, None, Metadata::new()),
+        }
+    }
+}
+
+// Returns the error for an event that would be appended before the last event in the log, which
+// was written at `latest`.
+fn out_of_order(latest: i64) -> AppError {
+    AppError::new(ErrorKind::User(format!(
+        "Unable to append, the last event in the log is later ({}).",
+        time::format_date_time(latest)
+    )))
+    .with_code(ErrorCode::OutOfOrder)
+}
+
+/// The `Change` enum describes a change to the log that a dry run would have made, see
+/// `LogFile::set_dry_run`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// The event would be appended at the given time.
+    Append(i64, Event),
+    /// The event at the given time would be removed while rewriting the log.
+    Remove(i64, Event),
+    /// The event would be added at the given time while rewriting the log.
+    Add(i64, Event),
+}
+
+impl Change {
+    /// Returns the verb for the change, such as `append`.
+    pub fn action(&self) -> &'static str {
+        match self {
+            Change::Append(..) => "append",
+            Change::Remove(..) => "remove",
+            Change::Add(..) => "add",
+        }
+    }
+
+    /// Returns the time and the event that would be changed.
+    pub fn entry(&self) -> (i64, &Event) {
+        match self {
+            Change::Append(timestamp, event)
+            | Change::Remove(timestamp, event)
+            | Change::Add(timestamp, event) => (*timestamp, event),
+        }
+    }
+}
+
+/// Receives each change a dry run would have made to the log, in the order they would have been
+/// made.
+pub type DryRun = Rc<dyn Fn(&Change)>;
+
+/// Number of bytes read at a time when reading the log backwards.
+const TAIL_CHUNK: u64 = 4096;
+
+/// An iterator over the events of the log, see `LogFile::events`.
+pub type Events<'a> = Box<dyn Iterator<Item = Result<(i64, Event), AppError>> + 'a>;
+
+// An event of the log along with the byte offset of its line within the log.
+type OffsetEvent = (u64, (i64, Event));
+
+/// The `Storage` enum is what a `LogFile` reads from and writes to, either a file on disk or a
+/// buffer in memory.
+enum Storage {
+    File { file: File, path: PathBuf },
+    Memory(Cursor<Vec<u8>>),
+}
+
+impl Read for Storage {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Storage::File { file, .. } => file.read(buf),
+            Storage::Memory(buffer) => buffer.read(buf),
+        }
+    }
+}
+
+impl Write for Storage {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Storage::File { file, .. } => file.write(buf),
+            Storage::Memory(buffer) => buffer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Storage::File { file, .. } => file.flush(),
+            Storage::Memory(buffer) => buffer.flush(),
+        }
+    }
+}
+
+impl Seek for Storage {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Storage::File { file, .. } => file.seek(pos),
+            Storage::Memory(buffer) => buffer.seek(pos),
+        }
+    }
+}
+
+/// The `LogFile` struct is a wrapper around a `File`, or a buffer in memory.
+///
+/// This ensures that one can only do "logging" actions to the log file. That is one can only
+/// append to the file or read from it. The `LogFile` also implements some handy functions for
+/// dealing with the log, like appending events or fetching the latest event of a log file.
+///
+/// If encryption is configured all reads and writes go through a `Cipher`, so the rest of the
+/// program never has to know whether the log is encrypted or not. If `normalize_projects` is set
+/// in the config file, project names are normalized both when they are written and when time is
+/// tallied, see `normalize_project`.
+///
+/// The cache, the locks, the days off, the archives and the backups are kept next to the log, a
+/// log in memory has none of them. Time within archived years is tallied out of the archives as
+/// well, see `archive`. If `auto_backup` is set, the log is backed up before the first change to
+/// it each day, see `Backup`. If `checksums` is set, each line written gets a checksum that
+/// `work doctor` verifies, see `checksum::seal`. If `record_user` is set, each event appended names
+/// the user who recorded it in its metadata, see `Config::user`.
+///
+/// A log that can't be written to, such as a log shared on a network mount, is opened read-only.
+/// Everything that only reads the log works as usual, while anything that would change the log or
+/// the files next to it fails with an error, see `set_read_only`.
+pub struct LogFile {
+    log: Storage,
+    cipher: Option<Cipher>,
+    normalize_projects: bool,
+    dry_run: Option<DryRun>,
+    clock: Rc<dyn Clock>,
+    archives: Option<Vec<Archive>>,
+    auto_backup: bool,
+    checksums: bool,
+    read_only: bool,
+    user: Option<String>,
+    filter: Option<Filter>,
+}
+
+impl LogFile {
+    /// Fetches the default path for the log file of the profile in use, creates it if it doesn't
+    /// exist and finally sets the `log` to the open file descriptor of the log file.
+    ///
+    /// If any of these actions fail to finish, the function will return an error message.
+    pub fn new(config: &Config) -> Result<Self, AppError> {
+        Self::of_profile(config, &profiles::current()?)
+    }
+
+    /// Opens the log of the given profile just like `new` does, see `profiles::dir`.
+    pub fn of_profile(config: &Config, profile: &str) -> Result<Self, AppError> {
+        let file_path = Self::log_file_path(profile)?;
+        let mut log_file = Self::from_path(file_path.clone())?;
+        log_file.cipher = Cipher::from_config(config)?;
+        log_file.normalize_projects = config.get("normalize_projects") == Some("true");
+        log_file.auto_backup = config.auto_backup()?;
+        log_file.checksums = config.checksums()?;
+        log_file.user = config.user()?;
+
+        debug!(
+            "Opened the log at {}{}",
+            file_path.display(),
+            if log_file.cipher.is_some() {
+                " with encryption"
+            } else {
+                ""
+            }
+        );
+
+        // Appending plain text to an encrypted log would corrupt it, so refuse to go any further
+        // without a key.
+        if log_file.cipher.is_none() && log_file.is_encrypted()? {
+            return Err(AppError::new(ErrorKind::LogFile(
+                "Work log is encrypted, please configure the passphrase or keyfile!".to_string(),
+            ))
+            .with_code(ErrorCode::Encryption));
+        }
+        Ok(log_file)
+    }
+
+    /// Reads a log from the standard input instead, such as a log filtered with `grep` or the logs
+    /// of two machines merged with `sort -n`. The input is kept in memory, see `in_memory`.
+    ///
+    /// Blank lines are skipped, and any other line that doesn't start with a UNIX timestamp results
+    /// in an error message.
+    pub fn from_stdin(config: &Config) -> Result<Self, AppError> {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        let mut contents = String::new();
+        for (number, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let timestamp = line.split(',').next().unwrap_or("").trim();
+            if timestamp.parse::<i64>().is_err() {
+                return Err(AppError::new(ErrorKind::User(format!(
+                    "Line {} of the input isn't a line of a log: {}",
+                    number + 1,
+                    line
+                )))
+                .with_code(ErrorCode::LogCorrupt));
+            }
+            contents.push_str(line);
+            contents.push('\n');
+        }
+
+        debug!("Read {} lines from stdin", contents.lines().count());
+        let mut log_file = Self::in_memory();
+        log_file.log = Storage::Memory(Cursor::new(contents.into_bytes()));
+        log_file.normalize_projects = config.get("normalize_projects") == Some("true");
+        Ok(log_file)
+    }
+
+    /// Opens the plain text log at the given path rather than the one in the data directory,
+    /// creating the log and its directory if they don't exist. Project names are kept as they are.
+    ///
+    /// This keeps tests and programs using Work as a library away from the log of the user.
+    pub fn from_path(path: PathBuf) -> Result<Self, AppError> {
+        Self::create_path(&path)?;
+        let (file, read_only) = Self::open(&path)?;
+        Ok(LogFile {
+            log: Storage::File { file, path },
+            cipher: None,
+            normalize_projects: false,
+            dry_run: None,
+            clock: Rc::new(SystemClock),
+            archives: None,
+            auto_backup: false,
+            checksums: false,
+            read_only,
+            user: None,
+            filter: None,
+        })
+    }
+
+    /// Returns an empty plain text log that is only kept in memory, which is gone once the
+    /// `LogFile` is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::log_file::{Event, LogFile};
+    /// let mut log = LogFile::in_memory();
+    /// log.append_event(&Event::from("1,Start,work,"), 1).unwrap();
+    /// assert_eq!(log.get_latest_entry().unwrap(), Some((1, Event::from("1,Start,work,"))));
+    /// ```
+    pub fn in_memory() -> Self {
+        LogFile {
+            log: Storage::Memory(Cursor::new(Vec::new())),
+            cipher: None,
+            normalize_projects: false,
+            dry_run: None,
+            clock: Rc::new(SystemClock),
+            archives: None,
+            auto_backup: false,
+            checksums: false,
+            read_only: false,
+            user: None,
+            filter: None,
+        }
+    }
+
+    /// Makes every following change to the log be handed to the given `DryRun` instead of being
+    /// written, see `--dry-run`. Changes to the files next to the log are skipped altogether.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// # use work_core::log_file::{Change, Event, LogFile};
+    /// let changes = Rc::new(RefCell::new(Vec::new()));
+    /// let seen = Rc::clone(&changes);
+    /// let mut log = LogFile::in_memory();
+    /// log.set_dry_run(Some(Rc::new(move |change: &Change| seen.borrow_mut().push(change.clone()))));
+    /// log.append_event(&Event::from("1,Start,work,"), 1).unwrap();
+    /// assert_eq!(log.get_latest_entry().unwrap(), None);
+    /// assert_eq!(changes.borrow()[0], Change::Append(1, Event::from("1,Start,work,")));
+    /// ```
+    pub fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+
+    /// Returns true if changes to the log are merely reported, see `set_dry_run`.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
+
+    /// Makes the log read the time from the given clock rather than the system clock, which lets
+    /// tests and library users freeze time.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::rc::Rc;
+    /// # use work_core::log_file::{Event, LogFile};
+    /// # use work_core::time::FixedClock;
+    /// let mut log = LogFile::in_memory();
+    /// log.set_clock(Rc::new(FixedClock(1000)));
+    /// log.append_event_now(&Event::from("1,Start,work,")).unwrap();
+    /// assert_eq!(log.get_latest_entry().unwrap(), Some((1000, Event::from("1,Start,work,"))));
+    /// ```
+    pub fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Returns the clock the log reads the time from, see `set_clock`.
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// Returns the current UNIX timestamp according to the clock of the log, see `set_clock`.
+    pub fn now(&self) -> i64 {
+        self.clock.now()
+    }
+
+    /// Makes every following change to the log fail with an error, see `--read-only`. A log that
+    /// can't be written to is read-only no matter what.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only |= read_only;
+    }
+
+    /// Returns true if the log can't be changed, see `set_read_only`.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Makes every following tally of the log only count the sessions that pass the given
+    /// `Filter`, see `--filter`. Filtered tallies are worked out from the sessions themselves, as
+    /// the cache only holds the totals of all of them.
+    pub fn set_filter(&mut self, filter: Option<Filter>) {
+        self.filter = filter;
+    }
+
+    /// Appends a given `Event` to the log with the given `timestamp`.
+    /// If the event can't be written to the log, see `Event::validate`, it is earlier than the last
+    /// event in the log, or it fails to append to the log, the function returns an error message.
+    /// The events of the log are kept in chronological order, which tallies rely on, so events
+    /// before the last one have to be added with `insert_events` instead.
+    pub fn append_event(&mut self, event: &Event, timestamp: i64) -> Result<(), AppError> {
+        let event = self.prepare(event)?;
+        match self.get_latest_entry()? {
+            // A stop before its start would count negative time.
+            Some((started, Event::Start(..))) if timestamp < started => {
+                if let Event::Stop(..) = event {
+                    return Err(AppError::new(ErrorKind::User(format!(
+                        "Unable to stop before the work started ({}).",
+                        time::format_date_time(started)
+                    )))
+                    .with_code(ErrorCode::OutOfOrder));
+                }
+                return Err(out_of_order(started));
+            }
+            Some((latest, _)) if timestamp < latest => return Err(out_of_order(latest)),
+            _ => {}
+        }
+        if let Some(dry_run) = &self.dry_run {
+            dry_run(&Change::Append(timestamp, event));
+            return Ok(());
+        }
+        let line = self.to_line(&event, timestamp);
+        self.write(&line)?;
+        debug!("Appended {}", line);
+        Ok(())
+    }
+
+    /// Adds the given events to the log in chronological order. Unlike `append_event` the events
+    /// may be earlier than the last event in the log, in which case the log is rewritten with each
+    /// of them inserted after the events at or before its time, see `rewrite`.
+    pub fn insert_events(&mut self, entries: &[(i64, Event)]) -> Result<(), AppError> {
+        let in_order = match self.get_latest_entry()? {
+            Some((latest, _)) => entries.iter().all(|(timestamp, _)| *timestamp >= latest),
+            None => true,
+        };
+        if in_order {
+            return entries
+                .iter()
+                .try_for_each(|(timestamp, event)| self.append_event(event, *timestamp));
+        }
+
+        let mut events = self.all_events()?;
+        for (timestamp, event) in entries {
+            let index = events
+                .iter()
+                .rposition(|(existing, _)| existing <= timestamp)
+                .map_or(0, |index| index + 1);
+            events.insert(index, (*timestamp, self.prepare(event)?));
+        }
+        self.rewrite(&events)
+    }
+
+    /// Appends a given `Event` to the log using the current UNIX timestamp of its clock.
+    /// If it fails to append to the log, the function returns an error message.
+    pub fn append_event_now(&mut self, event: &Event) -> Result<(), AppError> {
+        self.append_event(&event, self.now())
+    }
+
+    /// Reads and parses every event in the log, in the order they appear in the log.
+    /// If it fails to read the log, the function returns an error message.
+    pub fn all_events(&mut self) -> Result<Vec<(i64, Event)>, AppError> {
+        self.events()?.collect()
+    }
+
+    /// Returns an iterator over the events of the log, in the order they appear in the log.
+    ///
+    /// The log is read line by line and each event is only parsed once it is needed, so callers
+    /// that stop early never read the rest of the log. An encrypted log has to be decrypted as a
+    /// whole though, so it is read into memory up front.
+    pub fn events(&mut self) -> Result<Events<'_>, AppError> {
+        if self.cipher.is_some() && self.is_encrypted()? {
+            let events: Vec<_> = self.read()?.lines().map(parse_line).collect();
+            return Ok(Box::new(events.into_iter()));
+        }
+
+        Ok(Box::new(
+            self.events_from(0)?
+                .map(|entry| entry.map(|(_, event)| event)),
+        ))
+    }
+
+    /// Replaces the contents of the log with the given events.
+    ///
+    /// The new log is first written to a temporary file next to the log which is then moved over
+    /// the log, so a failure half way through never leaves a truncated log behind.
+    pub fn rewrite(&mut self, events: &[(i64, Event)]) -> Result<(), AppError> {
+        if let Some(dry_run) = self.dry_run.clone() {
+            let old = self.all_events()?;
+            old.iter()
+                .filter(|entry| !events.contains(entry))
+                .for_each(|(timestamp, event)| dry_run(&Change::Remove(*timestamp, event.clone())));
+            events
+                .iter()
+                .filter(|entry| !old.contains(entry))
+                .for_each(|(timestamp, event)| dry_run(&Change::Add(*timestamp, event.clone())));
+            return Ok(());
+        }
+
+        let mut contents = String::new();
+        events.iter().for_each(|(timestamp, event)| {
+            contents.push_str(&self.to_line(event, *timestamp));
+            contents.push('\n');
+        });
+        self.replace(&contents)?;
+        debug!("Rewrote the log with {} events", events.len());
+        Ok(())
+    }
+
+    /// Returns the final event in the log along with its timestamp, or `None` if the log is empty.
+    /// If it fails to read the log file, the function returns an error message.
+    ///
+    /// A plain text log is read backwards from its end, so only the final line is ever read.
+    pub fn get_latest_entry(&mut self) -> Result<Option<(i64, Event)>, AppError> {
+        if self.cipher.is_some() && self.is_encrypted()? {
+            return self.read()?.lines().last().map(parse_line).transpose();
+        }
+        last_line(&mut self.log)?
+            .as_deref()
+            .map(parse_line)
+            .transpose()
+    }
+
+    /// Returns the first line of the log that isn't an event, whose checksum doesn't match or that
+    /// was cut off, see `checksum::first_bad_line`, or `None` if the whole log is fine.
+    pub fn first_bad_line(&mut self) -> Result<Option<BadLine>, AppError> {
+        Ok(checksum::first_bad_line(&self.read()?))
+    }
+
+    /// Returns the name of every project work has been started on, sorted and without duplicates.
+    /// Projects are normalized if `normalize_projects` is set.
+    pub fn projects(&mut self) -> Result<BTreeSet<String>, AppError> {
+        let normalize = self.normalize_projects;
+        let mut projects = BTreeSet::new();
+        for event in self.events()? {
+            if let (_, Event::Start(Some(project), ..)) = event? {
+                if normalize {
+                    projects.insert(normalize_project(&project));
+                } else {
+                    projects.insert(project);
+                }
+            }
+        }
+        Ok(projects)
+    }
+
+    /// Finds all sessions that overlap a given `Interval` and sums up the time spent on each
+    /// project, then it returns the results as a `HashMap`.
+    ///
+    /// The events of the log are first paired up into sessions, see `sessions::reconstruct`, and
+    /// each session then contributes the time it shares with the interval to the project it was
+    /// started with. Returns `None` if no work was done within the interval.
+    ///
+    /// Projects are normalized if `normalize_projects` is set, so the time of entries written
+    /// before it was set is counted towards the normalized project as well.
+    pub fn tally_time(
+        &mut self,
+        interval: &time::Interval,
+    ) -> Result<Option<ProjectMap>, AppError> {
+        let now = self.now();
+        if self.filter.is_some() {
+            let projects = sessions::tally(self.sessions(interval)?, interval, now);
+            return Ok(self.tallied(projects));
+        }
+        let mut projects = match self.cache()? {
+            // Only the parts of the interval that aren't cached have to be read from the log.
+            Some(cache) => {
+                let (mut projects, rest) = cache.split(interval);
+                debug!(
+                    "Tallying {} - {} from the cache, reading {} uncached stretches from the log",
+                    time::format_date_time(interval.start),
+                    time::format_date_time(interval.end),
+                    rest.len()
+                );
+                for (offset, part) in rest {
+                    let events = self
+                        .events_from(offset)?
+                        .map(|entry| entry.map(|(_, event)| event));
+                    projects.merge(&tally_events(events, &part, now)?);
+                }
+                projects
+            }
+            None => {
+                debug!(
+                    "Tallying {} - {} from the whole log",
+                    time::format_date_time(interval.start),
+                    time::format_date_time(interval.end)
+                );
+                tally_events(self.events()?, interval, now)?
+            }
+        };
+        let archived = self.archived_events(interval)?;
+        if !archived.is_empty() {
+            projects.merge(&tally_events(archived.into_iter().map(Ok), interval, now)?);
+        }
+        Ok(self.tallied(projects))
+    }
+
+    /// Returns the projects of a tally, normalized if `normalize_projects` is set, or `None` if
+    /// no work was done.
+    fn tallied(&self, projects: ProjectMap) -> Option<ProjectMap> {
+        let projects = if self.normalize_projects {
+            projects.normalized()
+        } else {
+            projects
+        };
+
+        if projects.is_empty() {
+            None
+        } else {
+            Some(projects)
+        }
+    }
+
+    /// Returns the sessions of the archives and the log that may overlap the given `Interval`,
+    /// earliest first, leaving out those that don't pass the filter, see `set_filter`.
+    pub fn sessions(&mut self, interval: &time::Interval) -> Result<Vec<Session>, AppError> {
+        let events = self.events_with_archived(interval)?;
+        let mut sessions = sessions::reconstruct(&events);
+        sessions.retain(|session| self.passes_filter(session));
+        Ok(sessions)
+    }
+
+    /// Returns true if the given session passes the filter, or if there is none, see `set_filter`.
+    pub fn passes_filter(&self, session: &Session) -> bool {
+        match &self.filter {
+            Some(filter) => filter.matches(session, self.now()),
+            None => true,
+        }
+    }
+
+    /// Sums up the time spent for each client on each of its projects within a given `Interval`,
+    /// see `sessions::tally_clients`. Returns `None` if no work was done within the interval.
+    pub fn tally_clients(
+        &mut self,
+        interval: &time::Interval,
+    ) -> Result<Option<ProjectMap>, AppError> {
+        let now = self.now();
+        let clients = sessions::tally_clients(self.sessions(interval)?, interval, now);
+        if clients.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(clients))
+        }
+    }
+
+    /// Sums up the time spent by each user on each project within a given `Interval`, see
+    /// `sessions::tally_users`. Returns `None` if no work was done within the interval.
+    pub fn tally_users(
+        &mut self,
+        interval: &time::Interval,
+    ) -> Result<Option<ProjectMap>, AppError> {
+        let now = self.now();
+        let users = sessions::tally_users(self.sessions(interval)?, interval, now);
+        if users.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(users))
+        }
+    }
+
+    /// Sums up the time spent on each project within a given `Interval` like `tally_time`, but
+    /// rounds the time of each session first, see `Rounding::tally`. Returns the non-billable part
+    /// of the rounded time along with it.
+    pub fn tally_rounded(
+        &mut self,
+        interval: &time::Interval,
+        rounding: &Rounding,
+    ) -> Result<(Option<ProjectMap>, i64), AppError> {
+        let now = self.now();
+        let (projects, non_billable) = rounding.tally(self.sessions(interval)?, interval, now);
+        Ok((self.tallied(projects), non_billable))
+    }
+
+    /// Sums up the time spent by each user on each project within a given `Interval` like
+    /// `tally_users`, but rounds the time of each session first, see `Rounding::tally_users`.
+    /// Returns the non-billable part of the rounded time along with it.
+    pub fn tally_users_rounded(
+        &mut self,
+        interval: &time::Interval,
+        rounding: &Rounding,
+    ) -> Result<(Option<ProjectMap>, i64), AppError> {
+        let now = self.now();
+        let (users, non_billable) = rounding.tally_users(self.sessions(interval)?, interval, now);
+        if users.is_empty() {
+            Ok((None, non_billable))
+        } else {
+            Ok((Some(users), non_billable))
+        }
+    }
+
+    /// Sums up the billable and the non-billable time within a given `Interval`, see
+    /// `sessions::tally_billable`.
+    pub fn tally_billable(&mut self, interval: &time::Interval) -> Result<(i64, i64), AppError> {
+        let now = self.now();
+        Ok(sessions::tally_billable(
+            self.sessions(interval)?,
+            interval,
+            now,
+        ))
+    }
+
+    /// Returns the events of the archives whose sessions may fall within the given `Interval`,
+    /// earliest first, see `Archive::may_overlap`. Archives hold whole sessions, so the events can
+    /// be paired up into sessions on their own or ahead of the events of the log.
+    pub fn archived_events(
+        &mut self,
+        interval: &time::Interval,
+    ) -> Result<Vec<(i64, Event)>, AppError> {
+        let mut events = Vec::new();
+        for archive in self.archives()?.iter_mut() {
+            if archive.may_overlap(interval) {
+                events.extend_from_slice(archive.events()?);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Returns the timestamp of the earliest event, whether it is archived or in the log, or
+    /// `None` if there are no events at all.
+    pub fn first_timestamp(&mut self) -> Result<Option<i64>, AppError> {
+        let archived = match self.archives()?.first_mut() {
+            Some(archive) => archive.events()?.first().map(|(timestamp, _)| *timestamp),
+            None => None,
+        };
+        let logged = match self.events()?.next() {
+            Some(entry) => Some(entry?.0),
+            None => None,
+        };
+        Ok(archived.into_iter().chain(logged).min())
+    }
+
+    /// Moves the sessions within the given `Interval` out of the log into the archive of the year
+    /// each of them started in, and returns how many sessions went into each archive. Only whole
+    /// sessions that were stopped within the interval are archived.
+    ///
+    /// The archives are written before the log is rewritten, so a failure half way through never
+    /// loses a session. Encrypted logs aren't archived, since the archives would reveal their
+    /// contents.
+    pub fn archive(&mut self, interval: &time::Interval) -> Result<Vec<(i32, usize)>, AppError> {
+        if self.cipher.is_some() {
+            return Err(AppError::new(ErrorKind::User(
+                "Encrypted logs aren't archived, the archives would reveal their contents."
+                    .to_string(),
+            )));
+        }
+        let dir = match self.dir() {
+            Some(dir) => dir,
+            None => return Err(in_memory("archived")),
+        };
+        if !self.is_dry_run() {
+            self.check_writable("archived")?;
+        }
+
+        let events = self.all_events()?;
+        let mut kept = Vec::new();
+        let mut archived: BTreeMap<i32, Vec<sessions::Session>> = BTreeMap::new();
+        let mut index = 0;
+        while index < events.len() {
+            if let (
+                Some(start @ (start_time, Event::Start(..))),
+                Some((stop_time, Event::Stop(..))),
+            ) = (events.get(index), events.get(index + 1))
+            {
+                if *start_time >= interval.start && *stop_time <= interval.end {
+                    let session = sessions::Session {
+                        stop: Some(*stop_time),
+                        ..sessions::Session::from(LogEntry::from(start.clone()))
+                    };
+                    archived
+                        .entry(archive::year_of_timestamp(*start_time))
+                        .or_default()
+                        .push(session);
+                    index += 2;
+                    continue;
+                }
+            }
+            kept.push(events[index].clone());
+            index += 1;
+        }
+
+        if !self.is_dry_run() {
+            for (year, sessions) in &archived {
+                let mut archive = Archive::of_year(&dir, *year);
+                archive.add(sessions)?;
+                debug!(
+                    "Archived {} sessions into {}",
+                    sessions.len(),
+                    archive.path().display()
+                );
+            }
+            self.archives = None;
+        }
+        if !archived.is_empty() {
+            self.rewrite(&kept)?;
+        }
+        Ok(archived
+            .iter()
+            .map(|(year, sessions)| (*year, sessions.len()))
+            .collect())
+    }
+
+    /// Rebuilds the cache of daily totals out of the log and saves it next to the log.
+    ///
+    /// Encrypted logs aren't cached, since the cache would reveal their contents.
+    pub fn rebuild_cache(&mut self) -> Result<Cache, AppError> {
+        if self.cipher.is_some() {
+            return Err(AppError::new(ErrorKind::User(
+                "Encrypted logs aren't cached, the cache would reveal their contents.".to_string(),
+            )));
+        }
+        let (path, stamp) = match &self.log {
+            Storage::File { file, path } => (
+                path.with_file_name("work.cache"),
+                LogStamp::of(&file.metadata()?),
+            ),
+            Storage::Memory(_) => return Err(in_memory("cached")),
+        };
+        self.check_writable("cached")?;
+
+        let entries = self.events_from(0)?.collect::<Result<Vec<_>, _>>()?;
+        let cache = Cache::build(&entries, stamp, self.clock());
+        cache.save(&path)?;
+        Ok(cache)
+    }
+
+    /// Backs up the log and the config file into a new backup within the given directory, or within
+    /// the `backups` directory next to the log if none is given, see `Backup`.
+    pub fn backup(&self, within: Option<&Path>, gzip: bool) -> Result<Backup, AppError> {
+        let path = match &self.log {
+            Storage::File { path, .. } => path,
+            Storage::Memory(_) => return Err(in_memory("backed up")),
+        };
+        let within = within.map_or_else(|| path.with_file_name(BACKUPS_DIR), Path::to_path_buf);
+        Backup::create(
+            &within,
+            path,
+            &Config::config_file_path()?,
+            gzip,
+            self.now(),
+        )
+    }
+
+    /// Replaces the log and the config file with the ones in the given backup, see
+    /// `Backup::restore`. The current ones are backed up first so the restore can be undone, and
+    /// that backup is returned.
+    pub fn restore(&mut self, backup: &Backup) -> Result<Backup, AppError> {
+        backup.validate()?;
+        let path = match &self.log {
+            Storage::File { path, .. } => path.clone(),
+            Storage::Memory(_) => return Err(in_memory("restored")),
+        };
+        self.check_writable("restored")?;
+        let current = self.backup(None, false)?;
+        backup.restore(&path, &Config::config_file_path()?)?;
+
+        // The old file descriptor still points to the replaced log.
+        self.log = Storage::File {
+            file: Self::open(&path)?.0,
+            path,
+        };
+        self.archives = None;
+        Ok(current)
+    }
+
+    /// Returns the periods of the log that are locked, see `Locks`.
+    pub fn locks(&self) -> Result<Locks, AppError> {
+        match self.next_to_log("work.locks") {
+            Some(path) => Locks::load(&path),
+            None => Ok(Locks::default()),
+        }
+    }
+
+    /// Saves the periods of the log that are locked next to the log. Nothing is saved on a dry run.
+    ///
+    /// Encrypted logs aren't locked, since the locked periods are saved in plain text.
+    pub fn save_locks(&self, locks: &Locks) -> Result<(), AppError> {
+        let path = match self.next_to_log("work.locks") {
+            Some(path) => path,
+            None => return Err(in_memory("locked")),
+        };
+        self.check_unencrypted("locked")?;
+        if self.is_dry_run() {
+            return Ok(());
+        }
+        self.check_writable("locked")?;
+        locks.save(&path)
+    }
+
+    /// Returns the days off, see `DaysOff`.
+    pub fn days_off(&self) -> Result<DaysOff, AppError> {
+        match self.next_to_log("work.off") {
+            Some(path) => DaysOff::load(&path),
+            None => Ok(DaysOff::default()),
+        }
+    }
+
+    /// Saves the days off next to the log. Nothing is saved on a dry run.
+    ///
+    /// Encrypted logs aren't given days off, since the days off and their reasons are saved in
+    /// plain text.
+    pub fn save_days_off(&self, days_off: &DaysOff) -> Result<(), AppError> {
+        let path = match self.next_to_log("work.off") {
+            Some(path) => path,
+            None => return Err(in_memory("given days off")),
+        };
+        self.check_unencrypted("given days off")?;
+        if self.is_dry_run() {
+            return Ok(());
+        }
+        self.check_writable("given days off")?;
+        days_off.save(&path)
+    }
+
+    /// Returns the queue of sessions `work auto` detected, which is a log of its own kept in
+    /// `work.auto` next to the log until the sessions are reviewed, see `auto::watch`.
+    ///
+    /// Encrypted logs have no queue, since the queued sessions are kept in plain text.
+    #[cfg(feature = "auto")]
+    pub fn auto_queue(&self) -> Result<LogFile, AppError> {
+        let path = match self.next_to_log("work.auto") {
+            Some(path) => path,
+            None => return Err(in_memory("queued")),
+        };
+        self.check_unencrypted("queued")?;
+        let mut queue = LogFile::from_path(path)?;
+        queue.set_read_only(self.read_only);
+        queue.set_dry_run(self.dry_run.clone());
+        queue.set_clock(Rc::clone(&self.clock));
+        Ok(queue)
+    }
+
+    /// Returns the archives next to the log, reading which archives there are first if that hasn't
+    /// been done yet. A log kept in memory has no archives.
+    fn archives(&mut self) -> Result<&mut Vec<Archive>, AppError> {
+        if self.archives.is_none() {
+            let archives = match self.dir() {
+                Some(dir) => Archive::all_in(&dir)?,
+                None => Vec::new(),
+            };
+            self.archives = Some(archives);
+        }
+        Ok(self.archives.get_or_insert_with(Vec::new))
+    }
+
+    /// Returns the archived events that may fall within the given `Interval` followed by every
+    /// event of the log.
+    fn events_with_archived(
+        &mut self,
+        interval: &time::Interval,
+    ) -> Result<Vec<(i64, Event)>, AppError> {
+        let mut events = self.archived_events(interval)?;
+        events.extend(self.all_events()?);
+        Ok(events)
+    }
+
+    /// Returns the directory holding the log, or `None` if the log is kept in memory.
+    fn dir(&self) -> Option<PathBuf> {
+        match &self.log {
+            Storage::File { path, .. } => path.parent().map(Path::to_path_buf),
+            Storage::Memory(_) => None,
+        }
+    }
+
+    /// Returns the path of the file with the given name next to the log, or `None` if the log is
+    /// kept in memory.
+    fn next_to_log(&self, name: &str) -> Option<PathBuf> {
+        match &self.log {
+            Storage::File { path, .. } => Some(path.with_file_name(name)),
+            Storage::Memory(_) => None,
+        }
+    }
+
+    /// Returns the cache of daily totals, rebuilding it first if the log has changed since it was
+    /// built. Returns `None` for encrypted logs and logs kept in memory, and for read-only logs
+    /// whose cache is out of date.
+    fn cache(&mut self) -> Result<Option<Cache>, AppError> {
+        let (path, stamp) = match &self.log {
+            Storage::File { file, path } if self.cipher.is_none() => (
+                path.with_file_name("work.cache"),
+                LogStamp::of(&file.metadata()?),
+            ),
+            _ => return Ok(None),
+        };
+        match Cache::load(&path) {
+            Some(cache) if cache.is_valid(stamp, self.clock()) => Ok(Some(cache)),
+            _ if self.read_only => Ok(None),
+            _ => self.rebuild_cache().map(Some),
+        }
+    }
+
+    /// Returns an iterator over the events of a plain text log starting at the given byte offset,
+    /// each event along with the byte offset of its line.
+    fn events_from(
+        &mut self,
+        offset: u64,
+    ) -> Result<impl Iterator<Item = Result<OffsetEvent, AppError>> + '_, AppError> {
+        self.log.seek(SeekFrom::Start(offset))?;
+        let mut reader = BufReader::new(&mut self.log);
+        let mut position = offset;
+
+        Ok(iter::from_fn(move || {
+            let mut line = String::new();
+            match reader.read_line(&mut line) {
+                Ok(0) => None,
+                Ok(read) => {
+                    let line = line.trim_end_matches(&['\n', '\r'][..]);
+                    let entry = parse_line(line).map(|event| (position, event));
+                    position += read as u64;
+                    Some(entry)
+                }
+                Err(e) => Some(Err(AppError::from(e))),
+            }
+        }))
+    }
+
+    // Returns the event as it is written to the log, normalized if `normalize_projects` is set
+    // and naming the user if one is set, or an error if it can't be written to the log.
+    fn prepare(&self, event: &Event) -> Result<Event, AppError> {
+        let mut event = if self.normalize_projects {
+            event.normalized()
+        } else {
+            event.clone()
+        };
+        if let Some(user) = &self.user {
+            event
+                .metadata_mut()
+                .entry(USER_KEY.to_string())
+                .or_insert_with(|| user.to_string());
+        }
+        event.validate()?;
+        Ok(event)
+    }
+
+    /// Formats the event as a line of the log, with a checksum if `checksums` is set.
+    fn to_line(&self, event: &Event, timestamp: i64) -> String {
+        let line = event.to_log_line(timestamp);
+        if self.checksums {
+            checksum::seal(&line)
+        } else {
+            line
+        }
+    }
+
+    /// Reads the whole log into a string, decrypting it if needed. If it fails to read or decrypt
+    /// the log, the function returns an error message.
+    fn read(&mut self) -> Result<String, AppError> {
+        let mut data = Vec::new();
+        self.log.seek(SeekFrom::Start(0))?;
+        self.log.read_to_end(&mut data)?;
+
+        // A plain text log with encryption configured is still readable, it gets encrypted on the
+        // next write.
+        if Cipher::is_encrypted(&data) {
+            if let Some(cipher) = &mut self.cipher {
+                data = cipher.decrypt(&data)?;
+            }
+        }
+
+        match String::from_utf8(data) {
+            Ok(contents) => Ok(contents),
+            Err(_) => Err(AppError::new(ErrorKind::LogFile(
+                "Work log contains invalid UTF-8!".to_string(),
+            ))
+            .with_code(ErrorCode::LogCorrupt)),
+        }
+    }
+
+    /// Writes a given log event to the log, if it fails to write to the log, the function returns
+    /// an error message
+    ///
+    /// An encrypted log can't be appended to, so the whole log is read, extended with the new
+    /// event and then encrypted and written back.
+    fn write(&mut self, log_event: &str) -> Result<(), AppError> {
+        self.check_writable("changed")?;
+        self.back_up_daily()?;
+        if self.cipher.is_none() {
+            // A file is opened for appending, but a buffer in memory is written wherever it was
+            // last read.
+            self.log.seek(SeekFrom::End(0))?;
+            if let Err(e) = writeln!(self.log, "{}", log_event) {
+                return Err(AppError::from(e));
+            }
+            return Ok(());
+        }
+
+        let mut contents = self.read()?;
+        contents.push_str(log_event);
+        contents.push('\n');
+        self.replace(&contents)
+    }
+
+    /// Safely replaces the whole log with the given contents, encrypting them if needed.
+    fn replace(&mut self, contents: &str) -> Result<(), AppError> {
+        self.check_writable("changed")?;
+        self.back_up_daily()?;
+        let data = match &mut self.cipher {
+            Some(cipher) => cipher.encrypt(contents.as_bytes())?,
+            None => contents.as_bytes().to_vec(),
+        };
+
+        let path = match &mut self.log {
+            Storage::File { path, .. } => path.clone(),
+            Storage::Memory(buffer) => {
+                *buffer = Cursor::new(data);
+                return Ok(());
+            }
+        };
+        let tmp_path = path.with_extension("log.tmp");
+        let mut tmp_file = File::create(&tmp_path)?;
+        tmp_file.write_all(&data)?;
+        tmp_file.sync_all()?;
+        fs::rename(&tmp_path, &path)?;
+
+        // The old file descriptor still points to the replaced log.
+        self.log = Storage::File {
+            file: Self::open(&path)?.0,
+            path,
+        };
+        Ok(())
+    }
+
+    /// Backs up the log if `auto_backup` is set and it hasn't been backed up today yet.
+    fn back_up_daily(&mut self) -> Result<(), AppError> {
+        if !self.auto_backup {
+            return Ok(());
+        }
+        // Checking once is enough, however many changes follow.
+        self.auto_backup = false;
+        if let Some(within) = self.next_to_log(BACKUPS_DIR) {
+            if !backup::made_on(&within, self.clock.today())? {
+                let backup = self.backup(Some(&within), false)?;
+                debug!("Backed up the log to {}", backup.path().display());
+            }
+        }
+        Ok(())
+    }
+
+    /// Opens the log file at the given path for reading and appending, creating it if needed. A log
+    /// that can't be written to is opened read-only instead, which is returned along with the file.
+    fn open(path: &Path) -> Result<(File, bool), AppError> {
+        match OpenOptions::new()
+            .append(true)
+            .create(true)
+            .read(true)
+            .open(path)
+        {
+            Ok(file) => Ok((file, false)),
+            Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                let file = File::open(path)?;
+                debug!("Opened the log at {} read-only", path.display());
+                Ok((file, true))
+            }
+            Err(e) => Err(AppError::from(e)),
+        }
+    }
+
+    /// Returns an error saying the log can't be changed in the given way if it is read-only.
+    fn check_writable(&self, what: &str) -> Result<(), AppError> {
+        if !self.read_only {
+            return Ok(());
+        }
+        Err(AppError::new(ErrorKind::LogFile(format!(
+            "The log is read-only, so it can't be {}.",
+            what
+        )))
+        .with_code(ErrorCode::LogPermission))
+    }
+
+    /// Returns an error saying the log can't be changed in the given way if encryption is
+    /// configured, for changes that are saved next to the log in plain text.
+    fn check_unencrypted(&self, what: &str) -> Result<(), AppError> {
+        if self.cipher.is_none() {
+            return Ok(());
+        }
+        Err(AppError::new(ErrorKind::User(format!(
+            "The log is encrypted, so it can't be {} as that is saved next to it in plain text.",
+            what
+        )))
+        .with_code(ErrorCode::Encryption))
+    }
+
+    /// Checks whether the log on disk is encrypted.
+    fn is_encrypted(&mut self) -> Result<bool, AppError> {
+        let mut header = Vec::new();
+        self.log.seek(SeekFrom::Start(0))?;
+        (&mut self.log).take(16).read_to_end(&mut header)?;
+        Ok(Cipher::is_encrypted(&header))
+    }
+
+    /// Fetches the path of the `work.log` file of the given profile, see `profiles::dir`. If it
+    /// fails to find the directory of the profile, the function returns an error message.
+    fn log_file_path(profile: &str) -> Result<PathBuf, AppError> {
+        Ok(profiles::dir(profile)?.join("work.log"))
+    }
+
+    /// Creates the directory of the given path for the `work.log` file if it doesn't exist. If it
+    /// fails, the function exits with an error message.
+    fn create_path(path: &Path) -> Result<(), AppError> {
+        let parent = match path.parent() {
+            Some(parent) => parent,
+            None => return Ok(()),
+        };
+        match create_dir_all(parent) {
+            Err(e) => Err(AppError::new(ErrorKind::LogFile(format!(
+                "Unable to create 'work' folder: {}",
+                e
+            )))
+            .with_source(e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Helper function for the error of saving something next to a log that is kept in memory.
+fn in_memory(what: &str) -> AppError {
+    AppError::new(ErrorKind::User(format!(
+        "The log is only kept in memory, so it can't be {}.",
+        what
+    )))
+}
+
+// Helper function for summing up the time spent on each project within the given interval out of
+// the given events of the log. The events are all read before pairing them up, since a log that
+// isn't in chronological order is sorted first, see `sessions::reconstruct`. A session in progress
+// lasts until `now`.
+fn tally_events<I>(events: I, interval: &time::Interval, now: i64) -> Result<ProjectMap, AppError>
+where
+    I: Iterator<Item = Result<(i64, Event), AppError>>,
+{
+    let events = events.collect::<Result<Vec<_>, _>>()?;
+    Ok(sessions::tally(
+        sessions::reconstruct(&events),
+        interval,
+        now,
+    ))
+}
+
+// Helper function that reads the final line of the given reader, reading backwards from its end
+// in chunks of `TAIL_CHUNK` bytes until the whole line has been read.
+fn last_line<R: Read + Seek>(reader: &mut R) -> Result<Option<String>, AppError> {
+    let mut start = reader.seek(SeekFrom::End(0))?;
+    let mut tail = Vec::new();
+    // The final line is complete once a newline precedes it, a newline at the very end of the log
+    // only terminates it.
+    let line_start = |tail: &[u8]| {
+        tail[..tail.len().saturating_sub(1)]
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map(|newline| newline + 1)
+    };
+    while start > 0 && line_start(&tail).is_none() {
+        let chunk_start = start.saturating_sub(TAIL_CHUNK);
+        let mut chunk = vec![0; (start - chunk_start) as usize];
+        reader.seek(SeekFrom::Start(chunk_start))?;
+        reader.read_exact(&mut chunk)?;
+        chunk.append(&mut tail);
+        tail = chunk;
+        start = chunk_start;
+    }
+
+    let line = &tail[line_start(&tail).unwrap_or(0)..];
+    match std::str::from_utf8(line) {
+        Ok(line) => Ok(line.lines().last().map(|line| line.to_string())),
+        Err(_) => Err(AppError::new(ErrorKind::LogFile(
+            "Work log contains invalid UTF-8!".to_string(),
+        ))
+        .with_code(ErrorCode::LogCorrupt)),
+    }
+}
+
+// Helper function for parsing a single line of the log into a timestamp and an `Event`.
+fn parse_line(line: &str) -> Result<(i64, Event), AppError> {
+    // Split a line of the log file into two parts: `timestamp` and `Event`.
+    // This is done to seperate the timestamp from the rest of data.
+    let values: Vec<&str> = line.splitn(2, ',').map(|s| s.trim()).collect();
+    // A user can corrupt their own log file, which is reported rather than panicking half way
+    // through a report. `work doctor` finds the line.
+    match values[0].parse::<i64>() {
+        Ok(timestamp) => Ok((timestamp, Event::from(line))),
+        Err(_) => Err(AppError::new(ErrorKind::LogFile(format!(
+            "Work log is corrupt, this line isn't an event: {}\nRun `work doctor` to find it.",
+            line
+        )))
+        .with_code(ErrorCode::LogCorrupt)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_client_field() {
+        let event = Event::Start(
+            Some("work".to_string()),
+            None,
+            Some("acme".to_string()),
+            None,
+            Metadata::new(),
+        );
+        assert_eq!(event.to_log_line(1), "1,Start,work,,acme");
+        assert_eq!(Event::from("1,Start,work,,acme"), event);
+        assert_eq!(event.to_client(), "acme");
+        // Lines without a client are written and read just like before there were clients.
+        let event = Event::Stop(
+            None,
+            Some("Fixing bugs".to_string()),
+            None,
+            None,
+            Metadata::new(),
+        );
+        assert_eq!(event.to_log_line(2), "2,Stop,,Fixing bugs");
+        assert_eq!(Event::from("2,Stop,,Fixing bugs"), event);
+        assert_eq!(event.to_client(), NO_CLIENT_STR);
+    }
+
+    #[test]
+    fn test_metadata() {
+        let mut metadata = Metadata::new();
+        metadata.insert("machine".to_string(), "laptop".to_string());
+        metadata.insert("ticket".to_string(), "PRJ-12=a".to_string());
+        let event = Event::Start(Some("work".to_string()), None, None, Some(false), metadata);
+        let line = "1,Start,work,,,non-billable,machine=laptop,ticket=PRJ-12=a";
+        assert_eq!(event.to_log_line(1), line);
+        assert_eq!(Event::from(line), event);
+
+        // Keys this version doesn't know about survive a rewrite of the line, in order of key.
+        let line = "2,Stop,work,,,,zone=eu,future=1,";
+        assert_eq!(
+            Event::from(line).to_log_line(2),
+            "2,Stop,work,,,,future=1,zone=eu"
+        );
+        assert_eq!(
+            Event::from("3,Stop,work,,acme").metadata(),
+            &Metadata::new()
+        );
+    }
+
+    #[test]
+    fn test_record_user() {
+        let mut log = LogFile::in_memory();
+        log.append_event(&Event::from("0,Start,a,"), 0).unwrap();
+        log.append_event(&Event::from("100,Stop,a,"), 100).unwrap();
+        log.user = Some("alice".to_string());
+        log.append_event(&Event::from("200,Start,b,"), 200).unwrap();
+        log.append_event(&Event::from("300,Stop,b,,,,user=bob"), 300)
+            .unwrap();
+
+        let events = log.all_events().unwrap();
+        assert_eq!(events[2].1.to_user(), "alice");
+        assert_eq!(events[3].1.to_user(), "bob");
+        let users = log
+            .tally_users(&time::Interval::new(0, Some(1000)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(users[NO_USER_STR]["a"], 100);
+        assert_eq!(users["alice"]["b"], 100);
+    }
+
+    #[test]
+    fn test_read_only() {
+        let mut log = LogFile::in_memory();
+        let event = Event::from("1,Start,work,");
+        log.append_event(&event, 1).unwrap();
+        log.set_read_only(true);
+        log.set_read_only(false);
+        assert!(log.is_read_only());
+
+        let error = log.append_event(&event, 2).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::LogPermission);
+        assert!(log.rewrite(&[]).is_err());
+        assert_eq!(log.all_events().unwrap(), vec![(1, event.clone())]);
+
+        // A dry run doesn't change the log, so it works all the same.
+        log.set_dry_run(Some(Rc::new(|_: &Change| {})));
+        assert!(log.append_event(&event, 2).is_ok());
+    }
+
+    #[test]
+    fn test_insert_events() {
+        let mut log = LogFile::in_memory();
+        log.append_event(&Event::from("100,Start,a,"), 100).unwrap();
+        log.append_event(&Event::from("200,Stop,a,"), 200).unwrap();
+        log.append_event(&Event::from("500,Start,b,"), 500).unwrap();
+
+        // Stopping before the start, or appending anything before the last event, is refused.
+        let error = log
+            .append_event(&Event::from("400,Stop,b,"), 400)
+            .unwrap_err();
+        assert_eq!(error.code(), ErrorCode::OutOfOrder);
+        log.append_event(&Event::from("600,Stop,b,"), 600).unwrap();
+        let error = log
+            .append_event(&Event::from("300,Start,c,"), 300)
+            .unwrap_err();
+        assert_eq!(error.code(), ErrorCode::OutOfOrder);
+
+        log.insert_events(&[
+            (300, Event::from("300,Start,c,")),
+            (400, Event::from("400,Stop,c,")),
+        ])
+        .unwrap();
+        log.insert_events(&[(700, Event::from("700,Start,d,"))])
+            .unwrap();
+        let timestamps: Vec<i64> = log
+            .all_events()
+            .unwrap()
+            .into_iter()
+            .map(|(timestamp, _)| timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![100, 200, 300, 400, 500, 600, 700]);
+    }
+
+    #[test]
+    fn test_checksums() {
+        let mut log = LogFile::in_memory();
+        log.checksums = true;
+        let event = Event::from("1,Start,work,");
+        log.append_event(&event, 1).unwrap();
+        assert!(log.read().unwrap().starts_with("1,Start,work,,,,crc="));
+        assert_eq!(log.all_events().unwrap(), vec![(1, event)]);
+        assert_eq!(log.first_bad_line().unwrap(), None);
+
+        // A damaged line is reported rather than panicking.
+        log.log = Storage::Memory(Cursor::new(b"1,Start,work,\n#,Stop,work,\n".to_vec()));
+        assert!(log.all_events().is_err());
+        assert_eq!(log.first_bad_line().unwrap().unwrap().number, 2);
+    }
+
+    #[test]
+    fn test_last_line() {
+        let last = |contents: &str| last_line(&mut Cursor::new(contents.as_bytes())).unwrap();
+        assert_eq!(last(""), None);
+        assert_eq!(last("1,Start,a,\n"), Some("1,Start,a,".to_string()));
+        assert_eq!(
+            last("1,Start,a,\r\n2,Stop,a,"),
+            Some("2,Stop,a,".to_string())
+        );
+
+        // Lines spanning several chunks are read whole.
+        let long = format!("2,Start,{},\n", "á".repeat(TAIL_CHUNK as usize));
+        let log = format!("1,Stop,a,\n{}", long);
+        assert_eq!(last(&log), Some(long.trim_end().to_string()));
+    }
+
+    #[test]
+    fn test_in_memory() {
+        let start = Event::Start(Some("work".to_string()), None, None, None, Metadata::new());
+        let stop = Event::Stop(None, None, None, None, Metadata::new());
+        let mut log = LogFile::in_memory();
+        assert_eq!(log.get_latest_entry().unwrap(), None);
+        log.append_event(&start, 100).unwrap();
+        log.append_event(&stop, 200).unwrap();
+        assert_eq!(log.get_latest_entry().unwrap(), Some((200, stop.clone())));
+        // Reading the log in between doesn't change where the next event goes.
+        log.append_event(&start, 300).unwrap();
+        assert_eq!(
+            log.all_events().unwrap(),
+            vec![
+                (100, start.clone()),
+                (200, stop.clone()),
+                (300, start.clone())
+            ]
+        );
+
+        log.rewrite(&[(100, start), (200, stop)]).unwrap();
+        let projects = log.tally_time(&time::Interval::new(0, Some(1000))).unwrap();
+        assert_eq!(projects.unwrap()["work"]["No description"], 100);
+        assert_eq!(log.locks().unwrap(), Locks::default());
+        assert!(log.save_locks(&Locks::default()).is_err());
+        assert!(log.rebuild_cache().is_err());
+    }
+
+    #[test]
+    fn test_unsorted_log() {
+        let event = |line: &str| parse_line(line).unwrap();
+        // The logs of two machines pasted one after the other.
+        let mut log = LogFile::in_memory();
+        log.rewrite(&[
+            event("300,Start,b,"),
+            event("400,Stop,b,"),
+            event("100,Start,a,"),
+            event("200,Stop,a,"),
+        ])
+        .unwrap();
+        let projects = log
+            .tally_time(&time::Interval::new(0, Some(1000)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(projects["a"]["No description"], 100);
+        assert_eq!(projects["b"]["No description"], 100);
+    }
+
+    #[test]
+    fn test_from_path() {
+        let dir = std::env::temp_dir().join(format!("work-log-file-test-{}", std::process::id()));
+        let path = dir.join("work").join("work.log");
+        let start = Event::Start(Some("work".to_string()), None, None, None, Metadata::new());
+        let stop = Event::Stop(None, None, None, None, Metadata::new());
+
+        let mut log = LogFile::from_path(path.clone()).unwrap();
+        log.append_event(&start, 100).unwrap();
+        log.append_event(&stop, 200).unwrap();
+        assert!(log
+            .tally_time(&time::Interval::new(0, Some(1000)))
+            .unwrap()
+            .is_some());
+        assert!(path.with_file_name("work.cache").exists());
+        drop(log);
+
+        let mut log = LogFile::from_path(path).unwrap();
+        assert_eq!(log.all_events().unwrap(), vec![(100, start), (200, stop)]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_archive() {
+        let dir = std::env::temp_dir().join(format!("work-archived-{}", std::process::id()));
+        let year = archive::year_interval(2022).unwrap();
+        let event = |project: &str| {
+            Event::Start(Some(project.to_string()), None, None, None, Metadata::new())
+        };
+        let stop = Event::Stop(None, None, None, None, Metadata::new());
+
+        let mut log = LogFile::from_path(dir.join("work.log")).unwrap();
+        log.append_event(&event("old"), year.start + 100).unwrap();
+        log.append_event(&stop, year.start + 200).unwrap();
+        log.append_event(&event("new"), year.end + 100).unwrap();
+        log.append_event(&stop, year.end + 400).unwrap();
+        assert_eq!(log.archive(&year).unwrap(), vec![(2022, 1)]);
+        assert!(dir.join("work-2022.log.gz").exists());
+        assert_eq!(log.all_events().unwrap().len(), 2);
+
+        // The archived time is still counted, by a log opened later on as well.
+        let mut log = LogFile::from_path(dir.join("work.log")).unwrap();
+        let everything = time::Interval::new(0, Some(year.end + 1000));
+        let projects = log.tally_time(&everything).unwrap().unwrap();
+        assert_eq!(projects["old"].values().sum::<i64>(), 100);
+        assert_eq!(projects["new"].values().sum::<i64>(), 300);
+        assert_eq!(log.first_timestamp().unwrap(), Some(year.start + 100));
+        assert!(!log
+            .tally_time(&time::Interval::new(year.end, Some(year.end + 1000)))
+            .unwrap()
+            .unwrap()
+            .contains_key("old"));
+        assert_eq!(log.archive(&year).unwrap(), vec![]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_dry_run_sidecars() {
+        let dir = std::env::temp_dir().join(format!("work-dry-run-{}", std::process::id()));
+        let mut log = LogFile::from_path(dir.join("work.log")).unwrap();
+        log.set_dry_run(Some(Rc::new(|_: &Change| {})));
+
+        let mut locks = log.locks().unwrap();
+        locks.lock(time::Interval::new(0, Some(100)));
+        log.save_locks(&locks).unwrap();
+        let mut days_off = log.days_off().unwrap();
+        let day = chrono::NaiveDate::from_ymd(2026, 10, 16);
+        days_off.add(day, day, "Vacation");
+        log.save_days_off(&days_off).unwrap();
+
+        assert!(!dir.join("work.locks").exists());
+        assert!(!dir.join("work.off").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_encrypted() {
+        let dir = std::env::temp_dir().join(format!("work-encrypted-{}", std::process::id()));
+        let path = dir.join("work.log");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "100,Start,work,\n").unwrap();
+        let config = Config::parse("[encryption]\npassphrase = secret").unwrap();
+
+        // A plain log stays readable and is encrypted as soon as it is written to.
+        let mut log = LogFile::from_path(path.clone()).unwrap();
+        log.cipher = Cipher::from_config(&config).unwrap();
+        let stop = Event::Stop(Some("work".to_string()), None, None, None, Metadata::new());
+        log.append_event(&stop, 200).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        assert!(Cipher::is_encrypted(&data));
+        assert!(!String::from_utf8_lossy(&data).contains("work"));
+        assert_eq!(log.all_events().unwrap().len(), 2);
+
+        // Nothing is saved next to the log in plain text.
+        let mut locks = log.locks().unwrap();
+        locks.lock(time::Interval::new(0, Some(100)));
+        let error = log.save_locks(&locks).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::Encryption);
+        let mut days_off = log.days_off().unwrap();
+        let day = chrono::NaiveDate::from_ymd(2026, 10, 16);
+        days_off.add(day, day, "Vacation");
+        let error = log.save_days_off(&days_off).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::Encryption);
+        #[cfg(feature = "auto")]
+        assert!(matches!(log.auto_queue(), Err(error) if error.code() == ErrorCode::Encryption));
+        assert!(!dir.join("work.locks").exists());
+        assert!(!dir.join("work.off").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use notify_rust::Notification;
+
+use crate::config::Config;
+use crate::error::{AppError, ErrorKind};
+use crate::log_file::{Event, LogFile};
+use crate::time::{self, Interval};
+
+/// The `Notifier` struct shows desktop notifications about the work done.
+///
+/// Notifications are configured in the config file, a weekly budget can be set per project:
+///
+/// ```text
+/// [notifications]
+/// # Notify when `until --wait` is done, on by default.
+/// time_up = true
+/// # Notify once this much work has been done in a single day.
+/// daily_goal = 8h
+///
+/// [budgets]
+/// client-a = 20h
+/// ```
+///
+/// The daily goal and the budgets are only read once work stops, so a mistake in them doesn't get
+/// in the way of commands that don't stop work.
+#[derive(Clone, Debug, Default)]
+pub struct Notifier {
+    time_up: bool,
+    daily_goal: Option<String>,
+    budgets: HashMap<String, String>,
+}
+
+impl Notifier {
+    /// Creates a `Notifier` from the `notifications` and `budgets` sections of the config.
+    pub fn from_config(config: &Config) -> Self {
+        Notifier {
+            time_up: config.get("notifications.time_up") != Some("false"),
+            daily_goal: config.get("notifications.daily_goal").map(str::to_string),
+            budgets: config
+                .section("budgets")
+                .into_iter()
+                .map(|(project, budget)| (project.to_string(), budget.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Lets the user know that the time of work started with `until --wait` is up.
+    pub fn time_up(&self, event: &Event) -> Result<(), AppError> {
+        if self.time_up {
+            show(
+                "Time is up!",
+                &format!("Stopped working on {}.", event.to_string()),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Reminds the user to start tracking with the given message, see `Reminder`.
+    pub fn remind(&self, message: &str) -> Result<(), AppError> {
+        show("Time to start tracking", message)
+    }
+
+    /// Lets the user know if the work that just stopped, having lasted `duration` seconds, met
+    /// the daily goal or went over the weekly budget of its project. There is no daily goal on
+    /// days off.
+    pub fn worked(&self, log: &mut LogFile, event: &Event, duration: i64) -> Result<(), AppError> {
+        let day_off = log.days_off()?.get(log.clock().today()).is_some();
+        if let (Some(goal), false) = (self.daily_goal()?, day_off) {
+            let today = total(log, "today", None)?;
+            if crossed(today, duration, goal) {
+                show(
+                    "Daily goal met!",
+                    &format!("You have worked for {} today.", time::format_elapsed(today)),
+                )?;
+            }
+        }
+
+        let project = event.to_project();
+        if let Some(budget) = self.budget(&project)? {
+            let week = total(log, "this-week", Some(&project))?;
+            if crossed(week, duration, budget) {
+                show(
+                    "Over budget!",
+                    &format!(
+                        "{} hours have been spent on {} this week, the budget is {} hours.",
+                        time::format_elapsed(week),
+                        project,
+                        time::format_elapsed(budget)
+                    ),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    // Returns the number of seconds of work a day should have, if there is a daily goal.
+    fn daily_goal(&self) -> Result<Option<i64>, AppError> {
+        self.daily_goal.as_deref().map(duration).transpose()
+    }
+
+    // Returns the number of seconds of work the given project can take each week, if it has a
+    // budget.
+    fn budget(&self, project: &str) -> Result<Option<i64>, AppError> {
+        self.budgets
+            .get(project)
+            .map(|budget| duration(budget))
+            .transpose()
+    }
+}
+
+// Helper function that sums up the time spent within the given interval, on a single project if
+// one is given.
+fn total(log: &mut LogFile, interval: &str, project: Option<&str>) -> Result<i64, AppError> {
+    let interval = Interval::try_parse(interval, &time::Search::Backward, log.clock())?;
+    let projects = log.tally_time(&interval)?.unwrap_or_default();
+    Ok(projects
+        .iter()
+        .filter(|(name, _)| project.is_none() || project == Some(name.as_str()))
+        .flat_map(|(_, descriptions)| descriptions.values())
+        .sum())
+}
+
+// Returns true if the last `duration` seconds of the `total` took it to or over the `limit`.
+fn crossed(total: i64, duration: i64, limit: i64) -> bool {
+    total >= limit && total - duration < limit
+}
+
+// Helper function for parsing the durations found in the config.
+fn duration(input: &str) -> Result<i64, AppError> {
+    time::parse_duration(input).ok_or_else(|| {
+        AppError::new(ErrorKind::Config(format!(
+            "Invalid duration in config file: {}",
+            input
+        )))
+    })
+}
+
+// Shows a desktop notification. Failing to do so only warrants a warning, as the log has already
+// been changed, see `Hooks`.
+fn show(summary: &str, body: &str) -> Result<(), AppError> {
+    Notification::new()
+        .appname("work")
+        .summary(summary)
+        .body(body)
+        .show()
+        .map(|_| ())
+        .map_err(|e| {
+            AppError::new(ErrorKind::System(format!(
+                "Unable to show a notification: {}",
+                e
+            )))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossed() {
+        assert!(crossed(8 * 3600, 3600, 8 * 3600));
+        assert!(crossed(9 * 3600, 2 * 3600, 8 * 3600));
+        // The limit was already reached before.
+        assert!(!crossed(10 * 3600, 3600, 8 * 3600));
+        assert!(!crossed(7 * 3600, 3600, 8 * 3600));
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = Config::parse(
+            "[notifications]\ntime_up = false\ndaily_goal = 7:30h\n[budgets]\na = 20h",
+        )
+        .unwrap();
+        let notifier = Notifier::from_config(&config);
+        assert!(!notifier.time_up);
+        assert_eq!(notifier.daily_goal().unwrap(), Some(7 * 3600 + 1800));
+        assert_eq!(notifier.budget("a").unwrap(), Some(20 * 3600));
+        assert_eq!(notifier.budget("b").unwrap(), None);
+
+        // A mistake in a budget only matters to the project it belongs to.
+        let config = Config::parse("[budgets]\na = lots").unwrap();
+        let notifier = Notifier::from_config(&config);
+        assert!(notifier.budget("a").is_err());
+        assert_eq!(notifier.budget("b").unwrap(), None);
+    }
+}
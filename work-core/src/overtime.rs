@@ -0,0 +1,179 @@
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::arguments::TimeFormat;
+use crate::error::AppError;
+use crate::highlight::Highlight;
+use crate::log_file::LogFile;
+use crate::time::{self, format_delta, format_time, Interval};
+
+/// Format of the first day of each week.
+const WEEK_FORMAT: &str = "%a %d-%m-%Y";
+
+/// Number of work days in a week, the contracted hours are spread evenly over Monday to Friday.
+const WORK_DAYS: i64 = 5;
+
+/// A single week of the `Overtime` struct.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Week {
+    /// The Monday that starts the week.
+    pub monday: NaiveDate,
+    /// The time worked within the week.
+    pub worked: i64,
+    /// The time that was expected to be worked within the week according to the contract.
+    pub expected: i64,
+}
+
+impl Week {
+    /// Returns the time worked over the expected time, negative if less was worked.
+    pub fn balance(&self) -> i64 {
+        self.worked - self.expected
+    }
+}
+
+/// The `Overtime` struct holds the time worked in each week of an interval next to the contracted
+/// time, see `work overtime`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Overtime {
+    /// The weeks of the interval, earliest week first.
+    pub weeks: Vec<Week>,
+}
+
+impl Overtime {
+    /// Compares the time worked in each week of the given interval to the `weekly` contracted time.
+    /// The contracted time is spread evenly over the work days of each week, so weeks cut short by
+    /// the interval and days off are expected to be worked less. An interval that reaches back
+    /// before the log starts is shortened to start with the log, and one that reaches into the
+    /// future is shortened to end today.
+    pub fn new(log: &mut LogFile, interval: &Interval, weekly: i64) -> Result<Self, AppError> {
+        let end = interval.end.min(log.now());
+        let start = match log.events()?.next() {
+            Some(entry) => interval.start.max(entry?.0).min(end),
+            None => return Ok(Overtime { weeks: Vec::new() }),
+        };
+        let first_day = time::date_of(start);
+        let last_day = time::date_of((end - 1).max(start));
+        let days_off = log.days_off()?;
+
+        let mut weeks = Vec::new();
+        let mut monday =
+            first_day - Duration::days(i64::from(first_day.weekday().num_days_from_monday()));
+        while monday <= last_day {
+            let whole_week = Interval::new(
+                time::start_of_day(monday),
+                Some(time::start_of_day(monday + Duration::weeks(1))),
+            );
+            let worked = match whole_week.intersect(&Interval::new(start, Some(end))) {
+                Some(part) => log.tally_time(&part)?.map_or(0, |projects| {
+                    projects.values().flat_map(|descs| descs.values()).sum()
+                }),
+                None => 0,
+            };
+            let work_days = (0..7)
+                .map(|day| monday + Duration::days(day))
+                .filter(|day| first_day <= *day && *day <= last_day)
+                .filter(|day| days_off.is_work_day(*day))
+                .count() as i64;
+            weeks.push(Week {
+                monday,
+                worked,
+                expected: weekly * work_days / WORK_DAYS,
+            });
+            monday += Duration::weeks(1);
+        }
+        Ok(Overtime { weeks })
+    }
+
+    /// Returns the time worked over the expected time over all of the weeks, negative if less was
+    /// worked.
+    pub fn balance(&self) -> i64 {
+        self.weeks.iter().map(Week::balance).sum()
+    }
+
+    /// Returns the overtime as a line per week with the time worked, the time expected, the
+    /// balance of the week and the balance so far, followed by the overall balance.
+    pub fn as_text(&self, time_format: &TimeFormat, highlight: &dyn Highlight) -> String {
+        let mut rows = vec![[
+            "Week".to_string(),
+            "Worked".to_string(),
+            "Expected".to_string(),
+            "Balance".to_string(),
+            "Total".to_string(),
+        ]];
+        let mut total = 0;
+        for week in &self.weeks {
+            total += week.balance();
+            rows.push([
+                week.monday.format(WEEK_FORMAT).to_string(),
+                format_time(time_format, week.worked),
+                format_time(time_format, week.expected),
+                format_delta(time_format, week.balance()),
+                format_delta(time_format, total),
+            ]);
+        }
+        let widths: Vec<usize> = (0..4)
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut text = String::new();
+        for row in &rows {
+            text.push_str(&format!(
+                "{:<w0$}  {:<w1$}  {:<w2$}  {:<w3$}  {}\n",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                row[4],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3]
+            ));
+        }
+        let balance = self.balance();
+        let label = if balance < 0 { "Undertime" } else { "Overtime" };
+        text.push_str(&format!(
+            "{}: {}",
+            label,
+            highlight.duration(&format_time(time_format, balance.abs()))
+        ));
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::highlight::Plain;
+
+    #[test]
+    fn test_as_text() {
+        let monday = NaiveDate::from_ymd(2026, 9, 7);
+        let overtime = Overtime {
+            weeks: vec![
+                Week {
+                    monday,
+                    worked: 42 * 3600,
+                    expected: 40 * 3600,
+                },
+                Week {
+                    monday: monday + Duration::weeks(1),
+                    worked: 27 * 3600,
+                    expected: 32 * 3600,
+                },
+            ],
+        };
+        assert_eq!(overtime.balance(), -3 * 3600);
+        assert_eq!(
+            overtime.as_text(&TimeFormat::HoursApprox, &Plain),
+            "Week            Worked  Expected  Balance  Total\n\
+             Mon 07-09-2026  42      40        +2       +2\n\
+             Mon 14-09-2026  27      32        -5       -3\n\
+             Undertime: 3"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::error::{AppError, ErrorCode, ErrorKind};
+use crate::log_file::Event;
+
+/// Slack API method that updates the profile, and with it the status, of the user.
+const SLACK_PROFILE_URL: &str = "https://slack.com/api/users.profile.set";
+
+/// Emoji shown next to the status when none is configured.
+const DEFAULT_EMOJI: &str = ":computer:";
+
+/// The `Presence` struct keeps the Slack status of the user in line with the log, showing
+/// "Working on [PROJECT_NAME]" while work is in progress and clearing the status once it stops.
+///
+/// It is configured in the `[presence]` section of the config file with a Slack user token that
+/// has the `users.profile:write` scope, and optionally the emoji to show:
+///
+/// ```text
+/// [presence]
+/// slack_token = xoxp-...
+/// emoji = :hammer:
+/// ```
+#[derive(Clone, Debug)]
+pub struct Presence {
+    token: String,
+    emoji: String,
+}
+
+impl Presence {
+    /// Creates a `Presence` from the `presence.slack_token` and `presence.emoji` config values.
+    /// Returns `None` if no token is configured.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let token = config.get("presence.slack_token")?;
+        Some(Presence {
+            token: token.to_string(),
+            emoji: config
+                .get("presence.emoji")
+                .unwrap_or(DEFAULT_EMOJI)
+                .to_string(),
+        })
+    }
+
+    /// Sets the status to the work started by the given `start` event.
+    pub fn working(&self, event: &Event) -> Result<(), AppError> {
+        self.set_status(&format!("Working on {}", event.to_project()), &self.emoji)
+    }
+
+    /// Clears the status.
+    pub fn free(&self) -> Result<(), AppError> {
+        self.set_status("", "")
+    }
+
+    // Sends the given status to Slack. Slack answers failed requests with `ok` set to false and
+    // the reason in `error`.
+    fn set_status(&self, text: &str, emoji: &str) -> Result<(), AppError> {
+        let body = json!({
+            "profile": {
+                "status_text": text,
+                "status_emoji": emoji,
+                "status_expiration": 0,
+            }
+        });
+        let response = ureq::post(SLACK_PROFILE_URL)
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Content-Type", "application/json; charset=utf-8")
+            .send_string(&body.to_string())
+            .map_err(|e| presence_error(&e.to_string()))?
+            .into_string()
+            .map_err(|e| presence_error(&e.to_string()))?;
+
+        let response: Value =
+            serde_json::from_str(&response).map_err(|e| presence_error(&e.to_string()))?;
+        if response["ok"].as_bool() == Some(true) {
+            Ok(())
+        } else {
+            Err(presence_error(
+                response["error"].as_str().unwrap_or("unknown error"),
+            ))
+        }
+    }
+}
+
+// Helper function for building the error returned when the status can't be updated.
+fn presence_error(reason: &str) -> AppError {
+    AppError::new(ErrorKind::System(format!(
+        "Unable to update Slack status: {}",
+        reason
+    )))
+    .with_code(ErrorCode::Network)
+}
@@ -0,0 +1,141 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::config;
+use crate::error::{AppError, ErrorCode, ErrorKind};
+use crate::fuzzy;
+
+/// Name of the profile that lives in the data directory itself, used if no other is chosen.
+pub const DEFAULT: &str = "default";
+
+/// Name of the file in the data directory that holds the profile chosen with `profile switch`.
+const CURRENT_FILE: &str = "work.profile";
+
+/// Name of the directory within the data directory that holds a directory for every profile
+/// besides the default one.
+const PROFILES_DIR: &str = "profiles";
+
+/// Returns the name of the profile in use. That is `WORK_PROFILE` if it is set, which is what
+/// `--profile` sets as well, or otherwise the profile chosen with `work profile switch`, or the
+/// default profile if none was chosen.
+pub fn current() -> Result<String, AppError> {
+    if let Some(name) = env::var("WORK_PROFILE")
+        .ok()
+        .filter(|name| !name.is_empty())
+    {
+        return Ok(name);
+    }
+    let path = config::data_dir()?.join(CURRENT_FILE);
+    match fs::read_to_string(&path) {
+        Ok(name) if !name.trim().is_empty() => Ok(name.trim().to_string()),
+        Ok(_) => Ok(DEFAULT.to_string()),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(DEFAULT.to_string()),
+        Err(e) => Err(AppError::new(ErrorKind::Config(format!(
+            "Unable to read the current profile from {}: {}",
+            path.display(),
+            e
+        )))
+        .with_source(e)),
+    }
+}
+
+/// Returns the directory holding the log of the given profile, which is the data directory
+/// itself for the default profile. If the profile doesn't exist, the function returns an error
+/// message suggesting the closest profile that does.
+pub fn dir(name: &str) -> Result<PathBuf, AppError> {
+    if name == DEFAULT {
+        return config::data_dir();
+    }
+    let dir = config::data_dir()?.join(PROFILES_DIR).join(name);
+    if valid_name(name) && dir.is_dir() {
+        return Ok(dir);
+    }
+
+    let profiles = list()?;
+    let suggestion = match fuzzy::closest(name, profiles.iter().map(String::as_str)) {
+        Some(closest) => format!(" Did you mean {}?", closest),
+        None => String::new(),
+    };
+    Err(AppError::new(ErrorKind::User(format!(
+        "Unknown profile: {}.{} Create it with `work profile create {}`.",
+        name, suggestion, name
+    )))
+    .with_code(ErrorCode::InvalidInput))
+}
+
+/// Returns the names of all profiles, the default profile first and the others sorted by name.
+pub fn list() -> Result<Vec<String>, AppError> {
+    let mut profiles = Vec::new();
+    match fs::read_dir(config::data_dir()?.join(PROFILES_DIR)) {
+        Ok(entries) => {
+            for entry in entries {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().to_string();
+                if entry.file_type()?.is_dir() && valid_name(&name) && name != DEFAULT {
+                    profiles.push(name);
+                }
+            }
+        }
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => (),
+        Err(e) => return Err(e.into()),
+    }
+    profiles.sort();
+    profiles.insert(0, DEFAULT.to_string());
+    Ok(profiles)
+}
+
+/// Creates a new profile with the given name, which may only consist of letters, digits, `-` and
+/// `_`. If the profile already exists, the function returns an error message.
+pub fn create(name: &str) -> Result<PathBuf, AppError> {
+    if !valid_name(name) {
+        return Err(AppError::new(ErrorKind::User(format!(
+            "Invalid profile name: {}, use only letters, digits, - and _.",
+            name
+        )))
+        .with_code(ErrorCode::InvalidInput));
+    }
+    let dir = config::data_dir()?.join(PROFILES_DIR).join(name);
+    if name == DEFAULT || dir.exists() {
+        return Err(AppError::new(ErrorKind::User(format!(
+            "The profile {} already exists.",
+            name
+        )))
+        .with_code(ErrorCode::InvalidInput));
+    }
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Makes the given profile the one used when neither `--profile` nor `WORK_PROFILE` is given.
+pub fn switch(name: &str) -> Result<(), AppError> {
+    dir(name)?;
+    let data_dir = config::data_dir()?;
+    fs::create_dir_all(&data_dir)?;
+    fs::write(data_dir.join(CURRENT_FILE), format!("{}\n", name))?;
+    Ok(())
+}
+
+// Helper function that checks whether the given name can be used as the name of a profile, which
+// keeps profile names from reaching outside the profiles directory.
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_name() {
+        assert!(valid_name("personal"));
+        assert!(valid_name("client-a_2"));
+        assert!(!valid_name(""));
+        assert!(!valid_name("../work"));
+        assert!(!valid_name("a b"));
+    }
+}
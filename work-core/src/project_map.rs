@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use serde_json::{self, json, Value};
+
+use crate::arguments::TimeFormat;
+use crate::csv_output::{self, CsvOptions};
+use crate::highlight::Highlight;
+use crate::log_file::{normalize_project, Event};
+use crate::time::format_time;
+
+/// Least number of characters in the bar of the project with the most time spent on it, no matter
+/// how narrow the terminal is.
+const MIN_BAR_WIDTH: usize = 10;
+
+/// Character that bars are drawn with.
+const BAR: &str = "█";
+
+/// Label of the total time spent on all projects.
+const TOTAL_STR: &str = "Total";
+
+/// ProjectMap maps projects to descriptions which in turn is mapped to total spent time.
+///
+/// A project is mapped to a map which maps descriptions to the total time spent on a given project
+/// with a given description.
+pub type ProjectMap = HashMap<String, HashMap<String, i64>>;
+
+pub trait ProjectMapMethods {
+    // Functions for insertion.
+    fn add_event(&mut self, time: &i64, event: &Event);
+    fn add_time(&mut self, project: &str, description: &str, time: i64);
+    fn merge(&mut self, other: &ProjectMap);
+    fn normalized(&self) -> ProjectMap;
+
+    // Functions for output.
+    fn as_csv(&self, time_format: &TimeFormat, options: &CsvOptions) -> String;
+    fn as_json(&self, time_format: &TimeFormat, raw_seconds: bool) -> String;
+    fn as_bars(
+        &self,
+        time_format: &TimeFormat,
+        columns: usize,
+        highlight: &dyn Highlight,
+    ) -> String;
+}
+
+impl ProjectMapMethods for ProjectMap {
+    /// Adds a singular event and the time spent on it to the ProjectMap.
+    fn add_event(&mut self, time: &i64, event: &Event) {
+        self.add_time(&event.to_project(), &event.to_description(), *time);
+    }
+
+    /// Adds time spent on a given project with a given description to the ProjectMap.
+    fn add_time(&mut self, project: &str, description: &str, time: i64) {
+        *self
+            .entry(project.to_string())
+            .or_default()
+            .entry(description.to_string())
+            .or_insert(0) += time;
+    }
+
+    /// Adds all the time found in another ProjectMap to this one.
+    fn merge(&mut self, other: &ProjectMap) {
+        for (project, descs) in other {
+            for (desc, time) in descs {
+                self.add_time(project, desc, *time);
+            }
+        }
+    }
+
+    /// Returns a copy of the ProjectMap with the project names normalized, adding up the time of
+    /// projects that only differ in case or surrounding whitespace.
+    fn normalized(&self) -> ProjectMap {
+        let mut normalized = ProjectMap::new();
+        for (project, descs) in self {
+            for (desc, time) in descs {
+                normalized.add_time(&normalize_project(project), desc, *time);
+            }
+        }
+        normalized
+    }
+
+    /// Returns a CSV format of the ProjectMap as a string.
+    fn as_csv(&self, time_format: &TimeFormat, options: &CsvOptions) -> String {
+        let rows = self.iter().flat_map(|(project, descs)| {
+            descs.iter().map(move |(desc, time)| {
+                vec![
+                    project.to_string(),
+                    desc.to_string(),
+                    format_time(time_format, *time),
+                ]
+            })
+        });
+        csv_output::write(&["Project", "Description", "Time Spent"], rows, options)
+    }
+
+    /// Returns a JSON format of the ProjectMap as a string, see `duration_json` for how the time
+    /// spent on each description is given.
+    fn as_json(&self, time_format: &TimeFormat, raw_seconds: bool) -> String {
+        // This is incredibly dirty code, I know. I just can't be bothered with implementing a
+        // custom serde serializer right now and this works ok.
+        let mut tmp_map = HashMap::new();
+        for (project, descs) in self {
+            let mut tmp_descs = HashMap::new();
+            for (desc, time) in descs {
+                tmp_descs.insert(desc, duration_json(time_format, *time, raw_seconds));
+            }
+            tmp_map.insert(project, tmp_descs);
+        }
+        serde_json::to_string_pretty(&tmp_map).unwrap()
+    }
+
+    /// Returns the ProjectMap as a bar per project, largest project first, followed by the total
+    /// time spent on all projects. The bars are scaled so that each line fits within the given
+    /// number of columns.
+    fn as_bars(
+        &self,
+        time_format: &TimeFormat,
+        columns: usize,
+        highlight: &dyn Highlight,
+    ) -> String {
+        let mut totals: Vec<(&String, i64)> = self
+            .iter()
+            .map(|(project, descs)| (project, descs.values().sum()))
+            .collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        let total = totals.iter().map(|(_, time)| time).sum();
+
+        let longest = totals
+            .iter()
+            .map(|(project, _)| project.chars().count())
+            .max();
+        let width = longest.unwrap_or(0).max(TOTAL_STR.len());
+        let time_width = totals
+            .iter()
+            .map(|(_, time)| format_time(time_format, *time).len())
+            .max()
+            .unwrap_or(0)
+            .max(format_time(time_format, total).len());
+        let bar_width = bar_width(columns, width, time_width);
+        let max = totals.first().map_or(0, |(_, time)| *time);
+
+        let mut bars = String::new();
+        for (project, time) in &totals {
+            bars.push_str(&format!(
+                "{}  {:<bar_width$}  {}\n",
+                highlight.project(&format!("{:<width$}", project, width = width)),
+                bar(*time, max, bar_width),
+                highlight.duration(&format_time(time_format, *time)),
+                bar_width = bar_width
+            ));
+        }
+        bars.push_str(&format!(
+            "{:<width$}  {:<bar_width$}  {}",
+            TOTAL_STR,
+            "",
+            highlight.duration(&format_time(time_format, total)),
+            width = width,
+            bar_width = bar_width
+        ));
+        bars
+    }
+}
+
+/// Returns the number of characters the longest bar of a chart can take for each line to fit
+/// within the given number of columns, next to labels and times of the given widths. The bars are
+/// never narrower than `MIN_BAR_WIDTH`, no matter how narrow the terminal is.
+pub(crate) fn bar_width(columns: usize, label_width: usize, time_width: usize) -> usize {
+    columns
+        .saturating_sub(label_width + time_width + 4)
+        .max(MIN_BAR_WIDTH)
+}
+
+/// Returns the bar of `time` within a chart in which the bar of `max` is `width` characters long.
+///
+/// # Examples
+/// ```
+/// # use work_core::project_map::bar;
+/// assert_eq!(bar(3600, 7200, 10), "█████");
+/// // Any time at all gets at least a sliver of a bar.
+/// assert_eq!(bar(1, 7200, 10), "█");
+/// assert_eq!(bar(0, 0, 10), "");
+/// ```
+pub fn bar(time: i64, max: i64, width: usize) -> String {
+    let length = ((time * width as i64) / max.max(1)).max(if time > 0 { 1 } else { 0 });
+    BAR.repeat(length as usize)
+}
+
+/// Returns a duration in seconds as JSON, an object holding both the number of seconds and the
+/// duration formatted for humans, or the plain number of seconds if `raw_seconds` is set.
+///
+/// # Examples
+/// ```
+/// # use serde_json::json;
+/// # use work_core::arguments::TimeFormat;
+/// # use work_core::project_map::duration_json;
+/// assert_eq!(
+///     duration_json(&TimeFormat::HumanReadable, 9300, false),
+///     json!({ "seconds": 9300, "formatted": "2 hours and 35 minutes" })
+/// );
+/// assert_eq!(duration_json(&TimeFormat::HumanReadable, 9300, true), json!(9300));
+/// ```
+pub fn duration_json(time_format: &TimeFormat, seconds: i64, raw_seconds: bool) -> Value {
+    if raw_seconds {
+        json!(seconds)
+    } else {
+        json!({ "seconds": seconds, "formatted": format_time(time_format, seconds) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::highlight::Plain;
+
+    #[test]
+    fn test_as_bars_fits_columns() {
+        let mut map = ProjectMap::new();
+        map.add_time("work", "No description", 3 * 3600);
+        map.add_time("play", "No description", 3600);
+        let bars = map.as_bars(&TimeFormat::Minutes, 40, &Plain);
+        let lines: Vec<&str> = bars.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.chars().count() <= 40));
+        assert!(lines[0].starts_with("work"));
+        assert!(lines[2].starts_with(TOTAL_STR));
+        let bar_length = |line: &str| line.matches(BAR).count();
+        assert_eq!(bar_length(lines[0]) / 3, bar_length(lines[1]));
+    }
+
+    #[test]
+    fn test_as_csv_quotes_fields() {
+        let mut map = ProjectMap::new();
+        map.add_time("work", "Bugs, \"mostly\"", 3600);
+        assert_eq!(
+            map.as_csv(&TimeFormat::Minutes, &CsvOptions::default()),
+            "Project,Description,Time Spent\nwork,\"Bugs, \"\"mostly\"\"\",60\n"
+        );
+    }
+
+    #[test]
+    fn test_as_json() {
+        let mut map = ProjectMap::new();
+        map.add_time("work", "Bugs", 3600);
+        let json: Value = serde_json::from_str(&map.as_json(&TimeFormat::Minutes, false)).unwrap();
+        assert_eq!(
+            json,
+            json!({ "work": { "Bugs": { "seconds": 3600, "formatted": "60" } } })
+        );
+        let json: Value = serde_json::from_str(&map.as_json(&TimeFormat::Minutes, true)).unwrap();
+        assert_eq!(json, json!({ "work": { "Bugs": 3600 } }));
+    }
+
+    #[test]
+    fn test_normalized() {
+        let mut map = ProjectMap::new();
+        map.add_time("Backend", "No description", 60);
+        map.add_time("backend ", "No description", 30);
+        map.add_time("backend", "Bugs", 10);
+        let normalized = map.normalized();
+
+        assert_eq!(normalized.len(), 1);
+        assert_eq!(normalized["backend"]["No description"], 90);
+        assert_eq!(normalized["backend"]["Bugs"], 10);
+    }
+}
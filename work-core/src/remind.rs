@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Timelike, Weekday};
+
+use crate::service::{Schedule, Service};
+
+/// Name of the systemd units that run the reminder, see `Reminder::service`.
+pub const UNIT: &str = "work-remind";
+
+/// The `Reminder` struct describes when to remind the user to start tracking, which is when no work
+/// has been started by a given time of day. Work doesn't keep running to tell when that is, it is
+/// checked by running `work remind` at that time from cron or a systemd timer, see `crontab_line`
+/// and `service`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reminder {
+    /// Time of day by which work should have been started.
+    pub at: NaiveTime,
+    /// Only remind on Monday to Friday.
+    pub weekdays: bool,
+}
+
+impl Reminder {
+    /// Returns true if a reminder is due at the given local time, provided no work has been
+    /// started yet that day. It isn't due before the time of the reminder, on days off, or on
+    /// weekends if `weekdays` is set.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chrono::{NaiveDate, NaiveTime};
+    /// # use work_core::remind::Reminder;
+    /// let reminder = Reminder { at: NaiveTime::from_hms(9, 0, 0), weekdays: true };
+    /// let friday = NaiveDate::from_ymd(2026, 10, 16);
+    /// assert!(reminder.is_due(friday.and_hms(9, 30, 0), false));
+    /// assert!(!reminder.is_due(friday.and_hms(8, 30, 0), false));
+    /// assert!(!reminder.is_due(friday.and_hms(9, 30, 0), true));
+    /// assert!(!reminder.is_due(friday.succ().and_hms(9, 30, 0), false));
+    /// ```
+    pub fn is_due(&self, now: NaiveDateTime, day_off: bool) -> bool {
+        let weekend = matches!(now.weekday(), Weekday::Sat | Weekday::Sun);
+        now.time() >= self.at && !day_off && !(self.weekdays && weekend)
+    }
+
+    /// Returns the message to remind the user with.
+    pub fn message(&self) -> String {
+        format!(
+            "It's past {} and no work has been started today, don't forget to track it!",
+            self.at.format("%H:%M")
+        )
+    }
+
+    /// Returns the arguments of the `remind` command that checks this reminder.
+    pub fn arguments(&self) -> Vec<String> {
+        let mut arguments = vec![
+            "remind".to_string(),
+            "--at".to_string(),
+            self.at.format("%H:%M").to_string(),
+        ];
+        if self.weekdays {
+            arguments.push("--weekdays".to_string());
+        }
+        arguments
+    }
+
+    /// Returns a line for a crontab that runs the given executable of Work at the time of the
+    /// reminder.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chrono::NaiveTime;
+    /// # use std::path::Path;
+    /// # use work_core::remind::Reminder;
+    /// let reminder = Reminder { at: NaiveTime::from_hms(9, 15, 0), weekdays: true };
+    /// assert_eq!(
+    ///     reminder.crontab_line(Path::new("/usr/bin/work")),
+    ///     "15 9 * * 1-5 /usr/bin/work remind --at 09:15 --weekdays"
+    /// );
+    /// ```
+    pub fn crontab_line(&self, executable: &Path) -> String {
+        format!(
+            "{} {} * * {} {} {}",
+            self.at.minute(),
+            self.at.hour(),
+            if self.weekdays { "1-5" } else { "*" },
+            executable.display(),
+            self.arguments().join(" ")
+        )
+    }
+
+    /// Returns the service that runs the reminder at its time with the given environment, see
+    /// `service::environment`.
+    pub fn service(&self, environment: Vec<(String, String)>) -> Service {
+        Service {
+            name: UNIT.to_string(),
+            description: "Remind to start tracking work".to_string(),
+            arguments: self.arguments(),
+            schedule: Some(Schedule {
+                at: self.at,
+                weekdays: self.weekdays,
+            }),
+            environment,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service() {
+        let reminder = Reminder {
+            at: NaiveTime::from_hms(9, 0, 0),
+            weekdays: false,
+        };
+        let service = reminder.service(Vec::new());
+        assert_eq!(service.arguments, vec!["remind", "--at", "09:00"]);
+        assert_eq!(
+            service.schedule,
+            Some(Schedule {
+                at: NaiveTime::from_hms(9, 0, 0),
+                weekdays: false
+            })
+        );
+    }
+}
@@ -0,0 +1,669 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+
+use crate::arguments::TimeFormat;
+use crate::days_off;
+use crate::error::AppError;
+use crate::highlight::{Highlight, Plain};
+use crate::log_file::LogFile;
+use crate::project_map::{self, bar, duration_json, ProjectMap, ProjectMapMethods};
+use crate::rounding::Rounding;
+use crate::time::{self, format_time, Interval};
+
+/// Format of the dates of a report.
+const DATE_FORMAT: &str = "%a %d-%m-%Y";
+
+/// Format of the days in the JSON value of a report.
+const VALUE_FORMAT: &str = "%Y-%m-%d";
+
+/// Format of the days in the columns of the HTML table of a report.
+const COLUMN_FORMAT: &str = "%a %d-%m";
+
+/// Label of the total time spent on all projects.
+const TOTAL_STR: &str = "Total";
+
+/// Separates the plain text and HTML parts of a report formatted as an email.
+const BOUNDARY: &str = "work-report-boundary";
+
+/// The `Report` struct holds the time spent on each project within an interval, broken down by day,
+/// such as the weekly timesheets some teams require.
+#[derive(Clone, Debug)]
+pub struct Report {
+    /// First day of the report.
+    pub first_day: NaiveDate,
+    /// Last day of the report.
+    pub last_day: NaiveDate,
+    /// The time spent on each project per day. Days without any work are left out.
+    pub days: BTreeMap<NaiveDate, ProjectMap>,
+    /// The time spent on work that was marked as non-billable.
+    pub non_billable: i64,
+    /// The days off within the report along with their reasons, see `DaysOff`.
+    pub days_off: BTreeMap<NaiveDate, String>,
+    /// Whether the users take the place of the projects and the projects the place of the
+    /// descriptions, see `by_user`.
+    pub by_user: bool,
+}
+
+impl Report {
+    /// Tallies the time spent on each project on every day of the given interval. An interval that
+    /// reaches back before the log starts is shortened to start with the log, or with its earliest
+    /// archive. Days off within the interval are noted as well.
+    pub fn new(log: &mut LogFile, interval: &Interval) -> Result<Self, AppError> {
+        Self::tally(log, interval, false, None)
+    }
+
+    /// Tallies the time spent on each project on every day of the given interval just like `new`,
+    /// but rounds the time of each session up first, see `Rounding`. A session that runs past
+    /// midnight is rounded on each of its days.
+    pub fn rounded(
+        log: &mut LogFile,
+        interval: &Interval,
+        rounding: &Rounding,
+    ) -> Result<Self, AppError> {
+        Self::tally(log, interval, false, Some(rounding))
+    }
+
+    /// Tallies the time spent by each user on each project on every day of the given interval,
+    /// just like `new` does for projects, see `LogFile::tally_users`.
+    pub fn by_user(log: &mut LogFile, interval: &Interval) -> Result<Self, AppError> {
+        Self::tally(log, interval, true, None)
+    }
+
+    /// Tallies the time spent by each user on each project on every day of the given interval
+    /// just like `by_user`, but rounds the time of each session up first like `rounded` does.
+    pub fn rounded_by_user(
+        log: &mut LogFile,
+        interval: &Interval,
+        rounding: &Rounding,
+    ) -> Result<Self, AppError> {
+        Self::tally(log, interval, true, Some(rounding))
+    }
+
+    // Tallies the report for `new`, `rounded`, `by_user` and `rounded_by_user`.
+    fn tally(
+        log: &mut LogFile,
+        interval: &Interval,
+        by_user: bool,
+        rounding: Option<&Rounding>,
+    ) -> Result<Self, AppError> {
+        let start = match log.first_timestamp()? {
+            Some(first) => interval.start.max(first).min(interval.end),
+            None => interval.start,
+        };
+        let first_day = time::date_of(start);
+        let last_day = time::date_of((interval.end - 1).max(start));
+
+        let mut days = BTreeMap::new();
+        let mut rounded_non_billable = 0;
+        let mut day = first_day;
+        while day <= last_day {
+            let whole_day = Interval::new(
+                time::start_of_day(day),
+                Some(time::start_of_day(day.succ())),
+            );
+            if let Some(part) = whole_day.intersect(interval) {
+                let projects = match rounding {
+                    Some(rounding) => {
+                        let (projects, non_billable) = if by_user {
+                            log.tally_users_rounded(&part, rounding)?
+                        } else {
+                            log.tally_rounded(&part, rounding)?
+                        };
+                        rounded_non_billable += non_billable;
+                        projects
+                    }
+                    None if by_user => log.tally_users(&part)?,
+                    None => log.tally_time(&part)?,
+                };
+                if let Some(projects) = projects {
+                    days.insert(day, projects);
+                }
+            }
+            day = day.succ();
+        }
+        let non_billable = match rounding {
+            Some(_) => rounded_non_billable,
+            None => log.tally_billable(interval)?.1,
+        };
+
+        Ok(Report {
+            first_day,
+            last_day,
+            days,
+            non_billable,
+            days_off: log.days_off()?.within(first_day, last_day),
+            by_user,
+        })
+    }
+
+    /// Adds the work done and the days off of another report to this one, such as the report of
+    /// another profile over the same interval.
+    pub fn merge(&mut self, other: Report) {
+        self.first_day = self.first_day.min(other.first_day);
+        self.last_day = self.last_day.max(other.last_day);
+        for (day, projects) in other.days {
+            self.days.entry(day).or_default().merge(&projects);
+        }
+        self.non_billable += other.non_billable;
+        for (day, reason) in other.days_off {
+            self.days_off.entry(day).or_insert(reason);
+        }
+    }
+
+    /// Returns true if no work was done within the report, nor were any days off.
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty() && self.days_off.is_empty()
+    }
+
+    /// Returns the time spent on each project over the whole report.
+    pub fn projects(&self) -> ProjectMap {
+        let mut projects = ProjectMap::new();
+        for day in self.days.values() {
+            projects.merge(day);
+        }
+        projects
+    }
+
+    /// Returns the title of the report, which is also the subject of the email.
+    pub fn title(&self) -> String {
+        format!(
+            "Work report for {} - {}",
+            self.first_day.format(DATE_FORMAT),
+            self.last_day.format(DATE_FORMAT)
+        )
+    }
+
+    /// Returns the report as plain text, the time spent on each project per day followed by the
+    /// time spent on each project over the whole report. Days off are listed along with the days
+    /// worked, marked as such. If any of the work was non-billable, the billable and non-billable
+    /// time are listed separately at the end.
+    pub fn as_text(&self, time_format: &TimeFormat, highlight: &dyn Highlight) -> String {
+        let mut text = format!("{}\n", self.title());
+        for day in self.all_days() {
+            text.push_str(&format!(
+                "\n{}{}\n",
+                day.format(DATE_FORMAT),
+                self.day_off_note(day)
+            ));
+            if let Some(projects) = self.days.get(day) {
+                text.push_str(&text_lines(projects, time_format, highlight));
+            }
+        }
+
+        let projects = self.projects();
+        text.push_str(self.projects_title());
+        text.push_str(&text_lines(&projects, time_format, highlight));
+        text.push_str(&format!(
+            "{}: {}\n",
+            TOTAL_STR,
+            highlight.duration(&format_time(time_format, total(&projects)))
+        ));
+        text.push_str(&self.billable_lines(&projects, time_format, highlight));
+        text
+    }
+
+    /// Returns the report as a bar chart, a bar per day with the time worked on it followed by a
+    /// bar per project over the whole report, see `ProjectMapMethods::as_bars`. Days off are marked
+    /// just like in `as_text`. The bars are scaled so that each line fits within the given number
+    /// of columns.
+    pub fn as_chart(
+        &self,
+        time_format: &TimeFormat,
+        columns: usize,
+        highlight: &dyn Highlight,
+    ) -> String {
+        let days: Vec<(&NaiveDate, i64)> = self
+            .all_days()
+            .into_iter()
+            .map(|day| (day, self.days.get(day).map_or(0, total)))
+            .collect();
+        let label_width = self
+            .first_day
+            .format(DATE_FORMAT)
+            .to_string()
+            .chars()
+            .count();
+        // Days off are marked after their time, so the mark takes up room as well.
+        let time_width = days
+            .iter()
+            .map(|(day, time)| {
+                format_time(time_format, *time).chars().count()
+                    + self.day_off_note(day).chars().count()
+            })
+            .max()
+            .unwrap_or(0);
+        let bar_width = project_map::bar_width(columns, label_width, time_width);
+        let max = days.iter().map(|(_, time)| *time).max().unwrap_or(0);
+
+        let mut text = format!("{}\n\nDays\n", self.title());
+        for (day, time) in days {
+            text.push_str(&format!(
+                "{}  {:<bar_width$}  {}{}\n",
+                day.format(DATE_FORMAT),
+                bar(time, max, bar_width),
+                highlight.duration(&format_time(time_format, time)),
+                self.day_off_note(day),
+                bar_width = bar_width
+            ));
+        }
+
+        let projects = self.projects();
+        text.push_str(self.projects_title());
+        text.push_str(&projects.as_bars(time_format, columns, highlight));
+        text.push('\n');
+        text.push_str(&self.billable_lines(&projects, time_format, highlight));
+        text
+    }
+
+    /// Returns the report as an HTML document holding a table with a row per project and a column
+    /// per day. Just like the plain text report, days off and non-billable work get listed
+    /// separately.
+    pub fn as_html(&self, time_format: &TimeFormat) -> String {
+        let projects = self.projects();
+        let cell = |time: i64| {
+            if time > 0 {
+                format_time(time_format, time)
+            } else {
+                String::new()
+            }
+        };
+
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{title}</title></head>\n\
+             <body>\n<h1>{title}</h1>\n<table border=\"1\" cellpadding=\"4\" \
+             style=\"border-collapse: collapse\">\n<tr><th>{column}</th>",
+            title = escape(&self.title()),
+            column = if self.by_user { "User" } else { "Project" }
+        );
+        for day in self.days.keys() {
+            html.push_str(&format!("<th>{}</th>", day.format(COLUMN_FORMAT)));
+        }
+        html.push_str(&format!("<th>{}</th></tr>\n", TOTAL_STR));
+
+        for (project, time) in totals(&projects) {
+            html.push_str(&format!("<tr><td>{}</td>", escape(project)));
+            for day in self.days.values() {
+                let time = day.get(project).map_or(0, |descs| descs.values().sum());
+                html.push_str(&format!("<td>{}</td>", cell(time)));
+            }
+            html.push_str(&format!("<td>{}</td></tr>\n", cell(time)));
+        }
+
+        html.push_str(&format!("<tr><th>{}</th>", TOTAL_STR));
+        for day in self.days.values() {
+            html.push_str(&format!("<th>{}</th>", cell(total(day))));
+        }
+        html.push_str(&format!(
+            "<th>{}</th></tr>\n</table>\n",
+            cell(total(&projects))
+        ));
+        if !self.days_off.is_empty() {
+            let days_off: Vec<String> = self
+                .days_off
+                .iter()
+                .map(|(day, reason)| escape(&days_off::format_day(*day, reason)))
+                .collect();
+            html.push_str(&format!("<p>Days off: {}</p>\n", days_off.join(", ")));
+        }
+        for (label, time) in self.billable_split(&projects) {
+            html.push_str(&format!(
+                "<p>{}: {}</p>\n",
+                label,
+                format_time(time_format, time)
+            ));
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Returns the report as a JSON value, the model templates are rendered with, see
+    /// `work report --template`. It holds the `title`, the `first_day` and `last_day`, a list of
+    /// `days` with the `projects` worked on that day, the `projects` over the whole report, the
+    /// `total` time along with the `billable` and `non_billable` time. Days are given as
+    /// `YYYY-MM-DD` and each time as described by `duration_json`.
+    pub fn to_value(&self, time_format: &TimeFormat) -> Value {
+        let days: BTreeSet<&NaiveDate> = self.days.keys().chain(self.days_off.keys()).collect();
+        let days: Vec<Value> = days
+            .into_iter()
+            .map(|day| {
+                let projects = self.days.get(day).cloned().unwrap_or_default();
+                json!({
+                    "date": day.format(VALUE_FORMAT).to_string(),
+                    "weekday": day.format("%a").to_string(),
+                    "day_off": self.days_off.get(day),
+                    "projects": projects_value(&projects, time_format),
+                    "total": duration_json(time_format, total(&projects), false),
+                })
+            })
+            .collect();
+
+        let projects = self.projects();
+        json!({
+            "title": self.title(),
+            "first_day": self.first_day.format(VALUE_FORMAT).to_string(),
+            "last_day": self.last_day.format(VALUE_FORMAT).to_string(),
+            "days": days,
+            "projects": projects_value(&projects, time_format),
+            "total": duration_json(time_format, total(&projects), false),
+            "billable": duration_json(time_format, total(&projects) - self.non_billable, false),
+            "non_billable": duration_json(time_format, self.non_billable, false),
+        })
+    }
+
+    /// Returns the report as an email with both a plain text and an HTML version of the report,
+    /// ready to be piped into `sendmail -t`. The `From` and `To` headers are only added if given.
+    pub fn as_email(
+        &self,
+        time_format: &TimeFormat,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> String {
+        let mut email = String::new();
+        if let Some(from) = from {
+            email.push_str(&format!("From: {}\n", from));
+        }
+        if let Some(to) = to {
+            email.push_str(&format!("To: {}\n", to));
+        }
+        email.push_str(&format!(
+            "Subject: {}\nMIME-Version: 1.0\n\
+             Content-Type: multipart/alternative; boundary=\"{boundary}\"\n\n\
+             --{boundary}\n\
+             Content-Type: text/plain; charset=utf-8\n\
+             Content-Transfer-Encoding: 8bit\n\n\
+             {}\n\
+             --{boundary}\n\
+             Content-Type: text/html; charset=utf-8\n\
+             Content-Transfer-Encoding: 8bit\n\n\
+             {}\n\
+             --{boundary}--",
+            self.title(),
+            self.as_text(time_format, &Plain),
+            self.as_html(time_format),
+            boundary = BOUNDARY
+        ));
+        email
+    }
+
+    // Returns the days worked along with the days off, in order.
+    fn all_days(&self) -> BTreeSet<&NaiveDate> {
+        self.days.keys().chain(self.days_off.keys()).collect()
+    }
+
+    // Returns the note that marks the given day as a day off, along with its reason, if it is one.
+    fn day_off_note(&self, day: &NaiveDate) -> String {
+        match self.days_off.get(day).map(String::as_str) {
+            Some("") => " (day off)".to_string(),
+            Some(reason) => format!(" (day off: {})", reason),
+            None => String::new(),
+        }
+    }
+
+    // Returns the heading of the time spent over the whole report.
+    fn projects_title(&self) -> &'static str {
+        if self.by_user {
+            "\nUsers\n"
+        } else {
+            "\nProjects\n"
+        }
+    }
+
+    // Returns a line each for the billable and the non-billable time, see `billable_split`.
+    fn billable_lines(
+        &self,
+        projects: &ProjectMap,
+        time_format: &TimeFormat,
+        highlight: &dyn Highlight,
+    ) -> String {
+        self.billable_split(projects)
+            .into_iter()
+            .map(|(label, time)| {
+                format!(
+                    "{}: {}\n",
+                    label,
+                    highlight.duration(&format_time(time_format, time))
+                )
+            })
+            .collect()
+    }
+
+    // Returns the billable and the non-billable time along with their labels, or nothing if all of
+    // the work was billable.
+    fn billable_split(&self, projects: &ProjectMap) -> Vec<(&'static str, i64)> {
+        if self.non_billable > 0 {
+            vec![
+                ("Billable", total(projects) - self.non_billable),
+                ("Non-billable", self.non_billable),
+            ]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Returns the time spent on each project, sorted by project.
+pub(crate) fn totals(projects: &ProjectMap) -> Vec<(&String, i64)> {
+    let mut totals: Vec<(&String, i64)> = projects
+        .iter()
+        .map(|(project, descs)| (project, descs.values().sum()))
+        .collect();
+    totals.sort();
+    totals
+}
+
+// Helper function that lists the projects along with the time spent on each of them and on each
+// of their descriptions, sorted by project and description.
+fn projects_value(projects: &ProjectMap, time_format: &TimeFormat) -> Value {
+    totals(projects)
+        .into_iter()
+        .map(|(project, time)| {
+            let descriptions: BTreeMap<&String, &i64> = projects[project].iter().collect();
+            let descriptions: Vec<Value> = descriptions
+                .into_iter()
+                .map(|(description, time)| {
+                    json!({
+                        "name": description,
+                        "time": duration_json(time_format, *time, false),
+                    })
+                })
+                .collect();
+            json!({
+                "name": project,
+                "time": duration_json(time_format, time, false),
+                "descriptions": descriptions,
+            })
+        })
+        .collect()
+}
+
+/// Returns the time spent on all projects.
+pub fn total(projects: &ProjectMap) -> i64 {
+    projects.values().flat_map(|descs| descs.values()).sum()
+}
+
+// Helper function that lists the time spent on each project, one indented line per project.
+fn text_lines(
+    projects: &ProjectMap,
+    time_format: &TimeFormat,
+    highlight: &dyn Highlight,
+) -> String {
+    totals(projects)
+        .iter()
+        .map(|(project, time)| {
+            format!(
+                "  {}: {}\n",
+                highlight.project(project),
+                highlight.duration(&format_time(time_format, *time))
+            )
+        })
+        .collect()
+}
+
+/// Escapes the characters that have a special meaning in HTML.
+pub(crate) fn escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        let monday = NaiveDate::from_ymd(2026, 10, 12);
+        let mut first = ProjectMap::new();
+        first.add_time("work", "No description", 2 * 3600);
+        first.add_time("<play>", "No description", 1800);
+        let mut second = ProjectMap::new();
+        second.add_time("work", "Bugs", 3600);
+
+        let mut days = BTreeMap::new();
+        days.insert(monday, first);
+        days.insert(monday.succ().succ(), second);
+        Report {
+            first_day: monday,
+            last_day: NaiveDate::from_ymd(2026, 10, 18),
+            days,
+            non_billable: 0,
+            days_off: BTreeMap::new(),
+            by_user: false,
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let monday = NaiveDate::from_ymd(2026, 10, 12);
+        let mut merged = report();
+        let mut other = report();
+        other.first_day = monday.pred();
+        other.non_billable = 600;
+        other.days_off.insert(monday.succ(), "Vacation".to_string());
+        merged.merge(other);
+
+        assert_eq!(merged.first_day, monday.pred());
+        assert_eq!(merged.last_day, report().last_day);
+        assert_eq!(merged.days.len(), 2);
+        assert_eq!(merged.days[&monday]["work"]["No description"], 4 * 3600);
+        assert_eq!(merged.non_billable, 600);
+        assert_eq!(merged.days_off.len(), 1);
+    }
+
+    #[test]
+    fn test_as_text() {
+        assert_eq!(
+            report().as_text(&TimeFormat::Minutes, &Plain),
+            "Work report for Mon 12-10-2026 - Sun 18-10-2026\n\
+             \n\
+             Mon 12-10-2026\n  <play>: 30\n  work: 120\n\
+             \n\
+             Wed 14-10-2026\n  work: 60\n\
+             \n\
+             Projects\n  <play>: 30\n  work: 180\n\
+             Total: 210\n"
+        );
+    }
+
+    #[test]
+    fn test_as_chart() {
+        let monday = NaiveDate::from_ymd(2026, 10, 12);
+        let mut days_off = BTreeMap::new();
+        days_off.insert(monday.succ(), String::new());
+        let report = Report {
+            days_off,
+            ..report()
+        };
+        let chart = report.as_chart(&TimeFormat::Minutes, 40, &Plain);
+        let lines: Vec<&str> = chart.lines().collect();
+        assert_eq!(lines[2], "Days");
+        assert_eq!(lines[3], format!("Mon 12-10-2026  {}  150", "█".repeat(11)));
+        assert_eq!(
+            lines[4],
+            format!("Tue 13-10-2026  {}  0 (day off)", " ".repeat(11))
+        );
+        assert!(lines[5].starts_with("Wed 14-10-2026  ████  "));
+        assert_eq!(lines[7], "Projects");
+        assert!(lines[8].starts_with("work"));
+        assert!(chart.ends_with("210\n"));
+        // Only the title doesn't make room for the bars.
+        assert!(lines[1..].iter().all(|line| line.chars().count() <= 40));
+    }
+
+    #[test]
+    fn test_as_html_and_email() {
+        let html = report().as_html(&TimeFormat::Minutes);
+        assert!(html.contains("<th>Mon 12-10</th><th>Wed 14-10</th><th>Total</th>"));
+        assert!(html.contains("<tr><td>&lt;play&gt;</td><td>30</td><td></td><td>30</td></tr>"));
+        assert!(html.contains("<tr><th>Total</th><th>150</th><th>60</th><th>210</th></tr>"));
+
+        let email = report().as_email(&TimeFormat::Minutes, None, Some("boss@example.com"));
+        assert!(email.starts_with(
+            "To: boss@example.com\nSubject: Work report for Mon 12-10-2026 - Sun 18-10-2026\n"
+        ));
+        assert_eq!(email.matches(BOUNDARY).count(), 4);
+    }
+
+    #[test]
+    fn test_days_off() {
+        let monday = NaiveDate::from_ymd(2026, 10, 12);
+        let mut days_off = BTreeMap::new();
+        days_off.insert(monday, "Sick".to_string());
+        days_off.insert(monday.succ(), String::new());
+        let report = Report {
+            days_off,
+            ..report()
+        };
+        assert!(report.as_text(&TimeFormat::Minutes, &Plain).contains(
+            "\nMon 12-10-2026 (day off: Sick)\n  <play>: 30\n  work: 120\n\
+             \nTue 13-10-2026 (day off)\n\
+             \nWed 14-10-2026\n  work: 60\n"
+        ));
+        assert!(report
+            .as_html(&TimeFormat::Minutes)
+            .contains("<p>Days off: Mon 12-10-2026 (Sick), Tue 13-10-2026</p>"));
+    }
+
+    #[test]
+    fn test_to_value() {
+        let value = report().to_value(&TimeFormat::Minutes);
+        assert_eq!(value["first_day"], "2026-10-12");
+        assert_eq!(value["days"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            value["days"][1],
+            json!({
+                "date": "2026-10-14",
+                "weekday": "Wed",
+                "day_off": null,
+                "projects": [{
+                    "name": "work",
+                    "time": { "seconds": 3600, "formatted": "60" },
+                    "descriptions": [
+                        { "name": "Bugs", "time": { "seconds": 3600, "formatted": "60" } },
+                    ],
+                }],
+                "total": { "seconds": 3600, "formatted": "60" },
+            })
+        );
+        assert_eq!(value["projects"][0]["name"], "<play>");
+        assert_eq!(value["total"]["seconds"], 210 * 60);
+        assert_eq!(value["non_billable"]["seconds"], 0);
+    }
+
+    #[test]
+    fn test_non_billable() {
+        let report = Report {
+            non_billable: 1800,
+            ..report()
+        };
+        assert!(report
+            .as_text(&TimeFormat::Minutes, &Plain)
+            .ends_with("Total: 210\nBillable: 180\nNon-billable: 30\n"));
+        assert!(report
+            .as_html(&TimeFormat::Minutes)
+            .contains("<p>Billable: 180</p>\n<p>Non-billable: 30</p>"));
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use crate::arguments::Threshold;
+use crate::log_file::Event;
+use crate::project_map::{ProjectMap, ProjectMapMethods};
+use crate::sessions::{self, Session};
+use crate::time::Interval;
+
+/// The `Rounding` struct holds how the time of each session is rounded for billing, such as clients
+/// that bill every session in increments of 15 minutes with a minimum of 30 minutes, see
+/// `work report --round-per-session`. Unlike the approximate time formats, which round the totals,
+/// the rounding is applied to each session before the time is summed up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rounding {
+    /// The number of seconds the time of a session is rounded up to a multiple of, 0 to leave it.
+    pub increment: i64,
+    /// The least number of seconds a session is counted as.
+    pub minimum: i64,
+}
+
+impl Rounding {
+    /// Creates the rounding given by the `--round-per-session` and `--minimum` options, or `None`
+    /// if neither was given.
+    pub fn new(increment: Option<Threshold>, minimum: Option<Threshold>) -> Option<Self> {
+        if increment.is_none() && minimum.is_none() {
+            return None;
+        }
+        Some(Rounding {
+            increment: increment.map_or(0, |increment| increment.0),
+            minimum: minimum.map_or(0, |minimum| minimum.0),
+        })
+    }
+
+    /// Rounds the given number of seconds up to the next increment, and then up to the minimum.
+    /// Sessions that didn't last any time at all aren't counted.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::rounding::Rounding;
+    /// let rounding = Rounding { increment: 15 * 60, minimum: 30 * 60 };
+    /// assert_eq!(rounding.round(10 * 60), 30 * 60);
+    /// assert_eq!(rounding.round(31 * 60), 45 * 60);
+    /// assert_eq!(rounding.round(45 * 60), 45 * 60);
+    /// assert_eq!(rounding.round(0), 0);
+    /// ```
+    pub fn round(&self, seconds: i64) -> i64 {
+        if seconds <= 0 {
+            return 0;
+        }
+        let rounded = if self.increment > 0 {
+            (seconds + self.increment - 1) / self.increment * self.increment
+        } else {
+            seconds
+        };
+        rounded.max(self.minimum)
+    }
+
+    /// Sums up the time spent on each project within the given interval like `sessions::tally`
+    /// does, but rounds the part of each session that falls within the interval first. Returns the
+    /// non-billable part of the rounded time along with it. A session in progress lasts until `now`.
+    pub fn tally<I>(&self, sessions: I, interval: &Interval, now: i64) -> (ProjectMap, i64)
+    where
+        I: IntoIterator<Item = Session>,
+    {
+        self.tally_by(sessions, interval, now, |projects, time, event| {
+            projects.add_event(&time, event)
+        })
+    }
+
+    /// Sums up the time spent by each user on each project within the given interval like
+    /// `sessions::tally_users` does, but rounds the part of each session that falls within the
+    /// interval first, just like `tally`.
+    pub fn tally_users<I>(&self, sessions: I, interval: &Interval, now: i64) -> (ProjectMap, i64)
+    where
+        I: IntoIterator<Item = Session>,
+    {
+        self.tally_by(sessions, interval, now, |users, time, event| {
+            users.add_time(&event.to_user(), &event.to_project(), time)
+        })
+    }
+
+    // Helper function that adds the rounded time each session shares with the given interval to a
+    // map, along with the `start` event of the session, using `add`. Returns the non-billable part
+    // of the rounded time along with the map.
+    fn tally_by<I, F>(
+        &self,
+        sessions: I,
+        interval: &Interval,
+        now: i64,
+        mut add: F,
+    ) -> (ProjectMap, i64)
+    where
+        I: IntoIterator<Item = Session>,
+        F: FnMut(&mut ProjectMap, i64, &Event),
+    {
+        let mut projects: ProjectMap = HashMap::new();
+        let mut non_billable = 0;
+        for (session, span) in sessions::spans(sessions, now) {
+            if let Some(overlap) = sessions::overlap(&span, interval) {
+                let time = self.round(overlap.duration().num_seconds());
+                let event = session.start_event();
+                add(&mut projects, time, &event);
+                if !event.is_billable() {
+                    non_billable += time;
+                }
+            }
+        }
+        (projects, non_billable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_file::{Metadata, NO_USER_STR};
+
+    #[test]
+    fn test_tally() {
+        let events = vec![
+            (
+                0,
+                Event::Start(Some("api".to_string()), None, None, None, Metadata::new()),
+            ),
+            (
+                10 * 60,
+                Event::Stop(None, None, None, None, Metadata::new()),
+            ),
+            (
+                3600,
+                Event::Start(Some("api".to_string()), None, None, None, Metadata::new()),
+            ),
+            (
+                3600 + 20 * 60,
+                Event::Stop(None, None, None, None, Metadata::new()),
+            ),
+            (
+                2 * 3600,
+                Event::Start(
+                    Some("chores".to_string()),
+                    None,
+                    None,
+                    Some(false),
+                    Metadata::new(),
+                ),
+            ),
+            (
+                2 * 3600 + 60,
+                Event::Stop(None, None, None, None, Metadata::new()),
+            ),
+        ];
+        let rounding = Rounding {
+            increment: 15 * 60,
+            minimum: 15 * 60,
+        };
+        let (projects, non_billable) = rounding.tally(
+            sessions::reconstruct(&events),
+            &Interval::new(0, Some(3 * 3600)),
+            3 * 3600,
+        );
+        assert_eq!(projects["api"].values().sum::<i64>(), 45 * 60);
+        assert_eq!(projects["chores"].values().sum::<i64>(), 15 * 60);
+        assert_eq!(non_billable, 15 * 60);
+    }
+
+    #[test]
+    fn test_tally_users() {
+        let events = vec![
+            (0, Event::from("0,Start,api,,,,user=alice")),
+            (10 * 60, Event::from("600,Stop,api,")),
+            (3600, Event::from("3600,Start,api,")),
+            (3600 + 20 * 60, Event::from("4800,Stop,api,")),
+        ];
+        let rounding = Rounding {
+            increment: 15 * 60,
+            minimum: 0,
+        };
+        let (users, non_billable) = rounding.tally_users(
+            sessions::reconstruct(&events),
+            &Interval::new(0, Some(2 * 3600)),
+            2 * 3600,
+        );
+        assert_eq!(users["alice"]["api"], 15 * 60);
+        assert_eq!(users[NO_USER_STR]["api"], 30 * 60);
+        assert_eq!(non_billable, 0);
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(Rounding::new(None, None), None);
+        assert_eq!(
+            Rounding::new(None, Some(Threshold(1800))),
+            Some(Rounding {
+                increment: 0,
+                minimum: 1800
+            })
+        );
+    }
+}
@@ -0,0 +1,511 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveTime, Timelike};
+
+use crate::config;
+use crate::error::{AppError, ErrorKind};
+use crate::profiles;
+
+/// Directory the system-wide systemd units are installed in.
+const SYSTEM_UNIT_DIR: &str = "/etc/systemd/system";
+
+/// Directory the system-wide launchd daemons are installed in.
+const SYSTEM_DAEMON_DIR: &str = "/Library/LaunchDaemons";
+
+/// Prefix of the label of a launchd job, which is the name of the service after it.
+const LABEL_PREFIX: &str = "com.github.nielsing.";
+
+/// Variables of the graphical session that are passed on to a service, so it can tell the
+/// focused window or show notifications, if they are set when the service is installed.
+const SESSION_VARIABLES: &[&str] = &[
+    "DISPLAY",
+    "WAYLAND_DISPLAY",
+    "XAUTHORITY",
+    "DBUS_SESSION_BUS_ADDRESS",
+    "PATH",
+];
+
+/// The `Manager` enum lists the service managers `work install-service` can install services for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Manager {
+    Systemd,
+    Launchd,
+}
+
+impl Manager {
+    /// Returns the service manager of the platform Work was built for, which is launchd on macOS
+    /// and systemd anywhere else.
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Manager::Launchd
+        } else {
+            Manager::Systemd
+        }
+    }
+
+    /// Returns the name and contents of the files that run the given service with the given
+    /// executable of Work, see `Service::systemd_units` and `Service::launchd_plist`.
+    pub fn files(
+        &self,
+        service: &Service,
+        executable: &Path,
+        run_as: Option<&str>,
+    ) -> Vec<(String, String)> {
+        match self {
+            Manager::Systemd => service.systemd_units(executable, run_as),
+            Manager::Launchd => vec![service.launchd_plist(executable, run_as)],
+        }
+    }
+
+    /// Returns the names of the files a service with the given name may have been installed as.
+    pub fn file_names(&self, name: &str) -> Vec<String> {
+        match self {
+            Manager::Systemd => vec![format!("{}.service", name), format!("{}.timer", name)],
+            Manager::Launchd => vec![format!("{}{}.plist", LABEL_PREFIX, name)],
+        }
+    }
+
+    /// Returns the directory to install services in, which is the `systemd/user` directory within
+    /// the config directory for systemd units of the user or `~/Library/LaunchAgents` for launchd
+    /// agents, or the directory of the system-wide ones if `user` isn't set.
+    pub fn dir(&self, user: bool) -> Result<PathBuf, AppError> {
+        let dir = match (self, user) {
+            (Manager::Systemd, false) => return Ok(PathBuf::from(SYSTEM_UNIT_DIR)),
+            (Manager::Launchd, false) => return Ok(PathBuf::from(SYSTEM_DAEMON_DIR)),
+            (Manager::Systemd, true) => {
+                dirs::config_dir().map(|dir| dir.join("systemd").join("user"))
+            }
+            (Manager::Launchd, true) => {
+                dirs::home_dir().map(|dir| dir.join("Library").join("LaunchAgents"))
+            }
+        };
+        dir.ok_or_else(|| {
+            AppError::new(ErrorKind::System(
+                "Unable to find the directory of the services of the user!".to_string(),
+            ))
+        })
+    }
+
+    /// Returns the command that starts the given service once it is installed in the given
+    /// directory.
+    pub fn start_command(&self, service: &Service, dir: &Path, user: bool) -> String {
+        match self {
+            Manager::Systemd => format!(
+                "{systemctl} daemon-reload && {systemctl} enable --now {}",
+                service.unit(),
+                systemctl = systemctl(user)
+            ),
+            Manager::Launchd => format!(
+                "launchctl load -w {}",
+                dir.join(&self.file_names(&service.name)[0]).display()
+            ),
+        }
+    }
+
+    /// Returns the command that stops the service with the given name once it is uninstalled,
+    /// where `scheduled` tells whether it ran at a time of day.
+    pub fn stop_command(&self, name: &str, scheduled: bool, user: bool) -> String {
+        match self {
+            Manager::Systemd => format!(
+                "{systemctl} disable --now {}.{} && {systemctl} daemon-reload",
+                name,
+                if scheduled { "timer" } else { "service" },
+                systemctl = systemctl(user)
+            ),
+            Manager::Launchd => format!("launchctl remove {}{}", LABEL_PREFIX, name),
+        }
+    }
+}
+
+/// The `Schedule` struct describes the time of day a service runs at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    pub at: NaiveTime,
+    /// Only run on Monday to Friday.
+    pub weekdays: bool,
+}
+
+impl Schedule {
+    /// Returns the schedule in the form systemd's `OnCalendar` expects.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chrono::NaiveTime;
+    /// # use work_core::service::Schedule;
+    /// let schedule = Schedule { at: NaiveTime::from_hms(9, 15, 0), weekdays: true };
+    /// assert_eq!(schedule.on_calendar(), "Mon..Fri *-*-* 09:15:00");
+    /// ```
+    pub fn on_calendar(&self) -> String {
+        format!(
+            "{}*-*-* {}",
+            if self.weekdays { "Mon..Fri " } else { "" },
+            self.at.format("%H:%M:%S")
+        )
+    }
+}
+
+/// The `Service` struct describes a command of Work that runs in the background, either for as
+/// long as the machine is up or at a time of day, and the files that run it with systemd or
+/// launchd.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Service {
+    /// Name of the service, e.g. `work-auto`.
+    pub name: String,
+    pub description: String,
+    /// Arguments to run Work with.
+    pub arguments: Vec<String>,
+    /// When to run the command, or `None` to keep it running.
+    pub schedule: Option<Schedule>,
+    /// Variables to run Work with, see `environment`.
+    pub environment: Vec<(String, String)>,
+}
+
+impl Service {
+    /// Returns the name of the unit to enable, which is the timer of a service that runs at a time
+    /// of day.
+    pub fn unit(&self) -> String {
+        match self.schedule {
+            Some(_) => format!("{}.timer", self.name),
+            None => format!("{}.service", self.name),
+        }
+    }
+
+    /// Returns the name and contents of the systemd units that run the service with the given
+    /// executable of Work, which are a service and, if it runs at a time of day, a timer that
+    /// catches up on a run missed while the machine was off. Units installed system-wide run as
+    /// the given user, see `current_user`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::path::Path;
+    /// # use work_core::service::Service;
+    /// let service = Service {
+    ///     name: "work-auto".to_string(),
+    ///     description: "Track work automatically".to_string(),
+    ///     arguments: vec!["auto".to_string(), "run".to_string()],
+    ///     schedule: None,
+    ///     environment: vec![("WORK_HOME".to_string(), "/home/me/work".to_string())],
+    /// };
+    /// let units = service.systemd_units(Path::new("/usr/bin/work"), None);
+    /// assert_eq!(units.len(), 1);
+    /// assert!(units[0].1.contains("ExecStart=/usr/bin/work auto run\n"));
+    /// assert!(units[0].1.contains("Environment=\"WORK_HOME=/home/me/work\"\n"));
+    /// assert!(units[0].1.contains("WantedBy=default.target\n"));
+    /// ```
+    pub fn systemd_units(&self, executable: &Path, run_as: Option<&str>) -> Vec<(String, String)> {
+        let mut service = format!(
+            "[Unit]\nDescription={}\n\n[Service]\nType={}\nExecStart={} {}\n",
+            self.description,
+            if self.schedule.is_some() {
+                "oneshot"
+            } else {
+                "simple"
+            },
+            quote(&executable.to_string_lossy()),
+            self.arguments
+                .iter()
+                .map(|argument| quote(argument))
+                .collect::<Vec<String>>()
+                .join(" ")
+        );
+        for (name, value) in &self.environment {
+            service.push_str(&format!("Environment={}\n", assignment(name, value)));
+        }
+        if let Some(user) = run_as {
+            service.push_str(&format!("User={}\n", user));
+        }
+
+        let schedule = match self.schedule {
+            Some(schedule) => schedule,
+            None => {
+                service.push_str(&format!(
+                    "Restart=on-failure\n\n[Install]\nWantedBy={}\n",
+                    if run_as.is_some() {
+                        "multi-user.target"
+                    } else {
+                        "default.target"
+                    }
+                ));
+                return vec![(format!("{}.service", self.name), service)];
+            }
+        };
+        let timer = format!(
+            "[Unit]\nDescription={}\n\n[Timer]\nOnCalendar={}\nPersistent=true\n\n[Install]\n\
+             WantedBy=timers.target\n",
+            self.description,
+            schedule.on_calendar()
+        );
+        vec![
+            (format!("{}.service", self.name), service),
+            (format!("{}.timer", self.name), timer),
+        ]
+    }
+
+    /// Returns the name and contents of the launchd property list that runs the service with the
+    /// given executable of Work. A service that runs at a time of day also runs once the machine
+    /// wakes up if it slept through that time, and one that keeps running is restarted if it
+    /// fails. Daemons installed system-wide run as the given user, see `current_user`.
+    pub fn launchd_plist(&self, executable: &Path, run_as: Option<&str>) -> (String, String) {
+        let label = format!("{}{}", LABEL_PREFIX, self.name);
+        let mut plist = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \
+             \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+             <plist version=\"1.0\">\n<dict>\n\
+             \t<key>Label</key>\n\t<string>{}</string>\n\
+             \t<key>ProgramArguments</key>\n\t<array>\n",
+            escape_xml(&label)
+        );
+        for argument in Some(executable.to_string_lossy().to_string())
+            .into_iter()
+            .chain(self.arguments.iter().cloned())
+        {
+            plist.push_str(&format!("\t\t<string>{}</string>\n", escape_xml(&argument)));
+        }
+        plist.push_str("\t</array>\n\t<key>EnvironmentVariables</key>\n\t<dict>\n");
+        for (name, value) in &self.environment {
+            plist.push_str(&format!(
+                "\t\t<key>{}</key>\n\t\t<string>{}</string>\n",
+                escape_xml(name),
+                escape_xml(value)
+            ));
+        }
+        plist.push_str("\t</dict>\n");
+        if let Some(user) = run_as {
+            plist.push_str(&format!(
+                "\t<key>UserName</key>\n\t<string>{}</string>\n",
+                escape_xml(user)
+            ));
+        }
+        match self.schedule {
+            Some(schedule) => {
+                let time = format!(
+                    "<key>Hour</key><integer>{}</integer>\
+                     <key>Minute</key><integer>{}</integer>",
+                    schedule.at.hour(),
+                    schedule.at.minute()
+                );
+                plist.push_str("\t<key>StartCalendarInterval</key>\n");
+                if schedule.weekdays {
+                    plist.push_str("\t<array>\n");
+                    for weekday in 1..=5 {
+                        plist.push_str(&format!(
+                            "\t\t<dict><key>Weekday</key><integer>{}</integer>{}</dict>\n",
+                            weekday, time
+                        ));
+                    }
+                    plist.push_str("\t</array>\n");
+                } else {
+                    plist.push_str(&format!("\t<dict>{}</dict>\n", time));
+                }
+            }
+            None => plist.push_str(
+                "\t<key>RunAtLoad</key>\n\t<true/>\n\
+                 \t<key>KeepAlive</key>\n\t<dict>\n\
+                 \t\t<key>SuccessfulExit</key>\n\t\t<false/>\n\t</dict>\n",
+            ),
+        }
+        plist.push_str("</dict>\n</plist>\n");
+        (format!("{}.plist", label), plist)
+    }
+}
+
+/// Returns the variables a service needs to find the same log as Work does now, which are the
+/// data directory and the profile in use, along with those of the graphical session that are set.
+/// If the data directory can't be found, the function returns an error message.
+pub fn environment() -> Result<Vec<(String, String)>, AppError> {
+    let data_dir = config::data_dir()?;
+    // The service doesn't run in the current directory.
+    let data_dir = fs::canonicalize(&data_dir)
+        .or_else(|_| env::current_dir().map(|dir| dir.join(&data_dir)))
+        .map_err(|e| unable("find", &data_dir, e))?;
+    let mut environment = vec![
+        (
+            "WORK_HOME".to_string(),
+            data_dir.to_string_lossy().to_string(),
+        ),
+        ("WORK_PROFILE".to_string(), profiles::current()?),
+    ];
+    for name in SESSION_VARIABLES {
+        if let Some(value) = env::var(name).ok().filter(|value| !value.is_empty()) {
+            environment.push((name.to_string(), value));
+        }
+    }
+    Ok(environment)
+}
+
+/// Writes the given files into the given directory, creating it if needed, and returns their
+/// paths. If a file can't be written, the function returns an error message.
+pub fn install(files: &[(String, String)], dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    fs::create_dir_all(dir).map_err(|e| unable("create", dir, e))?;
+    let mut paths = Vec::new();
+    for (name, contents) in files {
+        let path = dir.join(name);
+        fs::write(&path, contents).map_err(|e| unable("write", &path, e))?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Removes the files with the given names from the given directory, and returns the paths of those
+/// that were there. If a file can't be removed, the function returns an error message.
+pub fn uninstall(names: &[String], dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let mut paths = Vec::new();
+    for name in names {
+        let path = dir.join(name);
+        match fs::remove_file(&path) {
+            Ok(()) => paths.push(path),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => (),
+            Err(e) => return Err(unable("remove", &path, e)),
+        }
+    }
+    Ok(paths)
+}
+
+/// Returns the user to run system-wide services as, which is the one who ran `sudo` if Work runs
+/// through it.
+pub fn current_user() -> Option<String> {
+    env::var("SUDO_USER")
+        .or_else(|_| env::var("USER"))
+        .ok()
+        .filter(|user| !user.is_empty())
+}
+
+// Helper function that quotes an argument of `ExecStart` if it holds anything systemd would
+// otherwise split it at, escaping the `%` of specifiers and the `$` of variables as well.
+fn quote(argument: &str) -> String {
+    let escaped = argument.replace('%', "%%").replace('$', "$$");
+    if argument.is_empty() || argument.contains(&[' ', '\t', '"', '\'', '\\'][..]) {
+        format!("\"{}\"", escape(&escaped))
+    } else {
+        escaped
+    }
+}
+
+// Helper function that returns the quoted assignment of a variable for `Environment`.
+fn assignment(name: &str, value: &str) -> String {
+    format!("\"{}={}\"", name, escape(&value.replace('%', "%%")))
+}
+
+// Helper function that escapes the backslashes and double quotes within a quoted word.
+fn escape(word: &str) -> String {
+    word.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Helper function that escapes the characters XML gives a meaning to.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Helper function that returns the `systemctl` command for the units of the user or the system.
+fn systemctl(user: bool) -> &'static str {
+    if user {
+        "systemctl --user"
+    } else {
+        "systemctl"
+    }
+}
+
+// Helper function that returns an error message for a file that couldn't be handled.
+fn unable(action: &str, path: &Path, error: io::Error) -> AppError {
+    AppError::new(ErrorKind::System(format!(
+        "Unable to {} {}: {}",
+        action,
+        path.display(),
+        error
+    )))
+    .with_source(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reminder() -> Service {
+        Service {
+            name: "work-remind".to_string(),
+            description: "Remind to start tracking work".to_string(),
+            arguments: vec![
+                "remind".to_string(),
+                "--at".to_string(),
+                "09:00".to_string(),
+            ],
+            schedule: Some(Schedule {
+                at: NaiveTime::from_hms(9, 0, 0),
+                weekdays: false,
+            }),
+            environment: vec![("WORK_HOME".to_string(), "/home/me/my work".to_string())],
+        }
+    }
+
+    #[test]
+    fn test_systemd_units() {
+        let service = reminder();
+        assert_eq!(service.unit(), "work-remind.timer");
+        let units = service.systemd_units(Path::new("/opt/my bin/work"), Some("me"));
+        assert_eq!(units[0].0, "work-remind.service");
+        assert!(units[0].1.contains("Type=oneshot\n"));
+        assert!(units[0]
+            .1
+            .contains("ExecStart=\"/opt/my bin/work\" remind --at 09:00\n"));
+        assert!(units[0]
+            .1
+            .contains("Environment=\"WORK_HOME=/home/me/my work\"\n"));
+        assert!(units[0].1.contains("User=me\n"));
+        assert_eq!(units[1].0, "work-remind.timer");
+        assert!(units[1].1.contains("OnCalendar=*-*-* 09:00:00\n"));
+    }
+
+    #[test]
+    fn test_launchd_plist() {
+        let mut service = reminder();
+        let (name, plist) = service.launchd_plist(Path::new("/usr/local/bin/work"), None);
+        assert_eq!(name, "com.github.nielsing.work-remind.plist");
+        assert!(plist.contains("<string>/usr/local/bin/work</string>\n\t\t<string>remind</string>"));
+        assert!(plist.contains("<key>WORK_HOME</key>\n\t\t<string>/home/me/my work</string>"));
+        assert!(plist.contains(
+            "<dict><key>Hour</key><integer>9</integer><key>Minute</key><integer>0</integer></dict>"
+        ));
+        assert!(!plist.contains("UserName"));
+
+        service.schedule = Some(Schedule {
+            at: NaiveTime::from_hms(9, 0, 0),
+            weekdays: true,
+        });
+        service.environment = vec![("PATH".to_string(), "<a&b>".to_string())];
+        let (_, plist) = service.launchd_plist(Path::new("/usr/local/bin/work"), Some("me"));
+        assert_eq!(plist.matches("<key>Weekday</key>").count(), 5);
+        assert!(plist.contains("<string>&lt;a&amp;b&gt;</string>"));
+        assert!(plist.contains("<key>UserName</key>\n\t<string>me</string>"));
+
+        service.schedule = None;
+        let (_, plist) = service.launchd_plist(Path::new("/usr/local/bin/work"), None);
+        assert!(plist.contains("<key>KeepAlive</key>"));
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("work"), "work");
+        assert_eq!(quote("50%"), "50%%");
+        assert_eq!(quote("$HOME"), "$$HOME");
+        assert_eq!(quote("a \"b\""), "\"a \\\"b\\\"\"");
+    }
+
+    #[test]
+    fn test_install_and_uninstall() {
+        let dir = env::temp_dir().join(format!("work-service-test-{}", std::process::id()));
+        let files = vec![("work-test.service".to_string(), "[Unit]\n".to_string())];
+        assert_eq!(install(&files, &dir).unwrap().len(), 1);
+        assert!(dir.join("work-test.service").exists());
+        let names = Manager::Systemd.file_names("work-test");
+        assert_eq!(uninstall(&names, &dir).unwrap().len(), 1);
+        assert!(uninstall(&names, &dir).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
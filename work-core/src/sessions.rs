@@ -0,0 +1,676 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::iter;
+use std::str::FromStr;
+
+use chrono::{Local, TimeZone};
+use log::{debug, trace};
+
+use crate::arguments::TimeFormat;
+use crate::error::{AppError, ErrorCode, ErrorKind};
+use crate::log_file::{Event, LogEntry, Metadata};
+use crate::project_map::{ProjectMap, ProjectMapMethods};
+use crate::time::{self, Interval};
+
+/// The `SessionId` struct uniquely identifies a session in the log.
+///
+/// The id is simply the timestamp of the session's `start` event. It is deterministic, since it
+/// doesn't depend on the position of the session in the log, and unique, since no two sessions
+/// can start at the same time.
+///
+/// # Examples
+/// ```
+/// # use work_core::sessions::SessionId;
+/// let id: SessionId = "1589205600".parse().unwrap();
+/// assert_eq!(id, SessionId(1589205600));
+/// assert_eq!(id.to_string(), "1589205600");
+/// assert!("yesterday".parse::<SessionId>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SessionId(pub i64);
+
+impl fmt::Display for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for SessionId {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<i64>() {
+            Ok(id) => Ok(SessionId(id)),
+            Err(_) => Err(AppError::new(ErrorKind::Parse(format!(
+                "Invalid session id: {}, the `log` command lists the ids of sessions",
+                s
+            )))),
+        }
+    }
+}
+
+/// A `Session` is a single stretch of work, reconstructed from a `start` event and the `stop` event
+/// following it in the log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    pub start: i64,
+    /// `None` if the session is still in progress.
+    pub stop: Option<i64>,
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub client: Option<String>,
+    /// `None` if it wasn't recorded whether the session is billable.
+    pub billable: Option<bool>,
+    /// The metadata of the `start` event of the session.
+    pub metadata: Metadata,
+}
+
+/// A session in progress, started by the given entry.
+impl From<LogEntry> for Session {
+    fn from(entry: LogEntry) -> Self {
+        Session {
+            start: entry.timestamp,
+            stop: None,
+            project: entry.project,
+            description: entry.description,
+            client: entry.client,
+            billable: entry.billable,
+            metadata: entry.metadata,
+        }
+    }
+}
+
+impl Session {
+    /// Creates a session of the given project for tests, without a client, billability or
+    /// metadata. The session is in progress if `stop` is `None`.
+    #[cfg(test)]
+    pub(crate) fn new(
+        start: i64,
+        stop: Option<i64>,
+        project: &str,
+        description: Option<&str>,
+    ) -> Self {
+        Session {
+            start,
+            stop,
+            project: Some(project.to_string()),
+            description: description.map(str::to_string),
+            client: None,
+            billable: None,
+            metadata: Metadata::new(),
+        }
+    }
+
+    /// Returns the id of the session.
+    pub fn id(&self) -> SessionId {
+        SessionId(self.start)
+    }
+
+    /// Returns the number of seconds spent on the session. A session in progress counts up until
+    /// `now`.
+    pub fn duration(&self, now: i64) -> i64 {
+        self.stop.unwrap_or(now) - self.start
+    }
+
+    /// Returns true if the session stops before or at the moment it starts, so it counts no time
+    /// or even negative time. A session in progress is never empty.
+    pub fn is_empty(&self) -> bool {
+        match self.stop {
+            Some(stop) => stop <= self.start,
+            None => false,
+        }
+    }
+
+    /// Returns the `start` event of the session.
+    pub fn start_event(&self) -> Event {
+        Event::Start(
+            self.project.clone(),
+            self.description.clone(),
+            self.client.clone(),
+            self.billable,
+            self.metadata.clone(),
+        )
+    }
+
+    /// Returns the `stop` event of the session.
+    pub fn stop_event(&self) -> Event {
+        Event::Stop(
+            self.project.clone(),
+            self.description.clone(),
+            self.client.clone(),
+            self.billable,
+            self.metadata.clone(),
+        )
+    }
+
+    /// Returns true if any part of the session falls within `start` and `end` (inclusive). A
+    /// session in progress lasts until `now`.
+    pub fn overlaps(&self, start: i64, end: i64, now: i64) -> bool {
+        self.start <= end && self.stop.unwrap_or(now) >= start
+    }
+
+    /// Formats the session as a single line for listings such as the `log` command, starting with
+    /// the id of the session. A session in progress is shown as lasting until `now`.
+    pub fn to_line(&self, now: i64) -> String {
+        self.format_line(self.start, self.stop, now)
+    }
+
+    /// Formats the session like `to_line`, but as a line per day for a session that runs past
+    /// midnight. Every line starts with the id of the session.
+    pub fn to_day_lines(&self, now: i64) -> Vec<String> {
+        let parts = Interval::new(self.start, Some(self.stop.unwrap_or(now))).split_at_midnight();
+        let last = parts.len() - 1;
+        parts
+            .iter()
+            .enumerate()
+            .map(|(i, part)| {
+                let stop = if i == last { self.stop } else { Some(part.end) };
+                self.format_line(part.start, stop, now)
+            })
+            .collect()
+    }
+
+    // Helper function that formats the part of the session from `start` until `stop`, or until `now`
+    // if there is no `stop`.
+    fn format_line(&self, start: i64, stop: Option<i64>, now: i64) -> String {
+        let end = match stop {
+            Some(stop) => time::format_clock(stop),
+            None => "now".to_string(),
+        };
+
+        format!(
+            "{}  {} {} - {}  {} ({})",
+            self.id(),
+            Local.timestamp(start, 0).format("%a %d-%m"),
+            time::format_clock(start),
+            end,
+            self.start_event().to_string(),
+            time::format_time(&TimeFormat::HumanReadable, stop.unwrap_or(now) - start)
+        )
+    }
+}
+
+/// Pairs up the `start` and `stop` events of the log into sessions.
+///
+/// The events are sorted by time first, keeping events at the same time in the order they were
+/// given, so a log that was edited by hand or merged from several logs is still paired up
+/// correctly. The events are assumed to alternate between `start` and `stop` events, as the log is
+/// supposed to. A `stop` event without a preceding `start` event is ignored and a trailing `start`
+/// event results in a session that is still in progress.
+pub fn reconstruct(events: &[(i64, Event)]) -> Vec<Session> {
+    let mut events = events.to_vec();
+    events.sort_by_key(|(timestamp, _)| *timestamp);
+    Sessions::new(events.into_iter()).collect()
+}
+
+/// Removes the sessions that stop before or at the moment they start from the given events, pairing
+/// up the events in the order they are given, as `Sessions` does. Returns the number of sessions
+/// removed.
+pub fn remove_empty(events: &mut Vec<(i64, Event)>) -> usize {
+    let mut empty = BTreeSet::new();
+    let mut open = None;
+    for (index, (timestamp, event)) in events.iter().enumerate() {
+        match (open, event) {
+            (_, Event::Start(..)) => open = Some((index, *timestamp)),
+            (Some((start_index, start)), Event::Stop(..)) => {
+                if *timestamp <= start {
+                    empty.insert(start_index);
+                    empty.insert(index);
+                }
+                open = None;
+            }
+            (None, Event::Stop(..)) => (),
+        }
+    }
+    let mut index = 0;
+    events.retain(|_| {
+        index += 1;
+        !empty.contains(&(index - 1))
+    });
+    empty.len() / 2
+}
+
+/// The `Sessions` iterator lazily pairs up the events of a given iterator into sessions, in the
+/// same way as `reconstruct` does but in the order the events are given, which saves reading the
+/// whole log when its events are known to be in order.
+pub struct Sessions<I> {
+    events: I,
+    open: Option<(i64, Event)>,
+}
+
+impl<I: Iterator<Item = (i64, Event)>> Sessions<I> {
+    pub fn new(events: I) -> Self {
+        Sessions { events, open: None }
+    }
+}
+
+impl<I: Iterator<Item = (i64, Event)>> Iterator for Sessions<I> {
+    type Item = Session;
+
+    fn next(&mut self) -> Option<Session> {
+        loop {
+            match (self.open.take(), self.events.next()) {
+                (None, Some(entry @ (_, Event::Start(..)))) => self.open = Some(entry),
+                (Some(start), Some(entry @ (_, Event::Start(..)))) => {
+                    // Two starts in a row, the first one never got stopped.
+                    self.open = Some(entry);
+                    return Some(to_session(&start, None));
+                }
+                (Some(start), Some((stop, Event::Stop(..)))) => {
+                    return Some(to_session(&start, Some(stop)))
+                }
+                (None, Some((_, Event::Stop(..)))) => (),
+                (Some(start), None) => return Some(to_session(&start, None)),
+                (None, None) => return None,
+            }
+        }
+    }
+}
+
+/// Sums up the time spent on each project and description within the given interval. Sessions that
+/// only partially overlap the interval contribute only the overlapping time.
+///
+/// Time is always attributed to the project and description of the session's `start` event. A
+/// session that was never stopped lasts until the next session starts, or until `now` if it is the
+/// last session.
+pub fn tally<I: IntoIterator<Item = Session>>(
+    sessions: I,
+    interval: &Interval,
+    now: i64,
+) -> ProjectMap {
+    tally_by(sessions, interval, now, |projects, time, event| {
+        projects.add_event(&time, event)
+    })
+}
+
+/// Sums up the time spent for each client on each of its projects within the given interval, in
+/// the same way as `tally` does for projects and descriptions. The clients take the place of the
+/// projects in the resulting map and their projects the place of the descriptions, so the map can
+/// be output just like the time spent on projects. Work without a client is counted under
+/// `NO_CLIENT_STR`.
+pub fn tally_clients<I: IntoIterator<Item = Session>>(
+    sessions: I,
+    interval: &Interval,
+    now: i64,
+) -> ProjectMap {
+    tally_by(sessions, interval, now, |clients, time, event| {
+        clients.add_time(&event.to_client(), &event.to_project(), time)
+    })
+}
+
+/// Sums up the time spent by each user on each project within the given interval, just like
+/// `tally_clients` does for clients. Work recorded without a user is counted under `NO_USER_STR`.
+pub fn tally_users<I: IntoIterator<Item = Session>>(
+    sessions: I,
+    interval: &Interval,
+    now: i64,
+) -> ProjectMap {
+    tally_by(sessions, interval, now, |users, time, event| {
+        users.add_time(&event.to_user(), &event.to_project(), time)
+    })
+}
+
+/// Sums up the billable and the non-billable time within the given interval, in the same way as
+/// `tally` does for projects and descriptions. Returns the billable time first. Sessions are
+/// billable unless they were marked otherwise.
+pub fn tally_billable<I: IntoIterator<Item = Session>>(
+    sessions: I,
+    interval: &Interval,
+    now: i64,
+) -> (i64, i64) {
+    let (mut billable, mut non_billable) = (0, 0);
+    tally_by(sessions, interval, now, |_, time, event| {
+        if event.is_billable() {
+            billable += time;
+        } else {
+            non_billable += time;
+        }
+    });
+    (billable, non_billable)
+}
+
+// Helper function that adds the time each session shares with the given interval to a map, along
+// with the `start` event of the session, using `add`.
+fn tally_by<I, F>(sessions: I, interval: &Interval, now: i64, mut add: F) -> ProjectMap
+where
+    I: IntoIterator<Item = Session>,
+    F: FnMut(&mut ProjectMap, i64, &Event),
+{
+    let mut projects: ProjectMap = HashMap::new();
+    let (mut counted, mut skipped) = (0, 0);
+
+    for (session, span) in spans(sessions, now) {
+        // Sessions are in chronological order, nothing after this one can be within the interval.
+        if session.start > interval.end {
+            break;
+        }
+        if let Some(overlap) = overlap(&span, interval) {
+            trace!(
+                "Counting {} seconds of {}",
+                overlap.duration().num_seconds(),
+                session.to_line(now)
+            );
+            counted += 1;
+            add(
+                &mut projects,
+                overlap.duration().num_seconds(),
+                &session.start_event(),
+            );
+        } else {
+            trace!(
+                "Skipping {}, it is outside of the interval",
+                session.to_line(now)
+            );
+            skipped += 1;
+        }
+    }
+    debug!(
+        "Counted {} sessions and skipped {} outside of the interval",
+        counted, skipped
+    );
+    projects
+}
+
+/// Pairs each session up with the time it spans. A session that was never stopped lasts until the
+/// next session starts, or until `now` if it is the last session.
+pub fn spans<I: IntoIterator<Item = Session>>(
+    sessions: I,
+    now: i64,
+) -> impl Iterator<Item = (Session, Interval)> {
+    let mut sessions = sessions.into_iter().peekable();
+    iter::from_fn(move || {
+        let session = sessions.next()?;
+        let stop = session
+            .stop
+            .or_else(|| sessions.peek().map(|next| next.start))
+            .unwrap_or(now);
+        let span = Interval::new(session.start, Some(stop));
+        Some((session, span))
+    })
+}
+
+/// Returns the part of a session's `span` that falls within the given interval. Sessions that
+/// merely touch the interval don't count, unless they don't last any time at all.
+pub fn overlap(span: &Interval, interval: &Interval) -> Option<Interval> {
+    span.intersect(interval)
+        .filter(|overlap| overlap.start < overlap.end || span.start == span.end)
+}
+
+// Helper function for creating a session out of a `start` event.
+fn to_session(start: &(i64, Event), stop: Option<i64>) -> Session {
+    Session {
+        stop,
+        ..Session::from(LogEntry::from(start.clone()))
+    }
+}
+/// Finds the session with the given id.
+pub fn find(sessions: &[Session], id: SessionId) -> Result<&Session, AppError> {
+    match sessions.iter().find(|session| session.id() == id) {
+        Some(session) => Ok(session),
+        None => Err(AppError::new(ErrorKind::User(format!(
+            "No session with id {}, the `log` command lists the ids of sessions",
+            id
+        )))
+        .with_code(ErrorCode::SessionNotFound)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_file::NO_CLIENT_STR;
+    use proptest::prelude::*;
+
+    // Helper function for building a log out of (gap, length, project) triplets, where each gap is
+    // the time between the previous session and the next one.
+    fn build_log(parts: &[(i64, i64, usize)]) -> Vec<(i64, Event)> {
+        let mut time = 0;
+        let mut events = Vec::new();
+        for (gap, length, project) in parts {
+            let project = Some(format!("project{}", project));
+            events.push((
+                time + gap,
+                Event::Start(project.clone(), None, None, None, Metadata::new()),
+            ));
+            events.push((
+                time + gap + length,
+                Event::Stop(project, None, None, None, Metadata::new()),
+            ));
+            time += gap + length;
+        }
+        events
+    }
+
+    fn total(projects: &ProjectMap) -> i64 {
+        projects.values().flat_map(|descs| descs.values()).sum()
+    }
+
+    #[test]
+    fn test_tally_clips_sessions_to_interval() {
+        let sessions = reconstruct(&build_log(&[(8 * 3600, 10 * 3600, 0)]));
+        let projects = tally(
+            sessions,
+            &Interval::new(9 * 3600, Some(17 * 3600)),
+            17 * 3600,
+        );
+        assert_eq!(projects["project0"]["No description"], 8 * 3600);
+    }
+
+    #[test]
+    fn test_tally_attributes_time_to_start_event() {
+        let events = vec![
+            (
+                0,
+                Event::Stop(Some("stray".to_string()), None, None, None, Metadata::new()),
+            ),
+            (
+                100,
+                Event::Start(Some("a".to_string()), None, None, None, Metadata::new()),
+            ),
+            (
+                200,
+                Event::Stop(Some("b".to_string()), None, None, None, Metadata::new()),
+            ),
+        ];
+        let projects = tally(reconstruct(&events), &Interval::new(150, Some(300)), 300);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects["a"]["No description"], 50);
+    }
+
+    #[test]
+    fn test_tally_unstopped_session_lasts_until_next_session() {
+        let events = vec![
+            (
+                100,
+                Event::Start(Some("a".to_string()), None, None, None, Metadata::new()),
+            ),
+            (
+                200,
+                Event::Start(Some("b".to_string()), None, None, None, Metadata::new()),
+            ),
+            (
+                300,
+                Event::Stop(Some("b".to_string()), None, None, None, Metadata::new()),
+            ),
+        ];
+        let projects = tally(reconstruct(&events), &Interval::new(0, Some(1000)), 1000);
+        assert_eq!(projects["a"]["No description"], 100);
+        assert_eq!(projects["b"]["No description"], 100);
+    }
+
+    #[test]
+    fn test_tally_session_in_progress_lasts_until_now() {
+        let events = vec![(
+            100,
+            Event::Start(Some("a".to_string()), None, None, None, Metadata::new()),
+        )];
+        let projects = tally(reconstruct(&events), &Interval::new(0, Some(1000)), 400);
+        assert_eq!(projects["a"]["No description"], 300);
+    }
+
+    #[test]
+    fn test_remove_empty() {
+        let mut events = build_log(&[(100, 60, 0), (100, 0, 1), (100, -60, 2), (100, 60, 3)]);
+        assert_eq!(
+            Sessions::new(events.iter().cloned())
+                .filter(|session| session.is_empty())
+                .count(),
+            2
+        );
+        assert_eq!(remove_empty(&mut events), 2);
+        let projects: Vec<Option<String>> = reconstruct(&events)
+            .into_iter()
+            .map(|session| session.project)
+            .collect();
+        assert_eq!(
+            projects,
+            vec![Some("project0".to_string()), Some("project3".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_tally_clients() {
+        let acme = Some("acme".to_string());
+        let events = vec![
+            (
+                100,
+                Event::Start(
+                    Some("a".to_string()),
+                    None,
+                    acme.clone(),
+                    None,
+                    Metadata::new(),
+                ),
+            ),
+            (
+                200,
+                Event::Stop(
+                    Some("a".to_string()),
+                    None,
+                    acme.clone(),
+                    None,
+                    Metadata::new(),
+                ),
+            ),
+            (
+                300,
+                Event::Start(Some("b".to_string()), None, acme, None, Metadata::new()),
+            ),
+            (
+                400,
+                Event::Stop(Some("b".to_string()), None, None, None, Metadata::new()),
+            ),
+            (
+                500,
+                Event::Start(Some("c".to_string()), None, None, None, Metadata::new()),
+            ),
+            (
+                600,
+                Event::Stop(Some("c".to_string()), None, None, None, Metadata::new()),
+            ),
+        ];
+        let clients = tally_clients(reconstruct(&events), &Interval::new(0, Some(1000)), 1000);
+        assert_eq!(clients.len(), 2);
+        assert_eq!(clients["acme"]["a"], 100);
+        assert_eq!(clients["acme"]["b"], 100);
+        assert_eq!(clients[NO_CLIENT_STR]["c"], 100);
+    }
+
+    #[test]
+    fn test_tally_billable() {
+        let events = vec![
+            (
+                100,
+                Event::Start(None, None, None, Some(true), Metadata::new()),
+            ),
+            (
+                200,
+                Event::Stop(None, None, None, Some(true), Metadata::new()),
+            ),
+            (
+                300,
+                Event::Start(None, None, None, Some(false), Metadata::new()),
+            ),
+            (
+                350,
+                Event::Stop(None, None, None, Some(false), Metadata::new()),
+            ),
+            (400, Event::Start(None, None, None, None, Metadata::new())),
+            (600, Event::Stop(None, None, None, None, Metadata::new())),
+        ];
+        let interval = Interval::new(0, Some(1000));
+        assert_eq!(
+            tally_billable(reconstruct(&events), &interval, interval.end),
+            (300, 50)
+        );
+    }
+
+    #[test]
+    fn test_to_day_lines() {
+        let day = chrono::NaiveDate::from_ymd(2026, 9, 7);
+        let session = Session::new(
+            time::start_of_day(day) + 22 * 3600,
+            Some(time::start_of_day(day.succ()) + 2 * 3600),
+            "a",
+            None,
+        );
+        let id = session.id().to_string();
+        let lines = session.to_day_lines(0);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(&format!("{}  Mon 07-09 22:00 - 00:00", id)));
+        assert!(lines[1].starts_with(&format!("{}  Tue 08-09 00:00 - 02:00", id)));
+
+        let session = Session {
+            stop: Some(session.start + 3600),
+            ..session
+        };
+        assert_eq!(session.to_day_lines(0), vec![session.to_line(0)]);
+    }
+
+    proptest! {
+        #[test]
+        fn shuffled_log_reconstructs_the_same_sessions(
+            (parts, shuffled) in prop::collection::vec((1..10_000i64, 1..10_000i64, 0..3usize), 0..20)
+                .prop_flat_map(|parts| {
+                    let shuffled = Just(build_log(&parts)).prop_shuffle();
+                    (Just(parts), shuffled)
+                })
+        ) {
+            prop_assert_eq!(reconstruct(&shuffled), reconstruct(&build_log(&parts)));
+        }
+
+        #[test]
+        fn tally_of_whole_log_sums_all_sessions(
+            parts in prop::collection::vec((0..10_000i64, 0..10_000i64, 0..3usize), 0..20)
+        ) {
+            let sessions = reconstruct(&build_log(&parts));
+            let projects = tally(sessions, &Interval::new(0, Some(i64::from(u32::MAX))), 0);
+            let expected: i64 = parts.iter().map(|(_, length, _)| length).sum();
+            prop_assert_eq!(total(&projects), expected);
+        }
+
+        #[test]
+        fn tally_is_additive_over_split_intervals(
+            parts in prop::collection::vec((0..10_000i64, 0..10_000i64, 0..3usize), 0..20),
+            start in 0..100_000i64,
+            middle in 0..100_000i64,
+            end in 0..100_000i64,
+        ) {
+            let mut bounds = [start, middle, end];
+            bounds.sort();
+            let [start, middle, end] = bounds;
+            let sessions = reconstruct(&build_log(&parts));
+
+            let whole = tally(sessions.clone(), &Interval::new(start, Some(end)), end);
+            let first = tally(sessions.clone(), &Interval::new(start, Some(middle)), end);
+            let second = tally(sessions, &Interval::new(middle, Some(end)), end);
+            for (project, descs) in &whole {
+                let time = |projects: &ProjectMap| {
+                    projects.get(project).map_or(0, |descs| descs.values().sum::<i64>())
+                };
+                prop_assert_eq!(descs.values().sum::<i64>(), time(&first) + time(&second));
+            }
+            prop_assert!(total(&whole) <= end - start);
+        }
+    }
+}
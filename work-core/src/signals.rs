@@ -0,0 +1,134 @@
+use std::io;
+use std::os::raw::c_int;
+use std::process::Child;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
+
+use crate::error::{AppError, ErrorKind};
+
+/// Signals that would otherwise end Work, see `Interrupts`.
+#[cfg(unix)]
+const SIGNALS: &[c_int] = &[libc::SIGINT, libc::SIGTERM, libc::SIGHUP];
+#[cfg(not(unix))]
+const SIGNALS: &[c_int] = &[libc::SIGINT, libc::SIGTERM];
+
+/// The `Interrupts` struct catches the signals that would otherwise end Work, such as the SIGINT
+/// of Ctrl-C or the SIGTERM of a shutdown, so a command wrapped by `while` can be interrupted
+/// without leaving the session it is tracked by unfinished. Once caught, the signals no longer end
+/// Work for the rest of its run, they are only remembered until `take` is called.
+pub struct Interrupts {
+    /// The last signal received, or 0 if none was received since the last `take`.
+    received: Arc<AtomicI32>,
+}
+
+impl Interrupts {
+    /// Starts catching the signals. If it fails to, the function returns an error message.
+    pub fn catch() -> Result<Self, AppError> {
+        let received = Arc::new(AtomicI32::new(0));
+        for &signal in SIGNALS {
+            let flag = Arc::clone(&received);
+            // Storing to an atomic is all the action does, which is safe within a signal handler.
+            let result = unsafe {
+                signal_hook_registry::register(signal, move || flag.store(signal, Ordering::SeqCst))
+            };
+            if let Err(e) = result {
+                return Err(AppError::new(ErrorKind::System(format!(
+                    "Unable to listen for interruptions: {}",
+                    e
+                )))
+                .with_source(e));
+            }
+        }
+        Ok(Interrupts { received })
+    }
+
+    /// Returns the last signal received since the previous call, if any.
+    pub fn take(&self) -> Option<c_int> {
+        match self.received.swap(0, Ordering::SeqCst) {
+            0 => None,
+            signal => Some(signal),
+        }
+    }
+}
+
+/// Sends the given signal on to a child process, so it gets to end the way it would have without
+/// Work in between.
+///
+/// The SIGINT of Ctrl-C is left out while Work runs in the foreground of a terminal, since the
+/// terminal sends it to the whole foreground process group, the child included, which would
+/// otherwise be interrupted twice.
+#[cfg(unix)]
+pub fn forward(child: &mut Child, signal: c_int) -> io::Result<()> {
+    send(child, signal, in_foreground())
+}
+
+/// Ends a child process, as signals can't be sent on to it on this platform.
+#[cfg(not(unix))]
+pub fn forward(child: &mut Child, _signal: c_int) -> io::Result<()> {
+    child.kill()
+}
+
+// Helper function that sends the given signal to a child process, unless it is a SIGINT the child
+// already got from the terminal as part of the `foreground` process group.
+#[cfg(unix)]
+fn send(child: &mut Child, signal: c_int, foreground: bool) -> io::Result<()> {
+    if signal == libc::SIGINT && foreground {
+        return Ok(());
+    }
+    // Both arguments are plain integers, the child merely might have ended already.
+    match unsafe { libc::kill(child.id() as libc::pid_t, signal) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+// Helper function that checks whether Work is in the process group the terminal it reads its input
+// from sends its signals to.
+#[cfg(unix)]
+fn in_foreground() -> bool {
+    // Only asks about a file descriptor every process has, failing if it isn't a terminal.
+    unsafe { libc::tcgetpgrp(libc::STDIN_FILENO) == libc::getpgrp() }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_forward() {
+        let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+        forward(&mut child, libc::SIGTERM).unwrap();
+        let status = child.wait().unwrap();
+        assert!(!status.success());
+    }
+
+    #[test]
+    fn test_forward_interrupt_once() {
+        let path = std::env::temp_dir().join(format!("work-signals-{}", std::process::id()));
+        // Counts the interruptions it gets while it runs for about a second.
+        let script = format!(
+            "trap 'echo INT >> {}' INT; i=0; while [ $i -lt 20 ]; do sleep 0.05; i=$((i+1)); done",
+            path.display()
+        );
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        thread::sleep(Duration::from_millis(200));
+
+        // Ctrl-C makes the terminal interrupt the whole foreground process group.
+        unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGINT) };
+        thread::sleep(Duration::from_millis(200));
+        send(&mut child, libc::SIGINT, true).unwrap();
+        assert!(child.wait().unwrap().success());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "INT\n");
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::arguments::TimeFormat;
+use crate::days_off::DaysOff;
+use crate::sessions::{self, Session};
+use crate::time::{self, format_time, Interval};
+
+/// Format of the day the standup looks back on.
+const DAY_FORMAT: &str = "%A %d-%m-%Y";
+
+/// Number of days to look back for a work day before settling for yesterday, so a log full of days
+/// off can't keep the search going forever.
+const MAX_LOOKBACK: i64 = 366;
+
+/// The `Standup` struct holds the work done on a single day as a list per project of what was
+/// worked on, ready to be pasted into a chat for a daily standup, see
+/// `work summary --daily-standup`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Standup {
+    pub day: NaiveDate,
+    /// The projects in the order they were first worked on during the day.
+    pub projects: Vec<Item>,
+}
+
+/// The work done on a single project during the day of a standup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Item {
+    pub project: String,
+    pub seconds: i64,
+    /// The descriptions of the sessions in the order they were first given, without repeats.
+    pub descriptions: Vec<String>,
+}
+
+impl Standup {
+    /// Creates a standup of the parts of the given sessions that fall within the given day.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chrono::NaiveDate;
+    /// # use work_core::log_file::Metadata;
+    /// # use work_core::sessions::Session;
+    /// # use work_core::standup::Standup;
+    /// # use work_core::time;
+    /// let day = NaiveDate::from_ymd(2026, 10, 16);
+    /// let morning = time::start_of_day(day) + 9 * 3600;
+    /// let session = Session {
+    ///     start: morning,
+    ///     stop: Some(morning + 2 * 3600),
+    ///     project: Some("api".to_string()),
+    ///     description: Some("Fixed the login".to_string()),
+    ///     client: None,
+    ///     billable: None,
+    ///     metadata: Metadata::new(),
+    /// };
+    /// let standup = Standup::new(vec![session], day, morning + 8 * 3600);
+    /// assert_eq!(standup.projects[0].seconds, 2 * 3600);
+    /// assert!(Standup::new(Vec::new(), day, morning).is_empty());
+    /// ```
+    pub fn new<I>(sessions: I, day: NaiveDate, now: i64) -> Self
+    where
+        I: IntoIterator<Item = Session>,
+    {
+        let interval = Interval::new(
+            time::start_of_day(day),
+            Some(time::start_of_day(day.succ())),
+        );
+        let mut projects: Vec<Item> = Vec::new();
+        for (session, span) in sessions::spans(sessions, now) {
+            let seconds = match sessions::overlap(&span, &interval) {
+                Some(overlap) if overlap.end > overlap.start => overlap.end - overlap.start,
+                _ => continue,
+            };
+            let project = session.start_event().to_project();
+            let index = match projects.iter().position(|item| item.project == project) {
+                Some(index) => index,
+                None => {
+                    projects.push(Item {
+                        project,
+                        seconds: 0,
+                        descriptions: Vec::new(),
+                    });
+                    projects.len() - 1
+                }
+            };
+            let item = &mut projects[index];
+            item.seconds += seconds;
+            if let Some(description) = session.description {
+                if !item.descriptions.contains(&description) {
+                    item.descriptions.push(description);
+                }
+            }
+        }
+        Standup { day, projects }
+    }
+
+    /// Returns true if no work was done on the day of the standup.
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty()
+    }
+
+    /// Returns the standup as a bulleted list of the projects along with the time spent on each,
+    /// followed by an indented list of their descriptions, below the day in bold. The text is
+    /// meant to be pasted into Slack, so it is never colored.
+    pub fn as_text(&self, time_format: &TimeFormat) -> String {
+        let mut text = format!("*{}*", self.day.format(DAY_FORMAT));
+        for item in &self.projects {
+            text.push_str(&format!(
+                "\n• {} ({})",
+                item.project,
+                format_time(time_format, item.seconds)
+            ));
+            for description in &item.descriptions {
+                text.push_str(&format!("\n    ◦ {}", description));
+            }
+        }
+        text
+    }
+}
+
+/// Returns the last day before `today` on which work was expected, that is yesterday unless it was
+/// a weekend or a day off, so the standup of a Monday looks back on the Friday before.
+///
+/// # Examples
+/// ```
+/// # use chrono::NaiveDate;
+/// # use work_core::days_off::DaysOff;
+/// # use work_core::standup::last_work_day;
+/// let monday = NaiveDate::from_ymd(2026, 10, 19);
+/// let friday = NaiveDate::from_ymd(2026, 10, 16);
+/// assert_eq!(last_work_day(monday, &DaysOff::default()), friday);
+/// assert_eq!(last_work_day(monday.succ(), &DaysOff::default()), monday);
+/// ```
+pub fn last_work_day(today: NaiveDate, days_off: &DaysOff) -> NaiveDate {
+    (1..=MAX_LOOKBACK)
+        .map(|days| today - Duration::days(days))
+        .find(|day| days_off.is_work_day(*day))
+        .unwrap_or_else(|| today.pred())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_as_text() {
+        let day = NaiveDate::from_ymd(2026, 10, 16);
+        let morning = time::start_of_day(day) + 9 * 3600;
+        let sessions = vec![
+            Session::new(morning - 24 * 3600, Some(morning - 23 * 3600), "old", None),
+            Session::new(
+                morning,
+                Some(morning + 2 * 3600),
+                "api",
+                Some("Fixed the login"),
+            ),
+            Session::new(
+                morning + 2 * 3600,
+                Some(morning + 3 * 3600),
+                "meetings",
+                None,
+            ),
+            Session::new(
+                morning + 4 * 3600,
+                Some(morning + 5 * 3600),
+                "api",
+                Some("Reviewed #42"),
+            ),
+            Session::new(
+                morning + 6 * 3600,
+                Some(morning + 7 * 3600),
+                "api",
+                Some("Fixed the login"),
+            ),
+        ];
+        assert_eq!(
+            Standup::new(sessions, day, morning + 8 * 3600).as_text(&TimeFormat::HoursApprox),
+            "*Friday 16-10-2026*\n\
+             • api (4)\n    \
+             ◦ Fixed the login\n    \
+             ◦ Reviewed #42\n\
+             • meetings (1)"
+        );
+    }
+
+    #[test]
+    fn test_last_work_day() {
+        let monday = NaiveDate::from_ymd(2026, 10, 19);
+        let thursday = NaiveDate::from_ymd(2026, 10, 15);
+        let mut days_off = DaysOff::default();
+        days_off.add(thursday.succ(), thursday.succ(), "Vacation");
+        assert_eq!(last_work_day(monday, &days_off), thursday);
+        assert_eq!(last_work_day(monday.pred(), &days_off), thursday);
+    }
+}
@@ -0,0 +1,501 @@
+use std::cmp::Ordering;
+
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone, Timelike};
+use serde_json::{json, Value};
+
+use crate::arguments::{HistogramKind, TimeFormat};
+use crate::error::AppError;
+use crate::highlight::Highlight;
+use crate::log_file::LogFile;
+use crate::sessions::{self, Session};
+use crate::time::{self, format_time, Interval};
+use crate::timeline;
+
+/// Number of weeks the moving average of a trend is taken over.
+const AVERAGE_WEEKS: usize = 4;
+
+/// Format of the first day of each week of a trend.
+const WEEK_FORMAT: &str = "%a %d-%m-%Y";
+
+/// Labels of the days of a weekday histogram.
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Number of seconds in each cell of a grid.
+const QUARTER_HOUR: i64 = 15 * 60;
+
+/// Shades of the cells of a grid, from the least time worked to the most.
+const SHADES: [&str; 4] = ["░", "▒", "▓", "█"];
+
+/// Fills the cells of a grid during which no work was done.
+const GAP: &str = "·";
+
+/// The `Trend` struct holds the time worked in each of the last few complete weeks, see
+/// `work stats --trend`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trend {
+    /// The Monday that starts each week along with the time worked that week, earliest week first.
+    pub weeks: Vec<(NaiveDate, i64)>,
+}
+
+impl Trend {
+    /// Tallies the time worked in each of the given number of weeks before the current week,
+    /// either on all projects or only on the given project.
+    pub fn new(log: &mut LogFile, weeks: usize, project: Option<&str>) -> Result<Self, AppError> {
+        let today = log.clock().today();
+        let this_monday = today - Duration::days(i64::from(today.weekday().num_days_from_monday()));
+
+        let mut totals = Vec::with_capacity(weeks);
+        for week in (1..=weeks).rev() {
+            let monday = this_monday - Duration::weeks(week as i64);
+            let interval = Interval::new(
+                time::start_of_day(monday),
+                Some(time::start_of_day(monday + Duration::weeks(1))),
+            );
+            let total = log.tally_time(&interval)?.map_or(0, |projects| {
+                projects
+                    .iter()
+                    .filter(|(name, _)| project.is_none() || project == Some(name.as_str()))
+                    .flat_map(|(_, descs)| descs.values())
+                    .sum()
+            });
+            totals.push((monday, total));
+        }
+        Ok(Trend { weeks: totals })
+    }
+
+    /// Returns the moving average of the time worked per week, the average over each week and up
+    /// to three weeks before it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chrono::NaiveDate;
+    /// # use work_core::stats::Trend;
+    /// let monday = NaiveDate::from_ymd(2026, 10, 5);
+    /// let trend = Trend { weeks: [10, 20, 30, 40, 50].iter().map(|time| (monday, *time)).collect() };
+    /// assert_eq!(trend.moving_average(), vec![10, 15, 20, 25, 35]);
+    /// ```
+    pub fn moving_average(&self) -> Vec<i64> {
+        (0..self.weeks.len())
+            .map(|i| {
+                let window = &self.weeks[(i + 1).saturating_sub(AVERAGE_WEEKS)..=i];
+                window.iter().map(|(_, time)| time).sum::<i64>() / window.len() as i64
+            })
+            .collect()
+    }
+
+    /// Returns whether the moving average went up, down or stayed the same over the trend.
+    pub fn direction(&self) -> Ordering {
+        let averages = self.moving_average();
+        match (averages.first(), averages.last()) {
+            (Some(first), Some(last)) => last.cmp(first),
+            _ => Ordering::Equal,
+        }
+    }
+
+    /// Returns the trend as a line per week with the time worked and the moving average, followed
+    /// by the direction of the trend.
+    pub fn as_text(&self, time_format: &TimeFormat, highlight: &dyn Highlight) -> String {
+        let rows: Vec<(String, String, String)> = self
+            .weeks
+            .iter()
+            .zip(self.moving_average())
+            .map(|((monday, time), average)| {
+                (
+                    monday.format(WEEK_FORMAT).to_string(),
+                    format_time(time_format, *time),
+                    format_time(time_format, average),
+                )
+            })
+            .collect();
+        let time_width = rows
+            .iter()
+            .map(|(_, time, _)| time.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Time".len());
+
+        let mut text = format!(
+            "{:<14}  {:<time_width$}  Average\n",
+            "Week",
+            "Time",
+            time_width = time_width
+        );
+        for (week, time, average) in &rows {
+            text.push_str(&format!(
+                "{:<14}  {}  {}\n",
+                week,
+                highlight.duration(&format!("{:<width$}", time, width = time_width)),
+                highlight.duration(average)
+            ));
+        }
+        text.push_str(match self.direction() {
+            Ordering::Greater => "Trending up",
+            Ordering::Less => "Trending down",
+            Ordering::Equal => "No change",
+        });
+        text
+    }
+}
+
+/// The `Histogram` struct holds the time worked within an interval by hour of the day or by day
+/// of the week, see `work stats --histogram`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    /// Whether the time is counted by hour or by weekday.
+    pub kind: HistogramKind,
+    /// The seconds worked in each hour of the day starting at midnight, or in each day of the week
+    /// starting on Monday.
+    pub seconds: Vec<i64>,
+}
+
+impl Histogram {
+    /// Creates an empty histogram of the given kind.
+    pub fn new(kind: HistogramKind) -> Self {
+        let buckets = match kind {
+            HistogramKind::Hourly => 24,
+            HistogramKind::Weekday => 7,
+        };
+        Histogram {
+            kind,
+            seconds: vec![0; buckets],
+        }
+    }
+
+    /// Counts the time of the given sessions that falls within the interval, either of all
+    /// sessions or only of those on the given project. A session in progress lasts until `now`.
+    pub fn of_sessions<I>(
+        kind: HistogramKind,
+        sessions: I,
+        interval: &Interval,
+        project: Option<&str>,
+        now: i64,
+    ) -> Self
+    where
+        I: IntoIterator<Item = Session>,
+    {
+        let mut histogram = Histogram::new(kind);
+        for (session, span) in sessions::spans(sessions, now) {
+            if project.is_some() && session.project.as_deref() != project {
+                continue;
+            }
+            if let Some(overlap) = sessions::overlap(&span, interval) {
+                histogram.add(&overlap);
+            }
+        }
+        histogram
+    }
+
+    /// Adds the time of the given interval to the hours or days of the week it falls in.
+    pub fn add(&mut self, interval: &Interval) {
+        let seconds = &mut self.seconds;
+        match self.kind {
+            HistogramKind::Hourly => bin(interval, 3600, |_, hour, time| seconds[hour] += time),
+            HistogramKind::Weekday => bin(interval, 24 * 3600, |day, _, time| seconds[day] += time),
+        }
+    }
+
+    /// Returns the label of each hour or day of the week, in the order of `seconds`.
+    pub fn labels(&self) -> Vec<String> {
+        match self.kind {
+            HistogramKind::Hourly => (0..24).map(|hour| format!("{:02}:00", hour)).collect(),
+            HistogramKind::Weekday => WEEKDAYS.iter().map(|day| day.to_string()).collect(),
+        }
+    }
+
+    /// Returns the histogram as a line per hour or day of the week with the minutes worked.
+    pub fn as_table(&self, highlight: &dyn Highlight) -> String {
+        let minutes: Vec<String> = self
+            .seconds
+            .iter()
+            .map(|seconds| time::get_minutes(*seconds).to_string())
+            .collect();
+        let width = minutes.iter().map(String::len).max().unwrap_or(0);
+        self.labels()
+            .iter()
+            .zip(&minutes)
+            .map(|(label, minutes)| {
+                format!(
+                    "{}  {}",
+                    label,
+                    highlight.duration(&format!("{:>width$}", minutes, width = width))
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Returns a JSON format of the histogram as a string, an object with the minutes worked per
+    /// hour or day of the week.
+    pub fn as_json(&self) -> String {
+        let key = match self.kind {
+            HistogramKind::Hourly => "hour",
+            HistogramKind::Weekday => "weekday",
+        };
+        let buckets: Vec<Value> = self
+            .labels()
+            .iter()
+            .zip(&self.seconds)
+            .map(|(label, seconds)| json!({ key: label, "minutes": time::get_minutes(*seconds) }))
+            .collect();
+        serde_json::to_string_pretty(&buckets).unwrap()
+    }
+}
+
+/// The `Grid` struct holds the time worked within an interval by day of the week and quarter of an
+/// hour, drawn as a punch card that shows the rhythm of work, see `work grid`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid {
+    /// The seconds worked in each quarter of an hour of each day of the week, starting at midnight
+    /// on Monday.
+    pub seconds: Vec<Vec<i64>>,
+}
+
+impl Grid {
+    /// Counts the time of the given sessions that falls within the interval. A session in progress
+    /// lasts until `now`.
+    pub fn of_sessions<I>(sessions: I, interval: &Interval, now: i64) -> Self
+    where
+        I: IntoIterator<Item = Session>,
+    {
+        let mut grid = Grid {
+            seconds: vec![vec![0; (24 * 3600 / QUARTER_HOUR) as usize]; WEEKDAYS.len()],
+        };
+        for (_, span) in sessions::spans(sessions, now) {
+            if let Some(overlap) = sessions::overlap(&span, interval) {
+                grid.add(&overlap);
+            }
+        }
+        grid
+    }
+
+    /// Adds the time of the given interval to the quarters of an hour it falls in.
+    pub fn add(&mut self, interval: &Interval) {
+        let seconds = &mut self.seconds;
+        bin(interval, QUARTER_HOUR, |day, quarter, time| {
+            seconds[day][quarter] += time
+        });
+    }
+
+    /// Returns true if no work was done within the grid.
+    pub fn is_empty(&self) -> bool {
+        self.seconds.iter().flatten().all(|seconds| *seconds == 0)
+    }
+
+    /// Returns the grid as a line per day of the week below a line naming the hours, which run from
+    /// the first hour worked on any day to the last. Each cell covers a quarter of an hour, or as
+    /// many quarters as it takes for the hours to fit within the given number of columns, and is
+    /// shaded by the time worked within it compared with the cell worked the most.
+    pub fn as_text(
+        &self,
+        time_format: &TimeFormat,
+        columns: usize,
+        highlight: &dyn Highlight,
+    ) -> String {
+        let quarters = self.seconds.iter().flat_map(|day| {
+            day.iter()
+                .enumerate()
+                .filter(|(_, seconds)| **seconds > 0)
+                .map(|(quarter, _)| quarter as i64)
+        });
+        let (first, last) = match (quarters.clone().min(), quarters.max()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return String::new(),
+        };
+        let per_hour = 3600 / QUARTER_HOUR;
+        let (from, to) = (first / per_hour, last / per_hour + 1);
+        let label_width = WEEKDAYS[0].len();
+        let available = columns.saturating_sub(label_width + 2) as i64;
+        let cells_per_hour = [4, 2, 1]
+            .iter()
+            .find(|cells| *cells * (to - from) <= available)
+            .map_or(1, |cells| *cells);
+        let width = (per_hour / cells_per_hour) as usize;
+
+        let rows: Vec<Vec<i64>> = self
+            .seconds
+            .iter()
+            .map(|day| {
+                day[(from * per_hour) as usize..(to * per_hour) as usize]
+                    .chunks(width)
+                    .map(|quarters| quarters.iter().sum())
+                    .collect()
+            })
+            .collect();
+        let max = rows.iter().flatten().max().map_or(1, |max| *max).max(1);
+
+        let mut text = format!(
+            "{:width$}  {}\n",
+            "",
+            timeline::ruler(from, to, cells_per_hour),
+            width = label_width
+        );
+        for (day, row) in WEEKDAYS.iter().zip(&rows) {
+            let cells: String = row
+                .iter()
+                .map(
+                    |seconds| match (seconds * SHADES.len() as i64 + max - 1) / max {
+                        0 => GAP,
+                        shade => SHADES[shade as usize - 1],
+                    },
+                )
+                .collect();
+            text.push_str(&format!("{}  {}\n", day, highlight.duration(&cells)));
+        }
+        text.push_str(&format!(
+            "\nEach cell covers {} minutes, {} marks the most time worked within a cell: {}",
+            width as i64 * QUARTER_HOUR / 60,
+            SHADES[SHADES.len() - 1],
+            highlight.duration(&format_time(time_format, max))
+        ));
+        text
+    }
+}
+
+// Helper function that splits the given interval at the bins of the given length it falls in,
+// counting from local midnight, and calls `add` with the day of the week counting from Monday, the
+// index of the bin within the day and the seconds of the interval within the bin. Histograms and
+// grids bin time alike through it.
+fn bin<F>(interval: &Interval, length: i64, mut add: F)
+where
+    F: FnMut(usize, usize, i64),
+{
+    let mut start = interval.start;
+    while start < interval.end {
+        let local = Local.timestamp(start, 0);
+        let since_midnight = i64::from(local.num_seconds_from_midnight());
+        let next = (start - since_midnight % length + length)
+            .min(time::start_of_day(local.naive_local().date().succ()))
+            .min(interval.end);
+        add(
+            local.weekday().num_days_from_monday() as usize,
+            (since_midnight / length) as usize,
+            next - start,
+        );
+        start = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::highlight::Plain;
+
+    fn trend(times: &[i64]) -> Trend {
+        let monday = NaiveDate::from_ymd(2026, 9, 7);
+        Trend {
+            weeks: times
+                .iter()
+                .enumerate()
+                .map(|(i, time)| (monday + Duration::weeks(i as i64), *time))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(trend(&[3600, 7200, 1800]).direction(), Ordering::Greater);
+        assert_eq!(trend(&[7200, 0, 0, 0, 0]).direction(), Ordering::Less);
+        assert_eq!(trend(&[3600]).direction(), Ordering::Equal);
+        assert_eq!(trend(&[]).direction(), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_as_text() {
+        assert_eq!(
+            trend(&[3600, 0, 7200]).as_text(&TimeFormat::Minutes, &Plain),
+            "Week            Time  Average\n\
+             Mon 07-09-2026  60    60\n\
+             Mon 14-09-2026  0     30\n\
+             Mon 21-09-2026  120   60\n\
+             No change"
+        );
+    }
+
+    #[test]
+    fn test_histogram() {
+        let monday = NaiveDate::from_ymd(2026, 9, 7);
+        let at = |day: NaiveDate, hour, minute| {
+            Local
+                .from_local_datetime(&day.and_hms(hour, minute, 0))
+                .unwrap()
+                .timestamp()
+        };
+        let session = |start, stop| Session::new(start, Some(stop), "work", None);
+        let sessions = vec![
+            session(at(monday, 9, 30), at(monday, 11, 0)),
+            session(at(monday.succ(), 23, 0), at(monday.succ().succ(), 0, 30)),
+        ];
+        let interval = Interval::new(at(monday, 0, 0), Some(at(monday, 10, 0) + 7 * 86400));
+
+        let hourly = Histogram::of_sessions(
+            HistogramKind::Hourly,
+            sessions.clone(),
+            &interval,
+            None,
+            interval.end,
+        );
+        assert_eq!(hourly.seconds[9], 1800);
+        assert_eq!(hourly.seconds[10], 3600);
+        assert_eq!(hourly.seconds[23], 3600);
+        assert_eq!(hourly.seconds[0], 1800);
+        assert_eq!(hourly.seconds.iter().sum::<i64>(), 3 * 3600);
+
+        let weekday = Histogram::of_sessions(
+            HistogramKind::Weekday,
+            sessions.clone(),
+            &interval,
+            None,
+            interval.end,
+        );
+        assert_eq!(&weekday.seconds[..3], &[5400, 3600, 1800]);
+        let json: Value = serde_json::from_str(&weekday.as_json()).unwrap();
+        assert_eq!(json[1], json!({ "weekday": "Tue", "minutes": 60 }));
+
+        let other = Histogram::of_sessions(
+            HistogramKind::Weekday,
+            sessions,
+            &interval,
+            Some("play"),
+            interval.end,
+        );
+        assert!(other.seconds.iter().all(|seconds| *seconds == 0));
+    }
+
+    #[test]
+    fn test_grid() {
+        let monday = NaiveDate::from_ymd(2026, 9, 7);
+        let at = |day: NaiveDate, hour, minute| {
+            Local
+                .from_local_datetime(&day.and_hms(hour, minute, 0))
+                .unwrap()
+                .timestamp()
+        };
+        let session = |start, stop| Session::new(start, Some(stop), "work", None);
+        let sessions = vec![
+            session(at(monday, 9, 0), at(monday, 9, 30)),
+            session(at(monday.succ(), 9, 15), at(monday.succ(), 9, 20)),
+            session(at(monday.succ(), 10, 50), at(monday.succ(), 11, 5)),
+        ];
+        let interval = Interval::new(at(monday, 0, 0), Some(at(monday, 0, 0) + 7 * 86400));
+        let grid = Grid::of_sessions(sessions, &interval, interval.end);
+        assert_eq!(grid.seconds[1][43], 600);
+        assert_eq!(grid.seconds[1][44], 300);
+        assert_eq!(
+            grid.as_text(&TimeFormat::Minutes, 80, &Plain),
+            "     09  10  11\n\
+             Mon  ██··········\n\
+             Tue  ·▒·····▓▒···\n\
+             Wed  ············\n\
+             Thu  ············\n\
+             Fri  ············\n\
+             Sat  ············\n\
+             Sun  ············\n\
+             \n\
+             Each cell covers 15 minutes, █ marks the most time worked within a cell: 15"
+        );
+        // Hours are merged into fewer cells to fit narrow terminals.
+        assert!(grid
+            .as_text(&TimeFormat::Minutes, 8, &Plain)
+            .contains("Mon  █··\n"));
+    }
+}
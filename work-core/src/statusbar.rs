@@ -0,0 +1,217 @@
+use serde_json::json;
+
+use crate::arguments::{BarStyle, TimeFormat};
+use crate::log_file::Event;
+use crate::time;
+
+/// Color of the i3blocks block while work is in progress.
+const WORKING_COLOR: &str = "#98c379";
+
+/// Colors of the tmux segment while work is in progress and while it isn't, in the names tmux
+/// understands.
+const TMUX_WORKING_COLOR: &str = "green";
+const TMUX_FREE_COLOR: &str = "colour244";
+
+/// Text shown while no work is in progress.
+const FREE_STR: &str = "Free";
+
+/// Renders the status of the log, given its final entry, in the format of the given status bar.
+///
+/// * `waybar` gets a JSON object with `text`, `tooltip`, `class` and `alt` fields, where `class`
+///   and `alt` are either `working` or `free`. Use it with `return-type` set to `json`.
+/// * `polybar` gets the text alone.
+/// * `i3blocks` gets the full text, short text and color of the block on separate lines.
+/// * `tmux` gets the text on a single line, colored with `#[fg=...]` and followed by `#[default]`
+///   so whatever follows it in the status line keeps its own style.
+///
+/// While working, the text shows the project along with how long it has been worked on, or how
+/// much time is left of work that is scheduled to stop.
+pub fn render(entry: &Option<(i64, Event)>, now: i64, style: BarStyle) -> String {
+    let (text, short_text, tooltip, class) = match entry {
+        Some((start, event @ Event::Start(..))) => {
+            let elapsed = time::format_elapsed(now - start);
+            (
+                format!("{} {}", bar_name(event), elapsed),
+                elapsed,
+                format!(
+                    "Working on {} since {}",
+                    event.to_string(),
+                    time::format_clock(*start)
+                ),
+                "working",
+            )
+        }
+        Some((stop, event @ Event::Stop(..))) if *stop > now => {
+            let left = format!("{} left", time::format_elapsed(stop - now));
+            (
+                format!("{} {}", bar_name(event), left),
+                left,
+                format!(
+                    "Working on {} until {}, {} remaining",
+                    event.to_string(),
+                    time::format_clock(*stop),
+                    time::format_time(&TimeFormat::HumanReadable, stop - now).to_lowercase()
+                ),
+                "working",
+            )
+        }
+        _ => (
+            FREE_STR.to_string(),
+            FREE_STR.to_string(),
+            "No work in progress".to_string(),
+            "free",
+        ),
+    };
+
+    match style {
+        BarStyle::Waybar => json!({
+            "text": text,
+            "tooltip": tooltip,
+            "class": class,
+            "alt": class,
+        })
+        .to_string(),
+        BarStyle::Polybar => text,
+        BarStyle::I3blocks => {
+            let color = if class == "working" {
+                WORKING_COLOR
+            } else {
+                ""
+            };
+            format!("{}\n{}\n{}", text, short_text, color)
+        }
+        BarStyle::Tmux => {
+            let color = if class == "working" {
+                TMUX_WORKING_COLOR
+            } else {
+                TMUX_FREE_COLOR
+            };
+            // A single `#` would start a format of its own.
+            format!("#[fg={}]{}#[default]", color, text.replace('#', "##"))
+        }
+    }
+}
+
+/// Renders the status of the log, given its final entry, as a compact indicator for a shell prompt
+/// such as `[backend 1h12m]`, or `[backend 20m left]` for work that is scheduled to stop. Nothing
+/// is rendered while no work is in progress, so the prompt stays as it was. Work running for longer
+/// than `warn_after` is flagged with an exclamation mark, such as `[backend 5h20m!]`.
+pub fn prompt(entry: &Option<(i64, Event)>, now: i64, warn_after: Option<i64>) -> String {
+    match entry {
+        Some((start, event @ Event::Start(..))) => {
+            let flag = match warn_after {
+                Some(limit) if now - start > limit => "!",
+                _ => "",
+            };
+            format!(
+                "[{} {}{}]",
+                bar_name(event),
+                time::format_compact(now - start),
+                flag
+            )
+        }
+        Some((stop, event @ Event::Stop(..))) if *stop > now => format!(
+            "[{} {} left]",
+            bar_name(event),
+            time::format_compact(stop - now)
+        ),
+        _ => String::new(),
+    }
+}
+
+// Helper function that returns the name of the project shown in the bar, which is kept short by
+// leaving out the description.
+fn bar_name(event: &Event) -> String {
+    match event {
+        Event::Start(Some(project), ..) | Event::Stop(Some(project), ..) => project.to_string(),
+        _ => "Working".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_file::Metadata;
+    use serde_json::Value;
+
+    #[test]
+    fn test_render_working() {
+        let entry = Some((
+            1000,
+            Event::Start(Some("work".to_string()), None, None, None, Metadata::new()),
+        ));
+        let now = 1000 + 2 * 3600 + 5 * 60;
+
+        let waybar: Value = serde_json::from_str(&render(&entry, now, BarStyle::Waybar)).unwrap();
+        assert_eq!(waybar["text"], "work 2:05");
+        assert_eq!(waybar["class"], "working");
+        assert_eq!(render(&entry, now, BarStyle::Polybar), "work 2:05");
+        assert_eq!(
+            render(&entry, now, BarStyle::I3blocks),
+            format!("work 2:05\n2:05\n{}", WORKING_COLOR)
+        );
+    }
+
+    #[test]
+    fn test_render_scheduled_and_free() {
+        let entry = Some((
+            1000 + 1800,
+            Event::Stop(None, None, None, None, Metadata::new()),
+        ));
+        assert_eq!(render(&entry, 1000, BarStyle::Polybar), "Working 0:30 left");
+        let waybar: Value = serde_json::from_str(&render(&entry, 1000, BarStyle::Waybar)).unwrap();
+        assert!(waybar["tooltip"]
+            .as_str()
+            .unwrap()
+            .ends_with(", 30 minutes remaining"));
+        // Once the scheduled stop has passed the work is done.
+        assert_eq!(render(&entry, 5000, BarStyle::Polybar), FREE_STR);
+        assert_eq!(render(&None, 5000, BarStyle::I3blocks), "Free\nFree\n");
+    }
+
+    #[test]
+    fn test_render_tmux() {
+        let entry = Some((
+            1000,
+            Event::Start(Some("#42".to_string()), None, None, None, Metadata::new()),
+        ));
+        assert_eq!(
+            render(&entry, 1000 + 3600 + 12 * 60, BarStyle::Tmux),
+            "#[fg=green]##42 1:12#[default]"
+        );
+        assert_eq!(
+            render(&None, 1000, BarStyle::Tmux),
+            "#[fg=colour244]Free#[default]"
+        );
+    }
+
+    #[test]
+    fn test_prompt() {
+        let start = Some((
+            1000,
+            Event::Start(
+                Some("backend".to_string()),
+                None,
+                None,
+                None,
+                Metadata::new(),
+            ),
+        ));
+        assert_eq!(
+            prompt(&start, 1000 + 3600 + 12 * 60, None),
+            "[backend 1h12m]"
+        );
+        assert_eq!(
+            prompt(&start, 1000 + 3600 + 12 * 60, Some(3600)),
+            "[backend 1h12m!]"
+        );
+        assert_eq!(prompt(&start, 1000 + 1800, Some(3600)), "[backend 30m]");
+        let stop = Some((
+            1000 + 1200,
+            Event::Stop(None, None, None, None, Metadata::new()),
+        ));
+        assert_eq!(prompt(&stop, 1000, Some(60)), "[Working 20m left]");
+        assert_eq!(prompt(&stop, 5000, None), "");
+        assert_eq!(prompt(&None, 5000, None), "");
+    }
+}
@@ -0,0 +1,228 @@
+use lazy_static::*;
+use regex::Regex;
+
+use crate::fuzzy;
+
+/// The forms of time input Work understands, listed after a time input that couldn't be parsed.
+pub const TIME_FORMS: &str = "9, 9:30, 9am, noon, 31 9:30, 31-12 9:30, 31-12-2026 9:30, 2h, \
+                              90m, 1:30h, 3d, 2w, now, today, yesterday, friday 9am, last friday, \
+                              2 days ago, in 3 hours";
+
+/// Words of natural time inputs, which misspelled words are matched against.
+const WORDS: [&str; 26] = [
+    "now",
+    "today",
+    "yesterday",
+    "tomorrow",
+    "noon",
+    "midnight",
+    "this-week",
+    "this",
+    "week",
+    "last",
+    "next",
+    "ago",
+    "in",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+    "minutes",
+    "hours",
+    "days",
+    "weeks",
+    "am",
+    "pm",
+];
+
+// Patterns of common near-misses of time inputs.
+lazy_static! {
+    // A time of day with another separator than a colon, e.g. 9.30.
+    static ref WRONG_SEPARATOR: Regex = Regex::new(r"^(\d{1,2})[.,;h](\d{2})$").unwrap();
+    // A time of day, whether or not it is in range, e.g. 24:00.
+    static ref CLOCK: Regex = Regex::new(r"^(\d+):(\d+)$").unwrap();
+    // A number on its own, e.g. 90.
+    static ref NUMBER: Regex = Regex::new(r"^\d+$").unwrap();
+    // A time on the 12-hour clock, whether or not it is in range, e.g. 13pm.
+    static ref AM_PM: Regex = Regex::new(r"^(\d+)(?::\d+)?\s*(am|pm)$").unwrap();
+    // A number followed by a unit other than the short ones, e.g. 2hours or 30 min.
+    static ref LONG_UNIT: Regex =
+        Regex::new(r"^(\d+)\s*(hrs?|hours?|mins?|minutes?|days?|weeks?|h|m|d|w)$").unwrap();
+    // A date with slashes or dots, e.g. 31/12 9:30.
+    static ref OTHER_DATE_SEPARATOR: Regex =
+        Regex::new(r"^(\d{1,2})[/.](\d{1,2})(?:[/.](\d{4}))?(\s.*)?$").unwrap();
+    // A date, whether or not it is in range, optionally followed by anything else.
+    static ref DATE: Regex = Regex::new(r"^(\d+)(?:-(\d+))?(?:-(\d{4}))?(\s.*)?$").unwrap();
+}
+
+/// Suggests what the user most likely meant by a time input that couldn't be parsed, or returns
+/// `None` if it doesn't look like a near-miss of any of the forms Work understands.
+///
+/// # Examples
+/// ```
+/// # use work_core::suggest::time_input;
+/// assert_eq!(time_input("9.30").unwrap(), "Did you mean 9:30?");
+/// assert_eq!(time_input("24:00").unwrap(), "Hours go up to 23, did you mean midnight?");
+/// assert_eq!(time_input("last fridya").unwrap(), "Did you mean last friday?");
+/// assert_eq!(time_input("someday"), None);
+/// ```
+pub fn time_input(unit: &str) -> Option<String> {
+    let unit = unit.trim().to_lowercase();
+
+    if let Some(captures) = WRONG_SEPARATOR.captures(&unit) {
+        let (hour, minute) = (number(&captures[1]), number(&captures[2]));
+        if hour < 24 && minute < 60 {
+            return Some(format!("Did you mean {}:{}?", hour, &captures[2]));
+        }
+    }
+    if let Some(captures) = CLOCK.captures(&unit) {
+        let (hour, minute) = (number(&captures[1]), number(&captures[2]));
+        return Some(if hour == 24 && minute == 0 {
+            "Hours go up to 23, did you mean midnight?".to_string()
+        } else if hour >= 24 {
+            format!(
+                "Hours go up to 23, did you mean {}h ago, or {}:{}h for hours and minutes?",
+                hour, hour, &captures[2]
+            )
+        } else if minute < 60 {
+            format!(
+                "Minutes take two digits, did you mean {}:{:02}?",
+                hour, minute
+            )
+        } else {
+            "Minutes go up to 59.".to_string()
+        });
+    }
+    if NUMBER.is_match(&unit) {
+        let amount = number(&unit);
+        return Some(format!(
+            "Hours go up to 23, did you mean {}m or {}h ago? Relative times need a unit.",
+            amount, amount
+        ));
+    }
+    if let Some(captures) = AM_PM.captures(&unit) {
+        if number(&captures[1]) > 12 {
+            return Some("The 12-hour clock goes up to 12, e.g. 9pm or 12am.".to_string());
+        }
+    }
+    if let Some(captures) = LONG_UNIT.captures(&unit) {
+        let short = &captures[2][..1];
+        return Some(format!(
+            "Did you mean {}{}, or {} {} ago?",
+            &captures[1],
+            short,
+            &captures[1],
+            long_unit(short)
+        ));
+    }
+    if let Some(captures) = OTHER_DATE_SEPARATOR.captures(&unit) {
+        let mut date = format!("{}-{}", &captures[1], &captures[2]);
+        if let Some(year) = captures.get(3) {
+            date.push_str(&format!("-{}", year.as_str()));
+        }
+        let rest = captures.get(4).map_or(" 9:00", |rest| rest.as_str());
+        return Some(format!("Dates use dashes, did you mean {}{}?", date, rest));
+    }
+    if let Some(captures) = DATE.captures(&unit) {
+        if number(&captures[1]) > 31 {
+            return Some("Days go up to 31.".to_string());
+        }
+        if captures
+            .get(2)
+            .is_some_and(|month| number(month.as_str()) > 12)
+        {
+            return Some("Months go up to 12.".to_string());
+        }
+        if captures.get(2).is_some() && captures.get(4).is_none() {
+            return Some(format!(
+                "A day needs a time of day as well, did you mean {} 0:00?",
+                unit
+            ));
+        }
+    }
+
+    let mut corrected = false;
+    let words: Vec<&str> = unit
+        .split_whitespace()
+        .map(|word| match fuzzy::closest(word, WORDS.iter().copied()) {
+            Some(closest) if word.chars().all(|c| c.is_alphabetic() || c == '-') => {
+                corrected = true;
+                closest
+            }
+            _ => word,
+        })
+        .collect();
+    if corrected {
+        return Some(format!("Did you mean {}?", words.join(" ")));
+    }
+    None
+}
+
+// Helper function that parses a number the patterns above already made sure of, saturating
+// numbers too large to fit.
+fn number(digits: &str) -> u64 {
+    digits.parse().unwrap_or(u64::MAX)
+}
+
+// Helper function that returns the word for the unit of a relative time input.
+fn long_unit(short: &str) -> &'static str {
+    match short {
+        "h" => "hours",
+        "m" => "minutes",
+        "d" => "days",
+        _ => "weeks",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_input() {
+        let suggest = |unit| time_input(unit).unwrap();
+        assert_eq!(suggest("9,30"), "Did you mean 9:30?");
+        assert_eq!(suggest("9h30"), "Did you mean 9:30?");
+        assert_eq!(
+            suggest("25:30"),
+            "Hours go up to 23, did you mean 25h ago, or 25:30h for hours and minutes?"
+        );
+        assert_eq!(suggest("9:75"), "Minutes go up to 59.");
+        assert_eq!(
+            suggest("9:5"),
+            "Minutes take two digits, did you mean 9:05?"
+        );
+        assert_eq!(
+            suggest("90"),
+            "Hours go up to 23, did you mean 90m or 90h ago? Relative times need a unit."
+        );
+        assert_eq!(
+            suggest("13pm"),
+            "The 12-hour clock goes up to 12, e.g. 9pm or 12am."
+        );
+        assert_eq!(suggest("2 hours"), "Did you mean 2h, or 2 hours ago?");
+        assert_eq!(suggest("30min"), "Did you mean 30m, or 30 minutes ago?");
+        assert_eq!(
+            suggest("31/12 9:30"),
+            "Dates use dashes, did you mean 31-12 9:30?"
+        );
+        assert_eq!(
+            suggest("31.12.2026"),
+            "Dates use dashes, did you mean 31-12-2026 9:00?"
+        );
+        assert_eq!(suggest("32 9:00"), "Days go up to 31.");
+        assert_eq!(suggest("3-13 9:00"), "Months go up to 12.");
+        assert_eq!(
+            suggest("3-12"),
+            "A day needs a time of day as well, did you mean 3-12 0:00?"
+        );
+        assert_eq!(suggest("Yesterdy"), "Did you mean yesterday?");
+        assert_eq!(suggest("next fridya 9am"), "Did you mean next friday 9am?");
+        assert!(time_input("99999999999999999999999:00").is_some());
+        assert_eq!(suggest("fridya 9am"), "Did you mean friday 9am?");
+        assert_eq!(time_input("last week"), None);
+    }
+}
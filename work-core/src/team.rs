@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+
+use crate::arguments::TimeFormat;
+use crate::error::{AppError, ErrorCode, ErrorKind};
+use crate::log_file::LogFile;
+use crate::project_map::{duration_json, ProjectMap, ProjectMapMethods};
+use crate::report::{total, totals};
+use crate::time::{self, format_time, Interval};
+
+/// Format of the dates of a team report.
+const DATE_FORMAT: &str = "%a %d-%m-%Y";
+
+/// Format of the days in the JSON value of a team report.
+const VALUE_FORMAT: &str = "%Y-%m-%d";
+
+/// Name of the log within the directory of a member, such as a synced copy of their data directory.
+const LOG: &str = "work.log";
+
+/// Label of the total time spent by the whole team.
+const TOTAL_STR: &str = "Total";
+
+/// The `TeamReport` struct holds the time each member of a team spent on each project within an
+/// interval, out of the logs of the whole team. It sits on top of the `ProjectMap` of each member,
+/// which are added up per project to tell who worked on what.
+#[derive(Clone, Debug)]
+pub struct TeamReport {
+    /// First day of the report.
+    pub first_day: NaiveDate,
+    /// Last day of the report.
+    pub last_day: NaiveDate,
+    /// The time each member spent on each project. Members without any work are left out.
+    pub members: BTreeMap<String, ProjectMap>,
+}
+
+impl TeamReport {
+    /// Tallies the time spent on each project within the given interval in the log of each member.
+    /// Project names are normalized if `normalize` is set, see `normalize_project`, which unifies
+    /// projects whose names the members spell differently. An interval that reaches back before
+    /// the earliest log starts is shortened to start with it.
+    pub fn new(
+        logs: &mut [(String, LogFile)],
+        interval: &Interval,
+        normalize: bool,
+    ) -> Result<Self, AppError> {
+        let mut first = None;
+        let mut members = BTreeMap::new();
+        for (member, log) in logs.iter_mut() {
+            if let Some(timestamp) = log.first_timestamp()? {
+                first = Some(first.map_or(timestamp, |first: i64| first.min(timestamp)));
+            }
+            if let Some(projects) = log.tally_time(interval)? {
+                let projects = if normalize {
+                    projects.normalized()
+                } else {
+                    projects
+                };
+                members
+                    .entry(member.to_string())
+                    .or_insert_with(ProjectMap::new)
+                    .merge(&projects);
+            }
+        }
+
+        let start = match first {
+            Some(first) => interval.start.max(first).min(interval.end),
+            None => interval.start,
+        };
+        Ok(TeamReport {
+            first_day: time::date_of(start),
+            last_day: time::date_of((interval.end - 1).max(start)),
+            members,
+        })
+    }
+
+    /// Returns true if no member did any work within the report.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Returns the time the whole team spent on each project.
+    pub fn projects(&self) -> ProjectMap {
+        let mut projects = ProjectMap::new();
+        for member in self.members.values() {
+            projects.merge(member);
+        }
+        projects
+    }
+
+    /// Returns the time each member spent on each project, keyed by project and then by member.
+    pub fn by_project(&self) -> BTreeMap<&str, BTreeMap<&str, i64>> {
+        let mut by_project: BTreeMap<&str, BTreeMap<&str, i64>> = BTreeMap::new();
+        for (member, projects) in &self.members {
+            for (project, time) in totals(projects) {
+                *by_project
+                    .entry(project.as_str())
+                    .or_default()
+                    .entry(member.as_str())
+                    .or_insert(0) += time;
+            }
+        }
+        by_project
+    }
+
+    /// Returns the title of the report.
+    pub fn title(&self) -> String {
+        format!(
+            "Team report for {} - {}",
+            self.first_day.format(DATE_FORMAT),
+            self.last_day.format(DATE_FORMAT)
+        )
+    }
+
+    /// Returns the report as plain text, the time the team spent on each project followed by the
+    /// time each member spent on it, and finally the time each member spent in total.
+    pub fn as_text(&self, time_format: &TimeFormat) -> String {
+        let mut text = format!("{}\n\n", self.title());
+        for (project, members) in self.by_project() {
+            let time = members.values().sum();
+            text.push_str(&format!(
+                "{}: {}\n",
+                project,
+                format_time(time_format, time)
+            ));
+            for (member, time) in members {
+                text.push_str(&format!(
+                    "  {}: {}\n",
+                    member,
+                    format_time(time_format, time)
+                ));
+            }
+        }
+
+        text.push_str("\nMembers\n");
+        for (member, projects) in &self.members {
+            text.push_str(&format!(
+                "  {}: {}\n",
+                member,
+                format_time(time_format, total(projects))
+            ));
+        }
+        text.push_str(&format!(
+            "{}: {}\n",
+            TOTAL_STR,
+            format_time(time_format, total(&self.projects()))
+        ));
+        text
+    }
+
+    /// Returns the report as a JSON value. Times are objects holding both the seconds and the
+    /// formatted time, or just the seconds if `raw_seconds` is set.
+    pub fn to_value(&self, time_format: &TimeFormat, raw_seconds: bool) -> Value {
+        let duration = |seconds| duration_json(time_format, seconds, raw_seconds);
+        let projects: Vec<Value> = self
+            .by_project()
+            .iter()
+            .map(|(project, members)| {
+                let time = members.values().sum();
+                let members: Vec<Value> = members
+                    .iter()
+                    .map(|(member, time)| json!({ "member": member, "time": duration(*time) }))
+                    .collect();
+                json!({ "project": project, "time": duration(time), "members": members })
+            })
+            .collect();
+        let members: Vec<Value> = self
+            .members
+            .iter()
+            .map(
+                |(member, projects)| json!({ "member": member, "time": duration(total(projects)) }),
+            )
+            .collect();
+        json!({
+            "first_day": self.first_day.format(VALUE_FORMAT).to_string(),
+            "last_day": self.last_day.format(VALUE_FORMAT).to_string(),
+            "projects": projects,
+            "members": members,
+            "total": duration(total(&self.projects())),
+        })
+    }
+}
+
+/// Opens the log of each member of a team within the given directory, along with the name of the
+/// member. A log is either a file such as `alice.log`, named after the member, or a directory such
+/// as `alice/` holding a `work.log`, like a synced copy of the data directory of the member. The
+/// logs are opened read-only and sorted by member.
+pub fn member_logs(dir: &Path) -> Result<Vec<(String, LogFile)>, AppError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(AppError::new(ErrorKind::User(format!(
+                "The directory {} doesn't exist.",
+                dir.display()
+            )))
+            .with_code(ErrorCode::LogNotFound))
+        }
+        Err(e) => return Err(AppError::from(e)),
+    };
+
+    let mut paths = BTreeMap::new();
+    for entry in entries {
+        let path = entry?.path();
+        let name = |name: Option<&OsStr>| name.map(|name| name.to_string_lossy().to_string());
+        if path.is_dir() && path.join(LOG).is_file() {
+            if let Some(member) = name(path.file_name()) {
+                paths.insert(member, path.join(LOG));
+            }
+        } else if path.is_file() && path.extension() == Some(OsStr::new("log")) {
+            if let Some(member) = name(path.file_stem()) {
+                paths.insert(member, path);
+            }
+        }
+    }
+    // Hidden files and directories aren't members, such as the backups of a synced directory.
+    paths.retain(|member, _| !member.starts_with('.'));
+    if paths.is_empty() {
+        return Err(AppError::new(ErrorKind::User(format!(
+            "No logs were found in {}, it should hold a log per member such as alice.log, or a \
+             directory per member holding {}.",
+            dir.display(),
+            LOG
+        )))
+        .with_code(ErrorCode::LogNotFound));
+    }
+
+    paths
+        .into_iter()
+        .map(|(member, path)| {
+            let mut log = LogFile::from_path(path)?;
+            log.set_read_only(true);
+            Ok((member, log))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_file::Event;
+
+    fn log(lines: &[(i64, &str)]) -> LogFile {
+        let mut log = LogFile::in_memory();
+        for (timestamp, line) in lines {
+            log.append_event(&Event::from(*line), *timestamp).unwrap();
+        }
+        log
+    }
+
+    #[test]
+    fn test_team_report() {
+        let mut logs = vec![
+            (
+                "alice".to_string(),
+                log(&[(0, "0,Start,Backend,"), (3600, "3600,Stop,Backend,")]),
+            ),
+            (
+                "bob".to_string(),
+                log(&[(0, "0,Start,backend,"), (1800, "1800,Stop,backend,")]),
+            ),
+            ("carol".to_string(), log(&[])),
+        ];
+        let interval = Interval::new(0, Some(7200));
+        let report = TeamReport::new(&mut logs, &interval, true).unwrap();
+        assert_eq!(report.members.len(), 2);
+
+        let by_project = report.by_project();
+        assert_eq!(by_project.len(), 1);
+        assert_eq!(by_project["backend"]["alice"], 3600);
+        assert_eq!(by_project["backend"]["bob"], 1800);
+        let value = report.to_value(&TimeFormat::Minutes, true);
+        assert_eq!(value["projects"][0]["time"], 5400);
+        assert_eq!(value["total"], 5400);
+
+        let report = TeamReport::new(&mut logs, &interval, false).unwrap();
+        assert_eq!(report.by_project().len(), 2);
+    }
+
+    #[test]
+    fn test_member_logs() {
+        let dir = std::env::temp_dir().join(format!("work-team-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("bob")).unwrap();
+        fs::write(dir.join("alice.log"), "0,Start,work,\n").unwrap();
+        fs::write(dir.join("bob").join(LOG), "0,Start,work,\n").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let logs = member_logs(&dir).unwrap();
+        let members: Vec<&str> = logs.iter().map(|(member, _)| member.as_str()).collect();
+        assert_eq!(members, vec!["alice", "bob"]);
+        assert!(logs.iter().all(|(_, log)| log.is_read_only()));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(member_logs(&dir).is_err());
+    }
+}
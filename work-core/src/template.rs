@@ -0,0 +1,68 @@
+use std::error::Error;
+use std::fs;
+
+use serde_json::Value;
+use tera::{Context, Tera};
+
+use crate::error::{AppError, ErrorKind};
+
+/// Renders the template in the file at the given path with the given model, such as the one
+/// returned by `Report::to_value`. Templates are written in the Tera template language, see
+/// https://keats.github.io/tera/docs/ for its syntax.
+///
+/// The output isn't escaped, since templates can produce anything from HTML to LaTeX.
+pub fn render_file(path: &str, model: &Value) -> Result<String, AppError> {
+    let template = fs::read_to_string(path).map_err(|e| {
+        AppError::new(ErrorKind::User(format!(
+            "Unable to read the template {}: {}",
+            path, e
+        )))
+        .with_source(e)
+    })?;
+    render(&template, model).map_err(|message| {
+        AppError::new(ErrorKind::User(format!(
+            "Unable to render the template {}: {}",
+            path, message
+        )))
+    })
+}
+
+// Helper function that renders a template with the given model, returning every cause of a
+// failure as its message since Tera's errors only tell the whole story along with their sources.
+fn render(template: &str, model: &Value) -> Result<String, String> {
+    let context = Context::from_value(model.clone()).map_err(|e| e.to_string())?;
+    Tera::one_off(template, &context, false).map_err(|e| {
+        let mut message = e.to_string();
+        let mut source = e.source();
+        while let Some(cause) = source {
+            message.push_str(&format!(": {}", cause));
+            source = cause.source();
+        }
+        message
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render() {
+        let model = json!({
+            "title": "Report",
+            "projects": [
+                { "name": "<work>", "time": { "seconds": 3600, "formatted": "1 hour" } },
+                { "name": "play", "time": { "seconds": 60, "formatted": "1 minute" } },
+            ],
+        });
+        let template = "{{ title }}\n{% for p in projects %}{{ p.name }} & {{ p.time.seconds }}\\\\\n{% endfor %}";
+        assert_eq!(
+            render(template, &model).unwrap(),
+            "Report\n<work> & 3600\\\\\nplay & 60\\\\\n"
+        );
+        assert!(render("{{ missing }}", &model)
+            .unwrap_err()
+            .contains("missing"));
+    }
+}
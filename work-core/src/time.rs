@@ -0,0 +1,1729 @@
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use lazy_static::*;
+use regex::Regex;
+
+use crate::arguments::TimeFormat;
+use crate::error::{AppError, ErrorCode, ErrorKind};
+use crate::suggest;
+
+/// Full name for an hour unit
+const HOUR_STR: &str = "hours";
+
+/// Full name for a minute unit
+const MINUTE_STR: &str = "minutes";
+
+/// Number of minutes it takes to approximate to an extra hour
+const APPROX_HOUR: i64 = 30;
+
+/// Number of minutes it takes to approximate to the next half an hour.
+/// Also used to approximate minutes to MINUTES divisable by 15.
+const APPROX_MINUTES: i64 = 15;
+
+/// Number of minutes in an hour
+const MINUTES_IN_HOUR: i64 = 60;
+
+/// Number of seconds in each of the units of relative time inputs
+const SECONDS_IN_MINUTE: i64 = 60;
+const SECONDS_IN_HOUR: i64 = 60 * SECONDS_IN_MINUTE;
+const SECONDS_IN_DAY: i64 = 24 * SECONDS_IN_HOUR;
+const SECONDS_IN_WEEK: i64 = 7 * SECONDS_IN_DAY;
+
+/// The `Clock` trait tells Work what time it is.
+///
+/// Everything that depends on the current time takes a clock, from resolving ambiguous time inputs
+/// such as `31 20:59` to the events a `LogFile` appends, see `LogFile::set_clock`. Passing a
+/// `FixedClock` instead of the `SystemClock` lets tests and library users freeze time.
+pub trait Clock {
+    /// Returns the current UNIX timestamp.
+    fn now(&self) -> i64;
+
+    /// Returns the current local date.
+    fn today(&self) -> NaiveDate {
+        date_of(self.now())
+    }
+}
+
+/// The `SystemClock` struct reads the time from the system, it is the clock Work uses by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        Local::now().timestamp()
+    }
+}
+
+/// The `FixedClock` struct is a clock that is frozen at a given UNIX timestamp.
+///
+/// # Examples
+/// ```
+/// # use chrono::NaiveDate;
+/// # use work_core::time::{self, FixedClock};
+/// let noon = FixedClock::at(NaiveDate::from_ymd(2026, 10, 16).and_hms(12, 0, 0));
+/// assert_eq!(
+///     time::parse_datetime("9", time::Search::Forward, &noon).unwrap(),
+///     NaiveDate::from_ymd(2026, 10, 17).and_hms(9, 0, 0)
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedClock(pub i64);
+
+impl FixedClock {
+    /// Returns a clock frozen at the given local date and time. Times skipped by daylight saving
+    /// time are moved an hour ahead.
+    pub fn at(date_time: NaiveDateTime) -> Self {
+        let local = Local
+            .from_local_datetime(&date_time)
+            .earliest()
+            .or_else(|| {
+                Local
+                    .from_local_datetime(&(date_time + Duration::hours(1)))
+                    .earliest()
+            })
+            .map_or(0, |date_time| date_time.timestamp());
+        FixedClock(local)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> i64 {
+        self.0
+    }
+}
+
+// Helper function for tests, returning a clock frozen at the given local time.
+#[cfg(test)]
+pub(crate) fn frozen(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> FixedClock {
+    FixedClock::at(NaiveDate::from_ymd(year, month, day).and_hms(hour, minute, 0))
+}
+
+/// Returns the current UNIX timestamp according to the system clock.
+pub fn now() -> i64 {
+    SystemClock.now()
+}
+
+/// Function that counts the hours in a given timestamp and returns an approximation of them.
+///
+/// If there are more than APPROX_HOUR minutes found as a remainder they will be counted as one hour.
+/// If there are more than APPROX_MINUTES minutes found as a remainder they will be counted as half
+/// an hour.
+///
+/// For example:
+/// ```
+/// # use work_core::time::approximate_hours;
+/// // 2 hours and 25 minutes -> 3 hours
+/// assert_eq!(approximate_hours((2 * 60 * 60) + (25 * 60)), 2.5);
+/// assert_eq!(approximate_hours(31 * 60), 1.0);
+/// assert_eq!(approximate_hours(16 * 60), 0.5);
+/// assert_eq!(approximate_hours(14 * 60), 0.0);
+/// ```
+fn approximate_hours(duration: i64) -> f64 {
+    let duration = Duration::seconds(duration);
+    let mut answer: f64 = duration.num_hours() as f64;
+    let remainder_minutes = duration.num_minutes() - (duration.num_hours() * 60);
+
+    if remainder_minutes > APPROX_HOUR {
+        answer += 1.0;
+    } else if remainder_minutes > APPROX_MINUTES {
+        answer += 0.5;
+    }
+    answer
+}
+
+/// Function that counts the minutes in a given timestamp and returns an approximation of them.
+///
+/// The approximation is in respect to APPROX_MINUTES, it adds just enough minutes such that the
+/// total number of minutes is dividable by APPROX_MINUTES.
+///
+/// For example:
+/// ```
+/// # use work_core::time::approximate_minutes;
+/// assert_eq!(approximate_minutes(16 * 60), 30);
+/// assert_eq!(approximate_minutes(15 * 60), 15);
+/// assert_eq!(approximate_minutes(31 * 60), 45);
+/// assert_eq!(approximate_minutes(14 * 60), 15);
+/// ```
+fn approximate_minutes(duration: i64) -> i64 {
+    let duration = Duration::seconds(duration);
+    let answer = duration.num_minutes();
+    let remainder_minutes = APPROX_MINUTES - (answer % APPROX_MINUTES);
+
+    if remainder_minutes != APPROX_MINUTES {
+        return answer + remainder_minutes;
+    }
+    answer
+}
+
+// Helper function for get_human_readable_form.
+// This function receives the total number of hours and remaining minutes and formats them to a
+// string.
+fn format_human_readable(hours: i64, minutes: i64) -> String {
+    let unit_format = |units, unit_name: &str| {
+        if units == 0 {
+            "".to_string()
+        } else if units == 1 {
+            // Remove "s" from unit_name to make it singular.
+            // This should be kept in mind if a unit that doesn't have an added "s" in plural is
+            // later added.
+            format!("1 {}", &unit_name[..unit_name.len() - 1])
+        } else {
+            format!("{} {}", units, unit_name)
+        }
+    };
+
+    if hours == 0 && minutes == 0 {
+        format!("Less than a minute")
+    } else if hours == 0 {
+        unit_format(minutes, MINUTE_STR)
+    } else if minutes == 0 {
+        unit_format(hours, HOUR_STR)
+    } else if hours == 1 && minutes == 1 {
+        "1 hour and 1 minute".to_string()
+    } else if hours == 1 {
+        format!("1 hour and {}", unit_format(minutes, MINUTE_STR))
+    } else if minutes == 1 {
+        format!("{} and 1 minute", unit_format(hours, HOUR_STR))
+    } else {
+        format!("{} hours and {} minutes", hours, minutes)
+    }
+}
+
+/// Receives number of seconds which signals a duration and returns the duration in human readable
+/// form.
+///
+/// # Example
+/// ```
+/// # use chrono::Duration;
+/// # use work_core::time::get_human_readable_form;
+/// assert_eq!(get_human_readable_form(Duration::seconds(15).num_seconds()), "Less than a minute");
+/// assert_eq!(get_human_readable_form(Duration::seconds(30).num_seconds()), "Less than a minute");
+/// assert_eq!(get_human_readable_form(Duration::seconds(60).num_seconds()), "1 minute");
+/// assert_eq!(get_human_readable_form(Duration::seconds(120).num_seconds()), "2 minutes");
+/// assert_eq!(get_human_readable_form(Duration::seconds(3600).num_seconds()), "1 hour");
+/// assert_eq!(get_human_readable_form(Duration::seconds(7200).num_seconds()), "2 hours");
+/// assert_eq!(get_human_readable_form(Duration::seconds(3660).num_seconds()), "1 hour and 1 minute");
+/// assert_eq!(get_human_readable_form(Duration::seconds(3720).num_seconds()), "1 hour and 2 minutes");
+/// assert_eq!(get_human_readable_form(Duration::seconds(7320).num_seconds()), "2 hours and 2 minutes");
+/// ```
+fn get_human_readable_form(duration: i64) -> String {
+    let duration = Duration::seconds(duration);
+    let total_hours = duration.num_hours();
+    let total_minutes = duration.num_minutes() % MINUTES_IN_HOUR;
+    format_human_readable(total_hours, total_minutes)
+}
+
+pub fn format_time(format: &TimeFormat, time: i64) -> String {
+    match format {
+        TimeFormat::Minutes => format!("{}", get_minutes(time)),
+        TimeFormat::MinutesApprox => format!("{}", approximate_minutes(time)),
+        TimeFormat::HoursApprox => format!("{}", approximate_hours(time)),
+        TimeFormat::HumanReadable => get_human_readable_form(time),
+    }
+}
+
+/// Formats a difference in time along with its sign, such as the time worked over or under a goal.
+///
+/// # Examples
+/// ```
+/// # use work_core::arguments::TimeFormat;
+/// # use work_core::time::format_delta;
+/// assert_eq!(format_delta(&TimeFormat::Minutes, 90 * 60), "+90");
+/// assert_eq!(format_delta(&TimeFormat::Minutes, -45 * 60), "-45");
+/// assert_eq!(format_delta(&TimeFormat::Minutes, 0), "0");
+/// ```
+pub fn format_delta(format: &TimeFormat, delta: i64) -> String {
+    let sign = if delta > 0 {
+        "+"
+    } else if delta < 0 {
+        "-"
+    } else {
+        ""
+    };
+    format!("{}{}", sign, format_time(format, delta.abs()))
+}
+
+/// Formats the time of day of a given UNIX timestamp as `HH:MM` in local time.
+pub fn format_clock(timestamp: i64) -> String {
+    Local.timestamp(timestamp, 0).format("%H:%M").to_string()
+}
+
+/// Formats a given UNIX timestamp as a date and time of day in local time, e.g.
+/// `Mon 12-10-2026 09:30`.
+pub fn format_date_time(timestamp: i64) -> String {
+    Local
+        .timestamp(timestamp, 0)
+        .format("%a %d-%m-%Y %H:%M")
+        .to_string()
+}
+
+/// Formats a duration of seconds as hours and minutes, the way a clock would show it.
+///
+/// # Examples
+/// ```
+/// # use work_core::time::format_elapsed;
+/// assert_eq!(format_elapsed(59), "0:00");
+/// assert_eq!(format_elapsed(3 * 3600 + 7 * 60), "3:07");
+/// assert_eq!(format_elapsed(100 * 3600), "100:00");
+/// ```
+pub fn format_elapsed(duration: i64) -> String {
+    let minutes = get_minutes(duration.max(0));
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Formats a duration of seconds as compactly as possible, as hours and minutes such as `1h12m`.
+///
+/// # Examples
+/// ```
+/// # use work_core::time::format_compact;
+/// assert_eq!(format_compact(59), "0m");
+/// assert_eq!(format_compact(45 * 60), "45m");
+/// assert_eq!(format_compact(3600), "1h");
+/// assert_eq!(format_compact(3600 + 12 * 60), "1h12m");
+/// ```
+pub fn format_compact(duration: i64) -> String {
+    let minutes = get_minutes(duration.max(0));
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h{}m", hours, minutes),
+    }
+}
+
+/// Parses a duration such as `8h`, `45m`, `1h30m` or `1:30h` into seconds. Hours and minutes may
+/// be fractions and be written out as well, such as `1.5h`, `90 minutes` or `1 hour 30 min`.
+/// Unlike `parse_time_input` it never reads the input as a time of day. Returns `None` if the
+/// input isn't a duration.
+///
+/// # Examples
+/// ```
+/// # use work_core::time::parse_duration;
+/// assert_eq!(parse_duration("8h"), Some(8 * 3600));
+/// assert_eq!(parse_duration("45m"), Some(45 * 60));
+/// assert_eq!(parse_duration("1h30m"), Some(90 * 60));
+/// assert_eq!(parse_duration("1:30h"), Some(90 * 60));
+/// assert_eq!(parse_duration("1.5h"), Some(90 * 60));
+/// assert_eq!(parse_duration("90 minutes"), Some(90 * 60));
+/// assert_eq!(parse_duration("1 hour 30 mins"), Some(90 * 60));
+/// assert_eq!(parse_duration("9"), None);
+/// assert_eq!(parse_duration("soon"), None);
+/// ```
+pub fn parse_duration(input: &str) -> Option<i64> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    let captures = DURATION.captures(input)?;
+    // Only one of the two alternatives matches, the groups of the other are left empty.
+    let number = |i| {
+        captures
+            .get(i)
+            .map_or(Some(0.0), |m| m.as_str().parse::<f64>().ok())
+    };
+    let hours = number(1)? + number(3)?;
+    let minutes = number(2)? + number(4)?;
+    let seconds = (hours * SECONDS_IN_HOUR as f64 + minutes * SECONDS_IN_MINUTE as f64).round();
+    if seconds < i64::MAX as f64 {
+        Some(seconds as i64)
+    } else {
+        None
+    }
+}
+
+/// Returns the number of minutes in a given duration of seconds
+pub fn get_minutes(duration: i64) -> i64 {
+    Duration::seconds(duration).num_minutes()
+}
+
+/// Helper function fro returning midnight of today as a NaiveDateTime
+pub fn today_date_time() -> NaiveDateTime {
+    NaiveDateTime::new(SystemClock.today(), NaiveTime::from_hms(0, 0, 0))
+}
+
+/// Returns the local date of a given UNIX timestamp.
+pub fn date_of(timestamp: i64) -> NaiveDate {
+    Local.timestamp(timestamp, 0).naive_local().date()
+}
+
+/// Returns the UNIX timestamp of the local midnight that starts a given date. On days where
+/// midnight is skipped by daylight saving time, the earliest time of the day is used instead.
+pub fn start_of_day(date: NaiveDate) -> i64 {
+    (0..24)
+        .filter_map(|hour| {
+            Local
+                .from_local_datetime(&date.and_hms(hour, 0, 0))
+                .earliest()
+        })
+        .next()
+        .map_or(0, |date_time| date_time.timestamp())
+}
+
+// Helper function for returning the current time as a NaiveDateTime
+fn now_date_time(clock: &dyn Clock) -> NaiveDateTime {
+    Local.timestamp(clock.now(), 0).naive_local()
+}
+
+// Helper function for returning midnight of yesterday as a NaiveDate
+fn yesterday(clock: &dyn Clock) -> NaiveDate {
+    clock.today() - Duration::days(1)
+}
+
+// Helper function for returning midnight of tomorrow as a NaiveDate
+fn tomorrow(clock: &dyn Clock) -> NaiveDate {
+    clock.today() + Duration::days(1)
+}
+
+// Helper function for returning the last month as a NaiveDate.
+// Months that don't have the given day, like the 31st of September, are skipped.
+fn last_month(day: u32, clock: &dyn Clock) -> NaiveDate {
+    let today = clock.today();
+    let (mut year, mut month) = (today.year(), today.month());
+
+    loop {
+        if month == 1 {
+            year -= 1;
+            month = 12;
+        } else {
+            month -= 1;
+        }
+        if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+            return date;
+        }
+    }
+}
+
+// Helper function for returning the next month as a NaiveDate.
+// Months that don't have the given day, like the 31st of September, are skipped.
+fn next_month(day: u32, clock: &dyn Clock) -> NaiveDate {
+    let today = clock.today();
+    let (mut year, mut month) = (today.year(), today.month());
+
+    loop {
+        if month == 12 {
+            year += 1;
+            month = 1;
+        } else {
+            month += 1;
+        }
+        if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+            return date;
+        }
+    }
+}
+
+/// Enum to determine whether an ambiguous time should be searched for forward or backward in time.
+#[derive(Clone, Copy)]
+pub enum Search {
+    Backward,
+    Forward,
+}
+
+// This function is for when a user enters 4 o'clock as an interval but the current time is 3
+// o'clock, this function ensures that the last possible date will be used.
+fn get_ambiguous_date(
+    given_time: &NaiveTime,
+    search_type: &Search,
+    clock: &dyn Clock,
+) -> NaiveDate {
+    let curr_time = now_date_time(clock).time();
+    match (*given_time > curr_time, search_type) {
+        // Asking for a time that is seemingly in the future.
+        // Backwards search? Give back yesterday.
+        (true, Search::Backward) => yesterday(clock),
+        // Forwards search? Give back today.
+        (true, Search::Forward) => clock.today(),
+        // Asking for a time that is seemingly in the past.
+        // Backwards search? Give back today.
+        (false, Search::Backward) => clock.today(),
+        // Forwards search? Give back tomorrow.
+        (false, Search::Forward) => tomorrow(clock),
+    }
+}
+
+// This function is for when a user enters 31 20:59 as an interval but the current day is the 23rd,
+// this function ensures that the last possible month will be used, or the first one when searching
+// forwards. Months that don't have the given day, like the 31st of September, are skipped.
+fn get_ambiguous_month(
+    day: u32,
+    given_time: &NaiveTime,
+    search_type: &Search,
+    clock: &dyn Clock,
+) -> NaiveDateTime {
+    let now = now_date_time(clock);
+    let at_time = |date| NaiveDateTime::new(date, *given_time);
+    let this_month = NaiveDate::from_ymd_opt(now.year(), now.month(), day).map(at_time);
+    match (this_month, search_type) {
+        (Some(date_time), Search::Backward) if date_time <= now => date_time,
+        (_, Search::Backward) => at_time(last_month(day, clock)),
+        (Some(date_time), Search::Forward) if date_time > now => date_time,
+        (_, Search::Forward) => at_time(next_month(day, clock)),
+    }
+}
+
+// This function is for when a user enters 31-12 20:59 as an interval, it returns the last such
+// time that has passed when searching backwards and the first one to come when searching forwards.
+// A day the month never has, such as 31-2, has no such time at all.
+fn get_ambiguous_year(
+    day: u32,
+    month: u32,
+    given_time: &NaiveTime,
+    search_type: &Search,
+    clock: &dyn Clock,
+) -> Option<NaiveDateTime> {
+    // 2000 was a leap year, so any day that is in the month some year is in it in 2000.
+    NaiveDate::from_ymd_opt(2000, month, day)?;
+    let now = now_date_time(clock);
+    let mut year = now.year();
+
+    loop {
+        let date_time = NaiveDate::from_ymd_opt(year, month, day)
+            .map(|date| NaiveDateTime::new(date, *given_time));
+        match (date_time, search_type) {
+            (Some(date_time), Search::Backward) if date_time <= now => return Some(date_time),
+            (Some(date_time), Search::Forward) if date_time > now => return Some(date_time),
+            (_, Search::Backward) => year -= 1,
+            (_, Search::Forward) => year += 1,
+        }
+    }
+}
+
+// Format rules for time inputs.
+lazy_static! {
+    // Validation for at X o'clock. All hours between 0 and 23 are allowed.
+    static ref AT_HOUR: Regex = Regex::new(r"^(0?\d|1\d|2[0-3])$").unwrap();
+    // Validation for X:Y o'clock. All minutes between 0 and 59 are allowed.
+    static ref AT_HOUR_MINUTES: Regex = Regex::new(r"^(0?\d|1\d|2[0-3]):(0?\d|[1-5]\d)$").unwrap();
+    // Validation for D X:Y. All days between 1-31 are allowed.
+    static ref AT_DAY_HOUR_MINUTES: Regex =
+        Regex::new(r"^(0?[1-9]|[1-2]\d|3[01])\s(0?\d|1\d|2[0-3]):(0?\d|[1-5]\d)$").unwrap();
+    // Validation for D-M X:Y. All M between 1-12 are allowed.
+    static ref AT_DAY_MONTH_HOUR_MINUTES: Regex =
+        Regex::new(r"^(0?[1-9]|[1-2]\d|3[01])-(0?[1-9]|1[0-2])\s(0?\d|1\d|2[0-3]):(0?\d|[1-5]\d)$")
+            .unwrap();
+    // Validation for D-M-Y X:Y. All four digit Y are allowed.
+    static ref AT_DAY_MONTH_YEAR_HOUR_MINUTES: Regex = Regex::new(
+        r"^(0?[1-9]|[1-2]\d|3[01])-(0?[1-9]|1[0-2])-(\d{4})\s(0?\d|1\d|2[0-3]):(0?\d|[1-5]\d)$"
+    )
+    .unwrap();
+    // Validation for Xh. All positive X are allowed, e.g. 36h.
+    static ref HOURS_AGO_OR_UNTIL: Regex = Regex::new(r"^0*[1-9]\d*h$").unwrap();
+    // Validation for Xm. All positive X are allowed, e.g. 90m.
+    static ref MINUTES_AGO_OR_UNTIL: Regex = Regex::new(r"^0*[1-9]\d*m$").unwrap();
+    // Validation for X:Yh. All X and all Y between 0 and 59 allowed.
+    // NOTE: This allows 0:0h, which makes little sense. Should this be changed?
+    static ref HOURS_AND_MINUTES_AGO_OR_UNTIL: Regex =
+        Regex::new(r"^\d+:(0?\d|[1-5]\d)h$").unwrap();
+    // Validation for Xd. All positive X are allowed.
+    static ref DAYS_AGO_OR_UNTIL: Regex = Regex::new(r"^0*[1-9]\d*d$").unwrap();
+    // Validation for Xw. All positive X are allowed.
+    static ref WEEKS_AGO_OR_UNTIL: Regex = Regex::new(r"^0*[1-9]\d*w$").unwrap();
+    // Validation for X o'clock on the 12-hour clock, e.g. 9am or 9:30pm.
+    static ref AT_HOUR_AM_PM: Regex =
+        Regex::new(r"^(0?[1-9]|1[0-2])(?::([0-5]\d))?(am|pm)$").unwrap();
+    // Validation for X units ago or in X units, e.g. 2 days ago or in 3 hours.
+    static ref UNITS_AGO_OR_UNTIL: Regex =
+        Regex::new(r"^(?:in\s+)?(\d+)\s+(minute|hour|day|week)s?(\s+ago)?$").unwrap();
+    // Validation for durations, either hours and minutes such as 1h30m, 1.5h or 1 hour 30 minutes,
+    // or H:MM hours such as 1:30h.
+    static ref DURATION: Regex = Regex::new(
+        r"(?i)^(?:(\d+(?:\.\d+)?)\s*(?:h|hrs?|hours?))?\s*(?:(\d+(?:\.\d+)?)\s*(?:m|mins?|minutes?))?$|^(\d+):([0-5]\d)h$"
+    )
+    .unwrap();
+}
+
+// Helper function for moving the current time back (or forward, when searching forward) by a given
+// number of seconds. Returns `None` if the result can't be represented.
+fn offset_now(
+    seconds: Option<i64>,
+    search_type: &Search,
+    clock: &dyn Clock,
+) -> Option<NaiveDateTime> {
+    // `Duration` counts milliseconds internally, larger values would overflow it.
+    let duration = Duration::seconds(seconds.filter(|s| *s <= i64::MAX / 1000)?);
+    match search_type {
+        Search::Backward => now_date_time(clock).checked_sub_signed(duration),
+        Search::Forward => now_date_time(clock).checked_add_signed(duration),
+    }
+}
+
+// Helper function for the error of a relative time input that reaches too far back or forward.
+fn out_of_range(unit: &str) -> AppError {
+    AppError::new(ErrorKind::Parse(format!(
+        "Time specifier out of range: {}",
+        unit
+    )))
+    .with_code(ErrorCode::InvalidTime)
+}
+
+/// Parses a single point in time, such as `9`, `31 20:59`, `3-1-2023 9:00`, `2h` or `last friday`.
+/// Ambiguous inputs are searched for in the direction given by `search_type`, starting from the
+/// time the given clock tells.
+///
+/// # Examples
+/// ```
+/// # use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+/// # use work_core::time::{parse_datetime, Search, SystemClock};
+/// assert_eq!(
+///     parse_datetime("3-1-2023 9:00", Search::Backward, &SystemClock).unwrap(),
+///     NaiveDateTime::new(NaiveDate::from_ymd(2023, 1, 3), NaiveTime::from_hms(9, 0, 0))
+/// );
+/// assert!(parse_datetime("blursday", Search::Backward, &SystemClock).is_err());
+/// ```
+pub fn parse_datetime(
+    input: &str,
+    search_type: Search,
+    clock: &dyn Clock,
+) -> Result<NaiveDateTime, AppError> {
+    parse_time_input(input, &search_type, clock)
+}
+
+/// The `parse_time_input` function is the function that does all the heavy lifting for the parsing
+/// of the inputted interval.
+///
+/// The function goes through each of the Regex rules from here above and if any one of them
+/// matches it parses the given time unit in correspondance with the rule that was matched. The
+/// actual parsing is done by the `chrono` library, each time we parse a value we call `unwrap()`.
+/// We are able to do this because the Regex rule has already validated the format of the given
+/// time input.
+///
+/// If a given time unit doesn't match any rule the function assumes an input error and returns an
+/// `AppError`.
+fn parse_time_input(
+    unit: &str,
+    search_type: &Search,
+    clock: &dyn Clock,
+) -> Result<NaiveDateTime, AppError> {
+    if AT_HOUR.is_match(unit) {
+        let time = NaiveTime::parse_from_str(&format!("{}:00", unit), "%H:%M").unwrap();
+        let date = get_ambiguous_date(&time, search_type, clock);
+        Ok(NaiveDateTime::new(date, time))
+    } else if AT_HOUR_MINUTES.is_match(unit) {
+        let time = NaiveTime::parse_from_str(unit, "%H:%M").unwrap();
+        let date = get_ambiguous_date(&time, search_type, clock);
+        Ok(NaiveDateTime::new(date, time))
+    } else if AT_DAY_HOUR_MINUTES.is_match(unit) {
+        let units: Vec<_> = unit.split_whitespace().collect();
+        let given_day = u32::from_str_radix(units[0], 10).unwrap();
+        let time = NaiveTime::parse_from_str(units[1], "%H:%M").unwrap();
+        Ok(get_ambiguous_month(given_day, &time, search_type, clock))
+    } else if AT_DAY_MONTH_HOUR_MINUTES.is_match(unit) {
+        let units: Vec<_> = unit.split_whitespace().collect();
+        let numbers: Vec<u32> = units[0].split('-').map(|n| n.parse().unwrap()).collect();
+        let time = NaiveTime::parse_from_str(units[1], "%H:%M").unwrap();
+        get_ambiguous_year(numbers[0], numbers[1], &time, search_type, clock).ok_or_else(|| {
+            AppError::new(ErrorKind::Parse(format!("Invalid date: {}", units[0])))
+                .with_code(ErrorCode::InvalidTime)
+        })
+    } else if AT_DAY_MONTH_YEAR_HOUR_MINUTES.is_match(unit) {
+        let units: Vec<_> = unit.split_whitespace().collect();
+        let date = NaiveDate::parse_from_str(units[0], "%d-%m-%Y").map_err(|_| {
+            AppError::new(ErrorKind::Parse(format!("Invalid date: {}", units[0])))
+                .with_code(ErrorCode::InvalidTime)
+        })?;
+        let time = NaiveTime::parse_from_str(units[1], "%H:%M").unwrap();
+        Ok(NaiveDateTime::new(date, time))
+    } else if HOURS_AGO_OR_UNTIL.is_match(unit) {
+        let hours = unit[..unit.len() - 1].parse::<i64>().ok();
+        offset_now(
+            hours.and_then(|h| h.checked_mul(SECONDS_IN_HOUR)),
+            search_type,
+            clock,
+        )
+        .ok_or_else(|| out_of_range(unit))
+    } else if MINUTES_AGO_OR_UNTIL.is_match(unit) {
+        let minutes = unit[..unit.len() - 1].parse::<i64>().ok();
+        offset_now(
+            minutes.and_then(|m| m.checked_mul(SECONDS_IN_MINUTE)),
+            search_type,
+            clock,
+        )
+        .ok_or_else(|| out_of_range(unit))
+    } else if HOURS_AND_MINUTES_AGO_OR_UNTIL.is_match(unit) {
+        let units: Vec<&str> = unit[..unit.len() - 1].split(':').collect();
+        let hours = units[0].parse::<i64>().ok();
+        let minutes: i64 = units[1].parse().unwrap();
+        let seconds = hours
+            .and_then(|h| h.checked_mul(SECONDS_IN_HOUR))
+            .and_then(|s| s.checked_add(minutes * SECONDS_IN_MINUTE));
+        offset_now(seconds, search_type, clock).ok_or_else(|| out_of_range(unit))
+    } else if DAYS_AGO_OR_UNTIL.is_match(unit) {
+        let days = unit[..unit.len() - 1].parse::<i64>().ok();
+        offset_now(
+            days.and_then(|d| d.checked_mul(SECONDS_IN_DAY)),
+            search_type,
+            clock,
+        )
+        .ok_or_else(|| out_of_range(unit))
+    } else if WEEKS_AGO_OR_UNTIL.is_match(unit) {
+        let weeks = unit[..unit.len() - 1].parse::<i64>().ok();
+        offset_now(
+            weeks.and_then(|w| w.checked_mul(SECONDS_IN_WEEK)),
+            search_type,
+            clock,
+        )
+        .ok_or_else(|| out_of_range(unit))
+    } else if unit == "today" {
+        Ok(NaiveDateTime::new(
+            clock.today(),
+            NaiveTime::from_hms(0, 0, 0),
+        ))
+    } else if unit == "yesterday" {
+        Ok(NaiveDateTime::new(
+            yesterday(clock),
+            NaiveTime::from_hms(0, 0, 0),
+        ))
+    } else if let Some(date_time) = parse_natural_input(unit, search_type, clock) {
+        Ok(date_time)
+    } else {
+        let mut message = format!("Invalid time specifier: {}", unit);
+        if let Some(suggestion) = suggest::time_input(unit) {
+            message.push_str(&format!(". {}", suggestion));
+        }
+        message.push_str(&format!(
+            "\nTimes look like: {}, see `work help times`",
+            suggest::TIME_FORMS
+        ));
+        Err(AppError::new(ErrorKind::Parse(message)).with_code(ErrorCode::InvalidTime))
+    }
+}
+
+// Helper function for parsing times of day such as `9`, `21:30`, `9am`, `9:30pm`, `noon` and
+// `midnight`.
+fn parse_clock_time(unit: &str) -> Option<NaiveTime> {
+    let unit = unit.to_lowercase();
+    if unit == "noon" {
+        Some(NaiveTime::from_hms(12, 0, 0))
+    } else if unit == "midnight" {
+        Some(NaiveTime::from_hms(0, 0, 0))
+    } else if AT_HOUR.is_match(&unit) {
+        NaiveTime::from_hms_opt(unit.parse().unwrap(), 0, 0)
+    } else if AT_HOUR_MINUTES.is_match(&unit) {
+        NaiveTime::parse_from_str(&unit, "%H:%M").ok()
+    } else {
+        let captures = AT_HOUR_AM_PM.captures(&unit)?;
+        let hour: u32 = captures[1].parse().unwrap();
+        let minute: u32 = captures.get(2).map_or(0, |m| m.as_str().parse().unwrap());
+        // 12am is midnight and 12pm is noon.
+        let hour = match &captures[3] {
+            "am" => hour % 12,
+            _ => hour % 12 + 12,
+        };
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    }
+}
+
+// Helper function for parsing the name of a weekday, either in full or abbreviated.
+fn parse_weekday(unit: &str) -> Option<Weekday> {
+    match unit {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+// Helper function for returning the closest date before (or after, when searching forward) today
+// that falls on the given weekday. Today itself is only considered if `include_today` is set.
+fn closest_weekday(
+    weekday: Weekday,
+    search_type: &Search,
+    include_today: bool,
+    clock: &dyn Clock,
+) -> NaiveDate {
+    let step = match search_type {
+        Search::Backward => Duration::days(-1),
+        Search::Forward => Duration::days(1),
+    };
+    let mut date = if include_today {
+        clock.today()
+    } else {
+        clock.today() + step
+    };
+    while date.weekday() != weekday {
+        date += step;
+    }
+    date
+}
+
+// Helper function for parsing natural language time inputs such as `now`, `noon`, `monday 9am`,
+// `this-week`, `last friday`, `tomorrow 14:30`, `2 days ago` or `in 3 hours`.
+//
+// A day on its own refers to midnight of that day and a time of day on its own is resolved like
+// any other ambiguous time of day. A plain weekday like `monday` refers to the closest monday in
+// the search direction (today included), while `last monday` and `next monday` always refer to a
+// monday before or after today.
+fn parse_natural_input(
+    unit: &str,
+    search_type: &Search,
+    clock: &dyn Clock,
+) -> Option<NaiveDateTime> {
+    let unit = unit.to_lowercase();
+
+    if unit == "now" {
+        return Some(now_date_time(clock));
+    }
+    if let Some(captures) = UNITS_AGO_OR_UNTIL.captures(&unit) {
+        let unit_seconds = match &captures[2] {
+            "minute" => SECONDS_IN_MINUTE,
+            "hour" => SECONDS_IN_HOUR,
+            "day" => SECONDS_IN_DAY,
+            _ => SECONDS_IN_WEEK,
+        };
+        let seconds = captures[1]
+            .parse::<i64>()
+            .ok()
+            .and_then(|amount| amount.checked_mul(unit_seconds));
+        // Either `in X units` or `X units ago`, but not both or neither.
+        return match (unit.starts_with("in"), captures.get(3).is_some()) {
+            (true, false) => offset_now(seconds, &Search::Forward, clock),
+            (false, true) => offset_now(seconds, &Search::Backward, clock),
+            _ => None,
+        };
+    }
+
+    let words: Vec<&str> = unit.split_whitespace().collect();
+    let (date, rest) = match &words[..] {
+        ["today", rest @ ..] => (Some(clock.today()), rest),
+        ["yesterday", rest @ ..] => (Some(yesterday(clock)), rest),
+        ["tomorrow", rest @ ..] => (Some(tomorrow(clock)), rest),
+        ["this-week", rest @ ..] | ["this", "week", rest @ ..] => (
+            Some(closest_weekday(
+                Weekday::Mon,
+                &Search::Backward,
+                true,
+                clock,
+            )),
+            rest,
+        ),
+        ["last", day, rest @ ..] => (
+            Some(closest_weekday(
+                parse_weekday(day)?,
+                &Search::Backward,
+                false,
+                clock,
+            )),
+            rest,
+        ),
+        ["next", day, rest @ ..] => (
+            Some(closest_weekday(
+                parse_weekday(day)?,
+                &Search::Forward,
+                false,
+                clock,
+            )),
+            rest,
+        ),
+        [day, rest @ ..] if parse_weekday(day).is_some() => (
+            Some(closest_weekday(
+                parse_weekday(day)?,
+                search_type,
+                true,
+                clock,
+            )),
+            rest,
+        ),
+        rest => (None, rest),
+    };
+
+    // Allow the time of day to be split up, as in `9 am`.
+    let time = match rest.concat() {
+        rest if rest.is_empty() => None,
+        rest => Some(parse_clock_time(&rest)?),
+    };
+    match (date, time) {
+        (Some(date), Some(time)) => Some(NaiveDateTime::new(date, time)),
+        (Some(date), None) => Some(NaiveDateTime::new(date, NaiveTime::from_hms(0, 0, 0))),
+        (None, Some(time)) => Some(NaiveDateTime::new(
+            get_ambiguous_date(&time, search_type, clock),
+            time,
+        )),
+        (None, None) => None,
+    }
+}
+
+// Helper function that checks whether the date of a time input depends on the search direction.
+fn is_ambiguous(unit: &str) -> bool {
+    parse_clock_time(unit).is_some()
+        || AT_DAY_HOUR_MINUTES.is_match(unit)
+        || AT_DAY_MONTH_HOUR_MINUTES.is_match(unit)
+}
+
+// Helper function that checks whether the date of an ambiguous time input had to be guessed, that
+// is whether the input was resolved to something other than the current day, month or year it
+// seemingly refers to. For example `31 20:59` resolving to the 31st of last month.
+fn is_guessed(unit: &str, resolved: &NaiveDateTime, clock: &dyn Clock) -> bool {
+    let today = clock.today();
+    let face_value = if parse_clock_time(unit).is_some() {
+        Some(today)
+    } else if AT_DAY_HOUR_MINUTES.is_match(unit) {
+        let day = unit.split_whitespace().next().unwrap().parse().unwrap();
+        NaiveDate::from_ymd_opt(today.year(), today.month(), day)
+    } else if AT_DAY_MONTH_HOUR_MINUTES.is_match(unit) {
+        let units: Vec<u32> = unit
+            .split_whitespace()
+            .next()
+            .unwrap()
+            .split('-')
+            .map(|unit| unit.parse().unwrap())
+            .collect();
+        NaiveDate::from_ymd_opt(today.year(), units[1], units[0])
+    } else {
+        return false;
+    };
+    face_value != Some(resolved.date())
+}
+
+/// The `Resolution` struct describes how an ambiguous part of a time input, one whose date depends
+/// on the search direction, was resolved.
+pub struct Resolution {
+    pub input: String,
+    pub date_time: NaiveDateTime,
+    /// True if the input was resolved to something other than what it seemingly refers to, for
+    /// example `31 20:59` resolving to the 31st of last month.
+    pub guessed: bool,
+}
+
+impl Resolution {
+    // Helper function for parsing a time input while keeping track of how it was resolved.
+    fn parse(
+        unit: &str,
+        search_type: &Search,
+        clock: &dyn Clock,
+    ) -> Result<(NaiveDateTime, Option<Resolution>), AppError> {
+        let date_time = parse_time_input(unit, search_type, clock)?;
+        if is_ambiguous(unit) {
+            let resolution = Resolution {
+                input: unit.to_string(),
+                date_time,
+                guessed: is_guessed(unit, &date_time, clock),
+            };
+            Ok((date_time, Some(resolution)))
+        } else {
+            Ok((date_time, None))
+        }
+    }
+}
+
+/// The earliest possible time, used as the start of intervals that have no start time such as
+/// `- 12` or `all`.
+pub const BEGINNING_OF_TIME: i64 = 0;
+
+/// The `Interval` struct represents a time interval that spans time from `start` to `end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    /// Creates a new `Interval`, if no `end` is given it is assumed to be the current system time.
+    /// The function takes in two arguments `start` and `end` which should correspond to the start
+    /// and end times of the interval, however if someone gives a start value that is larger than
+    /// the end value, rather than returning an error the values switch.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::time::Interval;
+    /// let interval = Interval::new(100, Some(50));
+    /// assert!(interval.end > interval.start);
+    ///
+    /// let interval = Interval::new(0, None);
+    /// assert!(interval.end > 0);
+    /// ```
+    pub fn new(start: i64, end: Option<i64>) -> Self {
+        let end = end.unwrap_or(now());
+        if start > end {
+            Interval {
+                start: end,
+                end: start,
+            }
+        } else {
+            Interval { start, end }
+        }
+    }
+
+    /// Returns the length of the interval.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chrono::Duration;
+    /// # use work_core::time::Interval;
+    /// assert_eq!(Interval::new(0, Some(3600)).duration(), Duration::hours(1));
+    /// ```
+    pub fn duration(&self) -> Duration {
+        Duration::seconds(self.end - self.start)
+    }
+
+    /// Returns true if the given timestamp is within the interval, both ends included.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::time::Interval;
+    /// let interval = Interval::new(100, Some(200));
+    /// assert!(interval.contains(100));
+    /// assert!(interval.contains(200));
+    /// assert!(!interval.contains(201));
+    /// ```
+    pub fn contains(&self, timestamp: i64) -> bool {
+        self.start <= timestamp && timestamp <= self.end
+    }
+
+    /// Returns the part of time the interval shares with `other`, or `None` if they don't meet.
+    ///
+    /// # Examples
+    /// ```
+    /// # use work_core::time::Interval;
+    /// let interval = Interval::new(100, Some(200));
+    /// assert_eq!(
+    ///     interval.intersect(&Interval::new(150, Some(300))),
+    ///     Some(Interval::new(150, Some(200)))
+    /// );
+    /// assert_eq!(interval.intersect(&Interval::new(300, Some(400))), None);
+    /// ```
+    pub fn intersect(&self, other: &Interval) -> Option<Interval> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        if start <= end {
+            Some(Interval { start, end })
+        } else {
+            None
+        }
+    }
+
+    /// Splits the interval at every local midnight within it, into the parts that fall on each day.
+    /// An interval within a single day is returned as it is.
+    pub fn split_at_midnight(&self) -> Vec<Interval> {
+        let mut parts = Vec::new();
+        let mut start = self.start;
+        loop {
+            let midnight = start_of_day(date_of(start).succ());
+            if midnight >= self.end {
+                parts.push(Interval {
+                    start,
+                    end: self.end,
+                });
+                return parts;
+            }
+            parts.push(Interval {
+                start,
+                end: midnight,
+            });
+            start = midnight;
+        }
+    }
+
+    /// `try_parse` tries to parse a given input string to a valid interval. The method also takes
+    /// in a `search_type` to tell parse_time_input whether it should search forwards or backwards
+    /// in time for ambiguous inputs, starting from the time the given clock tells.
+    ///
+    /// Either end of an interval may be left out, `9 -` spans from 9 o'clock until now and `- 12`
+    /// spans everything up until 12 o'clock. `all` spans all time.
+    pub fn try_parse(
+        str_interval: &str,
+        search_type: &Search,
+        clock: &dyn Clock,
+    ) -> Result<Self, AppError> {
+        Ok(Self::try_parse_with_resolutions(str_interval, search_type, clock)?.0)
+    }
+
+    /// Same as `try_parse`, but also returns the ambiguous parts of the input along with what they
+    /// were resolved to, so the guesses can be shown to the user.
+    pub fn try_parse_with_resolutions(
+        str_interval: &str,
+        search_type: &Search,
+        clock: &dyn Clock,
+    ) -> Result<(Self, Vec<Resolution>), AppError> {
+        match Resolution::parse(str_interval, search_type, clock) {
+            // Managed to parse the given time input. This means there was no end time specified.
+            // Current time is assumed.
+            Ok((start_date_time, resolution)) => Ok((
+                Interval::new(start_date_time.timestamp(), Some(clock.now())),
+                resolution.into_iter().collect(),
+            )),
+            // Unable to parse the given time input. Might be able to parse it as an interval
+            // input.
+            Err(e) => {
+                let str_interval = str_interval.trim();
+                if str_interval == "all" {
+                    return Ok((
+                        Interval::new(BEGINNING_OF_TIME, Some(clock.now())),
+                        Vec::new(),
+                    ));
+                } else if let Some(start) = str_interval.strip_suffix(" -") {
+                    let (start_date_time, resolution) =
+                        Resolution::parse(start, search_type, clock)?;
+                    return Ok((
+                        Interval::new(start_date_time.timestamp(), Some(clock.now())),
+                        resolution.into_iter().collect(),
+                    ));
+                } else if let Some(end) = str_interval.strip_prefix("- ") {
+                    let (end_date_time, resolution) = Resolution::parse(end, search_type, clock)?;
+                    return Ok((
+                        Interval::new(BEGINNING_OF_TIME, Some(end_date_time.timestamp())),
+                        resolution.into_iter().collect(),
+                    ));
+                }
+
+                let units: Vec<&str> = str_interval.split(" - ").collect();
+                match &units[..] {
+                    &[start, end] => {
+                        let (start_date_time, start_resolution) =
+                            Resolution::parse(start, search_type, clock)?;
+                        let (end_date_time, end_resolution) =
+                            Resolution::parse(end, search_type, clock)?;
+                        Ok((
+                            Interval::new(
+                                start_date_time.timestamp(),
+                                Some(end_date_time.timestamp()),
+                            ),
+                            start_resolution.into_iter().chain(end_resolution).collect(),
+                        ))
+                    }
+                    _ => Err(e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+    use proptest::prelude::*;
+
+    // Helper function for the given local time.
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd(year, month, day).and_hms(hour, minute, 0)
+    }
+
+    #[test]
+    fn regex_at_hour() {
+        let valid_hour1 = "9";
+        let valid_hour2 = "12";
+        let valid_hour3 = "04";
+        let valid_hour4 = "23";
+
+        let invalid_hour1 = "24";
+        let invalid_hour2 = "30";
+        let invalid_hour3 = "100";
+
+        assert!(AT_HOUR.is_match(valid_hour1));
+        assert!(AT_HOUR.is_match(valid_hour2));
+        assert!(AT_HOUR.is_match(valid_hour3));
+        assert!(AT_HOUR.is_match(valid_hour4));
+
+        assert!(!AT_HOUR.is_match(invalid_hour1));
+        assert!(!AT_HOUR.is_match(invalid_hour2));
+        assert!(!AT_HOUR.is_match(invalid_hour3));
+    }
+
+    #[test]
+    fn regex_at_hour_minutes() {
+        let valid_hour_minutes1 = "9:15";
+        let valid_hour_minutes2 = "09:01";
+        let valid_hour_minutes3 = "9:1";
+        let valid_hour_minutes4 = "21:21";
+        let valid_hour_minutes5 = "3:21";
+        let valid_hour_minutes6 = "19:59";
+
+        let invalid_hour_minutes1 = "24:00";
+        let invalid_hour_minutes2 = "19:60";
+        let invalid_hour_minutes3 = "30:15";
+
+        assert!(AT_HOUR_MINUTES.is_match(valid_hour_minutes1));
+        assert!(AT_HOUR_MINUTES.is_match(valid_hour_minutes2));
+        assert!(AT_HOUR_MINUTES.is_match(valid_hour_minutes3));
+        assert!(AT_HOUR_MINUTES.is_match(valid_hour_minutes4));
+        assert!(AT_HOUR_MINUTES.is_match(valid_hour_minutes5));
+        assert!(AT_HOUR_MINUTES.is_match(valid_hour_minutes6));
+
+        assert!(!AT_HOUR_MINUTES.is_match(invalid_hour_minutes1));
+        assert!(!AT_HOUR_MINUTES.is_match(invalid_hour_minutes2));
+        assert!(!AT_HOUR_MINUTES.is_match(invalid_hour_minutes3));
+    }
+
+    #[test]
+    fn regex_at_day_hour_minutes() {
+        let valid_day_hour_minutes1 = "9 15:15";
+        let valid_day_hour_minutes2 = "19\t19:29";
+        let valid_day_hour_minutes3 = "31 20:20";
+        let valid_day_hour_minutes4 = "03 23:59";
+
+        let invalid_day_hour_minutes1 = "32 15:15";
+        let invalid_day_hour_minutes2 = "-19\t19:29";
+        let invalid_day_hour_minutes3 = "3120:20";
+        let invalid_day_hour_minutes4 = "013 23:59";
+        let invalid_day_hour_minutes5 = "51 23:59";
+        let invalid_day_hour_minutes6 = "0 13:15";
+
+        assert!(AT_DAY_HOUR_MINUTES.is_match(valid_day_hour_minutes1));
+        assert!(AT_DAY_HOUR_MINUTES.is_match(valid_day_hour_minutes2));
+        assert!(AT_DAY_HOUR_MINUTES.is_match(valid_day_hour_minutes3));
+        assert!(AT_DAY_HOUR_MINUTES.is_match(valid_day_hour_minutes4));
+
+        assert!(!AT_DAY_HOUR_MINUTES.is_match(invalid_day_hour_minutes1));
+        assert!(!AT_DAY_HOUR_MINUTES.is_match(invalid_day_hour_minutes2));
+        assert!(!AT_DAY_HOUR_MINUTES.is_match(invalid_day_hour_minutes3));
+        assert!(!AT_DAY_HOUR_MINUTES.is_match(invalid_day_hour_minutes4));
+        assert!(!AT_DAY_HOUR_MINUTES.is_match(invalid_day_hour_minutes5));
+        assert!(!AT_DAY_HOUR_MINUTES.is_match(invalid_day_hour_minutes6));
+    }
+
+    #[test]
+    fn regex_at_day_month_hour_minutes() {
+        let valid_day_month_hour_minutes1 = "9-9 15:15";
+        let valid_day_month_hour_minutes2 = "19-09\t19:29";
+        let valid_day_month_hour_minutes3 = "31-12 20:20";
+        let valid_day_month_hour_minutes4 = "03-10 23:59";
+
+        let invalid_day_month_hour_minutes1 = "31-13 15:15";
+        let invalid_day_month_hour_minutes2 = "19:09\t19:29";
+        let invalid_day_month_hour_minutes3 = "20-21 20:20";
+        let invalid_day_month_hour_minutes4 = "20-00 20:20";
+
+        assert!(AT_DAY_MONTH_HOUR_MINUTES.is_match(valid_day_month_hour_minutes1));
+        assert!(AT_DAY_MONTH_HOUR_MINUTES.is_match(valid_day_month_hour_minutes2));
+        assert!(AT_DAY_MONTH_HOUR_MINUTES.is_match(valid_day_month_hour_minutes3));
+        assert!(AT_DAY_MONTH_HOUR_MINUTES.is_match(valid_day_month_hour_minutes4));
+
+        assert!(!AT_DAY_MONTH_HOUR_MINUTES.is_match(invalid_day_month_hour_minutes1));
+        assert!(!AT_DAY_MONTH_HOUR_MINUTES.is_match(invalid_day_month_hour_minutes2));
+        assert!(!AT_DAY_MONTH_HOUR_MINUTES.is_match(invalid_day_month_hour_minutes3));
+        assert!(!AT_DAY_MONTH_HOUR_MINUTES.is_match(invalid_day_month_hour_minutes4));
+    }
+
+    #[test]
+    fn regex_at_day_month_year_hour_minutes() {
+        let valid_day_month_year_hour_minutes1 = "3-1-2023 9:00";
+        let valid_day_month_year_hour_minutes2 = "31-12-1999\t23:59";
+        let valid_day_month_year_hour_minutes3 = "03-01-2023 09:00";
+
+        let invalid_day_month_year_hour_minutes1 = "3-1-23 9:00";
+        let invalid_day_month_year_hour_minutes2 = "3-13-2023 9:00";
+        let invalid_day_month_year_hour_minutes3 = "3-1-2023";
+
+        assert!(AT_DAY_MONTH_YEAR_HOUR_MINUTES.is_match(valid_day_month_year_hour_minutes1));
+        assert!(AT_DAY_MONTH_YEAR_HOUR_MINUTES.is_match(valid_day_month_year_hour_minutes2));
+        assert!(AT_DAY_MONTH_YEAR_HOUR_MINUTES.is_match(valid_day_month_year_hour_minutes3));
+
+        assert!(!AT_DAY_MONTH_YEAR_HOUR_MINUTES.is_match(invalid_day_month_year_hour_minutes1));
+        assert!(!AT_DAY_MONTH_YEAR_HOUR_MINUTES.is_match(invalid_day_month_year_hour_minutes2));
+        assert!(!AT_DAY_MONTH_YEAR_HOUR_MINUTES.is_match(invalid_day_month_year_hour_minutes3));
+    }
+
+    #[test]
+    fn test_parse_time_input_at_day_month_year_hour_minutes() {
+        let clock = frozen(2026, 10, 16, 13, 45);
+        assert_eq!(
+            parse_time_input("3-1-2023 9:00", &Search::Forward, &clock).unwrap(),
+            NaiveDateTime::new(
+                NaiveDate::from_ymd(2023, 1, 3),
+                NaiveTime::from_hms(9, 0, 0)
+            )
+        );
+        assert!(parse_time_input("31-2-2023 9:00", &Search::Backward, &clock).is_err());
+    }
+
+    #[test]
+    fn regex_hours_ago() {
+        let valid_hour1 = "13h";
+        let valid_hour2 = "23h";
+        let valid_hour3 = "1h";
+        let valid_hour4 = "05h";
+        let valid_hour5 = "99h";
+        let valid_hour6 = "24h";
+
+        let invalid_hour1 = "1.5h";
+        let invalid_hour2 = "h";
+        let invalid_hour3 = "-5h";
+        let invalid_hour4 = "13";
+        let invalid_hour5 = "0h";
+
+        assert!(HOURS_AGO_OR_UNTIL.is_match(valid_hour1));
+        assert!(HOURS_AGO_OR_UNTIL.is_match(valid_hour2));
+        assert!(HOURS_AGO_OR_UNTIL.is_match(valid_hour3));
+        assert!(HOURS_AGO_OR_UNTIL.is_match(valid_hour4));
+        assert!(HOURS_AGO_OR_UNTIL.is_match(valid_hour5));
+        assert!(HOURS_AGO_OR_UNTIL.is_match(valid_hour6));
+
+        assert!(!HOURS_AGO_OR_UNTIL.is_match(invalid_hour1));
+        assert!(!HOURS_AGO_OR_UNTIL.is_match(invalid_hour2));
+        assert!(!HOURS_AGO_OR_UNTIL.is_match(invalid_hour3));
+        assert!(!HOURS_AGO_OR_UNTIL.is_match(invalid_hour4));
+        assert!(!HOURS_AGO_OR_UNTIL.is_match(invalid_hour5));
+    }
+
+    #[test]
+    fn regex_minutes_ago() {
+        let valid_minutes1 = "01m";
+        let valid_minutes2 = "9m";
+        let valid_minutes3 = "19m";
+        let valid_minutes4 = "59m";
+        let valid_minutes5 = "35m";
+        let valid_minutes6 = "90m";
+
+        let invalid_minutes1 = "0m";
+        let invalid_minutes2 = "00m";
+        let invalid_minutes3 = "19";
+        let invalid_minutes4 = "m";
+
+        assert!(MINUTES_AGO_OR_UNTIL.is_match(valid_minutes1));
+        assert!(MINUTES_AGO_OR_UNTIL.is_match(valid_minutes2));
+        assert!(MINUTES_AGO_OR_UNTIL.is_match(valid_minutes3));
+        assert!(MINUTES_AGO_OR_UNTIL.is_match(valid_minutes4));
+        assert!(MINUTES_AGO_OR_UNTIL.is_match(valid_minutes5));
+        assert!(MINUTES_AGO_OR_UNTIL.is_match(valid_minutes6));
+
+        assert!(!MINUTES_AGO_OR_UNTIL.is_match(invalid_minutes1));
+        assert!(!MINUTES_AGO_OR_UNTIL.is_match(invalid_minutes2));
+        assert!(!MINUTES_AGO_OR_UNTIL.is_match(invalid_minutes3));
+        assert!(!MINUTES_AGO_OR_UNTIL.is_match(invalid_minutes4));
+    }
+
+    #[test]
+    fn regex_hours_and_minutes_ago() {
+        let valid_hours_and_minutes1 = "19:59h";
+        let valid_hours_and_minutes2 = "23:59h";
+        let valid_hours_and_minutes3 = "1:1h";
+        let valid_hours_and_minutes4 = "05:09h";
+        let valid_hours_and_minutes5 = "24:59h";
+
+        let invalid_hours_and_minutes1 = "19:59";
+        let invalid_hours_and_minutes2 = "24:60h";
+
+        assert!(HOURS_AND_MINUTES_AGO_OR_UNTIL.is_match(valid_hours_and_minutes1));
+        assert!(HOURS_AND_MINUTES_AGO_OR_UNTIL.is_match(valid_hours_and_minutes2));
+        assert!(HOURS_AND_MINUTES_AGO_OR_UNTIL.is_match(valid_hours_and_minutes3));
+        assert!(HOURS_AND_MINUTES_AGO_OR_UNTIL.is_match(valid_hours_and_minutes4));
+        assert!(HOURS_AND_MINUTES_AGO_OR_UNTIL.is_match(valid_hours_and_minutes5));
+
+        assert!(!HOURS_AND_MINUTES_AGO_OR_UNTIL.is_match(invalid_hours_and_minutes1));
+        assert!(!HOURS_AND_MINUTES_AGO_OR_UNTIL.is_match(invalid_hours_and_minutes2));
+    }
+
+    #[test]
+    fn regex_days_ago() {
+        let valid_days1 = "3d";
+        let valid_days2 = "03d";
+        let valid_days3 = "10d";
+        let valid_days4 = "100d";
+
+        let invalid_days1 = "0d";
+        let invalid_days2 = "d";
+        let invalid_days3 = "-3d";
+        let invalid_days4 = "3";
+
+        assert!(DAYS_AGO_OR_UNTIL.is_match(valid_days1));
+        assert!(DAYS_AGO_OR_UNTIL.is_match(valid_days2));
+        assert!(DAYS_AGO_OR_UNTIL.is_match(valid_days3));
+        assert!(DAYS_AGO_OR_UNTIL.is_match(valid_days4));
+
+        assert!(!DAYS_AGO_OR_UNTIL.is_match(invalid_days1));
+        assert!(!DAYS_AGO_OR_UNTIL.is_match(invalid_days2));
+        assert!(!DAYS_AGO_OR_UNTIL.is_match(invalid_days3));
+        assert!(!DAYS_AGO_OR_UNTIL.is_match(invalid_days4));
+    }
+
+    #[test]
+    fn regex_weeks_ago() {
+        let valid_weeks1 = "2w";
+        let valid_weeks2 = "02w";
+        let valid_weeks3 = "52w";
+
+        let invalid_weeks1 = "0w";
+        let invalid_weeks2 = "2W";
+        let invalid_weeks3 = "2 w";
+
+        assert!(WEEKS_AGO_OR_UNTIL.is_match(valid_weeks1));
+        assert!(WEEKS_AGO_OR_UNTIL.is_match(valid_weeks2));
+        assert!(WEEKS_AGO_OR_UNTIL.is_match(valid_weeks3));
+
+        assert!(!WEEKS_AGO_OR_UNTIL.is_match(invalid_weeks1));
+        assert!(!WEEKS_AGO_OR_UNTIL.is_match(invalid_weeks2));
+        assert!(!WEEKS_AGO_OR_UNTIL.is_match(invalid_weeks3));
+    }
+
+    #[test]
+    fn test_parse_time_input_at_hour() {
+        let clock = frozen(2026, 10, 16, 13, 45);
+        let curr_hour = now_date_time(&clock).hour();
+
+        for hour in 0..=23 {
+            let test_time;
+            if hour > curr_hour {
+                test_time = NaiveDateTime::new(yesterday(&clock), NaiveTime::from_hms(hour, 0, 0));
+            } else {
+                test_time = NaiveDateTime::new(clock.today(), NaiveTime::from_hms(hour, 0, 0));
+            }
+            assert_eq!(
+                parse_time_input(&hour.to_string(), &Search::Backward, &clock).unwrap(),
+                test_time
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_time_input_at_hour_minutes() {
+        let clock = frozen(2026, 10, 16, 13, 45);
+        let curr_time = now_date_time(&clock).time();
+
+        for hour in 0..=23 {
+            for minute in 0..=59 {
+                let test_time;
+                let fake_time = NaiveTime::from_hms(hour, minute, 0);
+                if fake_time > curr_time {
+                    test_time =
+                        NaiveDateTime::new(yesterday(&clock), NaiveTime::from_hms(hour, minute, 0));
+                } else {
+                    test_time =
+                        NaiveDateTime::new(clock.today(), NaiveTime::from_hms(hour, minute, 0));
+                }
+                println!("{}:{}", fake_time.hour(), fake_time.minute());
+                assert_eq!(
+                    parse_time_input(
+                        &format!("{}:{}", fake_time.hour(), fake_time.minute()),
+                        &Search::Backward,
+                        &clock
+                    )
+                    .unwrap(),
+                    test_time
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_months_without_given_day_are_skipped() {
+        let clock = frozen(2026, 10, 16, 13, 45);
+        assert_eq!(last_month(31, &clock), NaiveDate::from_ymd(2026, 8, 31));
+        assert_eq!(next_month(31, &clock), NaiveDate::from_ymd(2026, 12, 31));
+    }
+
+    #[test]
+    fn test_parse_clock_time() {
+        assert_eq!(
+            parse_clock_time("noon"),
+            Some(NaiveTime::from_hms(12, 0, 0))
+        );
+        assert_eq!(
+            parse_clock_time("Midnight"),
+            Some(NaiveTime::from_hms(0, 0, 0))
+        );
+        assert_eq!(parse_clock_time("9am"), Some(NaiveTime::from_hms(9, 0, 0)));
+        assert_eq!(
+            parse_clock_time("9:30pm"),
+            Some(NaiveTime::from_hms(21, 30, 0))
+        );
+        assert_eq!(parse_clock_time("12am"), Some(NaiveTime::from_hms(0, 0, 0)));
+        assert_eq!(
+            parse_clock_time("12pm"),
+            Some(NaiveTime::from_hms(12, 0, 0))
+        );
+        assert_eq!(
+            parse_clock_time("21:30"),
+            Some(NaiveTime::from_hms(21, 30, 0))
+        );
+
+        assert_eq!(parse_clock_time("13pm"), None);
+        assert_eq!(parse_clock_time("0am"), None);
+        assert_eq!(parse_clock_time("noonish"), None);
+    }
+
+    #[test]
+    fn test_parse_time_input_natural() {
+        let clock = frozen(2026, 10, 16, 13, 45);
+        let monday = parse_time_input("monday 9am", &Search::Backward, &clock).unwrap();
+        assert_eq!(monday.weekday(), Weekday::Mon);
+        assert_eq!(monday.time(), NaiveTime::from_hms(9, 0, 0));
+        assert!(
+            monday.date() <= clock.today() && monday.date() > clock.today() - Duration::weeks(1)
+        );
+
+        let friday = parse_time_input("last friday", &Search::Forward, &clock).unwrap();
+        assert_eq!(friday.weekday(), Weekday::Fri);
+        assert_eq!(friday.time(), NaiveTime::from_hms(0, 0, 0));
+        assert!(
+            friday.date() < clock.today() && friday.date() >= clock.today() - Duration::weeks(1)
+        );
+
+        let tuesday = parse_time_input("next tue 14:30", &Search::Backward, &clock).unwrap();
+        assert_eq!(tuesday.weekday(), Weekday::Tue);
+        assert!(
+            tuesday.date() > clock.today() && tuesday.date() <= clock.today() + Duration::weeks(1)
+        );
+
+        let days_ago = parse_time_input("2 days ago", &Search::Forward, &clock).unwrap();
+        assert_eq!(days_ago.date(), clock.today() - Duration::days(2));
+
+        let noon = parse_time_input("noon", &Search::Backward, &clock).unwrap();
+        assert_eq!(noon.time(), NaiveTime::from_hms(12, 0, 0));
+        assert!(noon <= now_date_time(&clock));
+
+        let this_week = parse_time_input("this-week", &Search::Forward, &clock).unwrap();
+        assert_eq!(this_week.weekday(), Weekday::Mon);
+        assert!(
+            this_week.date() <= clock.today()
+                && this_week.date() > clock.today() - Duration::weeks(1)
+        );
+
+        let tomorrow_noon = parse_time_input("Tomorrow 12 pm", &Search::Backward, &clock).unwrap();
+        assert_eq!(
+            tomorrow_noon,
+            NaiveDateTime::new(tomorrow(&clock), NaiveTime::from_hms(12, 0, 0))
+        );
+
+        assert!(parse_time_input("blursday", &Search::Backward, &clock).is_err());
+        assert!(parse_time_input("last 9am", &Search::Backward, &clock).is_err());
+        assert!(parse_time_input("in 2 days ago", &Search::Backward, &clock).is_err());
+        assert!(parse_time_input("monday teatime", &Search::Backward, &clock).is_err());
+    }
+
+    #[test]
+    fn test_parse_time_input_at_day_hour_minutes() {
+        // Friday the 16th of October at 17:00.
+        let clock = frozen(2026, 10, 16, 17, 0);
+        let parse = |unit, search| parse_time_input(unit, &search, &clock).unwrap();
+        assert_eq!(parse("16 9:00", Search::Backward), at(2026, 10, 16, 9, 0));
+        assert_eq!(parse("16 20:00", Search::Backward), at(2026, 9, 16, 20, 0));
+        assert_eq!(parse("16 20:00", Search::Forward), at(2026, 10, 16, 20, 0));
+        assert_eq!(parse("16 9:00", Search::Forward), at(2026, 11, 16, 9, 0));
+        assert_eq!(parse("3 9:00", Search::Backward), at(2026, 10, 3, 9, 0));
+        assert_eq!(parse("31 9:00", Search::Backward), at(2026, 8, 31, 9, 0));
+        assert_eq!(parse("31 9:00", Search::Forward), at(2026, 10, 31, 9, 0));
+        // April doesn't have a 31st.
+        let clock = frozen(2026, 3, 31, 10, 0);
+        let parse = |unit| parse_time_input(unit, &Search::Forward, &clock).unwrap();
+        assert_eq!(parse("31 9:00"), at(2026, 5, 31, 9, 0));
+        assert_eq!(parse("31 11:00"), at(2026, 3, 31, 11, 0));
+    }
+
+    #[test]
+    fn test_parse_time_input_at_day_month_hour_minutes() {
+        let clock = frozen(2026, 10, 16, 17, 0);
+        let parse = |unit, search| parse_time_input(unit, &search, &clock).unwrap();
+        assert_eq!(
+            parse("16-10 9:00", Search::Backward),
+            at(2026, 10, 16, 9, 0)
+        );
+        assert_eq!(
+            parse("16-10 20:00", Search::Backward),
+            at(2025, 10, 16, 20, 0)
+        );
+        assert_eq!(
+            parse("16-10 20:00", Search::Forward),
+            at(2026, 10, 16, 20, 0)
+        );
+        assert_eq!(parse("16-10 9:00", Search::Forward), at(2027, 10, 16, 9, 0));
+        assert_eq!(
+            parse("31-12 9:30", Search::Backward),
+            at(2025, 12, 31, 9, 30)
+        );
+        assert_eq!(parse("1-1 0:00", Search::Forward), at(2027, 1, 1, 0, 0));
+        assert_eq!(parse("29-2 9:00", Search::Backward), at(2024, 2, 29, 9, 0));
+        assert_eq!(parse("29-2 9:00", Search::Forward), at(2028, 2, 29, 9, 0));
+        assert!(parse_time_input("31-2 9:00", &Search::Backward, &clock).is_err());
+        assert!(parse_time_input("31-4 9:00", &Search::Forward, &clock).is_err());
+    }
+
+    #[test]
+    fn test_parse_time_input_hours_ago() {
+        let clock = frozen(2026, 10, 16, 13, 45);
+        assert_eq!(
+            parse_time_input("36h", &Search::Backward, &clock).unwrap(),
+            at(2026, 10, 15, 1, 45)
+        );
+
+        assert!(parse_time_input("99999999999h", &Search::Backward, &clock).is_err());
+        assert!(parse_time_input("99999999999999999999h", &Search::Forward, &clock).is_err());
+        assert!(
+            parse_time_input("99999999999999999 weeks ago", &Search::Backward, &clock).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_time_input_minutes_ago() {
+        let clock = frozen(2026, 10, 16, 13, 45);
+        assert_eq!(
+            parse_time_input("90m", &Search::Forward, &clock).unwrap(),
+            at(2026, 10, 16, 15, 15)
+        );
+    }
+
+    #[test]
+    fn test_parse_time_input_hours_and_minutes_ago() {
+        let clock = frozen(2026, 10, 16, 1, 0);
+        let parse = |unit, search| parse_time_input(unit, &search, &clock).unwrap();
+        assert_eq!(parse("1:30h", Search::Backward), at(2026, 10, 15, 23, 30));
+        assert_eq!(parse("1:30h", Search::Forward), at(2026, 10, 16, 2, 30));
+        assert_eq!(parse("25:05h", Search::Backward), at(2026, 10, 14, 23, 55));
+        assert!(parse_time_input("1:60h", &Search::Backward, &clock).is_err());
+        assert!(parse_time_input("99999999999999:00h", &Search::Backward, &clock).is_err());
+    }
+
+    #[test]
+    fn test_interval_try_from_str() {
+        let clock = frozen(2026, 10, 16, 17, 0);
+        let interval = |start: NaiveDateTime, end: NaiveDateTime| {
+            Interval::new(start.timestamp(), Some(end.timestamp()))
+        };
+        let parse = |unit, search| Interval::try_parse(unit, &search, &clock).unwrap();
+        assert_eq!(
+            parse("9 - 12", Search::Backward),
+            interval(at(2026, 10, 16, 9, 0), at(2026, 10, 16, 12, 0))
+        );
+        assert_eq!(
+            parse("9 - 12", Search::Forward),
+            interval(at(2026, 10, 17, 9, 0), at(2026, 10, 17, 12, 0))
+        );
+        assert_eq!(
+            parse("yesterday - today", Search::Backward),
+            interval(at(2026, 10, 15, 0, 0), at(2026, 10, 16, 0, 0))
+        );
+        assert_eq!(
+            parse("31 20:59 - 2h", Search::Backward),
+            interval(at(2026, 8, 31, 20, 59), at(2026, 10, 16, 15, 0))
+        );
+        assert!(Interval::try_parse("9 - 12 - 15", &Search::Backward, &clock).is_err());
+        assert!(Interval::try_parse("9 12", &Search::Backward, &clock).is_err());
+    }
+
+    #[test]
+    fn test_fixed_clock() {
+        let clock = frozen(2026, 10, 16, 0, 30);
+        assert_eq!(now_date_time(&clock), at(2026, 10, 16, 0, 30));
+        assert_eq!(clock.today(), NaiveDate::from_ymd(2026, 10, 16));
+        let parse = |unit, search| parse_time_input(unit, &search, &clock).unwrap();
+        assert_eq!(parse("now", Search::Backward), at(2026, 10, 16, 0, 30));
+        assert_eq!(parse("23", Search::Backward), at(2026, 10, 15, 23, 0));
+        assert_eq!(parse("0", Search::Backward), at(2026, 10, 16, 0, 0));
+        assert_eq!(parse("0", Search::Forward), at(2026, 10, 17, 0, 0));
+        assert_eq!(parse("0:30", Search::Forward), at(2026, 10, 17, 0, 30));
+        assert_eq!(
+            parse("last friday", Search::Backward),
+            at(2026, 10, 9, 0, 0)
+        );
+        assert_eq!(
+            parse("friday 9am", Search::Backward),
+            at(2026, 10, 16, 9, 0)
+        );
+    }
+
+    #[test]
+    fn test_interval_open_ended() {
+        let clock = frozen(2026, 10, 16, 17, 0);
+        let all = Interval::try_parse("all", &Search::Backward, &clock).unwrap();
+        assert_eq!(all.start, BEGINNING_OF_TIME);
+        assert_eq!(all.end, clock.now());
+
+        let since_yesterday =
+            Interval::try_parse("yesterday -", &Search::Backward, &clock).unwrap();
+        let yesterday = NaiveDateTime::new(yesterday(&clock), NaiveTime::from_hms(0, 0, 0));
+        assert_eq!(since_yesterday.start, yesterday.timestamp());
+        assert_eq!(since_yesterday.end, clock.now());
+
+        let until_yesterday =
+            Interval::try_parse(" - yesterday", &Search::Backward, &clock).unwrap();
+        assert_eq!(until_yesterday.start, BEGINNING_OF_TIME);
+        assert_eq!(until_yesterday.end, yesterday.timestamp());
+
+        assert!(Interval::try_parse("-", &Search::Backward, &clock).is_err());
+        assert!(Interval::try_parse("- yesterday -", &Search::Backward, &clock).is_err());
+    }
+
+    // Strategy for a local time between 2000 and 2050, at which the clock is frozen.
+    fn any_now() -> impl Strategy<Value = NaiveDateTime> {
+        (0..50 * 365i64, 0..24 * 3600i64).prop_map(|(days, seconds)| {
+            NaiveDate::from_ymd(2000, 1, 1).and_hms(0, 0, 0)
+                + Duration::days(days)
+                + Duration::seconds(seconds)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn times_of_day_resolve_within_a_day_in_the_search_direction(
+            now in any_now(),
+            hour in 0..24u32,
+            minute in 0..60u32,
+        ) {
+            let clock = FixedClock::at(now);
+            let now = now_date_time(&clock);
+            let unit = format!("{}:{:02}", hour, minute);
+            let back = parse_time_input(&unit, &Search::Backward, &clock).unwrap();
+            let ahead = parse_time_input(&unit, &Search::Forward, &clock).unwrap();
+            prop_assert_eq!(back.time(), NaiveTime::from_hms(hour, minute, 0));
+            prop_assert_eq!(ahead.time(), back.time());
+            prop_assert!(back <= now && back > now - Duration::days(1));
+            prop_assert!(ahead > now && ahead <= now + Duration::days(1));
+        }
+
+        #[test]
+        fn days_resolve_to_the_closest_month_that_has_them(
+            now in any_now(),
+            day in 1..=31u32,
+            hour in 0..24u32,
+        ) {
+            let clock = FixedClock::at(now);
+            let now = now_date_time(&clock);
+            let unit = format!("{} {}:00", day, hour);
+            let back = parse_time_input(&unit, &Search::Backward, &clock).unwrap();
+            let ahead = parse_time_input(&unit, &Search::Forward, &clock).unwrap();
+            for resolved in &[back, ahead] {
+                prop_assert_eq!(resolved.day(), day);
+                prop_assert_eq!(resolved.time(), NaiveTime::from_hms(hour, 0, 0));
+            }
+            // The 31st is at most two months away, as no two months in a row lack it.
+            prop_assert!(back <= now && back > now - Duration::days(62));
+            prop_assert!(ahead > now && ahead < now + Duration::days(62));
+        }
+
+        #[test]
+        fn dates_resolve_to_the_closest_year_that_has_them(
+            now in any_now(),
+            day in 1..=31u32,
+            month in 1..=12u32,
+        ) {
+            let clock = FixedClock::at(now);
+            let now = now_date_time(&clock);
+            let unit = format!("{}-{} 9:30", day, month);
+            let back = parse_time_input(&unit, &Search::Backward, &clock);
+            let ahead = parse_time_input(&unit, &Search::Forward, &clock);
+            if NaiveDate::from_ymd_opt(2000, month, day).is_none() {
+                prop_assert!(back.is_err() && ahead.is_err());
+                return Ok(());
+            }
+            let (back, ahead) = (back.unwrap(), ahead.unwrap());
+            for resolved in &[back, ahead] {
+                prop_assert_eq!((resolved.day(), resolved.month()), (day, month));
+                prop_assert_eq!(resolved.time(), NaiveTime::from_hms(9, 30, 0));
+            }
+            // Only the 29th of February takes more than a year to come around.
+            let years = if (day, month) == (29, 2) { 8 } else { 1 };
+            prop_assert!(back <= now && back > now - Duration::days(366 * years));
+            prop_assert!(ahead > now && ahead <= now + Duration::days(366 * years));
+        }
+
+        #[test]
+        fn intervals_span_their_resolved_ends(
+            now in any_now(),
+            start in 0..24u32,
+            end in 0..24u32,
+        ) {
+            let clock = FixedClock::at(now);
+            for search in &[Search::Backward, Search::Forward] {
+                let resolve = |hour: u32| {
+                    parse_time_input(&hour.to_string(), search, &clock).unwrap().timestamp()
+                };
+                let interval =
+                    Interval::try_parse(&format!("{} - {}", start, end), search, &clock).unwrap();
+                let expected = Interval::new(resolve(start), Some(resolve(end)));
+                prop_assert_eq!(interval, expected);
+                prop_assert!(interval.start <= interval.end);
+            }
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use crate::arguments::TimeFormat;
+use crate::highlight::Highlight;
+use crate::sessions::{self, Session};
+use crate::time::{self, format_time, Interval};
+
+/// The blocks that fill the cells of a timeline, one per project in turn. They differ in shade as
+/// well as in color, so projects can be told apart without colors too.
+const BLOCKS: [&str; 4] = ["█", "▓", "▒", "░"];
+
+/// Fills the cells of a timeline during which no work was done.
+const GAP: &str = "·";
+
+/// The numbers of cells an hour can be split into, each of which divides an hour evenly.
+const CELLS_PER_HOUR: [i64; 8] = [1, 2, 3, 4, 5, 6, 10, 12];
+
+/// Format of the days of a timeline, as in the list of sessions.
+const DAY_FORMAT: &str = "%a %d-%m";
+
+/// The `Timeline` struct holds the work done within an interval as blocks of time on each project,
+/// drawn as a line per day with the hours of the day running from left to right, see
+/// `work timeline`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Timeline {
+    /// The projects in the order they were first worked on, along with the seconds spent on each.
+    pub projects: Vec<(String, i64)>,
+    /// The blocks of work, earliest first.
+    pub blocks: Vec<Block>,
+}
+
+/// A stretch of work on a single project within a single day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Block {
+    /// The index of the project within the projects of the timeline.
+    pub project: usize,
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Timeline {
+    /// Creates a timeline of the parts of the given sessions that fall within the interval,
+    /// splitting sessions that run past midnight into a block per day.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chrono::NaiveDate;
+    /// # use work_core::log_file::Metadata;
+    /// # use work_core::sessions::Session;
+    /// # use work_core::time::{self, Interval};
+    /// # use work_core::timeline::Timeline;
+    /// let evening = time::start_of_day(NaiveDate::from_ymd(2026, 10, 12)) + 22 * 3600;
+    /// let session = Session {
+    ///     start: evening,
+    ///     stop: Some(evening + 3 * 3600),
+    ///     project: Some("api".to_string()),
+    ///     description: None,
+    ///     client: None,
+    ///     billable: None,
+    ///     metadata: Metadata::new(),
+    /// };
+    /// let interval = Interval::new(0, Some(i64::MAX / 2));
+    /// let timeline = Timeline::new(vec![session], &interval, interval.end);
+    /// assert_eq!(timeline.projects, vec![("api".to_string(), 3 * 3600)]);
+    /// assert_eq!(timeline.blocks.len(), 2);
+    /// ```
+    pub fn new<I>(sessions: I, interval: &Interval, now: i64) -> Self
+    where
+        I: IntoIterator<Item = Session>,
+    {
+        let mut timeline = Timeline::default();
+        for (session, span) in sessions::spans(sessions, now) {
+            // Sessions are in chronological order, none after this one can be within the interval.
+            if session.start > interval.end {
+                break;
+            }
+            let overlap = match sessions::overlap(&span, interval) {
+                Some(overlap) => overlap,
+                None => continue,
+            };
+            let project = session.start_event().to_project();
+            let index = match timeline
+                .projects
+                .iter()
+                .position(|(name, _)| *name == project)
+            {
+                Some(index) => index,
+                None => {
+                    timeline.projects.push((project, 0));
+                    timeline.projects.len() - 1
+                }
+            };
+            for part in overlap.split_at_midnight() {
+                if part.end > part.start {
+                    timeline.projects[index].1 += part.end - part.start;
+                    timeline.blocks.push(Block {
+                        project: index,
+                        start: part.start,
+                        end: part.end,
+                    });
+                }
+            }
+        }
+        timeline
+    }
+
+    /// Returns true if no work was done within the timeline.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Returns the timeline as a line per day from the first day worked to the last, below a line
+    /// naming the hours. The hours run from the first hour worked on any of the days to the last,
+    /// each split into as many cells as fit within the given number of columns. A cell shows the
+    /// block of the project worked on most during it, or a gap if no work was done during it.
+    /// The days are followed by the block and the time spent on each project.
+    pub fn as_text(
+        &self,
+        time_format: &TimeFormat,
+        columns: usize,
+        highlight: &dyn Highlight,
+    ) -> String {
+        let (first, last) = match (self.blocks.first(), self.blocks.last()) {
+            (Some(first), Some(last)) => (time::date_of(first.start), time::date_of(last.start)),
+            _ => return String::new(),
+        };
+        let (from, to) = self.hours();
+        let label_width = first.format(DAY_FORMAT).to_string().chars().count();
+        let available = columns.saturating_sub(label_width + 2) as i64;
+        let cells_per_hour = *CELLS_PER_HOUR
+            .iter()
+            .rev()
+            .find(|cells| *cells * (to - from) <= available)
+            .unwrap_or(&1);
+
+        let mut text = format!(
+            "{:width$}  {}\n",
+            "",
+            ruler(from, to, cells_per_hour),
+            width = label_width
+        );
+        let mut day = first;
+        while day <= last {
+            let blocks: Vec<Block> = self
+                .blocks
+                .iter()
+                .filter(|block| time::date_of(block.start) == day)
+                .cloned()
+                .collect();
+            let start = time::start_of_day(day) + from * 3600;
+            let line: String = cells(&blocks, start, (to - from) * cells_per_hour, cells_per_hour)
+                .into_iter()
+                .map(|cell| match cell {
+                    Some(project) => block(project, highlight),
+                    None => GAP.to_string(),
+                })
+                .collect();
+            text.push_str(&format!("{}  {}\n", day.format(DAY_FORMAT), line));
+            day = day.succ();
+        }
+
+        let width = self
+            .projects
+            .iter()
+            .map(|(project, _)| project.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (index, (project, seconds)) in self.projects.iter().enumerate() {
+            text.push_str(&format!(
+                "\n{} {}  {}",
+                block(index, highlight),
+                highlight.project(&format!("{:<width$}", project, width = width)),
+                highlight.duration(&format_time(time_format, *seconds))
+            ));
+        }
+        text
+    }
+
+    // Returns the first hour of the day worked on any of the days and the hour after the last one.
+    fn hours(&self) -> (i64, i64) {
+        let offsets: Vec<(i64, i64)> = self
+            .blocks
+            .iter()
+            .map(|block| {
+                let midnight = time::start_of_day(time::date_of(block.start));
+                (block.start - midnight, block.end - midnight)
+            })
+            .collect();
+        let from = offsets
+            .iter()
+            .map(|(start, _)| start / 3600)
+            .min()
+            .unwrap_or(0);
+        let to = offsets
+            .iter()
+            .map(|(_, end)| (end + 3599) / 3600)
+            .max()
+            .unwrap_or(24);
+        (from, to.min(24).max(from + 1))
+    }
+}
+
+// Helper function that returns the block of the project with the given index.
+fn block(project: usize, highlight: &dyn Highlight) -> String {
+    highlight.series(BLOCKS[project % BLOCKS.len()], project)
+}
+
+// Helper function that returns the project worked on most within each of the given number of
+// cells from `start` on, or `None` for cells during which no work was done.
+fn cells(blocks: &[Block], start: i64, count: i64, cells_per_hour: i64) -> Vec<Option<usize>> {
+    let length = 3600 / cells_per_hour;
+    (0..count)
+        .map(|index| {
+            let (cell_start, cell_end) = (start + index * length, start + (index + 1) * length);
+            let mut seconds: Vec<(usize, i64)> = Vec::new();
+            for block in blocks {
+                let overlap = block.end.min(cell_end) - block.start.max(cell_start);
+                if overlap <= 0 {
+                    continue;
+                }
+                match seconds
+                    .iter_mut()
+                    .find(|(project, _)| *project == block.project)
+                {
+                    Some((_, time)) => *time += overlap,
+                    None => seconds.push((block.project, overlap)),
+                }
+            }
+            seconds
+                .into_iter()
+                .max_by_key(|(_, time)| *time)
+                .map(|(project, _)| project)
+        })
+        .collect()
+}
+
+/// Returns the hours from `from` up to `to` placed above their first cell, leaving out those that
+/// would run into the one before.
+pub(crate) fn ruler(from: i64, to: i64, cells_per_hour: i64) -> String {
+    let width = ((to - from) * cells_per_hour) as usize;
+    let mut ruler = String::new();
+    for hour in from..to {
+        let position = ((hour - from) * cells_per_hour) as usize;
+        let free = ruler.is_empty() || position > ruler.len();
+        if free && position + 2 <= width {
+            ruler.push_str(&" ".repeat(position - ruler.len()));
+            ruler.push_str(&format!("{:02}", hour));
+        }
+    }
+    ruler
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cells() {
+        let blocks = vec![
+            Block {
+                project: 0,
+                start: 0,
+                end: 3600,
+            },
+            Block {
+                project: 1,
+                start: 3600,
+                end: 4200,
+            },
+            Block {
+                project: 0,
+                start: 4200,
+                end: 4500,
+            },
+            Block {
+                project: 0,
+                start: 5400,
+                end: 5460,
+            },
+        ];
+        // Quarters of an hour: the first quarter of the second hour has 10 minutes of the second
+        // project and 5 of the first, the third quarter has 1 minute of the first.
+        assert_eq!(
+            cells(&blocks, 0, 7, 4),
+            vec![Some(0), Some(0), Some(0), Some(0), Some(1), None, Some(0)]
+        );
+    }
+
+    #[test]
+    fn test_ruler() {
+        assert_eq!(ruler(9, 12, 3), "09 10 11");
+        // Labels that would run into the one before are left out.
+        assert_eq!(ruler(0, 6, 1), "00 03");
+    }
+}
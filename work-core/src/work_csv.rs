@@ -0,0 +1,361 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use csv::ReaderBuilder;
+
+use crate::csv_output::{self, CsvOptions};
+use crate::error::{AppError, ErrorCode, ErrorKind};
+use crate::log_file::{Event, Metadata};
+use crate::sessions::{self, Session};
+
+/// Names of the columns of the work CSV format, in order.
+pub const COLUMNS: [&str; 6] = [
+    "Start",
+    "Stop",
+    "Project",
+    "Description",
+    "Client",
+    "Billable",
+];
+
+/// Formats besides RFC 3339 that the times of imported rows may be in, in local time. Spreadsheets
+/// tend to rewrite times into one of these.
+const LOCAL_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"];
+
+/// Marks billable work in the `Billable` column, just like in the log.
+const BILLABLE_STR: &str = "billable";
+
+/// Marks non-billable work in the `Billable` column, just like in the log.
+const NON_BILLABLE_STR: &str = "non-billable";
+
+/// Writes the given sessions in the work CSV format, which `work import` reads back in. Each row is
+/// a session, with its start and stop given in RFC 3339 such as `2026-10-16T09:00:00+02:00`. The
+/// stop of a session in progress is left empty.
+pub fn write(sessions: &[Session]) -> String {
+    let rows = sessions.iter().map(|session| {
+        let billable = match session.billable {
+            Some(true) => BILLABLE_STR,
+            Some(false) => NON_BILLABLE_STR,
+            None => "",
+        };
+        vec![
+            format_time(session.start),
+            session.stop.map(format_time).unwrap_or_default(),
+            session.project.clone().unwrap_or_default(),
+            session.description.clone().unwrap_or_default(),
+            session.client.clone().unwrap_or_default(),
+            billable.to_string(),
+        ]
+    });
+    csv_output::write(&COLUMNS, rows, &CsvOptions::default())
+}
+
+/// Reads sessions in the work CSV format, see `write`, separated by the given delimiter. The first
+/// row has to name the columns, which may come in any order, and every row has to be a stopped
+/// session that can be written to the log.
+///
+/// # Examples
+/// ```
+/// # use work_core::work_csv;
+/// let input = "Start,Stop,Project\n2026-10-16 09:00,2026-10-16 10:30,backend\n";
+/// let sessions = work_csv::parse(input, b',').unwrap();
+/// assert_eq!(sessions[0].stop.unwrap() - sessions[0].start, 5400);
+/// assert!(work_csv::parse("Start,Stop\n2026-10-16 09:00,\n", b',').is_err());
+/// ```
+pub fn parse(input: &str, delimiter: u8) -> Result<Vec<Session>, AppError> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(input.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|e| invalid(&format!("The CSV can't be read, {}", e)))?
+        .clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name))
+    };
+    let (start, stop) = match (column(COLUMNS[0]), column(COLUMNS[1])) {
+        (Some(start), Some(stop)) => (start, stop),
+        _ => {
+            return Err(invalid(&format!(
+                "The CSV needs at least a {} and a {} column, its columns are {}.",
+                COLUMNS[0],
+                COLUMNS[1],
+                COLUMNS.join(", ")
+            )))
+        }
+    };
+    let project = column(COLUMNS[2]);
+    let description = column(COLUMNS[3]);
+    let client = column(COLUMNS[4]);
+    let billable = column(COLUMNS[5]);
+
+    let mut sessions = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        // The header is the first row.
+        let row = index + 2;
+        let record = record.map_err(|e| invalid(&format!("Row {} can't be read, {}", row, e)))?;
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+
+        let time_of = |column, name| match field(Some(column)) {
+            Some(value) => parse_time(value).ok_or_else(|| {
+                invalid(&format!(
+                    "The {} of row {} isn't a time: {}, use a time such as 2026-10-16 09:00.",
+                    name, row, value
+                ))
+            }),
+            None => Err(invalid(&format!("Row {} has no {}.", row, name))),
+        };
+        let (start, stop) = (time_of(start, "start")?, time_of(stop, "stop")?);
+        if stop <= start {
+            return Err(invalid(&format!("Row {} stops before it starts.", row)));
+        }
+        let billable = match field(billable).map(str::to_lowercase).as_deref() {
+            None => None,
+            Some(BILLABLE_STR) | Some("yes") | Some("true") => Some(true),
+            Some(NON_BILLABLE_STR) | Some("no") | Some("false") => Some(false),
+            Some(value) => {
+                return Err(invalid(&format!(
+                    "The billable column of row {} should be {} or {}, not {}.",
+                    row, BILLABLE_STR, NON_BILLABLE_STR, value
+                )))
+            }
+        };
+
+        let session = Session {
+            start,
+            stop: Some(stop),
+            project: field(project).map(str::to_string),
+            description: field(description).map(str::to_string),
+            client: field(client).map(str::to_string),
+            billable,
+            metadata: Metadata::new(),
+        };
+        session
+            .start_event()
+            .validate()
+            .map_err(|e| invalid(&format!("Row {} can't be imported, {}", row, e)))?;
+        sessions.push(session);
+    }
+    Ok(sessions)
+}
+
+/// The `Reconciled` struct is the log with imported sessions merged into it, see `reconcile`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reconciled {
+    /// The events of the log with the imported sessions merged in.
+    pub events: Vec<(i64, Event)>,
+    /// The imported sessions that weren't in the log before.
+    pub added: Vec<Session>,
+    /// The imported sessions that replace the session of the log starting at the same time.
+    pub corrected: Vec<Session>,
+    /// The number of imported sessions that were in the log already.
+    pub duplicates: usize,
+}
+
+/// Merges the imported sessions into the given events of the log. An imported session that starts
+/// at the same time as a session of the log corrects it, taking over its metadata, unless the two
+/// are the same in which case the imported one is a duplicate and left out. Sessions are sorted
+/// into the log by their start.
+///
+/// An imported session that overlaps another session of the log, or another imported session, is
+/// refused unless `force` is set. Sessions that merely touch are fine. A session in progress lasts
+/// until `now`.
+pub fn reconcile(
+    events: &[(i64, Event)],
+    imported: &[Session],
+    force: bool,
+    now: i64,
+) -> Result<Reconciled, AppError> {
+    let recorded = sessions::reconstruct(events);
+    let mut events = events.to_vec();
+    let mut added = Vec::new();
+    let mut corrected = Vec::new();
+    let mut duplicates = 0;
+    for session in imported {
+        match recorded
+            .iter()
+            .find(|recorded| recorded.start == session.start)
+        {
+            Some(recorded) if same(recorded, session) => duplicates += 1,
+            Some(recorded) => {
+                let mut session = session.clone();
+                session.metadata = recorded.metadata.clone();
+                corrected.push((recorded, session));
+            }
+            None => added.push(session.clone()),
+        }
+    }
+
+    // Both the recorded sessions that aren't corrected and the imported sessions are checked.
+    let kept = recorded
+        .iter()
+        .filter(|recorded| !corrected.iter().any(|(old, _)| old.start == recorded.start));
+    let new: Vec<&Session> = added
+        .iter()
+        .chain(corrected.iter().map(|(_, session)| session))
+        .collect();
+    let mut overlapping = Vec::new();
+    for (index, session) in new.iter().enumerate() {
+        let others = kept.clone().chain(new[index + 1..].iter().copied());
+        for other in others.filter(|other| overlap(session, other, now)) {
+            overlapping.push(format!(
+                "{}\n  overlaps {}",
+                session.to_line(now),
+                other.to_line(now)
+            ));
+        }
+    }
+    if !force && !overlapping.is_empty() {
+        return Err(AppError::new(ErrorKind::User(format!(
+            "The imported sessions overlap other work:\n{}\nUse --force to import them anyway.",
+            overlapping.join("\n")
+        )))
+        .with_code(ErrorCode::Overlap));
+    }
+
+    for (old, _) in &corrected {
+        if let Some(index) = events.iter().position(|(timestamp, event)| {
+            *timestamp == old.start && matches!(event, Event::Start(..))
+        }) {
+            // A recorded session that isn't in progress is followed by its stop.
+            let end = if old.stop.is_some() {
+                index + 2
+            } else {
+                index + 1
+            };
+            events.drain(index..end.min(events.len()));
+        }
+    }
+    for session in &new {
+        let stop = session.stop.unwrap_or(session.start);
+        let index = events
+            .iter()
+            .position(|(timestamp, _)| *timestamp > session.start)
+            .unwrap_or(events.len());
+        events.insert(index, (stop, session.stop_event()));
+        events.insert(index, (session.start, session.start_event()));
+    }
+
+    Ok(Reconciled {
+        events,
+        added,
+        corrected: corrected.into_iter().map(|(_, session)| session).collect(),
+        duplicates,
+    })
+}
+
+// Helper function that formats a timestamp as RFC 3339 in local time.
+fn format_time(timestamp: i64) -> String {
+    Local.timestamp(timestamp, 0).to_rfc3339()
+}
+
+// Helper function that parses a time given in RFC 3339, or in local time in one of the
+// `LOCAL_FORMATS`.
+fn parse_time(input: &str) -> Option<i64> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Some(time.timestamp());
+    }
+    LOCAL_FORMATS.iter().find_map(|format| {
+        let time = NaiveDateTime::parse_from_str(input, format).ok()?;
+        Local
+            .from_local_datetime(&time)
+            .earliest()
+            .map(|time| time.timestamp())
+    })
+}
+
+// Helper function that returns true if the two sessions share any time, merely touching doesn't
+// count. A session in progress lasts until `now`.
+fn overlap(session: &Session, other: &Session, now: i64) -> bool {
+    session.start < other.stop.unwrap_or(now) && other.start < session.stop.unwrap_or(now)
+}
+
+// Helper function that returns true if the two sessions are the same, apart from their metadata.
+fn same(recorded: &Session, imported: &Session) -> bool {
+    Session {
+        metadata: Metadata::new(),
+        ..recorded.clone()
+    } == *imported
+}
+
+// Helper function for the error of input that can't be imported.
+fn invalid(message: &str) -> AppError {
+    AppError::new(ErrorKind::User(message.to_string())).with_code(ErrorCode::InvalidInput)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_and_parse() {
+        let mut sessions = vec![
+            Session::new(1000, Some(2000), "a", None),
+            Session::new(3000, Some(4000), "b, c", None),
+        ];
+        sessions[1].billable = Some(false);
+        sessions[1].description = Some("Said \"hi\"".to_string());
+        let csv = write(&sessions);
+        assert!(csv.starts_with("Start,Stop,Project,Description,Client,Billable\n"));
+        assert_eq!(
+            parse(&csv, b',').unwrap_err().code(),
+            ErrorCode::InvalidInput
+        );
+
+        sessions[1].project = Some("b".to_string());
+        assert_eq!(parse(&write(&sessions), b',').unwrap(), sessions);
+        let semicolons = write(&sessions).replace(',', ";");
+        assert_eq!(parse(&semicolons, b';').unwrap(), sessions);
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let recorded = [
+            Session::new(1000, Some(2000), "a", None),
+            Session::new(3000, Some(4000), "b", None),
+        ];
+        let mut events = Vec::new();
+        for session in &recorded {
+            events.push((session.start, session.start_event()));
+            events.push((session.stop.unwrap(), session.stop_event()));
+        }
+        let mut metadata = Metadata::new();
+        metadata.insert("user".to_string(), "alice".to_string());
+        if let (_, Event::Start(.., old)) = &mut events[2] {
+            *old = metadata.clone();
+        }
+
+        let imported = [
+            Session::new(1000, Some(2000), "a", None),
+            Session::new(3000, Some(3500), "b", None),
+            Session::new(2000, Some(2500), "c", None),
+        ];
+        let reconciled = reconcile(&events, &imported, false, 5000).unwrap();
+        assert_eq!(reconciled.duplicates, 1);
+        assert_eq!(
+            reconciled.added,
+            vec![Session::new(2000, Some(2500), "c", None)]
+        );
+        assert_eq!(reconciled.corrected[0].metadata, metadata);
+        let starts: Vec<i64> = sessions::reconstruct(&reconciled.events)
+            .iter()
+            .map(|session| session.start)
+            .collect();
+        assert_eq!(starts, vec![1000, 2000, 3000]);
+        assert_eq!(reconciled.events[5].0, 3500);
+
+        let overlapping = [Session::new(1500, Some(2500), "d", None)];
+        let error = reconcile(&events, &overlapping, false, 5000).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::Overlap);
+        assert!(reconcile(&events, &overlapping, true, 5000).is_ok());
+    }
+}